@@ -0,0 +1,81 @@
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion};
+use plonky2::field::goldilocks_field::GoldilocksField;
+use plonky2::field::types::Sample;
+use serde::{Deserialize, Serialize};
+use starkyx::chip::builder::AirBuilder;
+use starkyx::chip::register::array::ArrayRegister;
+use starkyx::chip::register::cubic::CubicRegister;
+use starkyx::chip::register::element::ElementRegister;
+use starkyx::chip::trace::generator::ArithmeticGenerator;
+use starkyx::math::goldilocks::cubic::GoldilocksCubicParameters;
+use starkyx::prelude::*;
+use starkyx::trace::generator::TraceGenerator;
+
+const WIDTH: usize = 256;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct WideAccumulation;
+
+impl AirParameters for WideAccumulation {
+    type Field = GoldilocksField;
+    type CubicParams = GoldilocksCubicParameters;
+
+    const NUM_FREE_COLUMNS: usize = WIDTH;
+    const EXTENDED_COLUMNS: usize = 3;
+
+    type Instruction = EmptyInstruction<GoldilocksField>;
+}
+
+/// Builds a chip accumulating a wide array, either directly or through an expression that has to
+/// be evaluated value by value.
+fn wide_accumulation(contiguous: bool, num_rows: usize) -> ArithmeticGenerator<WideAccumulation> {
+    type F = GoldilocksField;
+
+    let mut builder = AirBuilder::<WideAccumulation>::new();
+    let array = builder.alloc_array::<ElementRegister>(WIDTH);
+    let challenges: ArrayRegister<CubicRegister> = builder.alloc_array_challenge(WIDTH);
+    if contiguous {
+        builder.accumulate_array(&challenges, &array);
+    } else {
+        builder.accumulate_expressions(&challenges, &[array.expr() * F::ONE]);
+    }
+
+    let (air, trace_data) = builder.build();
+    let generator = ArithmeticGenerator::new(trace_data, num_rows);
+
+    let writer = generator.new_writer();
+    for i in 0..num_rows {
+        for element in array.iter() {
+            writer.write(&element, &F::rand(), i);
+        }
+    }
+
+    let challenges = (0..3 * WIDTH).map(|_| F::rand()).collect::<Vec<_>>();
+    generator
+        .generate_round(&air, 0, &[], &mut [], &[])
+        .unwrap();
+    generator
+        .generate_round(&air, 1, &challenges, &mut [], &[])
+        .unwrap();
+
+    generator
+}
+
+fn bench_accumulation(c: &mut Criterion) {
+    let mut group = c.benchmark_group("accumulation");
+    group.sample_size(10);
+
+    let num_rows = 1 << 12;
+    for (name, contiguous) in [("contiguous", true), ("per_element", false)] {
+        let generator = wide_accumulation(contiguous, num_rows);
+        let writer = generator.new_writer();
+        group.bench_with_input(BenchmarkId::new(name, WIDTH), &generator, |b, generator| {
+            b.iter(|| generator.air_data.write_extended_trace(&writer))
+        });
+    }
+
+    group.finish();
+}
+
+criterion_group!(benches, bench_accumulation);
+criterion_main!(benches);
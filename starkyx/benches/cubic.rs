@@ -0,0 +1,32 @@
+use criterion::{black_box, criterion_group, criterion_main, BenchmarkId, Criterion};
+use starkyx::math::goldilocks::cubic::GF3;
+use starkyx::math::prelude::*;
+
+fn bench_batch_inverse(c: &mut Criterion) {
+    let mut group = c.benchmark_group("cubic_inverse");
+
+    for log_n in [10, 16] {
+        let n = 1 << log_n;
+        let elements = (0..n).map(|_| GF3::rand()).collect::<Vec<_>>();
+
+        group.bench_with_input(BenchmarkId::new("inverse", n), &elements, |b, elements| {
+            b.iter(|| {
+                elements
+                    .iter()
+                    .map(|x| black_box(x).inverse())
+                    .collect::<Vec<_>>()
+            })
+        });
+
+        group.bench_with_input(
+            BenchmarkId::new("batch_inverse", n),
+            &elements,
+            |b, elements| b.iter(|| GF3::batch_inverse(black_box(elements))),
+        );
+    }
+
+    group.finish();
+}
+
+criterion_group!(benches, bench_batch_inverse);
+criterion_main!(benches);
@@ -0,0 +1,185 @@
+use criterion::{criterion_group, criterion_main, Criterion};
+use num::bigint::RandBigInt;
+use plonky2::field::goldilocks_field::GoldilocksField;
+use plonky2::field::types::Field;
+use rand::thread_rng;
+use serde::{Deserialize, Serialize};
+use starkyx::chip::builder::AirBuilder;
+use starkyx::chip::ec::edwards::ed25519::params::{Ed25519, Ed25519BaseField};
+use starkyx::chip::ec::gadget::{EllipticCurveGadget, EllipticCurveWriter};
+use starkyx::chip::ec::point::AffinePointRegister;
+use starkyx::chip::ec::EllipticCurve;
+use starkyx::chip::field::instruction::FpInstruction;
+use starkyx::chip::register::array::ArrayRegister;
+use starkyx::chip::register::bit::BitRegister;
+use starkyx::chip::trace::generator::ArithmeticGenerator;
+use starkyx::chip::utils::biguint_to_bits_le;
+use starkyx::chip::AirParameters;
+use starkyx::math::fixed_base::FixedBaseTable;
+use starkyx::math::goldilocks::cubic::GoldilocksCubicParameters;
+
+const NUM_ROWS: usize = 1 << 16;
+const WINDOW_WIDTH: usize = 4;
+
+#[derive(Clone, Debug, Copy, Serialize, Deserialize)]
+struct BitByBitParameters;
+
+impl AirParameters for BitByBitParameters {
+    type Field = GoldilocksField;
+    type CubicParams = GoldilocksCubicParameters;
+
+    const NUM_ARITHMETIC_COLUMNS: usize = 1600;
+    const NUM_FREE_COLUMNS: usize = 300;
+    const EXTENDED_COLUMNS: usize = 2409;
+    type Instruction = FpInstruction<Ed25519BaseField>;
+}
+
+#[derive(Clone, Debug, Copy, Serialize, Deserialize)]
+struct WindowedParameters;
+
+impl AirParameters for WindowedParameters {
+    type Field = GoldilocksField;
+    type CubicParams = GoldilocksCubicParameters;
+
+    const NUM_ARITHMETIC_COLUMNS: usize = 4400;
+    const NUM_FREE_COLUMNS: usize = 32;
+    const EXTENDED_COLUMNS: usize = 6609;
+    type Instruction = FpInstruction<Ed25519BaseField>;
+}
+
+/// Builds a scalar multiplication chip with `scalar_mul`, reports its columns and the number of
+/// rows of one scalar multiplication, and benchmarks writing the trace of one scalar
+/// multiplication.
+fn bench_chip<L>(
+    c: &mut Criterion,
+    name: &str,
+    rows_per_scalar_mul: usize,
+    scalar_mul: impl FnOnce(
+        &mut AirBuilder<L>,
+        &ArrayRegister<BitRegister>,
+        &AffinePointRegister<Ed25519>,
+    ),
+) where
+    L: AirParameters<Field = GoldilocksField, Instruction = FpInstruction<Ed25519BaseField>>,
+{
+    type F = GoldilocksField;
+    type E = Ed25519;
+
+    let nb_bits = E::nb_scalar_bits();
+
+    let mut builder = AirBuilder::<L>::new();
+    let point = builder.alloc_public_ec_point();
+    let scalar = builder.alloc_array_public::<BitRegister>(nb_bits);
+    scalar_mul(&mut builder, &scalar, &point);
+    let (air, trace_data) = builder.build();
+    println!(
+        "{}: {} execution columns, {} columns in total, {} rows per scalar multiplication",
+        name,
+        air.execution_trace_length,
+        L::num_columns(),
+        rows_per_scalar_mul
+    );
+
+    let generator = ArithmeticGenerator::<L>::new(trace_data, NUM_ROWS);
+    let mut rng = thread_rng();
+    let point_value = &E::ec_generator() * rng.gen_biguint(256);
+    let scalar_value = rng.gen_biguint(nb_bits as u64);
+
+    let writer = generator.new_writer();
+    writer.write_ec_point(&point, &point_value, 0);
+    for (bit, value) in scalar
+        .iter()
+        .zip(biguint_to_bits_le(&scalar_value, nb_bits))
+    {
+        writer.write(&bit, &F::from_canonical_u8(value as u8), 0);
+    }
+    writer.write_global_instructions(&generator.air_data);
+
+    c.bench_function(name, |b| {
+        b.iter(|| {
+            for i in 0..rows_per_scalar_mul {
+                writer.write_row_instructions(&generator.air_data, i);
+            }
+        })
+    });
+}
+
+fn bench_ed_scalar_mul(c: &mut Criterion) {
+    let nb_bits = Ed25519::nb_scalar_bits();
+
+    bench_chip::<BitByBitParameters>(
+        c,
+        "ed25519_scalar_mul_bit_by_bit",
+        nb_bits,
+        |builder, scalar, point| {
+            builder.ed_msm(&[*scalar], &[*point]);
+        },
+    );
+
+    bench_chip::<WindowedParameters>(
+        c,
+        "ed25519_scalar_mul_windowed",
+        nb_bits / WINDOW_WIDTH,
+        |builder, scalar, point| {
+            builder.ed_scalar_mul_windowed(scalar, point, WINDOW_WIDTH);
+        },
+    );
+}
+
+/// Benchmarks writing the trace of one windowed scalar multiplication of the generator, from
+/// scratch and from the cached powers of the generator.
+fn bench_ed_scalar_mul_fixed_base(c: &mut Criterion) {
+    type F = GoldilocksField;
+    type L = WindowedParameters;
+    type E = Ed25519;
+
+    let nb_bits = E::nb_scalar_bits();
+    let rows_per_scalar_mul = nb_bits / WINDOW_WIDTH;
+
+    let mut builder = AirBuilder::<L>::new();
+    let point = builder.alloc_public_ec_point();
+    let scalar = builder.alloc_array_public::<BitRegister>(nb_bits);
+    let gadget = builder.ed_scalar_mul_windowed_gadget(&scalar, &point, WINDOW_WIDTH);
+    let (_, trace_data) = builder.build();
+
+    let generator = ArithmeticGenerator::<L>::new(trace_data, NUM_ROWS);
+    let mut rng = thread_rng();
+    let scalar_value = rng.gen_biguint(nb_bits as u64);
+
+    let writer = generator.new_writer();
+    writer.write_ec_point(&point, &E::ec_generator(), 0);
+    for (bit, value) in scalar
+        .iter()
+        .zip(biguint_to_bits_le(&scalar_value, nb_bits))
+    {
+        writer.write(&bit, &F::from_canonical_u8(value as u8), 0);
+    }
+    writer.write_global_instructions(&generator.air_data);
+
+    c.bench_function("ed25519_scalar_mul_fixed_base_from_scratch", |b| {
+        b.iter(|| {
+            for i in 0..rows_per_scalar_mul {
+                writer.write_row_instructions(&generator.air_data, i);
+            }
+        })
+    });
+
+    let table = FixedBaseTable::<E>::generator();
+    c.bench_function("ed25519_scalar_mul_fixed_base_cached", |b| {
+        b.iter(|| {
+            let accumulators =
+                writer.ed_scalar_mul_windowed_accumulators(&gadget, &table, &scalar_value, 0);
+            for i in 0..rows_per_scalar_mul {
+                writer.write_ed_scalar_mul_windowed(&gadget, &accumulators, i);
+                writer.write_row_instructions(&generator.air_data, i);
+            }
+        })
+    });
+}
+
+criterion_group! {
+    name = benches;
+    config = Criterion::default().sample_size(10);
+    targets = bench_ed_scalar_mul, bench_ed_scalar_mul_fixed_base
+}
+criterion_main!(benches);
@@ -0,0 +1,45 @@
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+use plonky2::field::goldilocks_field::GoldilocksField;
+use plonky2::field::types::{Field, PrimeField64};
+use rand::{thread_rng, Rng};
+use starkyx::chip::register::element::ElementRegister;
+use starkyx::chip::register::memory::MemorySlice;
+use starkyx::chip::register::RegisterSerializable;
+use starkyx::chip::table::log_derivative::entry::LogEntry;
+use starkyx::chip::trace::writer::TraceWriter;
+
+const WIDTH: usize = 16;
+const NUM_ROWS: usize = 1 << 18;
+const NUM_TABLE_ROWS: usize = 1 << 16;
+
+fn bench_multiplicities(c: &mut Criterion) {
+    type F = GoldilocksField;
+
+    let mut rng = thread_rng();
+    let writer = TraceWriter::new_with_value(F::ZERO, WIDTH, NUM_ROWS, 0, 0);
+    writer
+        .write_trace()
+        .unwrap()
+        .rows_mut()
+        .flatten()
+        .for_each(|x| *x = F::from_canonical_u16(rng.gen()));
+
+    let entries = (0..WIDTH)
+        .map(|i| LogEntry::Input(ElementRegister::from_register(MemorySlice::Local(i, 1))))
+        .collect::<Vec<_>>();
+
+    c.bench_function("range_check_multiplicities", |b| {
+        b.iter(|| {
+            black_box(writer.get_multiplicities_from_fn(
+                1,
+                NUM_TABLE_ROWS,
+                &entries,
+                &[],
+                |value: F| (value.to_canonical_u64() as usize, 0),
+            ))
+        })
+    });
+}
+
+criterion_group!(benches, bench_multiplicities);
+criterion_main!(benches);
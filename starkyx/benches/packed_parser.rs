@@ -0,0 +1,118 @@
+use criterion::{black_box, criterion_group, criterion_main, BenchmarkId, Criterion};
+use plonky2::field::goldilocks_field::GoldilocksField;
+use plonky2::field::packable::Packable;
+use plonky2::field::packed::PackedField;
+use plonky2::field::types::{Field, Sample};
+use serde::{Deserialize, Serialize};
+use starkyx::air::RAir;
+use starkyx::chip::builder::AirBuilder;
+use starkyx::chip::ec::edwards::ed25519::params::Ed25519BaseField;
+use starkyx::chip::field::mul::FpMulInstruction;
+use starkyx::chip::field::register::FieldRegister;
+use starkyx::chip::AirParameters;
+use starkyx::math::goldilocks::cubic::GoldilocksCubicParameters;
+use starkyx::plonky2::parser::consumer::ConstraintConsumer;
+use starkyx::plonky2::parser::StarkParser;
+
+type F = GoldilocksField;
+type P = <F as Packable>::Packing;
+
+const NUM_MULS: usize = 8;
+const NUM_ROWS: usize = 1 << 10;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct WideMul;
+
+impl AirParameters for WideMul {
+    type Field = GoldilocksField;
+    type CubicParams = GoldilocksCubicParameters;
+
+    const NUM_ARITHMETIC_COLUMNS: usize = 124 * NUM_MULS;
+    const NUM_FREE_COLUMNS: usize = 2;
+    const EXTENDED_COLUMNS: usize = 3 * 62 * NUM_MULS + 9;
+
+    type Instruction = FpMulInstruction<Ed25519BaseField>;
+}
+
+/// Evaluates the constraints of a chip of field multiplications on every row of a random trace,
+/// either row by row or `P::WIDTH` rows at a time with the packed parser.
+fn bench_packed_parser(c: &mut Criterion) {
+    let mut builder = AirBuilder::<WideMul>::new();
+    for _ in 0..NUM_MULS {
+        let a = builder.alloc::<FieldRegister<Ed25519BaseField>>();
+        let b = builder.alloc::<FieldRegister<Ed25519BaseField>>();
+        builder.fp_mul(&a, &b);
+    }
+    let (air, _) = builder.build();
+
+    let width = WideMul::num_columns();
+    let trace = (0..NUM_ROWS)
+        .map(|_| F::rand_vec(width))
+        .collect::<Vec<_>>();
+    let challenges = F::rand_vec(air.num_challenges);
+    let global_values = F::rand_vec(air.num_global_values);
+    let public_values = F::rand_vec(air.num_public_values);
+    let alphas = F::rand_vec(2);
+
+    let packed_trace = (0..NUM_ROWS / P::WIDTH)
+        .map(|i| {
+            (0..width)
+                .map(|column| {
+                    let values = (0..P::WIDTH)
+                        .map(|row| trace[i * P::WIDTH + row][column])
+                        .collect::<Vec<_>>();
+                    *P::from_slice(&values)
+                })
+                .collect::<Vec<_>>()
+        })
+        .collect::<Vec<_>>();
+    let broadcast = |values: &[F]| values.iter().map(|x| P::from(*x)).collect::<Vec<_>>();
+    let packed_challenges = broadcast(&challenges);
+    let packed_global_values = broadcast(&global_values);
+    let packed_public_values = broadcast(&public_values);
+
+    let mut group = c.benchmark_group("constraint_eval");
+
+    group.bench_function(BenchmarkId::new("scalar", NUM_MULS), |b| {
+        b.iter(|| {
+            for i in 0..NUM_ROWS {
+                let mut consumer =
+                    ConstraintConsumer::<F>::new(alphas.clone(), F::ONE, F::ZERO, F::ZERO);
+                let mut parser = StarkParser::<F, F, F, 2, 1>::new(
+                    &trace[i],
+                    &trace[(i + 1) % NUM_ROWS],
+                    &global_values,
+                    &public_values,
+                    &challenges,
+                    &mut consumer,
+                );
+                air.eval(&mut parser);
+                black_box(consumer.accumulators());
+            }
+        })
+    });
+
+    group.bench_function(BenchmarkId::new("packed", NUM_MULS), |b| {
+        b.iter(|| {
+            for i in 0..packed_trace.len() {
+                let mut consumer =
+                    ConstraintConsumer::<P>::new(alphas.clone(), P::ONES, P::ZEROS, P::ZEROS);
+                let mut parser = StarkParser::<F, F, P, 2, 1>::new(
+                    &packed_trace[i],
+                    &packed_trace[(i + 1) % packed_trace.len()],
+                    &packed_global_values,
+                    &packed_public_values,
+                    &packed_challenges,
+                    &mut consumer,
+                );
+                air.eval(&mut parser);
+                black_box(consumer.accumulators());
+            }
+        })
+    });
+
+    group.finish();
+}
+
+criterion_group!(benches, bench_packed_parser);
+criterion_main!(benches);
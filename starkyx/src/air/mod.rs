@@ -38,6 +38,13 @@ pub trait RAirData {
 
     fn num_public_inputs(&self) -> usize;
 
+    /// The columns of the trace that are not referenced by any constraint.
+    ///
+    /// The prover is free to fill these columns with random values.
+    fn unused_columns(&self) -> Vec<usize> {
+        Vec::new()
+    }
+
     fn num_rounds(&self) -> usize {
         self.round_data().len()
     }
@@ -14,6 +14,21 @@ pub trait AirParser: Sized {
     fn global_slice(&self) -> &[Self::Var];
     fn public_slice(&self) -> &[Self::Var];
 
+    /// The values of the row `shift` rows after the current one.
+    ///
+    /// Besides the current and next rows, the trace is only opened at the shifts of
+    /// [`StarkyConfig::opening_shifts`]. The rows wrap around the trace, so a constraint on the
+    /// shifted rows must either hold cyclically or be filtered by the AIR.
+    ///
+    /// [`StarkyConfig::opening_shifts`]: crate::plonky2::stark::config::StarkyConfig::opening_shifts
+    fn shifted_slice(&self, shift: usize) -> &[Self::Var] {
+        match shift {
+            0 => self.local_slice(),
+            1 => self.next_slice(),
+            _ => panic!("The trace is not opened at shift {}", shift),
+        }
+    }
+
     fn constraint(&mut self, constraint: Self::Var);
     fn constraint_transition(&mut self, constraint: Self::Var);
     fn constraint_first_row(&mut self, constraint: Self::Var);
@@ -35,6 +35,10 @@ impl<L: AirParameters> RAirData for Chip<L> {
     fn width(&self) -> usize {
         L::NUM_ARITHMETIC_COLUMNS + L::NUM_FREE_COLUMNS + L::EXTENDED_COLUMNS
     }
+
+    fn unused_columns(&self) -> Vec<usize> {
+        self.unused_columns.clone()
+    }
 }
 
 impl<AP: AirParser, L: AirParameters<Field = AP::Field>> RAir<AP> for Chip<L>
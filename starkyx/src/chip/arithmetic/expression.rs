@@ -62,6 +62,11 @@ impl<F: Field> ArithmeticExpression<F> {
         self.expression.registers()
     }
 
+    /// The degree of the expression as a polynomial in the trace values.
+    pub fn degree(&self) -> usize {
+        self.expression.degree()
+    }
+
     /// Returns true if any of the registers in the expression is a trace register.
     pub fn is_trace(&self) -> bool {
         !self.registers().iter().all(|reg| !reg.is_trace())
@@ -74,6 +74,19 @@ impl<F: Field> ArithmeticExpressionSlice<F> {
         }
     }
 
+    /// The degree of the expression as a polynomial in the trace values.
+    pub fn degree(&self) -> usize {
+        match self {
+            ArithmeticExpressionSlice::Input(_) => 1,
+            ArithmeticExpressionSlice::Const(_) => 0,
+            ArithmeticExpressionSlice::Add(left, right) => left.degree().max(right.degree()),
+            ArithmeticExpressionSlice::Sub(left, right) => left.degree().max(right.degree()),
+            ArithmeticExpressionSlice::ConstMul(_, expr) => expr.degree(),
+            ArithmeticExpressionSlice::ScalarMul(left, right) => left.degree() + right.degree(),
+            ArithmeticExpressionSlice::Mul(left, right) => left.degree() + right.degree(),
+        }
+    }
+
     pub(crate) fn read_from_slice(&self, slice: &[F]) -> Vec<F> {
         match self {
             ArithmeticExpressionSlice::Input(input) => input.read_from_slice(slice).to_vec(),
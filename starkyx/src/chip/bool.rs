@@ -124,6 +124,10 @@ impl<F: Field> Instruction<F> for SelectInstruction {
             writer.write_slice(&self.result, &false_value);
         }
     }
+
+    fn trace_layout(&self) -> Vec<MemorySlice> {
+        vec![self.result]
+    }
 }
 
 // #[cfg(test)]
@@ -3,6 +3,8 @@ use crate::chip::arithmetic::expression::ArithmeticExpression;
 use crate::chip::arithmetic::ArithmeticConstraint;
 use crate::chip::instruction::assign::{AssignInstruction, AssignType};
 use crate::chip::instruction::set::AirInstruction;
+use crate::chip::register::bit::BitRegister;
+use crate::chip::register::element::ElementRegister;
 use crate::chip::register::Register;
 use crate::chip::AirParameters;
 
@@ -39,6 +41,30 @@ impl<L: AirParameters> AirBuilder<L> {
         self.constraints.push(constraint.into());
     }
 
+    /// Asserts that `expression` evaluates to one of the values in `set`.
+    ///
+    /// The assertion is the constraint `prod_i (expression - v_i) == 0`, whose degree is the size
+    /// of the set times the degree of `expression`. A chip supports constraints of degree at most
+    /// 3, so only small sets, such as a handful of opcodes, can be checked this way.
+    pub fn assert_expression_in_set(
+        &mut self,
+        expression: ArithmeticExpression<L::Field>,
+        set: &[L::Field],
+    ) {
+        assert!(!set.is_empty(), "cannot assert membership in an empty set");
+        let product = set[1..]
+            .iter()
+            .fold(expression.clone() - set[0], |acc, value| {
+                acc * (expression.clone() - *value)
+            });
+        assert!(
+            product.degree() <= 3,
+            "set membership constraint has degree {}, but a chip supports degree at most 3",
+            product.degree()
+        );
+        self.assert_expression_zero(product);
+    }
+
     #[inline]
     pub fn assert_expressions_equal(
         &mut self,
@@ -79,6 +105,27 @@ impl<L: AirParameters> AirBuilder<L> {
         self.constraints.push(constraint.into());
     }
 
+    /// Asserts that the expressions `a` and `b` are equal on every row.
+    ///
+    /// Expressions of several elements, such as those of arrays or field registers, are compared
+    /// element-wise, so this generalizes [`Self::assert_equal`] from registers to computed values.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `a` and `b` do not have the same number of elements.
+    pub fn assert_expr_eq(
+        &mut self,
+        a: ArithmeticExpression<L::Field>,
+        b: ArithmeticExpression<L::Field>,
+    ) {
+        assert_eq!(
+            a.size, b.size,
+            "Cannot assert the equality of expressions of sizes {} and {}",
+            a.size, b.size
+        );
+        self.assert_expression_zero(a - b);
+    }
+
     #[inline]
     pub fn assert_equal<T: Register>(&mut self, a: &T, b: &T) {
         self.assert_expression_zero(a.expr() - b.expr());
@@ -156,6 +203,53 @@ impl<L: AirParameters> AirBuilder<L> {
         instr
     }
 
+    /// Allocates a bit register and sets it to the value of `expression`.
+    ///
+    /// The expression is expected to evaluate to either `0` or `1`, which is enforced by
+    /// asserting that the returned register is boolean.
+    pub fn bit_from_expr(&mut self, expression: ArithmeticExpression<L::Field>) -> BitRegister {
+        if expression.is_trace() {
+            // Trace bit registers are constrained to be boolean on allocation.
+            let bit = self.alloc::<BitRegister>();
+            self.set_to_expression(&bit, expression);
+            bit
+        } else {
+            let bit = self.alloc_public::<BitRegister>();
+            self.set_to_expression_public(&bit, expression);
+            self.assert_expression_zero(bit.expr() * bit.not_expr());
+            bit
+        }
+    }
+
+    /// Allocates a register following the recurrence `reg[0] = init` and `reg' = transition(reg)`.
+    ///
+    /// The register is allocated before `transition` is called, so the transition can refer to the
+    /// new register and to any register allocated before it. Both the boundary and the transition
+    /// are written by the row instructions.
+    pub fn alloc_recurrence<T: Register>(
+        &mut self,
+        init: T::Value<L::Field>,
+        transition: impl Fn(&T) -> ArithmeticExpression<L::Field>,
+    ) -> T {
+        let register = self.alloc::<T>();
+        let init = ArithmeticExpression::from_constant_vec(T::align(&init).to_vec());
+        self.set_to_expression_first_row(&register, init);
+        self.set_to_expression_transition(&register.next(), transition(&register));
+        register
+    }
+
+    /// Allocates a register holding the running product of `value` over the rows of the trace.
+    ///
+    /// The accumulator satisfies `acc[0] = value[0]` and `acc' = acc * value'`, a constraint of
+    /// degree 2. Both are written by the row instructions, so `value` must be written on the next
+    /// row before the instructions of the current row.
+    pub fn alloc_running_product(&mut self, value: &ElementRegister) -> ElementRegister {
+        let acc = self.alloc::<ElementRegister>();
+        self.set_to_expression_first_row(&acc, value.expr());
+        self.set_to_expression_transition(&acc.next(), acc.expr() * value.next().expr());
+        acc
+    }
+
     #[inline]
     pub fn assert_zero(&mut self, data: &impl Register) {
         self.assert_expression_zero(data.expr());
@@ -1,23 +1,78 @@
+use anyhow::{ensure, Result};
+
 use super::{AirBuilder, AirParameters};
+use crate::chip::arithmetic::expression::ArithmeticExpression;
+use crate::chip::instruction::assign::{AssignInstruction, AssignType};
 use crate::chip::instruction::set::AirInstruction;
 use crate::chip::register::array::ArrayRegister;
+use crate::chip::register::bit::BitRegister;
 use crate::chip::register::cell::CellType;
 use crate::chip::register::element::ElementRegister;
 use crate::chip::register::memory::MemorySlice;
+use crate::chip::register::strided::StridedArrayRegister;
 use crate::chip::register::{Register, RegisterSerializable};
+use crate::math::prelude::*;
+
+/// Returns the index following an allocation of `size` cells at index `start` of a memory region.
+///
+/// Panics if the index overflows, instead of wrapping around into memory that is already in use.
+pub(crate) fn allocation_end(start: usize, size: usize, region: &str) -> usize {
+    start.checked_add(size).unwrap_or_else(|| {
+        panic!(
+            "Allocation index overflow: cannot allocate {} cells of {} memory at index {}",
+            size, region, start
+        )
+    })
+}
+
+/// Returns the number of cells of an array of `length` registers of `size_of` cells each.
+///
+/// Panics if the number of cells overflows, as [`allocation_end`] does for the index.
+pub(crate) fn allocation_size(size_of: usize, length: usize, region: &str) -> usize {
+    size_of.checked_mul(length).unwrap_or_else(|| {
+        panic!(
+            "Allocation index overflow: cannot allocate {} registers of {} cells of {} memory",
+            length, size_of, region
+        )
+    })
+}
+
+/// The values taken by a padded array on the rows where it is not active, see
+/// [`AirBuilder::alloc_array_padded`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Padding {
+    /// The array is zero on inactive rows.
+    Zero,
+    /// The array keeps its value from the previous row on inactive rows.
+    CopyForward,
+}
 
 impl<L: AirParameters> AirBuilder<L> {
     /// Allocates `size` cells/columns worth of memory and returns it as a `MemorySlice`.
     pub(crate) fn get_local_memory(&mut self, size: usize) -> MemorySlice {
         let register = MemorySlice::Local(self.local_index, size);
-        self.local_index += size;
+        self.local_index = allocation_end(self.local_index, size, "local");
+        self.local_allocations.push(register);
         register
     }
 
-    fn get_extended_memory(&mut self, size: usize) -> MemorySlice {
+    fn get_extended_memory(&mut self, size: usize) -> Result<MemorySlice> {
+        let extended_end = allocation_end(self.extended_index, size, "extended");
+        if self.strict {
+            let num_extended_columns =
+                extended_end - L::NUM_ARITHMETIC_COLUMNS - L::NUM_FREE_COLUMNS;
+            ensure!(
+                num_extended_columns <= L::EXTENDED_COLUMNS,
+                "Not enough extended columns. Allocating {} columns uses {} of the {} extended columns.",
+                size,
+                num_extended_columns,
+                L::EXTENDED_COLUMNS
+            );
+        }
         let register = MemorySlice::Local(self.extended_index, size);
-        self.extended_index += size;
-        register
+        self.extended_index = extended_end;
+        self.local_allocations.push(register);
+        Ok(register)
     }
 
     fn get_challenge_memory(&mut self, size: usize) -> MemorySlice {
@@ -36,15 +91,27 @@ impl<L: AirParameters> AirBuilder<L> {
     /// cell will be range checked using the lookup table to be in the range `[0, 2^16]`.
     fn get_local_u16_memory(&mut self, size: usize) -> MemorySlice {
         let register = MemorySlice::Local(self.local_arithmetic_index, size);
-        self.local_arithmetic_index += size;
+        self.local_arithmetic_index =
+            allocation_end(self.local_arithmetic_index, size, "arithmetic");
+        self.local_allocations.push(register);
         register
     }
 
+    /// Allocates a raw region of `size_of` free columns and returns it as a `MemorySlice`.
+    ///
+    /// This is the untyped counterpart of [`Self::alloc`], for code that only knows the width of
+    /// a register at runtime. The columns count towards `L::NUM_FREE_COLUMNS`, and the caller can
+    /// wrap the slice in any register with `Element` cells, e.g. using
+    /// [`ArrayRegister::from_register_unsafe`].
+    pub fn alloc_dyn(&mut self, size_of: usize) -> MemorySlice {
+        self.get_local_memory(size_of)
+    }
+
     /// Allocates a new local register according to type `T` which implements the Register trait
     /// and returns it.
     pub fn alloc<T: Register>(&mut self) -> T {
         let register = match T::CELL {
-            CellType::Element => self.get_local_memory(T::size_of()),
+            CellType::Element => self.alloc_dyn(T::size_of()),
             CellType::U16 => self.get_local_u16_memory(T::size_of()),
             CellType::Bit => {
                 let reg = self.get_local_memory(T::size_of());
@@ -56,26 +123,69 @@ impl<L: AirParameters> AirBuilder<L> {
         T::from_register(register)
     }
 
+    /// Allocates `size` arithmetic cells/columns that are excluded from the internal range check.
+    fn get_local_unchecked_u16_memory(&mut self, size: usize) -> MemorySlice {
+        let register = self.get_local_u16_memory(size);
+        self.unchecked_arithmetic_columns
+            .extend(self.local_arithmetic_index - size..self.local_arithmetic_index);
+        register
+    }
+
     /// Allocates a new local register according to type `T` which implements the Register trait
     /// and returns it.
+    ///
+    /// Unlike [`Self::alloc`], `U16` cells are not added to the internal range check. The caller
+    /// is responsible for constraining their range by other means.
+    pub fn alloc_no_range_check<T: Register>(&mut self) -> T {
+        match T::CELL {
+            CellType::U16 => T::from_register(self.get_local_unchecked_u16_memory(T::size_of())),
+            _ => self.alloc::<T>(),
+        }
+    }
+
+    pub fn alloc_array_no_range_check<T: Register>(&mut self, length: usize) -> ArrayRegister<T> {
+        match T::CELL {
+            CellType::U16 => {
+                ArrayRegister::<T>::from_register_unsafe(self.get_local_unchecked_u16_memory(
+                    allocation_size(T::size_of(), length, "arithmetic"),
+                ))
+            }
+            _ => self.alloc_array::<T>(length),
+        }
+    }
+
+    /// Allocates a new local register according to type `T` which implements the Register trait
+    /// and returns it.
+    ///
+    /// Panics in strict mode if the register does not fit in the extended columns, see
+    /// [`Self::try_alloc_extended`] for the fallible version.
     pub(crate) fn alloc_extended<T: Register>(&mut self) -> T {
+        self.try_alloc_extended()
+            .unwrap_or_else(|error| panic!("{}", error))
+    }
+
+    /// Allocates a new extended register according to type `T` which implements the Register
+    /// trait and returns it.
+    ///
+    /// In strict mode, returns an error if the register does not fit in the extended columns.
+    pub fn try_alloc_extended<T: Register>(&mut self) -> Result<T> {
         let register = match T::CELL {
-            CellType::Element => self.get_extended_memory(T::size_of()),
+            CellType::Element => self.get_extended_memory(T::size_of())?,
             CellType::U16 => unreachable!("Extended U16 not implemented"),
             CellType::Bit => {
-                let reg = self.get_extended_memory(T::size_of());
+                let reg = self.get_extended_memory(T::size_of())?;
                 let constraint = AirInstruction::bits(&reg);
                 self.register_air_instruction_internal(constraint);
                 reg
             }
         };
-        T::from_register(register)
+        Ok(T::from_register(register))
     }
 
     pub fn alloc_array<T: Register>(&mut self, length: usize) -> ArrayRegister<T> {
-        let size_of = T::size_of() * length;
+        let size_of = allocation_size(T::size_of(), length, "local");
         let register = match T::CELL {
-            CellType::Element => self.get_local_memory(size_of),
+            CellType::Element => self.alloc_dyn(size_of),
             CellType::U16 => self.get_local_u16_memory(size_of),
             CellType::Bit => {
                 let reg = self.get_local_memory(size_of);
@@ -87,19 +197,115 @@ impl<L: AirParameters> AirBuilder<L> {
         ArrayRegister::<T>::from_register_unsafe(register)
     }
 
+    /// Allocates an array of `length` registers, asserting in debug builds that `length` is at
+    /// most `max`.
+    ///
+    /// Meant for lengths computed at runtime, to catch an accidentally huge allocation where it
+    /// happens rather than when the columns run out.
+    pub fn alloc_array_bounded<T: Register>(
+        &mut self,
+        length: usize,
+        max: usize,
+    ) -> ArrayRegister<T> {
+        debug_assert!(
+            length <= max,
+            "array length {} exceeds the maximum of {}",
+            length,
+            max
+        );
+        self.alloc_array::<T>(length)
+    }
+
+    /// Allocates a register described by `doc`, see [`Self::document`].
+    pub fn alloc_with_doc<T: Register>(&mut self, doc: &str) -> T {
+        let register = self.alloc::<T>();
+        self.document(&register, doc);
+        register
+    }
+
+    /// Attaches the description `doc` to `register`.
+    ///
+    /// The descriptions are part of the constraints exported by
+    /// [`Self::export_constraints`] and of the diagnostics of
+    /// [`AirTraceData::validate_trace`](crate::chip::trace::data::AirTraceData::validate_trace),
+    /// so that a serialized chip describes its own registers.
+    pub fn document<T: Register>(&mut self, register: &T, doc: &str) {
+        self.register_docs
+            .push((*register.register(), doc.to_string()));
+    }
+
+    /// Allocates an array of `length` registers which is only written on the rows where `active`
+    /// is set.
+    ///
+    /// On the other rows, the array is constrained to, and written by the trace instructions with,
+    /// the value given by `padding`, so that a recurrence over the array also holds on the padding
+    /// rows of a trace. With [`Padding::CopyForward`], the value on an inactive row is written
+    /// from the previous row, so `active` must be written on the next row before the instructions
+    /// of a row are. An inactive first row is not constrained.
+    pub fn alloc_array_padded<T: Register>(
+        &mut self,
+        length: usize,
+        active: &BitRegister,
+        padding: Padding,
+    ) -> ArrayRegister<T> {
+        let array = self.alloc_array::<T>(length);
+        for element in array.iter() {
+            let instruction = match padding {
+                Padding::Zero => AirInstruction::Assign(AssignInstruction::new(
+                    ArithmeticExpression::from_constant_vec(vec![L::Field::ZERO; T::size_of()]),
+                    *element.register(),
+                    AssignType::All,
+                ))
+                .as_filtered(active.not_expr()),
+                Padding::CopyForward => AirInstruction::Assign(AssignInstruction::new(
+                    element.expr(),
+                    *element.next().register(),
+                    AssignType::Transition,
+                ))
+                .as_filtered(active.next().not_expr()),
+            };
+            self.register_air_instruction_internal(instruction);
+        }
+        array
+    }
+
+    /// Allocates an array of `length` registers laid out at a fixed `stride`.
+    ///
+    /// The block of `length * stride` registers is reserved at once, and the registers between
+    /// the elements of the array are available as the other lanes of the block, see
+    /// [`StridedArrayRegister::lane`].
+    pub fn alloc_array_strided<T: Register>(
+        &mut self,
+        length: usize,
+        stride: usize,
+    ) -> StridedArrayRegister<T> {
+        let block = self.alloc_array::<T>(length * stride);
+        StridedArrayRegister::new(block, stride)
+    }
+
     pub fn alloc_array_extended<T: Register>(&mut self, length: usize) -> ArrayRegister<T> {
-        let size_of = T::size_of() * length;
+        self.try_alloc_array_extended(length)
+            .unwrap_or_else(|error| panic!("{}", error))
+    }
+
+    /// Allocates an array of extended registers, returning an error in strict mode if the array
+    /// does not fit in the extended columns.
+    pub fn try_alloc_array_extended<T: Register>(
+        &mut self,
+        length: usize,
+    ) -> Result<ArrayRegister<T>> {
+        let size_of = allocation_size(T::size_of(), length, "extended");
         let register = match T::CELL {
-            CellType::Element => self.get_extended_memory(size_of),
+            CellType::Element => self.get_extended_memory(size_of)?,
             CellType::U16 => unreachable!("Extended U16 not implemented"),
             CellType::Bit => {
-                let reg = self.get_extended_memory(size_of);
+                let reg = self.get_extended_memory(size_of)?;
                 let constraint = AirInstruction::bits(&reg);
                 self.register_air_instruction_internal(constraint);
                 reg
             }
         };
-        ArrayRegister::<T>::from_register_unsafe(register)
+        Ok(ArrayRegister::<T>::from_register_unsafe(register))
     }
 
     pub fn alloc_challenge<T: Register>(&mut self) -> T {
@@ -108,7 +314,7 @@ impl<L: AirParameters> AirBuilder<L> {
     }
 
     pub fn alloc_array_challenge<T: Register>(&mut self, length: usize) -> ArrayRegister<T> {
-        let size_of = T::size_of() * length;
+        let size_of = allocation_size(T::size_of(), length, "challenge");
         let register = self.get_challenge_memory(size_of);
         ArrayRegister::<T>::from_register_unsafe(register)
     }
@@ -125,7 +331,7 @@ impl<L: AirParameters> AirBuilder<L> {
     }
 
     pub fn alloc_array_global<T: Register>(&mut self, length: usize) -> ArrayRegister<T> {
-        let size_of = T::size_of() * length;
+        let size_of = allocation_size(T::size_of(), length, "global");
         let register = match T::CELL {
             CellType::Element => self.get_global_memory(size_of),
             CellType::U16 => unreachable!("Global U16 not supported"),
@@ -134,8 +340,11 @@ impl<L: AirParameters> AirBuilder<L> {
         ArrayRegister::<T>::from_register_unsafe(register)
     }
 
-    /// Allocates a new local register according to type `T` which implements the Register trait
+    /// Allocates a new public register according to type `T` which implements the Register trait
     /// and returns it.
+    ///
+    /// A public register can be used in any expression, including the per-row constraints of the
+    /// trace, and is read from the public inputs by the parser.
     pub fn alloc_public<T: Register>(&mut self) -> T {
         let register = match T::CELL {
             CellType::Element => self.get_public_memory(T::size_of()),
@@ -153,7 +362,7 @@ impl<L: AirParameters> AirBuilder<L> {
     }
 
     pub fn alloc_array_public<T: Register>(&mut self, length: usize) -> ArrayRegister<T> {
-        let size_of = T::size_of() * length;
+        let size_of = allocation_size(T::size_of(), length, "public");
         let register = match T::CELL {
             CellType::Element => self.get_public_memory(size_of),
             CellType::U16 => {
@@ -4,6 +4,7 @@ pub mod range_check;
 pub mod shared_memory;
 
 use core::cmp::Ordering;
+use std::collections::{HashMap, HashSet};
 
 use self::shared_memory::SharedMemory;
 use super::arithmetic::expression::ArithmeticExpression;
@@ -12,8 +13,10 @@ use super::instruction::clock::ClockInstruction;
 use super::instruction::set::AirInstruction;
 use super::memory::pointer::accumulate::PointerAccumulator;
 use super::register::array::ArrayRegister;
+use super::register::cell::CellType;
 use super::register::cubic::CubicRegister;
 use super::register::element::ElementRegister;
+use super::register::memory::MemorySlice;
 use super::register::Register;
 use super::table::accumulator::Accumulator;
 use super::table::bus::channel::BusChannel;
@@ -21,17 +24,47 @@ use super::table::bus::global::Bus;
 use super::table::lookup::table::LookupTable;
 use super::table::lookup::values::LookupValues;
 use super::table::powers::Powers;
+use super::table::rlc::RandomLinearCombination;
 use super::trace::data::AirTraceData;
 use super::{AirParameters, Chip};
 use crate::chip::register::RegisterSerializable;
 
+/// The number of columns of each kind allocated by an [`AirBuilder`], compared to the budgets
+/// declared by its [`AirParameters`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ColumnReport {
+    pub arithmetic_used: usize,
+    pub arithmetic_budget: usize,
+    pub free_used: usize,
+    pub free_budget: usize,
+    pub extended_used: usize,
+    pub extended_budget: usize,
+}
+
+impl ColumnReport {
+    pub fn arithmetic_unused(&self) -> usize {
+        self.arithmetic_budget.saturating_sub(self.arithmetic_used)
+    }
+
+    pub fn free_unused(&self) -> usize {
+        self.free_budget.saturating_sub(self.free_used)
+    }
+
+    pub fn extended_unused(&self) -> usize {
+        self.extended_budget.saturating_sub(self.extended_used)
+    }
+}
+
 #[derive(Debug, Clone)]
 #[allow(clippy::type_complexity)]
 pub struct AirBuilder<L: AirParameters> {
-    local_index: usize,
+    pub(crate) local_index: usize,
     local_arithmetic_index: usize,
-    extended_index: usize,
+    pub(crate) extended_index: usize,
+    strict: bool,
     pub(crate) internal_range_check: bool,
+    unchecked_arithmetic_columns: HashSet<usize>,
+    pub(crate) local_allocations: Vec<MemorySlice>,
     pub(crate) shared_memory: SharedMemory,
     pub(crate) global_arithmetic: Vec<ElementRegister>,
     pub(crate) instructions: Vec<AirInstruction<L::Field, L::Instruction>>,
@@ -40,12 +73,15 @@ pub struct AirBuilder<L: AirParameters> {
     pub(crate) global_constraints: Vec<Constraint<L>>,
     pub(crate) powers: Vec<Powers<L::Field, L::CubicParams>>,
     pub(crate) accumulators: Vec<Accumulator<L::Field, L::CubicParams>>,
+    pub(crate) random_linear_combinations: Vec<RandomLinearCombination<L::Field, L::CubicParams>>,
     pub(crate) pointer_row_accumulators: Vec<PointerAccumulator<L::Field, L::CubicParams>>,
     pub(crate) pointer_global_accumulators: Vec<PointerAccumulator<L::Field, L::CubicParams>>,
     pub(crate) bus_channels: Vec<BusChannel<CubicRegister, L::CubicParams>>,
     pub(crate) buses: Vec<Bus<CubicRegister, L::CubicParams>>,
     pub(crate) lookup_values: Vec<LookupValues<L::Field, L::CubicParams>>,
     pub(crate) lookup_tables: Vec<LookupTable<L::Field, L::CubicParams>>,
+    pub(crate) register_docs: Vec<(MemorySlice, String)>,
+    constants: HashMap<(CellType, Vec<L::Field>), MemorySlice>,
     range_data: Option<(
         LookupTable<L::Field, L::CubicParams>,
         LookupValues<L::Field, L::CubicParams>,
@@ -66,38 +102,82 @@ impl<L: AirParameters> AirBuilder<L> {
             local_index: L::NUM_ARITHMETIC_COLUMNS,
             local_arithmetic_index: 0,
             extended_index: L::NUM_ARITHMETIC_COLUMNS + L::NUM_FREE_COLUMNS,
+            strict: false,
             global_arithmetic: Vec::new(),
             shared_memory,
             internal_range_check: true,
+            unchecked_arithmetic_columns: HashSet::new(),
+            local_allocations: Vec::new(),
             instructions: Vec::new(),
             global_instructions: Vec::new(),
             constraints: Vec::new(),
             global_constraints: Vec::new(),
             powers: Vec::new(),
             accumulators: Vec::new(),
+            random_linear_combinations: Vec::new(),
             pointer_row_accumulators: Vec::new(),
             pointer_global_accumulators: Vec::new(),
             bus_channels: Vec::new(),
             buses: Vec::new(),
             lookup_values: Vec::new(),
             lookup_tables: Vec::new(),
+            register_docs: Vec::new(),
+            constants: HashMap::new(),
             range_data: None,
         }
     }
 
+    /// Sets whether the builder is strict about the budget of extended columns and about
+    /// unconstrained registers.
+    ///
+    /// In strict mode:
+    /// - an allocation exceeding `L::EXTENDED_COLUMNS` fails right away instead of when the chip
+    ///   is built, pointing to the gadget that allocated the column. [`Self::try_alloc_extended`]
+    ///   returns an error, and the infallible allocations panic with it.
+    /// - building the chip panics if some allocated registers appear in no constraint, instead of
+    ///   logging a warning. See [`Self::unconstrained_registers`].
+    pub fn set_strict(&mut self, strict: bool) {
+        self.strict = strict;
+    }
+
+    /// Returns a public register holding the constant `value`.
+    ///
+    /// Constants are interned, so that calling this method repeatedly with the same value and
+    /// cell type returns the same register instead of allocating a new one.
     pub fn constant<T: Register>(&mut self, value: &T::Value<L::Field>) -> T {
+        let values = T::align(value).to_vec();
+        if let Some(register) = self.constants.get(&(T::CELL, values.clone())) {
+            return T::from_register(*register);
+        }
         let register = self.alloc_public::<T>();
         self.set_to_expression_public(
             &register,
-            ArithmeticExpression::from_constant_vec(T::align(value).to_vec()),
+            ArithmeticExpression::from_constant_vec(values.clone()),
         );
+        self.constants
+            .insert((T::CELL, values), *register.register());
         register
     }
 
-    pub(crate) fn constant_array<T: Register>(
+    /// Allocates an array of public registers bound to the constant `values`.
+    ///
+    /// The values appear in the public inputs, and a global constraint asserts that they are equal
+    /// to the constants, so that a verifier can rely on them, e.g. for the initial state of a hash.
+    /// As in [`Self::constant`], arrays of the same values are interned.
+    pub fn constant_array<T: Register>(
         &mut self,
         values: &[T::Value<L::Field>],
     ) -> ArrayRegister<T> {
+        let key = (
+            T::CELL,
+            values
+                .iter()
+                .flat_map(|value| T::align(value).to_vec())
+                .collect::<Vec<_>>(),
+        );
+        if let Some(register) = self.constants.get(&key) {
+            return ArrayRegister::from_register_unsafe(*register);
+        }
         let array = self.alloc_array_public::<T>(values.len());
 
         for (register, value) in array.iter().zip(values.iter()) {
@@ -106,6 +186,7 @@ impl<L: AirParameters> AirBuilder<L> {
                 ArithmeticExpression::from_constant_vec(T::align(value).to_vec()),
             );
         }
+        self.constants.insert(key, *array.register());
 
         array
     }
@@ -123,6 +204,21 @@ impl<L: AirParameters> AirBuilder<L> {
         }
     }
 
+    /// Prints out a log message (using the log::debug! macro) with the value of the expression.
+    ///
+    /// The expression is evaluated on every row during trace generation, or once if it only
+    /// depends on public and global values, so that intermediate values can be inspected without
+    /// allocating a register for them.
+    pub fn watch_expression(&mut self, expression: ArithmeticExpression<L::Field>, name: &str) {
+        let is_trace = expression.is_trace();
+        let instruction = AirInstruction::WatchExpression(name.to_string(), expression);
+        if is_trace {
+            self.register_air_instruction_internal(instruction);
+        } else {
+            self.register_global_air_instruction_internal(instruction);
+        }
+    }
+
     /// Registers an custom instruction with the builder.
     pub fn register_instruction<I>(&mut self, instruction: I)
     where
@@ -201,7 +297,14 @@ impl<L: AirParameters> AirBuilder<L> {
         clk
     }
 
-    pub fn build(mut self) -> (Chip<L>, AirTraceData<L>) {
+    pub fn build(self) -> (Chip<L>, AirTraceData<L>) {
+        let (chip, trace_data, _) = self.finalize();
+        (chip, trace_data)
+    }
+
+    /// Builds the chip like [`Self::build`], and also returns the number of columns of each kind
+    /// used by the chip, so that the column constants of `L` can be tuned to the minimum.
+    pub fn finalize(mut self) -> (Chip<L>, AirTraceData<L>, ColumnReport) {
         // Register all bus constraints.
         for i in 0..self.buses.len() {
             self.register_bus_constraint(i);
@@ -211,10 +314,33 @@ impl<L: AirParameters> AirBuilder<L> {
             self.constraints.push(channel.clone().into());
         }
 
+        // Check for registers that are not constrained, before the range checks count as a use
+        // of the arithmetic columns.
+        let unconstrained_registers = self.unconstrained_registers();
+        if !unconstrained_registers.is_empty() {
+            let registers = unconstrained_registers
+                .iter()
+                .map(
+                    |register| match self.register_docs.iter().find(|(r, _)| r == register) {
+                        Some((_, doc)) => format!("{:?} ({})", register, doc),
+                        None => format!("{:?}", register),
+                    },
+                )
+                .collect::<Vec<_>>()
+                .join(", ");
+            let message = format!(
+                "{} allocated registers appear in no constraint: {}",
+                unconstrained_registers.len(),
+                registers
+            );
+            if self.strict {
+                panic!("{}", message);
+            }
+            log::warn!("{}", message);
+        }
+
         // Add the range checks
-        if (L::NUM_ARITHMETIC_COLUMNS > 0 || !self.global_arithmetic.is_empty())
-            && self.internal_range_check
-        {
+        if self.internal_range_check && !self.range_checked_arithmetic().is_empty() {
             self.arithmetic_range_checks();
         }
 
@@ -228,8 +354,8 @@ impl<L: AirParameters> AirBuilder<L> {
                 L::NUM_FREE_COLUMNS
             ),
             Ordering::Less => {
-                println!(
-                    "Warning: {} free columns unused",
+                log::warn!(
+                    "{} free columns unused",
                     L::NUM_FREE_COLUMNS - num_free_columns
                 );
             }
@@ -245,8 +371,8 @@ impl<L: AirParameters> AirBuilder<L> {
                 L::NUM_ARITHMETIC_COLUMNS
             ),
             Ordering::Less => {
-                println!(
-                    "Warning: {} arithmetic columns unused",
+                log::warn!(
+                    "{} arithmetic columns unused",
                     L::NUM_ARITHMETIC_COLUMNS - num_arithmetic_columns
                 );
             }
@@ -263,19 +389,35 @@ impl<L: AirParameters> AirBuilder<L> {
                 L::EXTENDED_COLUMNS
             ),
             Ordering::Less => {
-                println!(
-                    "Warning: {} extended columns unused",
+                log::warn!(
+                    "{} extended columns unused",
                     L::EXTENDED_COLUMNS - num_extended_columns
                 );
             }
             Ordering::Equal => {}
         }
 
+        // The free and extended columns that were not allocated are not referenced by any
+        // constraint.
+        let unused_columns = (self.local_index..L::NUM_ARITHMETIC_COLUMNS + L::NUM_FREE_COLUMNS)
+            .chain(self.extended_index..L::num_columns())
+            .collect();
+
+        let report = ColumnReport {
+            arithmetic_used: num_arithmetic_columns,
+            arithmetic_budget: L::NUM_ARITHMETIC_COLUMNS,
+            free_used: num_free_columns,
+            free_budget: L::NUM_FREE_COLUMNS,
+            extended_used: num_extended_columns,
+            extended_budget: L::EXTENDED_COLUMNS,
+        };
+
         let execution_trace_length = self.local_index;
         (
             Chip {
                 constraints: self.constraints,
                 global_constraints: self.global_constraints,
+                unused_columns,
                 num_challenges: self.shared_memory.challenge_index(),
                 execution_trace_length,
                 num_public_values: self.shared_memory.public_index(),
@@ -290,6 +432,7 @@ impl<L: AirParameters> AirBuilder<L> {
                 global_instructions: self.global_instructions,
                 powers: self.powers,
                 accumulators: self.accumulators,
+                random_linear_combinations: self.random_linear_combinations,
                 pointer_row_accumulators: self.pointer_row_accumulators,
                 pointer_global_accumulators: self.pointer_global_accumulators,
                 bus_channels: self.bus_channels,
@@ -297,7 +440,10 @@ impl<L: AirParameters> AirBuilder<L> {
                 lookup_values: self.lookup_values,
                 lookup_tables: self.lookup_tables,
                 range_data: self.range_data,
+                register_docs: self.register_docs,
+                merged_range_data: Vec::new(),
             },
+            report,
         )
     }
 }
@@ -309,19 +455,27 @@ pub(crate) mod tests {
     pub use plonky2::field::goldilocks_field::GoldilocksField;
     use serde::{Deserialize, Serialize};
 
+    use super::memory::Padding;
     use super::*;
     use crate::air::fibonacci::FibonacciAir;
     pub use crate::air::parser::AirParser;
     pub use crate::air::RAir;
     pub use crate::chip::instruction::empty::EmptyInstruction;
+    use crate::chip::instruction::set::watch_message;
+    use crate::chip::register::bit::BitRegister;
     pub use crate::chip::register::u16::U16Register;
     pub use crate::chip::register::RegisterSerializable;
     pub use crate::chip::trace::generator::ArithmeticGenerator;
+    use crate::chip::trace::writer::data::AirWriterData;
+    use crate::chip::trace::writer::AirWriter;
     pub use crate::math::goldilocks::cubic::GoldilocksCubicParameters;
     use crate::math::prelude::*;
     pub use crate::maybe_rayon::*;
+    use crate::plonky2::stark::config::CurtaPoseidonGoldilocksConfig;
     pub use crate::plonky2::stark::config::PoseidonGoldilocksStarkConfig;
+    use crate::plonky2::stark::prover::StarkyProver;
     pub(crate) use crate::plonky2::stark::tests::{test_recursive_starky, test_starky};
+    use crate::plonky2::stark::verifier::StarkyVerifier;
     pub use crate::plonky2::stark::Starky;
     pub use crate::trace::window_parser::TraceWindowParser;
 
@@ -352,8 +506,7 @@ pub(crate) mod tests {
         let constr_2 = builder.set_to_expression_transition(&x_1.next(), x_0.expr() + x_1.expr());
 
         let (mut air, mut air_data) = builder.build();
-        air.num_public_values = 3;
-        air_data.num_public_inputs = 3;
+        air.set_public_values(&mut air_data, 3);
 
         let num_rows = 1 << 10;
         let public_inputs = [
@@ -408,8 +561,7 @@ pub(crate) mod tests {
         ];
 
         let (mut air, mut air_data) = builder.build();
-        air.num_public_values = 3;
-        air_data.num_public_inputs = 3;
+        air.set_public_values(&mut air_data, 3);
 
         let generator = ArithmeticGenerator::<L>::new(air_data, num_rows);
 
@@ -431,6 +583,115 @@ pub(crate) mod tests {
         test_recursive_starky(stark, config, generator, &public_inputs);
     }
 
+    #[test]
+    fn test_builder_fibonacci_recurrence() {
+        type F = GoldilocksField;
+        type L = FibonacciParameters;
+        type SC = PoseidonGoldilocksStarkConfig;
+
+        // The two registers depend on each other, so the first one is allocated beforehand and
+        // constrained by hand, while the second one follows `x1' = x0 + x1` from `x1[0] = 1`.
+        let mut builder = AirBuilder::<L>::new();
+        let x_0 = builder.alloc::<ElementRegister>();
+        builder.set_to_expression_first_row(&x_0, ArithmeticExpression::zero());
+        let x_1 =
+            builder.alloc_recurrence::<ElementRegister>(F::ONE, |x_1| x_0.expr() + x_1.expr());
+        builder.set_to_expression_transition(&x_0.next(), x_1.expr());
+
+        let num_rows = 1 << 10;
+        let (air, trace_data) = builder.build();
+        let generator = ArithmeticGenerator::<L>::new(trace_data, num_rows);
+
+        let writer = generator.new_writer();
+        for i in 0..num_rows {
+            writer.write_row_instructions(&generator.air_data, i);
+        }
+        assert_eq!(
+            writer.read(&x_1, num_rows - 1),
+            FibonacciAir::fibonacci(num_rows - 1, F::ZERO, F::ONE)
+        );
+
+        let stark = Starky::new(air);
+        let config = SC::standard_fast_config(num_rows);
+
+        // Generate proof and verify as a stark
+        test_starky(&stark, &config, &generator, &[]);
+
+        // Test the recursive proof.
+        test_recursive_starky(stark, config, generator, &[]);
+    }
+
+    #[test]
+    fn test_builder_fibonacci_public_inputs() {
+        type F = GoldilocksField;
+        type L = FibonacciParameters;
+        type C = CurtaPoseidonGoldilocksConfig;
+        type SC = PoseidonGoldilocksStarkConfig;
+
+        let mut builder = AirBuilder::<L>::new();
+        let x_0 = builder.alloc::<ElementRegister>();
+        let x_1 = builder.alloc::<ElementRegister>();
+        let public = builder.alloc_array_public::<ElementRegister>(3);
+
+        // The trace starts at the initial values given by the public inputs.
+        builder.assert_equal_first_row(&x_0, &public.get(0));
+        builder.assert_equal_first_row(&x_1, &public.get(1));
+        // x0' <- x1
+        builder.set_to_expression_transition(&x_0.next(), x_1.expr());
+        // x1' <- x0 + x1
+        builder.set_to_expression_transition(&x_1.next(), x_0.expr() + x_1.expr());
+        // The last value is the output given by the public inputs.
+        builder.assert_expressions_equal_last_row(x_1.expr(), public.get(2).expr());
+
+        let num_rows = 1 << 10;
+        let (air, trace_data) = builder.build();
+        let generator = ArithmeticGenerator::<L>::new(trace_data, num_rows);
+
+        let public_inputs = [
+            F::ZERO,
+            F::ONE,
+            FibonacciAir::fibonacci(num_rows - 1, F::ZERO, F::ONE),
+        ];
+        let writer = generator.new_writer();
+        writer.write_array(&public, public_inputs, 0);
+        writer.write(&x_0, &F::ZERO, 0);
+        writer.write(&x_1, &F::ONE, 0);
+        for i in 0..num_rows {
+            writer.write_row_instructions(&generator.air_data, i);
+        }
+
+        let stark = Starky::new(air);
+        let config = SC::standard_fast_config(num_rows);
+
+        // Generate proof and verify as a stark
+        test_starky(&stark, &config, &generator, &public_inputs);
+
+        // A trace starting at other values is rejected.
+        let other_inputs = [F::ONE, F::ONE, public_inputs[2]];
+        let proof =
+            StarkyProver::<F, C, 2>::prove(&config, &stark, &generator, &other_inputs).unwrap();
+        assert!(StarkyVerifier::verify(&config, &stark, proof, &other_inputs).is_err());
+
+        // Test the recursive proof.
+        test_recursive_starky(stark, config, generator, &public_inputs);
+    }
+
+    #[test]
+    #[should_panic(expected = "Mismatched public value counts")]
+    fn test_builder_public_values_mismatch() {
+        type L = FibonacciParameters;
+
+        let mut builder = AirBuilder::<L>::new();
+        let x_0 = builder.alloc::<ElementRegister>();
+        let x_1 = builder.alloc::<ElementRegister>();
+        builder.set_to_expression_transition(&x_0.next(), x_1.expr());
+        builder.set_to_expression_transition(&x_1.next(), x_0.expr() + x_1.expr());
+
+        let (mut air, mut air_data) = builder.build();
+        air_data.num_public_inputs = 2;
+        air.set_public_values(&mut air_data, 3);
+    }
+
     #[derive(Debug, Clone, Serialize, Deserialize)]
     pub struct SimpleTestParameters;
 
@@ -496,6 +757,354 @@ pub(crate) mod tests {
         test_recursive_starky(stark, config, generator, &public_inputs);
     }
 
+    #[derive(Debug, Clone, Serialize, Deserialize)]
+    pub struct NoRangeCheckTestParameters;
+
+    impl AirParameters for NoRangeCheckTestParameters {
+        type Field = GoldilocksField;
+        type CubicParams = GoldilocksCubicParameters;
+        type Instruction = EmptyInstruction<GoldilocksField>;
+        const NUM_ARITHMETIC_COLUMNS: usize = 3;
+        const NUM_FREE_COLUMNS: usize = 3;
+        const EXTENDED_COLUMNS: usize = 12;
+    }
+
+    #[test]
+    fn test_builder_no_range_check() {
+        type F = GoldilocksField;
+        type L = NoRangeCheckTestParameters;
+        type SC = PoseidonGoldilocksStarkConfig;
+
+        let mut builder = AirBuilder::<L>::new();
+        let x_0 = builder.alloc::<U16Register>();
+        let x_1 = builder.alloc::<U16Register>();
+        // `x_ext` is checked externally by being equal to the clock, which is bounded by the
+        // number of rows.
+        let x_ext = builder.alloc_no_range_check::<U16Register>();
+
+        let clk = builder.clock();
+        builder.assert_expressions_equal(clk.expr(), x_ext.expr());
+
+        assert_eq!(builder.range_checked_arithmetic().len(), 2);
+
+        let (air, trace_data) = builder.build();
+        let num_rows = 1 << 16;
+        let generator = ArithmeticGenerator::<L>::new(trace_data, num_rows);
+
+        let writer = generator.new_writer();
+        for i in 0..num_rows {
+            writer.write(&x_0, &F::from_canonical_usize(i % 7), i);
+            writer.write(&x_1, &F::from_canonical_usize(23), i);
+            writer.write(&x_ext, &F::from_canonical_usize(i), i);
+            writer.write_row_instructions(&generator.air_data, i);
+        }
+        writer.write_global_instructions(&generator.air_data);
+        let stark = Starky::new(air);
+        let config = SC::standard_fast_config(num_rows);
+
+        let public_inputs = writer.public().unwrap().clone();
+
+        // Generate proof and verify as a stark
+        test_starky(&stark, &config, &generator, &public_inputs);
+    }
+
+    #[derive(Debug, Clone, Serialize, Deserialize)]
+    pub struct BitFromExprTestParameters;
+
+    impl AirParameters for BitFromExprTestParameters {
+        type Field = GoldilocksField;
+        type CubicParams = GoldilocksCubicParameters;
+        type Instruction = EmptyInstruction<GoldilocksField>;
+        const NUM_ARITHMETIC_COLUMNS: usize = 0;
+        const NUM_FREE_COLUMNS: usize = 3;
+        const EXTENDED_COLUMNS: usize = 0;
+    }
+
+    #[test]
+    fn test_builder_bit_from_expr() {
+        type F = GoldilocksField;
+        type L = BitFromExprTestParameters;
+        type SC = PoseidonGoldilocksStarkConfig;
+
+        let mut builder = AirBuilder::<L>::new();
+        let a = builder.alloc::<BitRegister>();
+        let b = builder.alloc::<BitRegister>();
+
+        // Constrain `b => a`, so that `a - b` is boolean.
+        builder.assert_expression_zero(b.expr() * a.not_expr());
+        let a_minus_b = builder.bit_from_expr(a.expr() - b.expr());
+
+        let (air, trace_data) = builder.build();
+        let num_rows = 1 << 10;
+        let generator = ArithmeticGenerator::<L>::new(trace_data, num_rows);
+
+        let writer = generator.new_writer();
+        for i in 0..num_rows {
+            let (a_val, b_val) = [(0, 0), (1, 0), (1, 1)][i % 3];
+            writer.write(&a, &F::from_canonical_u8(a_val), i);
+            writer.write(&b, &F::from_canonical_u8(b_val), i);
+            writer.write_row_instructions(&generator.air_data, i);
+            assert_eq!(
+                writer.read(&a_minus_b, i),
+                F::from_canonical_u8(a_val - b_val)
+            );
+        }
+        writer.write_global_instructions(&generator.air_data);
+        let stark = Starky::new(air);
+        let config = SC::standard_fast_config(num_rows);
+
+        let public_inputs = writer.public().unwrap().clone();
+
+        // Generate proof and verify as a stark
+        test_starky(&stark, &config, &generator, &public_inputs);
+    }
+
+    #[derive(Debug, Clone, Serialize, Deserialize)]
+    pub struct InSetTestParameters;
+
+    impl AirParameters for InSetTestParameters {
+        type Field = GoldilocksField;
+        type CubicParams = GoldilocksCubicParameters;
+        type Instruction = EmptyInstruction<GoldilocksField>;
+        const NUM_ARITHMETIC_COLUMNS: usize = 0;
+        const NUM_FREE_COLUMNS: usize = 1;
+        const EXTENDED_COLUMNS: usize = 0;
+    }
+
+    fn prove_in_set(values: impl Fn(usize) -> u32) {
+        type F = GoldilocksField;
+        type L = InSetTestParameters;
+        type SC = PoseidonGoldilocksStarkConfig;
+
+        let mut builder = AirBuilder::<L>::new();
+        let opcode = builder.alloc::<ElementRegister>();
+        builder.assert_expression_in_set(opcode.expr(), &[F::ONE, F::TWO, F::from_canonical_u8(3)]);
+
+        let (air, trace_data) = builder.build();
+        let num_rows = 1 << 10;
+        let generator = ArithmeticGenerator::<L>::new(trace_data, num_rows);
+
+        let writer = generator.new_writer();
+        for i in 0..num_rows {
+            writer.write(&opcode, &F::from_canonical_u32(values(i)), i);
+        }
+        let stark = Starky::new(air);
+        let config = SC::standard_fast_config(num_rows);
+
+        // Generate proof and verify as a stark
+        test_starky(&stark, &config, &generator, &[]);
+    }
+
+    #[test]
+    fn test_builder_expression_in_set() {
+        prove_in_set(|i| 1 + (i % 3) as u32);
+    }
+
+    #[test]
+    #[should_panic(expected = "the vanishing polynomial is not divisible by Z_H")]
+    fn test_builder_expression_not_in_set() {
+        prove_in_set(|i| if i == 5 { 4 } else { 1 + (i % 3) as u32 });
+    }
+
+    #[test]
+    #[should_panic(expected = "set membership constraint has degree 4")]
+    fn test_builder_expression_in_set_degree_too_high() {
+        type F = GoldilocksField;
+        type L = InSetTestParameters;
+
+        let mut builder = AirBuilder::<L>::new();
+        let opcode = builder.alloc::<ElementRegister>();
+        let set = [1, 2, 3, 4].map(F::from_canonical_u8);
+        builder.assert_expression_in_set(opcode.expr(), &set);
+    }
+
+    #[derive(Debug, Clone, Serialize, Deserialize)]
+    pub struct ExprEqTestParameters;
+
+    impl AirParameters for ExprEqTestParameters {
+        type Field = GoldilocksField;
+        type CubicParams = GoldilocksCubicParameters;
+        type Instruction = EmptyInstruction<GoldilocksField>;
+        const NUM_ARITHMETIC_COLUMNS: usize = 0;
+        const NUM_FREE_COLUMNS: usize = 6;
+        const EXTENDED_COLUMNS: usize = 0;
+    }
+
+    #[test]
+    fn test_builder_assert_expr_eq() {
+        type F = GoldilocksField;
+        type L = ExprEqTestParameters;
+        type SC = PoseidonGoldilocksStarkConfig;
+
+        let mut builder = AirBuilder::<L>::new();
+        let a = builder.alloc_array::<ElementRegister>(2);
+        let b = builder.alloc_array::<ElementRegister>(2);
+        let c = builder.alloc_array::<ElementRegister>(2);
+
+        // c = a + b and 2c = (a + b) + (b + a), element-wise.
+        builder.assert_expr_eq(c.expr(), a.expr() + b.expr());
+        builder.assert_expr_eq(
+            c.expr() + c.expr(),
+            (a.expr() + b.expr()) + (b.expr() + a.expr()),
+        );
+
+        let (air, trace_data) = builder.build();
+        let num_rows = 1 << 10;
+        let generator = ArithmeticGenerator::<L>::new(trace_data, num_rows);
+
+        let writer = generator.new_writer();
+        for i in 0..num_rows {
+            let a_val = [F::from_canonical_usize(i), F::from_canonical_usize(3 * i)];
+            let b_val = [
+                F::from_canonical_usize(i + 7),
+                F::from_canonical_usize(i * i),
+            ];
+            let c_val = [a_val[0] + b_val[0], a_val[1] + b_val[1]];
+            writer.write_array(&a, a_val, i);
+            writer.write_array(&b, b_val, i);
+            writer.write_array(&c, c_val, i);
+        }
+        let stark = Starky::new(air);
+        let config = SC::standard_fast_config(num_rows);
+
+        // Generate proof and verify as a stark
+        test_starky(&stark, &config, &generator, &[]);
+    }
+
+    #[test]
+    #[should_panic(expected = "Cannot assert the equality of expressions of sizes 2 and 1")]
+    fn test_builder_assert_expr_eq_size_mismatch() {
+        let mut builder = AirBuilder::<ExprEqTestParameters>::new();
+        let a = builder.alloc_array::<ElementRegister>(2);
+        let b = builder.alloc::<ElementRegister>();
+        builder.assert_expr_eq(a.expr(), b.expr());
+    }
+
+    #[test]
+    fn test_builder_constant_interning() {
+        type F = GoldilocksField;
+        type L = ExprEqTestParameters;
+        type SC = PoseidonGoldilocksStarkConfig;
+
+        let mut builder = AirBuilder::<L>::new();
+        let five = builder.constant::<ElementRegister>(&F::from_canonical_u8(5));
+        let five_again = builder.constant::<ElementRegister>(&F::from_canonical_u8(5));
+        let six = builder.constant::<ElementRegister>(&F::from_canonical_u8(6));
+        assert_eq!(five.register(), five_again.register());
+        assert_ne!(five.register(), six.register());
+
+        // Constants of different cell types are not shared.
+        let one = builder.constant::<ElementRegister>(&F::ONE);
+        let one_bit = builder.constant::<BitRegister>(&F::ONE);
+        assert_ne!(one.register(), one_bit.register());
+
+        let values = [5, 6].map(F::from_canonical_u8);
+        let array = builder.constant_array::<ElementRegister>(&values);
+        let array_again = builder.constant_array::<ElementRegister>(&values);
+        assert_eq!(array.register(), array_again.register());
+
+        // x = 5 + 6 on every row, using the shared constants.
+        let x = builder.alloc::<ElementRegister>();
+        builder.assert_expr_eq(x.expr(), five.expr() + six.expr());
+        builder.assert_expr_eq(x.expr(), five_again.expr() + array.get(1).expr());
+
+        let (air, trace_data) = builder.build();
+        let num_rows = 1 << 5;
+        let generator = ArithmeticGenerator::<L>::new(trace_data, num_rows);
+
+        let writer = generator.new_writer();
+        writer.write_global_instructions(&generator.air_data);
+        for i in 0..num_rows {
+            writer.write(&x, &F::from_canonical_u8(11), i);
+        }
+        let stark = Starky::new(air);
+        let config = SC::standard_fast_config(num_rows);
+        let public = writer.public().unwrap().clone();
+
+        // The repeated constants share their public inputs.
+        assert_eq!(public.len(), 6);
+
+        // Generate proof and verify as a stark
+        test_starky(&stark, &config, &generator, &public);
+    }
+
+    #[derive(Debug, Clone, Serialize, Deserialize)]
+    pub struct RunningProductTestParameters;
+
+    impl AirParameters for RunningProductTestParameters {
+        type Field = GoldilocksField;
+        type CubicParams = GoldilocksCubicParameters;
+        type Instruction = EmptyInstruction<GoldilocksField>;
+        const NUM_ARITHMETIC_COLUMNS: usize = 0;
+        const NUM_FREE_COLUMNS: usize = 2;
+        const EXTENDED_COLUMNS: usize = 0;
+    }
+
+    #[test]
+    fn test_builder_running_product() {
+        type F = GoldilocksField;
+        type L = RunningProductTestParameters;
+        type SC = PoseidonGoldilocksStarkConfig;
+
+        let mut builder = AirBuilder::<L>::new();
+        let value = builder.alloc::<ElementRegister>();
+        let product = builder.alloc_running_product(&value);
+
+        let (air, trace_data) = builder.build();
+        let num_rows = 1 << 10;
+        let generator = ArithmeticGenerator::<L>::new(trace_data, num_rows);
+
+        let values = (0..num_rows)
+            .map(|i| F::from_canonical_usize(3 * i + 2))
+            .collect::<Vec<_>>();
+
+        let writer = generator.new_writer();
+        for (i, v) in values.iter().enumerate() {
+            writer.write(&value, v, i);
+        }
+        // The accumulator of the next row is written by the current row, so the rows are written
+        // sequentially after all the values.
+        let mut expected = F::ONE;
+        for (i, v) in values.iter().enumerate() {
+            writer.write_row_instructions(&generator.air_data, i);
+            expected *= *v;
+            assert_eq!(writer.read(&product, i), expected);
+        }
+
+        let stark = Starky::new(air);
+        let config = SC::standard_fast_config(num_rows);
+
+        // Generate proof and verify as a stark
+        test_starky(&stark, &config, &generator, &[]);
+
+        // Test the recursive proof.
+        test_recursive_starky(stark, config, generator, &[]);
+    }
+
+    #[test]
+    fn test_builder_constant_array() {
+        type F = GoldilocksField;
+        type L = FibonacciParameters;
+
+        let values = [3u32, 1, 4, 1, 5].map(F::from_canonical_u32);
+
+        let mut builder = AirBuilder::<L>::new();
+        let x_0 = builder.alloc::<ElementRegister>();
+        let x_1 = builder.alloc::<ElementRegister>();
+        builder.set_to_expression_transition(&x_0.next(), x_1.expr());
+        let constants = builder.constant_array::<ElementRegister>(&values);
+        let (_, trace_data) = builder.build();
+
+        assert_eq!(trace_data.num_public_inputs, values.len());
+
+        let mut writer_data = AirWriterData::new(&trace_data, 1);
+        let mut writer = writer_data.public_writer();
+        trace_data.write_global_instructions(&mut writer);
+
+        assert_eq!(writer.read_vec(&constants), values.to_vec());
+        assert_eq!(writer_data.public, values.to_vec());
+    }
+
     #[test]
     fn test_builder_public_range_check() {
         type F = GoldilocksField;
@@ -532,4 +1141,258 @@ pub(crate) mod tests {
         // Test the recursive proof.
         test_recursive_starky(stark, config, generator, &public_inputs);
     }
+
+    #[test]
+    fn test_builder_watch_expression() {
+        type F = GoldilocksField;
+        type L = FibonacciParameters;
+
+        let _ = env_logger::builder().is_test(true).try_init();
+
+        let mut builder = AirBuilder::<L>::new();
+        let x_0 = builder.alloc::<ElementRegister>();
+        let x_1 = builder.alloc::<ElementRegister>();
+        builder.set_to_expression_transition(&x_0.next(), x_1.expr());
+        builder.set_to_expression_transition(&x_1.next(), x_0.expr() + x_1.expr());
+        builder.watch_expression(x_0.expr() + x_1.expr(), "x_0 + x_1");
+
+        let (_, air_data) = builder.build();
+        let Some(AirInstruction::WatchExpression(name, expression)) = air_data.instructions.last()
+        else {
+            panic!("the watch instruction was not registered");
+        };
+        let (name, expression) = (name.clone(), expression.clone());
+
+        let num_rows = 1 << 5;
+        let generator = ArithmeticGenerator::<L>::new(air_data, num_rows);
+        let writer = generator.new_writer();
+
+        writer.write(&x_0, &F::ZERO, 0);
+        writer.write(&x_1, &F::ONE, 0);
+        for i in 0..num_rows {
+            writer.write_row_instructions(&generator.air_data, i);
+        }
+
+        for i in 0..num_rows {
+            let value = writer.read_expression(&expression, i);
+            let expected = FibonacciAir::fibonacci(i + 1, F::ZERO, F::ONE);
+            assert_eq!(value, vec![expected]);
+            assert_eq!(
+                watch_message(&name, Some(i), &value),
+                format!("row {}: , x_0 + x_1: [{:?}]", i, expected)
+            );
+        }
+    }
+
+    #[test]
+    fn test_builder_alloc_array_bounded() {
+        let mut builder = AirBuilder::<FibonacciParameters>::new();
+        let array = builder.alloc_array_bounded::<ElementRegister>(2, 2);
+        assert_eq!(array.len(), 2);
+    }
+
+    #[test]
+    #[cfg(debug_assertions)]
+    #[should_panic(expected = "array length 3 exceeds the maximum of 2")]
+    fn test_builder_alloc_array_bounded_exceeds_max() {
+        let mut builder = AirBuilder::<FibonacciParameters>::new();
+        builder.alloc_array_bounded::<ElementRegister>(3, 2);
+    }
+
+    #[derive(Debug, Clone, Serialize, Deserialize)]
+    pub struct PaddedArrayTestParameters;
+
+    impl AirParameters for PaddedArrayTestParameters {
+        type Field = GoldilocksField;
+        type CubicParams = GoldilocksCubicParameters;
+        type Instruction = EmptyInstruction<GoldilocksField>;
+        const NUM_ARITHMETIC_COLUMNS: usize = 0;
+        const NUM_FREE_COLUMNS: usize = 4;
+        const EXTENDED_COLUMNS: usize = 0;
+    }
+
+    #[test]
+    fn test_builder_alloc_array_padded() {
+        type F = GoldilocksField;
+        type L = PaddedArrayTestParameters;
+        type SC = PoseidonGoldilocksStarkConfig;
+
+        let mut builder = AirBuilder::<L>::new();
+        let active = builder.alloc::<BitRegister>();
+        let values = builder.alloc_array_padded::<ElementRegister>(1, &active, Padding::Zero);
+        let counter =
+            builder.alloc_array_padded::<ElementRegister>(1, &active, Padding::CopyForward);
+        let (value, counter) = (values.get(0), counter.get(0));
+
+        // A running sum of the values, which is unchanged on the padding rows.
+        let sum = builder.alloc::<ElementRegister>();
+        builder.set_to_expression_first_row(&sum, value.expr());
+        builder.set_to_expression_transition(&sum.next(), sum.expr() + value.next().expr());
+
+        // The counter increases by at most one on each row, so it must not drop to zero.
+        let step = counter.next().expr() - counter.expr();
+        builder.assert_expression_zero_transition(step.clone() * (step - F::ONE));
+
+        let (air, trace_data) = builder.build();
+        let num_rows = 1 << 10;
+        let num_active_rows = 600;
+        let generator = ArithmeticGenerator::<L>::new(trace_data, num_rows);
+
+        let writer = generator.new_writer();
+        for i in 0..num_rows {
+            writer.write(&active, &F::from_bool(i < num_active_rows), i);
+        }
+        for i in 0..num_active_rows {
+            writer.write(&value, &F::from_canonical_usize(i), i);
+            writer.write(&counter, &F::from_canonical_usize(i), i);
+        }
+        for i in 0..num_rows {
+            writer.write_row_instructions(&generator.air_data, i);
+        }
+
+        let expected_sum = F::from_canonical_usize(num_active_rows * (num_active_rows - 1) / 2);
+        let last_counter = F::from_canonical_usize(num_active_rows - 1);
+        for i in num_active_rows..num_rows {
+            assert_eq!(writer.read(&value, i), F::ZERO);
+            assert_eq!(writer.read(&counter, i), last_counter);
+            assert_eq!(writer.read(&sum, i), expected_sum);
+        }
+
+        let stark = Starky::new(air);
+        let config = SC::standard_fast_config(num_rows);
+
+        // Generate proof and verify as a stark
+        test_starky(&stark, &config, &generator, &[]);
+    }
+
+    #[test]
+    fn test_builder_alloc_dyn() {
+        type F = GoldilocksField;
+        type L = FibonacciParameters;
+        type SC = PoseidonGoldilocksStarkConfig;
+
+        let mut builder = AirBuilder::<L>::new();
+        let x = ArrayRegister::<ElementRegister>::from_register_unsafe(builder.alloc_dyn(2));
+        assert_eq!(x.len(), 2);
+        let (x_0, x_1) = (x.get(0), x.get(1));
+
+        builder.set_to_expression_transition(&x_0.next(), x_1.expr());
+        builder.set_to_expression_transition(&x_1.next(), x_0.expr() + x_1.expr());
+
+        let (air, trace_data) = builder.build();
+        let num_rows = 1 << 10;
+        let generator = ArithmeticGenerator::<L>::new(trace_data, num_rows);
+        let writer = generator.new_writer();
+        writer.write(&x_0, &F::ZERO, 0);
+        writer.write(&x_1, &F::ONE, 0);
+        for i in 0..num_rows {
+            writer.write_row_instructions(&generator.air_data, i);
+        }
+        assert_eq!(
+            writer.read(&x_1, num_rows - 1),
+            FibonacciAir::fibonacci(num_rows - 1, F::ZERO, F::ONE)
+        );
+
+        let stark = Starky::new(air);
+        let config = SC::standard_fast_config(num_rows);
+
+        // Generate proof and verify as a stark
+        test_starky(&stark, &config, &generator, &[]);
+    }
+
+    #[test]
+    #[should_panic(expected = "Not enough free columns")]
+    fn test_builder_alloc_dyn_exceeds_free_columns() {
+        let mut builder = AirBuilder::<FibonacciParameters>::new();
+        builder.alloc_dyn(3);
+        builder.build();
+    }
+
+    #[test]
+    #[should_panic(expected = "Allocation index overflow")]
+    fn test_builder_alloc_index_overflow() {
+        let mut builder = AirBuilder::<FibonacciParameters>::new();
+        builder.alloc::<ElementRegister>();
+        builder.alloc_dyn(usize::MAX);
+    }
+
+    #[test]
+    #[should_panic(expected = "Allocation index overflow")]
+    fn test_builder_alloc_array_size_overflow() {
+        let mut builder = AirBuilder::<FibonacciParameters>::new();
+        builder.alloc_array::<CubicRegister>(usize::MAX);
+    }
+
+    #[test]
+    #[should_panic(expected = "Allocation index overflow")]
+    fn test_builder_alloc_array_extended_size_overflow() {
+        let mut builder = AirBuilder::<FibonacciParameters>::new();
+        builder.alloc_array_extended::<CubicRegister>(usize::MAX);
+    }
+
+    #[test]
+    #[should_panic(expected = "Not enough extended columns")]
+    fn test_builder_strict_extended_columns() {
+        let mut builder = AirBuilder::<SimpleTestParameters>::new();
+        builder.set_strict(true);
+        // The first four cubic registers fill the 12 extended columns.
+        for _ in 0..4 {
+            builder.alloc_extended::<CubicRegister>();
+        }
+        builder.alloc_extended::<CubicRegister>();
+    }
+
+    #[test]
+    fn test_builder_strict_try_extended_columns() {
+        let mut builder = AirBuilder::<SimpleTestParameters>::new();
+        builder.set_strict(true);
+        for _ in 0..4 {
+            assert!(builder.try_alloc_extended::<CubicRegister>().is_ok());
+        }
+        let error = builder.try_alloc_extended::<CubicRegister>().unwrap_err();
+        assert!(error.to_string().contains("Not enough extended columns"));
+        assert!(builder
+            .try_alloc_array_extended::<ElementRegister>(1)
+            .is_err());
+
+        // Without strict mode, the budget is only checked when the chip is built.
+        builder.set_strict(false);
+        assert!(builder.try_alloc_extended::<CubicRegister>().is_ok());
+    }
+
+    #[derive(Debug, Clone, Serialize, Deserialize)]
+    struct ColumnReportParameters;
+
+    impl AirParameters for ColumnReportParameters {
+        type Field = GoldilocksField;
+        type CubicParams = GoldilocksCubicParameters;
+        type Instruction = EmptyInstruction<GoldilocksField>;
+        const NUM_ARITHMETIC_COLUMNS: usize = 4;
+        const NUM_FREE_COLUMNS: usize = 5;
+        const EXTENDED_COLUMNS: usize = 6;
+    }
+
+    #[test]
+    fn test_builder_column_report() {
+        let mut builder = AirBuilder::<ColumnReportParameters>::new();
+        builder.alloc_array_no_range_check::<U16Register>(3);
+        builder.alloc_array::<ElementRegister>(2);
+        builder.alloc_array_extended::<ElementRegister>(4);
+
+        let (_, _, report) = builder.finalize();
+        assert_eq!(
+            report,
+            ColumnReport {
+                arithmetic_used: 3,
+                arithmetic_budget: 4,
+                free_used: 2,
+                free_budget: 5,
+                extended_used: 4,
+                extended_budget: 6,
+            }
+        );
+        assert_eq!(report.arithmetic_unused(), 1);
+        assert_eq!(report.free_unused(), 3);
+        assert_eq!(report.extended_unused(), 2);
+    }
 }
@@ -9,6 +9,22 @@ use crate::chip::table::lookup::values::LookupValues;
 use crate::chip::AirParameters;
 
 impl<L: AirParameters> AirBuilder<L> {
+    /// Returns the arithmetic columns and the global arithmetic registers that are range checked
+    /// by the builder, skipping the columns allocated with `alloc_no_range_check`.
+    pub(crate) fn range_checked_arithmetic(&self) -> Vec<ElementRegister> {
+        ArrayRegister::<ElementRegister>::from_register_unsafe(MemorySlice::Local(
+            0,
+            L::NUM_ARITHMETIC_COLUMNS,
+        ))
+        .into_iter()
+        .filter(|register| match register.register() {
+            MemorySlice::Local(index, _) => !self.unchecked_arithmetic_columns.contains(index),
+            _ => unreachable!("Arithmetic columns are local"),
+        })
+        .chain(self.global_arithmetic.iter().copied())
+        .collect()
+    }
+
     pub(crate) fn arithmetic_range_checks(&mut self) {
         let table = self.alloc::<ElementRegister>();
 
@@ -19,13 +35,7 @@ impl<L: AirParameters> AirBuilder<L> {
         self.assert_expressions_equal_first_row(table.expr(), zero());
         self.assert_expressions_equal_transition(table.expr() + one(), table.next().expr());
 
-        let values = ArrayRegister::<ElementRegister>::from_register_unsafe(MemorySlice::Local(
-            0,
-            L::NUM_ARITHMETIC_COLUMNS,
-        ))
-        .into_iter()
-        .chain(self.global_arithmetic.iter().copied())
-        .collect::<Vec<_>>();
+        let values = self.range_checked_arithmetic();
 
         let multiplicities = self.alloc_array::<ElementRegister>(1);
         let mut table_data = self.new_lookup(&[table], &multiplicities);
@@ -1,6 +1,7 @@
 use alloc::sync::Arc;
 use std::sync::Mutex;
 
+use super::memory::allocation_end;
 use crate::chip::register::memory::MemorySlice;
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
@@ -42,7 +43,7 @@ impl SharedMemory {
     pub fn get_global_memory(&self, size: usize) -> MemorySlice {
         let mut core = self.0.lock().unwrap();
         let register = MemorySlice::Global(core.global_index, size);
-        core.global_index += size;
+        core.global_index = allocation_end(core.global_index, size, "global");
         register
     }
 
@@ -50,7 +51,7 @@ impl SharedMemory {
     pub fn get_public_memory(&self, size: usize) -> MemorySlice {
         let mut core = self.0.lock().unwrap();
         let register = MemorySlice::Public(core.public_index, size);
-        core.public_index += size;
+        core.public_index = allocation_end(core.public_index, size, "public");
         register
     }
 
@@ -58,7 +59,7 @@ impl SharedMemory {
     pub fn get_challenge_memory(&self, size: usize) -> MemorySlice {
         let mut core = self.0.lock().unwrap();
         let register = MemorySlice::Challenge(core.challenge_index, size);
-        core.challenge_index += size;
+        core.challenge_index = allocation_end(core.challenge_index, size, "challenge");
         register
     }
 }
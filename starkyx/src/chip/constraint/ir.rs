@@ -0,0 +1,579 @@
+//! A portable representation of the constraints of a chip.
+//!
+//! The types in this module only refer to registers by their memory location and to field
+//! elements by their canonical `u64` value, so a [`ConstraintIR`] can be serialized and consumed
+//! by tools that do not depend on the chip's Rust types, such as external auditors or verifiers.
+
+use core::marker::PhantomData;
+
+use serde::{Deserialize, Serialize};
+
+use super::Constraint;
+use crate::air::extension::cubic::CubicParser;
+use crate::air::parser::AirParser;
+use crate::air::AirConstraint;
+use crate::chip::arithmetic::expression::ArithmeticExpression;
+use crate::chip::arithmetic::expression_slice::ArithmeticExpressionSlice;
+use crate::chip::arithmetic::ArithmeticConstraint;
+use crate::chip::builder::AirBuilder;
+use crate::chip::instruction::assign::AssignType;
+use crate::chip::instruction::set::AirInstruction;
+use crate::chip::instruction::Instruction;
+use crate::chip::register::memory::MemorySlice;
+use crate::chip::AirParameters;
+use crate::math::prelude::*;
+use crate::polynomial::parser::PolynomialParser;
+
+/// The constraint system of a chip, as exported by [`AirBuilder::export_constraints`].
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ConstraintIR {
+    pub num_arithmetic_columns: usize,
+    pub num_free_columns: usize,
+    pub num_extended_columns: usize,
+    /// Constraints evaluated on the rows of the trace.
+    pub constraints: Vec<ConstraintNode>,
+    /// Constraints evaluated once, on the global values.
+    pub global_constraints: Vec<ConstraintNode>,
+    /// Descriptions of the documented registers.
+    pub registers: Vec<RegisterDoc>,
+}
+
+/// The kind of memory a register lives in.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum RegisterKind {
+    Local,
+    Next,
+    Public,
+    Global,
+    Challenge,
+}
+
+/// A contiguous range of `length` cells starting at `index` in the memory of kind `kind`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RegisterRef {
+    pub kind: RegisterKind,
+    pub index: usize,
+    pub length: usize,
+}
+
+/// The description of a register, attached with [`AirBuilder::document`].
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RegisterDoc {
+    pub register: RegisterRef,
+    pub doc: String,
+}
+
+/// The rows on which a constraint is enforced.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Boundary {
+    First,
+    Last,
+    Transition,
+    All,
+}
+
+/// An arithmetic expression over registers, with constants given by their canonical value.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ExpressionIR {
+    Input(RegisterRef),
+    Const(Vec<u64>),
+    Add(Box<ExpressionIR>, Box<ExpressionIR>),
+    Sub(Box<ExpressionIR>, Box<ExpressionIR>),
+    ConstMul(u64, Box<ExpressionIR>),
+    ScalarMul(Box<ExpressionIR>, Box<ExpressionIR>),
+    Mul(Box<ExpressionIR>, Box<ExpressionIR>),
+}
+
+/// A single constraint of the chip.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ConstraintNode {
+    /// The expression, of `size` elements, vanishes on the rows given by `boundary`.
+    Arithmetic {
+        boundary: Boundary,
+        size: usize,
+        expression: ExpressionIR,
+    },
+    /// The register `target` equals `source` on the rows given by `boundary`.
+    Assign {
+        boundary: Boundary,
+        target: RegisterRef,
+        source: ExpressionIR,
+    },
+    /// Every cell of the register is a bit.
+    Bit(RegisterRef),
+    /// The constraints of `constraint` multiplied by `filter`.
+    Filtered {
+        filter: ExpressionIR,
+        constraint: Box<ConstraintNode>,
+    },
+}
+
+impl From<&MemorySlice> for RegisterRef {
+    fn from(slice: &MemorySlice) -> Self {
+        let (kind, index, length) = match *slice {
+            MemorySlice::Local(index, length) => (RegisterKind::Local, index, length),
+            MemorySlice::Next(index, length) => (RegisterKind::Next, index, length),
+            MemorySlice::Public(index, length) => (RegisterKind::Public, index, length),
+            MemorySlice::Global(index, length) => (RegisterKind::Global, index, length),
+            MemorySlice::Challenge(index, length) => (RegisterKind::Challenge, index, length),
+        };
+        Self {
+            kind,
+            index,
+            length,
+        }
+    }
+}
+
+impl From<AssignType> for Boundary {
+    fn from(kind: AssignType) -> Self {
+        match kind {
+            AssignType::First => Boundary::First,
+            AssignType::Last => Boundary::Last,
+            AssignType::Transition => Boundary::Transition,
+            AssignType::All => Boundary::All,
+        }
+    }
+}
+
+impl ExpressionIR {
+    fn from_slice<F: PrimeField64>(slice: &ArithmeticExpressionSlice<F>) -> Self {
+        let boxed = |slice: &ArithmeticExpressionSlice<F>| Box::new(Self::from_slice(slice));
+        match slice {
+            ArithmeticExpressionSlice::Input(input) => ExpressionIR::Input(input.into()),
+            ArithmeticExpressionSlice::Const(values) => {
+                ExpressionIR::Const(values.iter().map(|v| v.as_canonical_u64()).collect())
+            }
+            ArithmeticExpressionSlice::Add(a, b) => ExpressionIR::Add(boxed(a), boxed(b)),
+            ArithmeticExpressionSlice::Sub(a, b) => ExpressionIR::Sub(boxed(a), boxed(b)),
+            ArithmeticExpressionSlice::ConstMul(c, a) => {
+                ExpressionIR::ConstMul(c.as_canonical_u64(), boxed(a))
+            }
+            ArithmeticExpressionSlice::ScalarMul(a, b) => {
+                ExpressionIR::ScalarMul(boxed(a), boxed(b))
+            }
+            ArithmeticExpressionSlice::Mul(a, b) => ExpressionIR::Mul(boxed(a), boxed(b)),
+        }
+    }
+}
+
+impl<F: PrimeField64> From<&ArithmeticExpression<F>> for ExpressionIR {
+    fn from(expression: &ArithmeticExpression<F>) -> Self {
+        Self::from_slice(&expression.expression)
+    }
+}
+
+/// A node of the expression graph built by an [`IRParser`].
+#[derive(Debug, Clone, Copy)]
+enum Node {
+    Input(RegisterRef),
+    Const(u64),
+    Add(usize, usize),
+    Sub(usize, usize),
+    Neg(usize),
+    Mul(usize, usize),
+}
+
+/// A parser that records the constraints evaluated on it as [`ConstraintNode::Arithmetic`]
+/// nodes, each of size one.
+///
+/// The variables of the parser are the indices of the nodes of an expression graph, which are
+/// expanded into an [`ExpressionIR`] when a constraint is recorded.
+#[derive(Debug)]
+pub struct IRParser<F> {
+    nodes: Vec<Node>,
+    local: Vec<usize>,
+    next: Vec<usize>,
+    challenges: Vec<usize>,
+    global: Vec<usize>,
+    public: Vec<usize>,
+    constraints: Vec<ConstraintNode>,
+    _marker: PhantomData<F>,
+}
+
+impl<F: PrimeField64> IRParser<F> {
+    fn new(
+        num_columns: usize,
+        num_challenges: usize,
+        num_global_values: usize,
+        num_public_values: usize,
+    ) -> Self {
+        let mut parser = Self {
+            nodes: Vec::new(),
+            local: Vec::new(),
+            next: Vec::new(),
+            challenges: Vec::new(),
+            global: Vec::new(),
+            public: Vec::new(),
+            constraints: Vec::new(),
+            _marker: PhantomData,
+        };
+        parser.local = parser.inputs(RegisterKind::Local, num_columns);
+        parser.next = parser.inputs(RegisterKind::Next, num_columns);
+        parser.challenges = parser.inputs(RegisterKind::Challenge, num_challenges);
+        parser.global = parser.inputs(RegisterKind::Global, num_global_values);
+        parser.public = parser.inputs(RegisterKind::Public, num_public_values);
+        parser
+    }
+
+    fn inputs(&mut self, kind: RegisterKind, length: usize) -> Vec<usize> {
+        (0..length)
+            .map(|index| {
+                self.push(Node::Input(RegisterRef {
+                    kind,
+                    index,
+                    length: 1,
+                }))
+            })
+            .collect()
+    }
+
+    fn push(&mut self, node: Node) -> usize {
+        self.nodes.push(node);
+        self.nodes.len() - 1
+    }
+
+    fn expression(&self, node: usize) -> ExpressionIR {
+        let boxed = |node| Box::new(self.expression(node));
+        match self.nodes[node] {
+            Node::Input(register) => ExpressionIR::Input(register),
+            Node::Const(value) => ExpressionIR::Const(vec![value]),
+            Node::Add(a, b) => ExpressionIR::Add(boxed(a), boxed(b)),
+            Node::Sub(a, b) => ExpressionIR::Sub(boxed(a), boxed(b)),
+            Node::Neg(a) => ExpressionIR::ConstMul((-F::ONE).as_canonical_u64(), boxed(a)),
+            Node::Mul(a, b) => ExpressionIR::Mul(boxed(a), boxed(b)),
+        }
+    }
+
+    fn record(&mut self, boundary: Boundary, constraint: usize) {
+        let expression = self.expression(constraint);
+        self.constraints.push(ConstraintNode::Arithmetic {
+            boundary,
+            size: 1,
+            expression,
+        });
+    }
+
+    /// Returns the constraints recorded since the last call.
+    fn take_constraints(&mut self) -> Vec<ConstraintNode> {
+        core::mem::take(&mut self.constraints)
+    }
+
+    fn lower_instruction<I: Instruction<F> + AirConstraint<Self>>(
+        &mut self,
+        instruction: &AirInstruction<F, I>,
+    ) -> Vec<ConstraintNode> {
+        match instruction {
+            AirInstruction::Assign(assign) => {
+                return vec![ConstraintNode::Assign {
+                    boundary: assign.kind.into(),
+                    target: (&assign.target).into(),
+                    source: (&assign.source).into(),
+                }]
+            }
+            AirInstruction::BitConstraint(bit) => {
+                return vec![ConstraintNode::Bit((&bit.0).into())]
+            }
+            AirInstruction::Filtered(filter, instruction) => {
+                let filter = ExpressionIR::from(filter);
+                return self
+                    .lower_instruction(instruction)
+                    .into_iter()
+                    .map(|constraint| ConstraintNode::Filtered {
+                        filter: filter.clone(),
+                        constraint: Box::new(constraint),
+                    })
+                    .collect();
+            }
+            AirInstruction::CustomInstruction(i) => AirConstraint::<Self>::eval(i, self),
+            AirInstruction::Select(i) => i.eval(self),
+            AirInstruction::Cycle(i) => i.eval(self),
+            AirInstruction::Clock(i) => i.eval(self),
+            AirInstruction::ProcessId(i) => i.eval(self),
+            AirInstruction::SignedRange(i) => i.eval(self),
+            AirInstruction::BitRange(i) => i.eval(self),
+            AirInstruction::Mem(i) => i.eval(self),
+            AirInstruction::Watch(..) | AirInstruction::WatchExpression(..) => {}
+        }
+        self.take_constraints()
+    }
+
+    fn lower_constraint<L: AirParameters<Field = F>>(
+        &mut self,
+        constraint: &Constraint<L>,
+    ) -> Vec<ConstraintNode>
+    where
+        L::Instruction: AirConstraint<Self>,
+    {
+        let arithmetic = |boundary, expression: &ArithmeticExpression<F>| {
+            vec![ConstraintNode::Arithmetic {
+                boundary,
+                size: expression.size,
+                expression: expression.into(),
+            }]
+        };
+        match constraint {
+            Constraint::Instruction(instruction) => return self.lower_instruction(instruction),
+            Constraint::Arithmetic(ArithmeticConstraint::First(e)) => {
+                return arithmetic(Boundary::First, e)
+            }
+            Constraint::Arithmetic(ArithmeticConstraint::Last(e)) => {
+                return arithmetic(Boundary::Last, e)
+            }
+            Constraint::Arithmetic(ArithmeticConstraint::Transition(e)) => {
+                return arithmetic(Boundary::Transition, e)
+            }
+            Constraint::Arithmetic(ArithmeticConstraint::All(e)) => {
+                return arithmetic(Boundary::All, e)
+            }
+            Constraint::Powers(powers) => powers.eval(self),
+            Constraint::Accumulator(accumulator) => accumulator.eval(self),
+            Constraint::RandomLinearCombination(rlc) => rlc.eval(self),
+            Constraint::Pointer(accumulator) => accumulator.eval(self),
+            Constraint::BusChannel(bus_channel) => bus_channel.eval(self),
+            Constraint::Bus(bus) => bus.eval(self),
+            Constraint::Lookup(lookup) => lookup.eval(self),
+        }
+        self.take_constraints()
+    }
+}
+
+impl<F: PrimeField64> AirParser for IRParser<F> {
+    type Field = F;
+    type Var = usize;
+
+    fn local_slice(&self) -> &[Self::Var] {
+        &self.local
+    }
+
+    fn next_slice(&self) -> &[Self::Var] {
+        &self.next
+    }
+
+    fn challenge_slice(&self) -> &[Self::Var] {
+        &self.challenges
+    }
+
+    fn global_slice(&self) -> &[Self::Var] {
+        &self.global
+    }
+
+    fn public_slice(&self) -> &[Self::Var] {
+        &self.public
+    }
+
+    fn constraint(&mut self, constraint: Self::Var) {
+        self.record(Boundary::All, constraint);
+    }
+
+    fn constraint_transition(&mut self, constraint: Self::Var) {
+        self.record(Boundary::Transition, constraint);
+    }
+
+    fn constraint_first_row(&mut self, constraint: Self::Var) {
+        self.record(Boundary::First, constraint);
+    }
+
+    fn constraint_last_row(&mut self, constraint: Self::Var) {
+        self.record(Boundary::Last, constraint);
+    }
+
+    fn constant(&mut self, value: Self::Field) -> Self::Var {
+        self.push(Node::Const(value.as_canonical_u64()))
+    }
+
+    fn add(&mut self, a: Self::Var, b: Self::Var) -> Self::Var {
+        self.push(Node::Add(a, b))
+    }
+
+    fn sub(&mut self, a: Self::Var, b: Self::Var) -> Self::Var {
+        self.push(Node::Sub(a, b))
+    }
+
+    fn neg(&mut self, a: Self::Var) -> Self::Var {
+        self.push(Node::Neg(a))
+    }
+
+    fn mul(&mut self, a: Self::Var, b: Self::Var) -> Self::Var {
+        self.push(Node::Mul(a, b))
+    }
+}
+
+impl<F: PrimeField64> PolynomialParser for IRParser<F> {}
+
+impl<F: PrimeField64, E: CubicParameters<F>> CubicParser<E> for IRParser<F> {}
+
+impl<L: AirParameters> AirBuilder<L> {
+    /// Exports the constraints registered so far as a [`ConstraintIR`].
+    ///
+    /// Assignments, bit constraints and filters are exported as their own nodes, and every other
+    /// constraint, including those of the custom instructions, is lowered to the arithmetic
+    /// constraints it evaluates. The constraints added by [`AirBuilder::build`], such as the range
+    /// checks of the arithmetic columns, are not part of the export.
+    pub fn export_constraints(&self) -> ConstraintIR
+    where
+        L::Instruction: AirConstraint<IRParser<L::Field>>,
+    {
+        let num_columns = L::num_columns()
+            .max(self.local_index)
+            .max(self.extended_index);
+        let mut parser = IRParser::<L::Field>::new(
+            num_columns,
+            self.shared_memory.challenge_index(),
+            self.shared_memory.global_index(),
+            self.shared_memory.public_index(),
+        );
+        let mut lower = |constraints: &[Constraint<L>]| -> Vec<ConstraintNode> {
+            constraints
+                .iter()
+                .flat_map(|constraint| parser.lower_constraint(constraint))
+                .collect()
+        };
+        ConstraintIR {
+            num_arithmetic_columns: L::NUM_ARITHMETIC_COLUMNS,
+            num_free_columns: L::NUM_FREE_COLUMNS,
+            num_extended_columns: L::EXTENDED_COLUMNS,
+            constraints: lower(&self.constraints),
+            global_constraints: lower(&self.global_constraints),
+            registers: self
+                .register_docs
+                .iter()
+                .map(|(register, doc)| RegisterDoc {
+                    register: register.into(),
+                    doc: doc.clone(),
+                })
+                .collect(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::chip::builder::tests::*;
+    use crate::chip::register::element::ElementRegister;
+    use crate::chip::register::{Register, RegisterSerializable};
+
+    #[derive(Debug, Clone, Serialize, Deserialize)]
+    pub struct FibonacciIRParameters;
+
+    impl AirParameters for FibonacciIRParameters {
+        type Field = GoldilocksField;
+        type CubicParams = GoldilocksCubicParameters;
+        type Instruction = EmptyInstruction<GoldilocksField>;
+
+        const NUM_FREE_COLUMNS: usize = 2;
+    }
+
+    #[test]
+    fn test_export_fibonacci_constraints() {
+        type L = FibonacciIRParameters;
+
+        let mut builder = AirBuilder::<L>::new();
+        let x_0 = builder.alloc_with_doc::<ElementRegister>("F(n)");
+        let x_1 = builder.alloc::<ElementRegister>();
+        builder.document(&x_1, "F(n + 1)");
+        builder.set_to_expression_transition(&x_0.next(), x_1.expr());
+        builder.set_to_expression_transition(&x_1.next(), x_0.expr() + x_1.expr());
+
+        let ir = builder.export_constraints();
+
+        let bytes = bincode::serialize(&ir).unwrap();
+        let decoded: ConstraintIR = bincode::deserialize(&bytes).unwrap();
+        assert_eq!(decoded, ir);
+
+        let local = |index| {
+            ExpressionIR::Input(RegisterRef {
+                kind: RegisterKind::Local,
+                index,
+                length: 1,
+            })
+        };
+        let next = |index| RegisterRef {
+            kind: RegisterKind::Next,
+            index,
+            length: 1,
+        };
+        assert_eq!(
+            decoded.constraints,
+            vec![
+                ConstraintNode::Assign {
+                    boundary: Boundary::Transition,
+                    target: next(0),
+                    source: local(1),
+                },
+                ConstraintNode::Assign {
+                    boundary: Boundary::Transition,
+                    target: next(1),
+                    source: ExpressionIR::Add(Box::new(local(0)), Box::new(local(1))),
+                },
+            ]
+        );
+        assert!(decoded.global_constraints.is_empty());
+        assert_eq!(
+            decoded.registers,
+            vec![
+                RegisterDoc {
+                    register: RegisterRef {
+                        kind: RegisterKind::Local,
+                        index: 0,
+                        length: 1,
+                    },
+                    doc: "F(n)".to_string(),
+                },
+                RegisterDoc {
+                    register: RegisterRef {
+                        kind: RegisterKind::Local,
+                        index: 1,
+                        length: 1,
+                    },
+                    doc: "F(n + 1)".to_string(),
+                },
+            ]
+        );
+        assert_eq!(decoded.num_free_columns, 2);
+    }
+
+    #[test]
+    fn test_export_clock_constraints() {
+        type L = FibonacciIRParameters;
+
+        let mut builder = AirBuilder::<L>::new();
+        let clk = builder.clock();
+
+        let ir = builder.export_constraints();
+
+        let index = match clk.register() {
+            MemorySlice::Local(index, _) => *index,
+            _ => unreachable!("the clock is a trace register"),
+        };
+        let input = |kind| {
+            Box::new(ExpressionIR::Input(RegisterRef {
+                kind,
+                index,
+                length: 1,
+            }))
+        };
+        assert_eq!(
+            ir.constraints,
+            vec![
+                ConstraintNode::Arithmetic {
+                    boundary: Boundary::First,
+                    size: 1,
+                    expression: *input(RegisterKind::Local),
+                },
+                ConstraintNode::Arithmetic {
+                    boundary: Boundary::Transition,
+                    size: 1,
+                    expression: ExpressionIR::Sub(
+                        Box::new(ExpressionIR::Sub(
+                            input(RegisterKind::Next),
+                            input(RegisterKind::Local),
+                        )),
+                        Box::new(ExpressionIR::Const(vec![1])),
+                    ),
+                },
+            ]
+        );
+    }
+}
@@ -9,17 +9,22 @@ use super::table::bus::channel::BusChannel;
 use super::table::bus::global::Bus;
 use super::table::lookup::constraint::LookupChipConstraint;
 use super::table::powers::Powers;
+use super::table::rlc::RandomLinearCombination;
 use super::AirParameters;
 use crate::air::extension::cubic::CubicParser;
 use crate::air::parser::{AirParser, MulParser};
 use crate::air::AirConstraint;
 
+pub mod ir;
+pub mod usage;
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub enum Constraint<L: AirParameters> {
     Instruction(AirInstruction<L::Field, L::Instruction>),
     Arithmetic(ArithmeticConstraint<L::Field>),
     Powers(Powers<L::Field, L::CubicParams>),
     Accumulator(Accumulator<L::Field, L::CubicParams>),
+    RandomLinearCombination(RandomLinearCombination<L::Field, L::CubicParams>),
     Pointer(PointerAccumulator<L::Field, L::CubicParams>),
     BusChannel(BusChannel<CubicRegister, L::CubicParams>),
     Bus(Bus<CubicRegister, L::CubicParams>),
@@ -65,6 +70,7 @@ where
             Constraint::Arithmetic(constraint) => constraint.eval(parser),
             Constraint::Powers(powers) => powers.eval(parser),
             Constraint::Accumulator(accumulator) => accumulator.eval(parser),
+            Constraint::RandomLinearCombination(rlc) => rlc.eval(parser),
             Constraint::Pointer(accumulator) => accumulator.eval(parser),
             Constraint::BusChannel(bus_channel) => bus_channel.eval(parser),
             Constraint::Bus(bus) => bus.eval(parser),
@@ -85,6 +91,12 @@ impl<L: AirParameters> From<Accumulator<L::Field, L::CubicParams>> for Constrain
     }
 }
 
+impl<L: AirParameters> From<RandomLinearCombination<L::Field, L::CubicParams>> for Constraint<L> {
+    fn from(rlc: RandomLinearCombination<L::Field, L::CubicParams>) -> Self {
+        Self::RandomLinearCombination(rlc)
+    }
+}
+
 impl<L: AirParameters> From<PointerAccumulator<L::Field, L::CubicParams>> for Constraint<L> {
     fn from(accumulator: PointerAccumulator<L::Field, L::CubicParams>) -> Self {
         Self::Pointer(accumulator)
@@ -0,0 +1,312 @@
+//! Detection of the registers that are not referenced by any constraint.
+//!
+//! The value of such a register is a free variable that the prover can set arbitrarily, which is
+//! usually a sign of a missing constraint.
+
+use core::marker::PhantomData;
+
+use super::Constraint;
+use crate::air::extension::cubic::CubicParser;
+use crate::air::parser::AirParser;
+use crate::air::AirConstraint;
+use crate::chip::builder::AirBuilder;
+use crate::chip::instruction::set::AirInstruction;
+use crate::chip::instruction::Instruction;
+use crate::chip::register::memory::MemorySlice;
+use crate::chip::AirParameters;
+use crate::math::prelude::*;
+
+/// A node of the expression graph built by a [`UsageParser`].
+#[derive(Debug, Clone, Copy)]
+enum Node {
+    /// A trace column, or a value that does not depend on the trace for `None`.
+    Leaf(Option<usize>),
+    Unary(usize),
+    Binary(usize, usize),
+}
+
+/// A parser that records the trace columns each constraint depends on.
+///
+/// The variables of the parser are the indices of the nodes of an expression graph, so that the
+/// columns a constraint depends on are the leaves reachable from it.
+#[derive(Debug)]
+pub(crate) struct UsageParser<F> {
+    nodes: Vec<Node>,
+    local: Vec<usize>,
+    next: Vec<usize>,
+    challenges: Vec<usize>,
+    global: Vec<usize>,
+    public: Vec<usize>,
+    constraints: Vec<usize>,
+    _marker: PhantomData<F>,
+}
+
+impl<F: Field> UsageParser<F> {
+    pub(crate) fn new(
+        num_columns: usize,
+        num_challenges: usize,
+        num_global_values: usize,
+        num_public_values: usize,
+    ) -> Self {
+        let mut parser = Self {
+            nodes: Vec::new(),
+            local: Vec::new(),
+            next: Vec::new(),
+            challenges: Vec::new(),
+            global: Vec::new(),
+            public: Vec::new(),
+            constraints: Vec::new(),
+            _marker: PhantomData,
+        };
+        parser.local = parser.leaves((0..num_columns).map(Some));
+        parser.next = parser.leaves((0..num_columns).map(Some));
+        parser.challenges = parser.leaves((0..num_challenges).map(|_| None));
+        parser.global = parser.leaves((0..num_global_values).map(|_| None));
+        parser.public = parser.leaves((0..num_public_values).map(|_| None));
+        parser
+    }
+
+    fn leaves(&mut self, columns: impl Iterator<Item = Option<usize>>) -> Vec<usize> {
+        columns
+            .map(|column| self.push(Node::Leaf(column)))
+            .collect()
+    }
+
+    fn push(&mut self, node: Node) -> usize {
+        self.nodes.push(node);
+        self.nodes.len() - 1
+    }
+
+    /// Marks the trace columns of `register` as used, e.g. for the registers written by an
+    /// instruction.
+    pub(crate) fn mark_used(&mut self, register: &MemorySlice) {
+        match *register {
+            MemorySlice::Local(index, length) | MemorySlice::Next(index, length) => {
+                let columns = &self.local[index..index + length];
+                self.constraints.extend_from_slice(columns);
+            }
+            _ => {}
+        }
+    }
+
+    /// Returns, for every trace column, whether a constraint depends on it.
+    pub(crate) fn used_columns(&self) -> Vec<bool> {
+        let mut used = vec![false; self.local.len()];
+        let mut visited = vec![false; self.nodes.len()];
+        let mut stack = self.constraints.clone();
+        while let Some(node) = stack.pop() {
+            if visited[node] {
+                continue;
+            }
+            visited[node] = true;
+            match self.nodes[node] {
+                Node::Leaf(Some(column)) => used[column] = true,
+                Node::Leaf(None) => {}
+                Node::Unary(a) => stack.push(a),
+                Node::Binary(a, b) => stack.extend([a, b]),
+            }
+        }
+        used
+    }
+}
+
+impl<F: Field> AirParser for UsageParser<F> {
+    type Field = F;
+    type Var = usize;
+
+    fn local_slice(&self) -> &[Self::Var] {
+        &self.local
+    }
+
+    fn next_slice(&self) -> &[Self::Var] {
+        &self.next
+    }
+
+    fn challenge_slice(&self) -> &[Self::Var] {
+        &self.challenges
+    }
+
+    fn global_slice(&self) -> &[Self::Var] {
+        &self.global
+    }
+
+    fn public_slice(&self) -> &[Self::Var] {
+        &self.public
+    }
+
+    fn constraint(&mut self, constraint: Self::Var) {
+        self.constraints.push(constraint);
+    }
+
+    fn constraint_transition(&mut self, constraint: Self::Var) {
+        self.constraints.push(constraint);
+    }
+
+    fn constraint_first_row(&mut self, constraint: Self::Var) {
+        self.constraints.push(constraint);
+    }
+
+    fn constraint_last_row(&mut self, constraint: Self::Var) {
+        self.constraints.push(constraint);
+    }
+
+    fn constant(&mut self, _value: Self::Field) -> Self::Var {
+        self.push(Node::Leaf(None))
+    }
+
+    fn add(&mut self, a: Self::Var, b: Self::Var) -> Self::Var {
+        self.push(Node::Binary(a, b))
+    }
+
+    fn sub(&mut self, a: Self::Var, b: Self::Var) -> Self::Var {
+        self.push(Node::Binary(a, b))
+    }
+
+    fn neg(&mut self, a: Self::Var) -> Self::Var {
+        self.push(Node::Unary(a))
+    }
+
+    fn mul(&mut self, a: Self::Var, b: Self::Var) -> Self::Var {
+        self.push(Node::Binary(a, b))
+    }
+}
+
+impl<F: Field, E: CubicParameters<F>> CubicParser<E> for UsageParser<F> {}
+
+impl<L: AirParameters> AirBuilder<L> {
+    /// Returns the trace registers allocated so far whose columns appear in no constraint and in
+    /// the constrained registers of no instruction.
+    ///
+    /// The bit constraints added when allocating a `BitRegister`, and the range checks of the
+    /// arithmetic columns added by [`AirBuilder::build`], do not count as a use of a register.
+    pub fn unconstrained_registers(&self) -> Vec<MemorySlice> {
+        let num_columns = L::num_columns()
+            .max(self.local_index)
+            .max(self.extended_index);
+        let mut parser = UsageParser::<L::Field>::new(
+            num_columns,
+            self.shared_memory.challenge_index(),
+            self.shared_memory.global_index(),
+            self.shared_memory.public_index(),
+        );
+        for constraint in self
+            .constraints
+            .iter()
+            .chain(self.global_constraints.iter())
+        {
+            Self::eval_usage(constraint, &mut parser);
+        }
+
+        let used = parser.used_columns();
+        self.local_allocations
+            .iter()
+            .filter(|register| match register {
+                MemorySlice::Local(index, length) => !used[*index..*index + length].contains(&true),
+                _ => unreachable!("Trace allocations are local"),
+            })
+            .copied()
+            .collect()
+    }
+
+    fn eval_usage(constraint: &Constraint<L>, parser: &mut UsageParser<L::Field>) {
+        match constraint {
+            Constraint::Instruction(instruction) => {
+                Self::eval_instruction_usage(instruction, parser)
+            }
+            Constraint::Arithmetic(constraint) => constraint.eval(parser),
+            Constraint::Powers(powers) => powers.eval(parser),
+            Constraint::Accumulator(accumulator) => accumulator.eval(parser),
+            Constraint::RandomLinearCombination(rlc) => rlc.eval(parser),
+            Constraint::Pointer(accumulator) => accumulator.eval(parser),
+            Constraint::BusChannel(bus_channel) => bus_channel.eval(parser),
+            Constraint::Bus(bus) => bus.eval(parser),
+            Constraint::Lookup(lookup) => lookup.eval(parser),
+        }
+    }
+
+    fn eval_instruction_usage(
+        instruction: &AirInstruction<L::Field, L::Instruction>,
+        parser: &mut UsageParser<L::Field>,
+    ) {
+        match instruction {
+            // The constraints of custom instructions are only known through the registers they
+            // depend on.
+            AirInstruction::CustomInstruction(i) => {
+                for register in i.constrained_registers() {
+                    parser.mark_used(&register);
+                }
+            }
+            AirInstruction::BitConstraint(_) => {}
+            AirInstruction::Assign(i) => i.eval(parser),
+            AirInstruction::Select(i) => i.eval(parser),
+            AirInstruction::Cycle(i) => i.eval(parser),
+            AirInstruction::Clock(i) => i.eval(parser),
+            AirInstruction::ProcessId(i) => i.eval(parser),
+            AirInstruction::SignedRange(i) => i.eval(parser),
+            AirInstruction::BitRange(i) => i.eval(parser),
+            AirInstruction::Filtered(filter, i) => {
+                for value in filter.eval(parser) {
+                    parser.constraint(value);
+                }
+                Self::eval_instruction_usage(i, parser);
+            }
+            AirInstruction::Mem(i) => i.eval(parser),
+            AirInstruction::Watch(_, _) | AirInstruction::WatchExpression(_, _) => {}
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde::{Deserialize, Serialize};
+
+    use super::*;
+    use crate::chip::builder::tests::*;
+    use crate::chip::register::bit::BitRegister;
+    use crate::chip::register::element::ElementRegister;
+    use crate::chip::register::Register;
+
+    #[derive(Debug, Clone, Serialize, Deserialize)]
+    pub struct UsageParameters;
+
+    impl AirParameters for UsageParameters {
+        type Field = GoldilocksField;
+        type CubicParams = GoldilocksCubicParameters;
+        type Instruction = EmptyInstruction<GoldilocksField>;
+
+        const NUM_ARITHMETIC_COLUMNS: usize = 1;
+        const NUM_FREE_COLUMNS: usize = 5;
+    }
+
+    #[test]
+    fn test_unconstrained_registers() {
+        let mut builder = AirBuilder::<UsageParameters>::new();
+        let x_0 = builder.alloc::<ElementRegister>();
+        let x_1 = builder.alloc::<ElementRegister>();
+        builder.set_to_expression_transition(&x_0.next(), x_1.expr());
+        builder.set_to_expression_transition(&x_1.next(), x_0.expr() + x_1.expr());
+
+        let unused = builder.alloc::<ElementRegister>();
+        let bit = builder.alloc::<BitRegister>();
+        let limb = builder.alloc::<U16Register>();
+        let selected = builder.alloc::<ElementRegister>();
+        builder.assert_expressions_equal(selected.expr(), x_0.expr() * limb.expr());
+
+        assert_eq!(
+            builder.unconstrained_registers(),
+            vec![*unused.register(), *bit.register()]
+        );
+        builder.build();
+    }
+
+    #[test]
+    #[should_panic(expected = "appear in no constraint")]
+    fn test_unconstrained_registers_strict() {
+        let mut builder = AirBuilder::<UsageParameters>::new();
+        builder.set_strict(true);
+        let x = builder.alloc::<ElementRegister>();
+        builder.set_to_expression_first_row(&x, GoldilocksField::ONE.into());
+        builder.alloc_with_doc::<ElementRegister>("unused");
+        builder.build();
+    }
+}
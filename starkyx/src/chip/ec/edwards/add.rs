@@ -1,8 +1,14 @@
+use num::{BigUint, One, Zero};
+
 use super::{EdwardsCurve, EdwardsParameters};
+use crate::chip::arithmetic::expression::ArithmeticExpression;
 use crate::chip::builder::AirBuilder;
 use crate::chip::ec::point::AffinePointRegister;
 use crate::chip::field::instruction::FromFieldInstruction;
+use crate::chip::field::parameters::FieldParameters;
+use crate::chip::register::Register;
 use crate::chip::AirParameters;
+use crate::polynomial::Polynomial;
 
 impl<L: AirParameters> AirBuilder<L> {
     pub fn ed_add<E: EdwardsParameters>(
@@ -64,6 +70,98 @@ impl<L: AirParameters> AirBuilder<L> {
     {
         self.ed_add(p, p)
     }
+
+    /// Computes the negation `-P = (-x, y)` of a point `P = (x, y)` on a twisted Edwards curve.
+    pub fn ed_negate<E: EdwardsParameters>(
+        &mut self,
+        p: &AffinePointRegister<EdwardsCurve<E>>,
+    ) -> AffinePointRegister<EdwardsCurve<E>>
+    where
+        L::Instruction: FromFieldInstruction<E::BaseField>,
+    {
+        let zero = self.fp_zero::<E::BaseField>();
+        let neg_x = self.fp_sub(&zero, &p.x);
+        AffinePointRegister::new(neg_x, p.y)
+    }
+
+    /// Computes the difference `P - Q` of two points, as the sum of `P` and `-Q`.
+    pub fn ed_sub<E: EdwardsParameters>(
+        &mut self,
+        p: &AffinePointRegister<EdwardsCurve<E>>,
+        q: &AffinePointRegister<EdwardsCurve<E>>,
+    ) -> AffinePointRegister<EdwardsCurve<E>>
+    where
+        L::Instruction: FromFieldInstruction<E::BaseField>,
+    {
+        let neg_q = self.ed_negate(q);
+        self.ed_add(p, &neg_q)
+    }
+
+    /// Computes the sum of `points` with a single addition gadget, adding one point per row.
+    ///
+    /// A rotating one-hot selector picks the point added at each row of a cycle of `points.len()`
+    /// rows, and the running sum is copied forward to the next row, starting every cycle at the
+    /// identity. The points are read on every row, so they must be public or constant over a
+    /// cycle. The returned register holds the running sum after the current row, which on the
+    /// last row of each cycle is the sum of all the points.
+    pub fn ed_add_many<E: EdwardsParameters>(
+        &mut self,
+        points: &[AffinePointRegister<EdwardsCurve<E>>],
+    ) -> AffinePointRegister<EdwardsCurve<E>>
+    where
+        L::Instruction: FromFieldInstruction<E::BaseField>,
+    {
+        assert!(!points.is_empty(), "cannot add an empty set of points");
+
+        // The neutral element of an Edwards curve is the affine point (0, 1).
+        let identity_x = Polynomial::<L::Field>::from_biguint_field(
+            &BigUint::zero(),
+            E::BaseField::NB_BITS_PER_LIMB,
+            E::BaseField::NB_LIMBS,
+        );
+        let identity_y = Polynomial::<L::Field>::from_biguint_field(
+            &BigUint::one(),
+            E::BaseField::NB_BITS_PER_LIMB,
+            E::BaseField::NB_LIMBS,
+        );
+        let identity_x_expr = ArithmeticExpression::from_constant_vec(identity_x.coefficients);
+        let identity_y_expr = ArithmeticExpression::from_constant_vec(identity_y.coefficients);
+
+        // addend = sum_j selector_j * P_j.
+        let selectors = self.loop_instr(points.len());
+        let (addend_x_expr, addend_y_expr) = points
+            .iter()
+            .enumerate()
+            .map(|(j, point)| {
+                let selector = selectors.get_iteration_reg(j).expr();
+                (selector.clone() * point.x.expr(), selector * point.y.expr())
+            })
+            .reduce(|(acc_x, acc_y), (x, y)| (acc_x + x, acc_y + y))
+            .unwrap();
+        let addend = AffinePointRegister::<EdwardsCurve<E>>::new(self.alloc(), self.alloc());
+        self.set_to_expression(&addend.x, addend_x_expr);
+        self.set_to_expression(&addend.y, addend_y_expr);
+
+        // The running sum starts every cycle at the identity.
+        let acc = AffinePointRegister::<EdwardsCurve<E>>::new(self.alloc(), self.alloc());
+        self.set_to_expression_first_row(&acc.x, identity_x_expr.clone());
+        self.set_to_expression_first_row(&acc.y, identity_y_expr.clone());
+
+        let result = self.ed_add(&acc, &addend);
+
+        // Carry the result to the next row, resetting the running sum at the end of each cycle.
+        let end_bit = selectors.get_iteration_reg(points.len() - 1);
+        self.set_to_expression_transition(
+            &acc.x.next(),
+            end_bit.expr() * identity_x_expr + end_bit.not_expr() * result.x.expr(),
+        );
+        self.set_to_expression_transition(
+            &acc.y.next(),
+            end_bit.expr() * identity_y_expr + end_bit.not_expr() * result.y.expr(),
+        );
+
+        result
+    }
 }
 
 #[cfg(test)]
@@ -76,6 +174,7 @@ mod tests {
     use crate::chip::builder::tests::*;
     use crate::chip::ec::edwards::ed25519::params::{Ed25519, Ed25519BaseField};
     use crate::chip::ec::gadget::{EllipticCurveGadget, EllipticCurveWriter};
+    use crate::chip::ec::point::AffinePoint;
     use crate::chip::ec::EllipticCurve;
     use crate::chip::field::instruction::FpInstruction;
 
@@ -139,4 +238,133 @@ mod tests {
         // Test the recursive proof.
         test_recursive_starky(stark, config, generator, &public);
     }
+
+    #[derive(Clone, Debug, Copy, Serialize, Deserialize)]
+    pub struct Ed25519AddManyTest;
+
+    impl AirParameters for Ed25519AddManyTest {
+        type Field = GoldilocksField;
+        type CubicParams = GoldilocksCubicParameters;
+
+        const NUM_ARITHMETIC_COLUMNS: usize = 800;
+        const NUM_FREE_COLUMNS: usize = 10;
+        const EXTENDED_COLUMNS: usize = 1209;
+        type Instruction = FpInstruction<Ed25519BaseField>;
+    }
+
+    #[test]
+    fn test_ed25519_add_many() {
+        type L = Ed25519AddManyTest;
+        type SC = PoseidonGoldilocksStarkConfig;
+        type E = Ed25519;
+
+        let num_points = 8;
+
+        let mut builder = AirBuilder::<L>::new();
+        let points = (0..num_points)
+            .map(|_| builder.alloc_public_ec_point())
+            .collect::<Vec<_>>();
+        let sum = builder.ed_add_many(&points);
+
+        let num_rows = 1 << 16;
+        let (air, trace_data) = builder.build();
+        let generator = ArithmeticGenerator::<L>::new(trace_data, num_rows);
+
+        let base = E::ec_generator();
+        let mut rng = thread_rng();
+        let point_values = (0..num_points)
+            .map(|_| &base * rng.gen_biguint(256))
+            .collect::<Vec<_>>();
+        let expected = point_values
+            .iter()
+            .cloned()
+            .reduce(|acc, p| &acc + &p)
+            .unwrap();
+
+        let writer = generator.new_writer();
+        for (point, value) in points.iter().zip(point_values.iter()) {
+            writer.write_ec_point(point, value, 0);
+        }
+        writer.write_global_instructions(&generator.air_data);
+
+        // The running sum is carried between rows, so the trace is written sequentially.
+        for i in 0..num_rows {
+            writer.write_row_instructions(&generator.air_data, i);
+        }
+
+        for i in (num_points - 1..num_rows).step_by(num_points) {
+            let value: AffinePoint<E> = writer.read_ec_point(&sum, i);
+            assert_eq!(value, expected);
+        }
+
+        let stark = Starky::new(air);
+        let config = SC::standard_fast_config(num_rows);
+        let public = writer.public().unwrap().clone();
+
+        // Generate proof and verify as a stark
+        test_starky(&stark, &config, &generator, &public);
+
+        // Test the recursive proof.
+        test_recursive_starky(stark, config, generator, &public);
+    }
+
+    #[derive(Clone, Debug, Copy, Serialize, Deserialize)]
+    pub struct Ed25519SubTest;
+
+    impl AirParameters for Ed25519SubTest {
+        type Field = GoldilocksField;
+        type CubicParams = GoldilocksCubicParameters;
+
+        const NUM_ARITHMETIC_COLUMNS: usize = 1720;
+        const NUM_FREE_COLUMNS: usize = 2;
+        const EXTENDED_COLUMNS: usize = 2589;
+        type Instruction = FpInstruction<Ed25519BaseField>;
+    }
+
+    #[test]
+    fn test_ed25519_negate_and_sub() {
+        type L = Ed25519SubTest;
+        type SC = PoseidonGoldilocksStarkConfig;
+        type E = Ed25519;
+
+        let mut builder = AirBuilder::<L>::new();
+
+        let p = builder.alloc_ec_point();
+        let q = builder.alloc_ec_point();
+        let neg_p = builder.ed_negate(&p);
+        let identity = builder.ed_add(&p, &neg_p);
+        let p_minus_q = builder.ed_sub(&p, &q);
+
+        let num_rows = 1 << 16;
+        let (air, trace_data) = builder.build();
+        let generator = ArithmeticGenerator::<L>::new(trace_data, num_rows);
+
+        let base = E::ec_generator();
+        let mut rng = thread_rng();
+        let p_int = &base * rng.gen_biguint(256);
+        let q_int = &base * rng.gen_biguint(256);
+        let expected = &p_int + &E::ec_neg(&q_int);
+        let neutral = E::neutral();
+
+        // The constant zero used by the negation is written by a global instruction.
+        let writer = generator.new_writer();
+        writer.write_global_instructions(&generator.air_data);
+        (0..num_rows).into_par_iter().for_each(|i| {
+            writer.write_ec_point(&p, &p_int, i);
+            writer.write_ec_point(&q, &q_int, i);
+            writer.write_row_instructions(&generator.air_data, i);
+
+            let identity_value: AffinePoint<E> = writer.read_ec_point(&identity, i);
+            assert_eq!(identity_value, neutral);
+            let p_minus_q_value: AffinePoint<E> = writer.read_ec_point(&p_minus_q, i);
+            assert_eq!(p_minus_q_value, expected);
+        });
+
+        let stark = Starky::new(air);
+        let config = SC::standard_fast_config(num_rows);
+        let public = writer.public().unwrap().clone();
+
+        // Generate proof and verify as a stark
+        test_starky(&stark, &config, &generator, &public);
+    }
 }
@@ -19,6 +19,10 @@ impl<L: AirParameters> AirBuilder<L> {
     /// and the positive suqare root of `x^2`, where `x` is the x-coordinate of `p`. To insure
     /// soundness, the caller MUST verify that `r` is within the field modulus range, i.e
     /// `0 <= r < modulus`.
+    ///
+    /// If `compressed_p` is a public input, the decompression is done by global instructions.
+    /// This allows a public key to be given as public inputs by its `y` coordinate and sign bit
+    /// only, and the full point to be used in the rest of the AIR.
     pub fn ed25519_decompress(
         &mut self,
         compressed_p: &CompressedPointRegister,
@@ -335,6 +339,51 @@ mod tests {
         test_recursive_starky(stark, config, generator, &public);
     }
 
+    #[test]
+    fn test_ed25519_decompress_public() {
+        type L = Ed25519DecompressTest;
+        type SC = PoseidonGoldilocksStarkConfig;
+
+        let mut builder = AirBuilder::<L>::new();
+
+        let compressed_p_reg = builder.alloc_public_ec_compressed_point();
+        let (affine_p_reg, _) = builder.ed25519_decompress(&compressed_p_reg);
+        let expected_affine_p = builder.alloc_public_ec_point();
+        builder.assert_equal(&expected_affine_p.x, &affine_p_reg.x);
+        builder.assert_equal(&expected_affine_p.y, &affine_p_reg.y);
+
+        let num_rows = 1 << 16;
+        let (air, trace_data) = builder.build();
+        let generator = ArithmeticGenerator::<L>::new(trace_data, num_rows);
+
+        let compressed_p_bytes = hex::decode(COMPRESSED_P[0]).unwrap();
+        let compressed_p = CompressedEdwardsY(compressed_p_bytes.try_into().unwrap());
+        let affine_p = AffinePoint::<EdwardsCurve<Ed25519Parameters>>::new(
+            BigUint::from_str(X_VALUES[0]).unwrap(),
+            BigUint::from_str(Y_VALUES[0]).unwrap(),
+        );
+
+        let writer = generator.new_writer();
+        writer.write_ec_compressed_point(&compressed_p_reg, &compressed_p, 0);
+        writer.write_ec_point(&expected_affine_p, &affine_p, 0);
+        writer.write_global_instructions(&generator.air_data);
+        assert_eq!(writer.read_ec_point(&affine_p_reg, 0), affine_p);
+
+        (0..num_rows).into_par_iter().for_each(|i| {
+            writer.write_row_instructions(&generator.air_data, i);
+        });
+
+        let stark = Starky::new(air);
+        let config = SC::standard_fast_config(num_rows);
+        let public = writer.public().unwrap().clone();
+
+        // Generate proof and verify as a stark
+        test_starky(&stark, &config, &generator, &public);
+
+        // Test the recursive proof.
+        test_recursive_starky(stark, config, generator, &public);
+    }
+
     #[test]
     fn test_ed25519_decompress() {
         for i in 0..NUM_TEST_CASES {
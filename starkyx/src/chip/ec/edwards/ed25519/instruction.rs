@@ -8,12 +8,15 @@ use crate::chip::ec::ECInstruction;
 use crate::chip::field::add::FpAddInstruction;
 use crate::chip::field::den::FpDenInstruction;
 use crate::chip::field::div::FpDivInstruction;
+use crate::chip::field::div_or_zero::FpDivOrZeroInstruction;
 use crate::chip::field::inner_product::FpInnerProductInstruction;
 use crate::chip::field::instruction::FromFieldInstruction;
 use crate::chip::field::mul::FpMulInstruction;
 use crate::chip::field::mul_const::FpMulConstInstruction;
+use crate::chip::field::nonzero::FpNonZeroInstruction;
 use crate::chip::field::sub::FpSubInstruction;
 use crate::chip::instruction::Instruction;
+use crate::chip::register::memory::MemorySlice;
 use crate::chip::trace::writer::{AirWriter, TraceWriter};
 use crate::math::field::PrimeField64;
 use crate::polynomial::parser::PolynomialParser;
@@ -66,6 +69,20 @@ impl<F: PrimeField64> Instruction<F> for Ed25519FpInstruction {
             }
         }
     }
+
+    fn trace_layout(&self) -> Vec<MemorySlice> {
+        match self {
+            Ed25519FpInstruction::EC(instruction) => Instruction::<F>::trace_layout(instruction),
+            Ed25519FpInstruction::Sqrt(instruction) => Instruction::<F>::trace_layout(instruction),
+        }
+    }
+
+    fn name(&self) -> &'static str {
+        match self {
+            Ed25519FpInstruction::EC(instruction) => Instruction::<F>::name(instruction),
+            Ed25519FpInstruction::Sqrt(instruction) => Instruction::<F>::name(instruction),
+        }
+    }
 }
 
 impl From<LimbBitInstruction> for Ed25519FpInstruction {
@@ -98,6 +115,12 @@ impl From<FpDivInstruction<Ed25519BaseField>> for Ed25519FpInstruction {
     }
 }
 
+impl From<FpDivOrZeroInstruction<Ed25519BaseField>> for Ed25519FpInstruction {
+    fn from(i: FpDivOrZeroInstruction<Ed25519BaseField>) -> Self {
+        Self::EC(i.into())
+    }
+}
+
 impl From<FpDenInstruction<Ed25519BaseField>> for Ed25519FpInstruction {
     fn from(i: FpDenInstruction<Ed25519BaseField>) -> Self {
         Self::EC(i.into())
@@ -115,3 +138,9 @@ impl From<FpMulConstInstruction<Ed25519BaseField>> for Ed25519FpInstruction {
         Self::EC(i.into())
     }
 }
+
+impl From<FpNonZeroInstruction<Ed25519BaseField>> for Ed25519FpInstruction {
+    fn from(i: FpNonZeroInstruction<Ed25519BaseField>) -> Self {
+        Self::EC(i.into())
+    }
+}
@@ -12,6 +12,7 @@ use crate::chip::field::register::FieldRegister;
 use crate::chip::instruction::Instruction;
 use crate::chip::register::array::ArrayRegister;
 use crate::chip::register::bit::BitRegister;
+use crate::chip::register::memory::MemorySlice;
 use crate::chip::register::u16::U16Register;
 use crate::chip::register::{Register, RegisterSerializable};
 use crate::chip::trace::writer::{AirWriter, TraceWriter};
@@ -181,6 +182,17 @@ impl<F: PrimeField64> Instruction<F> for Ed25519FpSqrtInstruction {
 
         self.square.write_to_air(writer);
     }
+
+    fn trace_layout(&self) -> Vec<MemorySlice> {
+        // The square rewrites its result, the input of the square root, with its own value.
+        vec![
+            *self.square.a.register(),
+            *self.limb_witness.register(),
+            *self.square.carry.register(),
+            *self.square.witness_low.register(),
+            *self.square.witness_high.register(),
+        ]
+    }
 }
 
 pub fn sqrt(a: BigUint) -> BigUint {
@@ -0,0 +1,261 @@
+use num::{BigUint, One, Zero};
+
+use super::{EdwardsCurve, EdwardsParameters};
+use crate::chip::arithmetic::expression::ArithmeticExpression;
+use crate::chip::builder::AirBuilder;
+use crate::chip::ec::point::AffinePointRegister;
+use crate::chip::field::instruction::FromFieldInstruction;
+use crate::chip::field::parameters::FieldParameters;
+use crate::chip::register::array::ArrayRegister;
+use crate::chip::register::bit::BitRegister;
+use crate::chip::register::Register;
+use crate::chip::AirParameters;
+use crate::polynomial::Polynomial;
+
+/// The maximal number of points whose subset sums are precomputed in a single lookup table.
+///
+/// A table for `k` points holds `2^k` entries and costs `2^k - 1` point selections per row.
+pub const ED_MSM_WINDOW: usize = 4;
+
+impl<L: AirParameters> AirBuilder<L> {
+    /// Computes the multi-scalar multiplication `sum(s_i * P_i)` of the given scalars and points.
+    ///
+    /// The scalars are given by their little-endian bits and must all have the same length
+    /// `nb_bits`. The gadget processes one bit of every scalar per row, starting with the most
+    /// significant one, so that the doubling of the accumulator is shared by all the points
+    /// (Straus' interleaving, also known as Shamir's trick for two points). The points are split
+    /// into windows of at most [`ED_MSM_WINDOW`] points, and the subset sums of each window are
+    /// precomputed so that every row costs one doubling and one addition per window.
+    ///
+    /// The computation repeats every `nb_bits` rows. The returned register holds the value of the
+    /// accumulator after the current row, which on the last row of each cycle is the result of the
+    /// multi-scalar multiplication.
+    pub fn ed_msm<E: EdwardsParameters>(
+        &mut self,
+        scalars: &[ArrayRegister<BitRegister>],
+        points: &[AffinePointRegister<EdwardsCurve<E>>],
+    ) -> AffinePointRegister<EdwardsCurve<E>>
+    where
+        L::Instruction: FromFieldInstruction<E::BaseField>,
+    {
+        assert_eq!(
+            scalars.len(),
+            points.len(),
+            "the number of scalars and points must be equal"
+        );
+        assert!(!scalars.is_empty(), "cannot compute an empty MSM");
+        let nb_bits = scalars[0].len();
+        assert!(nb_bits > 0, "scalars must have at least one bit");
+        assert!(
+            scalars.iter().all(|s| s.len() == nb_bits),
+            "all scalars must have the same number of bits"
+        );
+
+        // The neutral element of an Edwards curve is the affine point (0, 1), and the addition
+        // formula is complete, so no special care is needed for the first additions.
+        let identity_x = Polynomial::<L::Field>::from_biguint_field(
+            &BigUint::zero(),
+            E::BaseField::NB_BITS_PER_LIMB,
+            E::BaseField::NB_LIMBS,
+        );
+        let identity_y = Polynomial::<L::Field>::from_biguint_field(
+            &BigUint::one(),
+            E::BaseField::NB_BITS_PER_LIMB,
+            E::BaseField::NB_LIMBS,
+        );
+        let identity_x_expr = ArithmeticExpression::from_constant_vec(identity_x.coefficients);
+        let identity_y_expr = ArithmeticExpression::from_constant_vec(identity_y.coefficients);
+        let identity = AffinePointRegister::new(self.fp_zero(), self.fp_one());
+
+        // Precompute the subset sums of each window of points.
+        let tables = points
+            .chunks(ED_MSM_WINDOW)
+            .map(|window| self.ed_msm_table(window, &identity))
+            .collect::<Vec<_>>();
+
+        // A rotating one-hot selector picks the bit of the scalars processed at each row. The
+        // first row of each cycle processes the most significant bit.
+        let selectors = self.loop_instr(nb_bits);
+        let bits = scalars
+            .iter()
+            .map(|scalar| {
+                let expression = (0..nb_bits).fold(ArithmeticExpression::zero(), |acc, j| {
+                    acc + selectors.get_iteration_reg(j).expr() * scalar.get(nb_bits - 1 - j).expr()
+                });
+                self.bit_from_expr(expression)
+            })
+            .collect::<Vec<_>>();
+
+        // The accumulator starts every cycle at the identity.
+        let acc = AffinePointRegister::<EdwardsCurve<E>>::new(self.alloc(), self.alloc());
+        self.set_to_expression_first_row(&acc.x, identity_x_expr.clone());
+        self.set_to_expression_first_row(&acc.y, identity_y_expr.clone());
+
+        // result = 2 * acc + sum_i bit_i * P_i.
+        let mut result = self.ed_double(&acc);
+        for (table, window_bits) in tables.iter().zip(bits.chunks(ED_MSM_WINDOW)) {
+            let addend = self.ed_msm_table_lookup(table, window_bits);
+            result = self.ed_add(&result, &addend);
+        }
+
+        // Carry the result to the next row, resetting the accumulator at the end of each cycle.
+        let end_bit = selectors.get_iteration_reg(nb_bits - 1);
+        self.set_to_expression_transition(
+            &acc.x.next(),
+            end_bit.expr() * identity_x_expr + end_bit.not_expr() * result.x.expr(),
+        );
+        self.set_to_expression_transition(
+            &acc.y.next(),
+            end_bit.expr() * identity_y_expr + end_bit.not_expr() * result.y.expr(),
+        );
+
+        result
+    }
+
+    /// Computes the table of all subset sums of `points`, where the entry at index `m` is the sum
+    /// of the points whose index bit is set in `m`.
+    fn ed_msm_table<E: EdwardsParameters>(
+        &mut self,
+        points: &[AffinePointRegister<EdwardsCurve<E>>],
+        identity: &AffinePointRegister<EdwardsCurve<E>>,
+    ) -> Vec<AffinePointRegister<EdwardsCurve<E>>>
+    where
+        L::Instruction: FromFieldInstruction<E::BaseField>,
+    {
+        let mut table = vec![*identity];
+        for point in points {
+            let sums = table[1..]
+                .iter()
+                .map(|entry| self.ed_add(entry, point))
+                .collect::<Vec<_>>();
+            table.push(*point);
+            table.extend(sums);
+        }
+        table
+    }
+
+    /// Selects the entry of `table` at the index whose little-endian bits are given by `bits`.
+    pub(super) fn ed_msm_table_lookup<E: EdwardsParameters>(
+        &mut self,
+        table: &[AffinePointRegister<EdwardsCurve<E>>],
+        bits: &[BitRegister],
+    ) -> AffinePointRegister<EdwardsCurve<E>> {
+        assert_eq!(table.len(), 1 << bits.len());
+        let mut entries = table.to_vec();
+        for bit in bits {
+            entries = entries
+                .chunks_exact(2)
+                .map(|pair| {
+                    let x = self.select(bit, &pair[1].x, &pair[0].x);
+                    let y = self.select(bit, &pair[1].y, &pair[0].y);
+                    AffinePointRegister::new(x, y)
+                })
+                .collect();
+        }
+        entries[0]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use num::bigint::RandBigInt;
+    use rand::thread_rng;
+    use serde::{Deserialize, Serialize};
+
+    use super::*;
+    use crate::chip::builder::tests::*;
+    use crate::chip::ec::edwards::ed25519::params::{Ed25519, Ed25519BaseField};
+    use crate::chip::ec::gadget::{EllipticCurveGadget, EllipticCurveWriter};
+    use crate::chip::ec::point::AffinePoint;
+    use crate::chip::ec::EllipticCurve;
+    use crate::chip::field::instruction::FpInstruction;
+    use crate::chip::utils::biguint_to_bits_le;
+
+    #[derive(Clone, Debug, Copy, Serialize, Deserialize)]
+    pub struct Ed25519MsmTest;
+
+    impl AirParameters for Ed25519MsmTest {
+        type Field = GoldilocksField;
+        type CubicParams = GoldilocksCubicParameters;
+
+        const NUM_ARITHMETIC_COLUMNS: usize = 2112;
+        const NUM_FREE_COLUMNS: usize = 262;
+        const EXTENDED_COLUMNS: usize = 3177;
+        type Instruction = FpInstruction<Ed25519BaseField>;
+    }
+
+    #[test]
+    fn test_ed25519_msm() {
+        type F = GoldilocksField;
+        type L = Ed25519MsmTest;
+        type SC = PoseidonGoldilocksStarkConfig;
+        type E = Ed25519;
+
+        let nb_bits = E::nb_scalar_bits();
+        let num_pairs = 4;
+
+        let mut builder = AirBuilder::<L>::new();
+
+        let points = (0..num_pairs)
+            .map(|_| builder.alloc_public_ec_point())
+            .collect::<Vec<_>>();
+        let scalars = (0..num_pairs)
+            .map(|_| builder.alloc_array_public::<BitRegister>(nb_bits))
+            .collect::<Vec<_>>();
+
+        let result = builder.ed_msm(&scalars, &points);
+
+        let num_rows = 1 << 16;
+        let (air, trace_data) = builder.build();
+        let generator = ArithmeticGenerator::<L>::new(trace_data, num_rows);
+
+        let mut rng = thread_rng();
+        let base = E::ec_generator();
+        let point_values = (0..num_pairs)
+            .map(|_| &base * rng.gen_biguint(256))
+            .collect::<Vec<_>>();
+        let scalar_values = (0..num_pairs)
+            .map(|_| rng.gen_biguint(nb_bits as u64))
+            .collect::<Vec<_>>();
+
+        let expected = point_values
+            .iter()
+            .zip(scalar_values.iter())
+            .map(|(point, scalar)| point * scalar)
+            .reduce(|acc, p| &acc + &p)
+            .unwrap();
+
+        let writer = generator.new_writer();
+        for ((point, scalar), (point_value, scalar_value)) in points
+            .iter()
+            .zip(scalars.iter())
+            .zip(point_values.iter().zip(scalar_values.iter()))
+        {
+            writer.write_ec_point(point, point_value, 0);
+            for (bit, value) in scalar.iter().zip(biguint_to_bits_le(scalar_value, nb_bits)) {
+                writer.write(&bit, &F::from_canonical_u8(value as u8), 0);
+            }
+        }
+        writer.write_global_instructions(&generator.air_data);
+
+        // The accumulator is carried between rows, so the trace is written sequentially.
+        for i in 0..num_rows {
+            writer.write_row_instructions(&generator.air_data, i);
+        }
+
+        for i in (nb_bits - 1..num_rows).step_by(nb_bits) {
+            let value: AffinePoint<E> = writer.read_ec_point(&result, i);
+            assert_eq!(value, expected);
+        }
+
+        let stark = Starky::new(air);
+        let config = SC::standard_fast_config(num_rows);
+        let public = writer.public().unwrap().clone();
+
+        // Generate proof and verify as a stark
+        test_starky(&stark, &config, &generator, &public);
+
+        // Test the recursive proof.
+        test_recursive_starky(stark, config, generator, &public);
+    }
+}
@@ -0,0 +1,406 @@
+use num::{BigUint, One, Zero};
+use serde::{Deserialize, Serialize};
+
+use super::{EdwardsCurve, EdwardsParameters};
+use crate::chip::arithmetic::expression::ArithmeticExpression;
+use crate::chip::builder::AirBuilder;
+use crate::chip::ec::gadget::EllipticCurveWriter;
+use crate::chip::ec::point::{AffinePoint, AffinePointRegister};
+use crate::chip::field::instruction::FromFieldInstruction;
+use crate::chip::field::parameters::FieldParameters;
+use crate::chip::register::array::ArrayRegister;
+use crate::chip::register::bit::BitRegister;
+use crate::chip::register::Register;
+use crate::chip::trace::writer::TraceWriter;
+use crate::chip::AirParameters;
+use crate::math::fixed_base::FixedBaseTable;
+use crate::math::prelude::*;
+use crate::polynomial::Polynomial;
+
+/// The registers of a windowed scalar multiplication, see [`AirBuilder::ed_scalar_mul_windowed`].
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(bound = "")]
+pub struct EdScalarMulWindowedGadget<E: EdwardsParameters> {
+    /// The point multiplied by the scalar.
+    pub point: AffinePointRegister<EdwardsCurve<E>>,
+    /// The accumulator before the current row.
+    pub acc: AffinePointRegister<EdwardsCurve<E>>,
+    /// The accumulator after the current row.
+    pub result: AffinePointRegister<EdwardsCurve<E>>,
+    /// The one-hot selectors of the window processed at each row of a cycle.
+    pub selectors: Vec<BitRegister>,
+    pub width: usize,
+}
+
+impl<L: AirParameters> AirBuilder<L> {
+    /// Computes the scalar multiplication `s * P` processing `width` bits of the scalar per row.
+    ///
+    /// The scalar is given by its little-endian bits, whose number must be a multiple of `width`.
+    /// The multiples `0, P, 2P, ..., (2^width - 1)P` of the point are precomputed, and every row
+    /// multiplies the accumulator by `2^width` and adds the multiple selected by one window of the
+    /// scalar, starting with the most significant one. Compared to [`Self::ed_msm`] with a single
+    /// point, which processes one bit per row, this divides the number of rows and of additions of
+    /// a multiple of the point by `width`, while the total number of doublings is unchanged, at the
+    /// cost of the precomputed table and of wider rows.
+    ///
+    /// The computation repeats every `scalar.len() / width` rows. The returned register holds the
+    /// value of the accumulator after the current row, which on the last row of each cycle is the
+    /// result of the scalar multiplication.
+    pub fn ed_scalar_mul_windowed<E: EdwardsParameters>(
+        &mut self,
+        scalar: &ArrayRegister<BitRegister>,
+        point: &AffinePointRegister<EdwardsCurve<E>>,
+        width: usize,
+    ) -> AffinePointRegister<EdwardsCurve<E>>
+    where
+        L::Instruction: FromFieldInstruction<E::BaseField>,
+    {
+        self.ed_scalar_mul_windowed_gadget(scalar, point, width)
+            .result
+    }
+
+    /// Computes the scalar multiplication of [`Self::ed_scalar_mul_windowed`], returning all the
+    /// registers carried between rows so that the trace can be written with
+    /// [`TraceWriter::write_ed_scalar_mul_windowed`].
+    pub fn ed_scalar_mul_windowed_gadget<E: EdwardsParameters>(
+        &mut self,
+        scalar: &ArrayRegister<BitRegister>,
+        point: &AffinePointRegister<EdwardsCurve<E>>,
+        width: usize,
+    ) -> EdScalarMulWindowedGadget<E>
+    where
+        L::Instruction: FromFieldInstruction<E::BaseField>,
+    {
+        assert!(width > 0, "the window width must be positive");
+        let nb_bits = scalar.len();
+        assert!(
+            nb_bits > 0 && nb_bits % width == 0,
+            "the number of bits of the scalar must be a positive multiple of the window width"
+        );
+        let nb_windows = nb_bits / width;
+
+        // The neutral element of an Edwards curve is the affine point (0, 1).
+        let identity_x = Polynomial::<L::Field>::from_biguint_field(
+            &BigUint::zero(),
+            E::BaseField::NB_BITS_PER_LIMB,
+            E::BaseField::NB_LIMBS,
+        );
+        let identity_y = Polynomial::<L::Field>::from_biguint_field(
+            &BigUint::one(),
+            E::BaseField::NB_BITS_PER_LIMB,
+            E::BaseField::NB_LIMBS,
+        );
+        let identity_x_expr = ArithmeticExpression::from_constant_vec(identity_x.coefficients);
+        let identity_y_expr = ArithmeticExpression::from_constant_vec(identity_y.coefficients);
+        let identity = AffinePointRegister::new(self.fp_zero(), self.fp_one());
+
+        // Precompute the multiples of the point, where the entry at index `m` is `m * P`.
+        let mut table = vec![identity, *point];
+        for _ in 2..(1 << width) {
+            let multiple = self.ed_add(table.last().unwrap(), point);
+            table.push(multiple);
+        }
+
+        // A rotating one-hot selector picks the window of the scalar processed at each row. The
+        // first row of each cycle processes the most significant window.
+        let selectors = self.loop_instr(nb_windows);
+        let window_bits = (0..width)
+            .map(|j| {
+                let expression = (0..nb_windows).fold(ArithmeticExpression::zero(), |acc, i| {
+                    acc + selectors.get_iteration_reg(i).expr()
+                        * scalar.get((nb_windows - 1 - i) * width + j).expr()
+                });
+                self.bit_from_expr(expression)
+            })
+            .collect::<Vec<_>>();
+
+        // The accumulator starts every cycle at the identity.
+        let acc = AffinePointRegister::<EdwardsCurve<E>>::new(self.alloc(), self.alloc());
+        self.set_to_expression_first_row(&acc.x, identity_x_expr.clone());
+        self.set_to_expression_first_row(&acc.y, identity_y_expr.clone());
+
+        // result = 2^width * acc + window * P.
+        let mut result = acc;
+        for _ in 0..width {
+            result = self.ed_double(&result);
+        }
+        let addend = self.ed_msm_table_lookup(&table, &window_bits);
+        let result = self.ed_add(&result, &addend);
+
+        // Carry the result to the next row, resetting the accumulator at the end of each cycle.
+        let end_bit = selectors.get_iteration_reg(nb_windows - 1);
+        self.set_to_expression_transition(
+            &acc.x.next(),
+            end_bit.expr() * identity_x_expr + end_bit.not_expr() * result.x.expr(),
+        );
+        self.set_to_expression_transition(
+            &acc.y.next(),
+            end_bit.expr() * identity_y_expr + end_bit.not_expr() * result.y.expr(),
+        );
+
+        EdScalarMulWindowedGadget {
+            point: *point,
+            acc,
+            result,
+            selectors: (0..nb_windows)
+                .map(|i| selectors.get_iteration_reg(i))
+                .collect(),
+            width,
+        }
+    }
+}
+
+impl<F: PrimeField64> TraceWriter<F> {
+    /// Computes the accumulators of the rows of a cycle of a windowed scalar multiplication of a
+    /// fixed base by `scalar`, to be written with [`Self::write_ed_scalar_mul_windowed`].
+    ///
+    /// The accumulator of each row is carried from the one of the previous row with `width`
+    /// doublings and the addition of the multiple of the base selected by the window, taken from
+    /// the cached powers of `table`. The accumulators are computed once per scalar and shared by
+    /// all the cycles multiplying the same scalar.
+    ///
+    /// Panics if the base of `table` is not the value of the point of the gadget at `row_index`.
+    pub fn ed_scalar_mul_windowed_accumulators<E: EdwardsParameters>(
+        &self,
+        gadget: &EdScalarMulWindowedGadget<E>,
+        table: &FixedBaseTable<EdwardsCurve<E>>,
+        scalar: &BigUint,
+        row_index: usize,
+    ) -> Vec<AffinePoint<EdwardsCurve<E>>> {
+        let point: AffinePoint<EdwardsCurve<E>> = self.read_ec_point(&gadget.point, row_index);
+        assert_eq!(
+            &point,
+            table.base(),
+            "the base of the table is not the point of the gadget"
+        );
+
+        let nb_windows = gadget.selectors.len();
+        let nb_bits = nb_windows * gadget.width;
+        let window_mask = (BigUint::one() << gadget.width) - 1u32;
+
+        // Before processing a window, the accumulator holds the multiple of the base by the most
+        // significant windows of the scalar already processed in the cycle.
+        let mut acc = EdwardsCurve::<E>::neutral();
+        let mut accumulators = Vec::with_capacity(nb_windows);
+        for window in 0..nb_windows {
+            accumulators.push(acc.clone());
+            for _ in 0..gadget.width {
+                acc = acc.ed_double();
+            }
+            let digit = (scalar >> (nb_bits - (window + 1) * gadget.width)) & &window_mask;
+            acc = acc.ed_add(&table.scalar_mul(&digit));
+        }
+        accumulators
+    }
+
+    /// Writes the registers carried to the row `row_index` of a windowed scalar multiplication of
+    /// a fixed base, given the accumulators computed by
+    /// [`Self::ed_scalar_mul_windowed_accumulators`].
+    ///
+    /// The accumulator and the selectors of a row are otherwise written by the instructions of
+    /// the previous row, so that the trace must be written sequentially. Once they are written by
+    /// this method, the instructions of every row can be written independently, for example in
+    /// parallel.
+    pub fn write_ed_scalar_mul_windowed<E: EdwardsParameters>(
+        &self,
+        gadget: &EdScalarMulWindowedGadget<E>,
+        accumulators: &[AffinePoint<EdwardsCurve<E>>],
+        row_index: usize,
+    ) {
+        let nb_windows = gadget.selectors.len();
+        assert_eq!(
+            accumulators.len(),
+            nb_windows,
+            "expected one accumulator per window"
+        );
+        let window = row_index % nb_windows;
+        for (i, selector) in gadget.selectors.iter().enumerate() {
+            self.write(selector, &F::from_bool(i == window), row_index);
+        }
+        self.write_ec_point(&gadget.acc, &accumulators[window], row_index);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use num::bigint::RandBigInt;
+    use rand::thread_rng;
+    use serde::{Deserialize, Serialize};
+
+    use super::*;
+    use crate::chip::builder::tests::*;
+    use crate::chip::ec::edwards::ed25519::params::{Ed25519, Ed25519BaseField};
+    use crate::chip::ec::gadget::{EllipticCurveGadget, EllipticCurveWriter};
+    use crate::chip::ec::EllipticCurve;
+    use crate::chip::field::instruction::FpInstruction;
+    use crate::chip::utils::biguint_to_bits_le;
+
+    #[derive(Clone, Debug, Copy, Serialize, Deserialize)]
+    pub struct Ed25519WindowedScalarMulTest;
+
+    impl AirParameters for Ed25519WindowedScalarMulTest {
+        type Field = GoldilocksField;
+        type CubicParams = GoldilocksCubicParameters;
+
+        const NUM_ARITHMETIC_COLUMNS: usize = 4000;
+        const NUM_FREE_COLUMNS: usize = 400;
+        const EXTENDED_COLUMNS: usize = 6009;
+        type Instruction = FpInstruction<Ed25519BaseField>;
+    }
+
+    #[test]
+    fn test_ed25519_scalar_mul_windowed() {
+        type F = GoldilocksField;
+        type L = Ed25519WindowedScalarMulTest;
+        type SC = PoseidonGoldilocksStarkConfig;
+        type E = Ed25519;
+
+        let nb_bits = E::nb_scalar_bits();
+        let width = 2;
+
+        let mut builder = AirBuilder::<L>::new();
+
+        let point = builder.alloc_public_ec_point();
+        let scalar = builder.alloc_array_public::<BitRegister>(nb_bits);
+
+        let windowed = builder.ed_scalar_mul_windowed(&scalar, &point, width);
+        let bit_by_bit = builder.ed_msm(&[scalar], &[point]);
+
+        let num_rows = 1 << 16;
+        let (air, trace_data) = builder.build();
+        let generator = ArithmeticGenerator::<L>::new(trace_data, num_rows);
+
+        let mut rng = thread_rng();
+        let point_value = &E::ec_generator() * rng.gen_biguint(256);
+        let scalar_value = rng.gen_biguint(nb_bits as u64);
+        let expected = &point_value * &scalar_value;
+
+        let writer = generator.new_writer();
+        writer.write_ec_point(&point, &point_value, 0);
+        for (bit, value) in scalar
+            .iter()
+            .zip(biguint_to_bits_le(&scalar_value, nb_bits))
+        {
+            writer.write(&bit, &F::from_canonical_u8(value as u8), 0);
+        }
+        writer.write_global_instructions(&generator.air_data);
+
+        // The accumulators are carried between rows, so the trace is written sequentially.
+        for i in 0..num_rows {
+            writer.write_row_instructions(&generator.air_data, i);
+        }
+
+        for i in (nb_bits / width - 1..num_rows).step_by(nb_bits / width) {
+            let value: AffinePoint<E> = writer.read_ec_point(&windowed, i);
+            assert_eq!(value, expected);
+        }
+        for i in (nb_bits - 1..num_rows).step_by(nb_bits) {
+            let windowed_value: AffinePoint<E> = writer.read_ec_point(&windowed, i);
+            let bit_by_bit_value: AffinePoint<E> = writer.read_ec_point(&bit_by_bit, i);
+            assert_eq!(windowed_value, bit_by_bit_value);
+        }
+
+        let stark = Starky::new(air);
+        let config = SC::standard_fast_config(num_rows);
+        let public = writer.public().unwrap().clone();
+
+        // Generate proof and verify as a stark
+        test_starky(&stark, &config, &generator, &public);
+    }
+
+    #[test]
+    fn test_ed25519_scalar_mul_windowed_fixed_base() {
+        type F = GoldilocksField;
+        type L = Ed25519WindowedScalarMulTest;
+        type SC = PoseidonGoldilocksStarkConfig;
+        type E = Ed25519;
+
+        let nb_bits = E::nb_scalar_bits();
+        let width = 2;
+
+        let mut builder = AirBuilder::<L>::new();
+
+        let point = builder.alloc_public_ec_point();
+        let scalar = builder.alloc_array_public::<BitRegister>(nb_bits);
+
+        let gadget = builder.ed_scalar_mul_windowed_gadget(&scalar, &point, width);
+
+        let num_rows = 1 << 16;
+        let (air, trace_data) = builder.build();
+
+        let table = FixedBaseTable::<E>::generator();
+        let mut rng = thread_rng();
+        let scalar_value = rng.gen_biguint(nb_bits as u64);
+
+        let write_inputs = |writer: &TraceWriter<F>, generator: &ArithmeticGenerator<L>| {
+            writer.write_ec_point(&point, table.base(), 0);
+            for (bit, value) in scalar
+                .iter()
+                .zip(biguint_to_bits_le(&scalar_value, nb_bits))
+            {
+                writer.write(&bit, &F::from_canonical_u8(value as u8), 0);
+            }
+            writer.write_global_instructions(&generator.air_data);
+        };
+
+        // Write the trace from scratch, carrying the accumulator between rows.
+        let generator = ArithmeticGenerator::<L>::new(trace_data.clone(), num_rows);
+        let writer = generator.new_writer();
+        write_inputs(&writer, &generator);
+        for i in 0..num_rows {
+            writer.write_row_instructions(&generator.air_data, i);
+        }
+
+        // Write the trace from the cached powers of the generator, with independent rows.
+        let cached_generator = ArithmeticGenerator::<L>::new(trace_data, num_rows);
+        let cached_writer = cached_generator.new_writer();
+        write_inputs(&cached_writer, &cached_generator);
+        let accumulators =
+            cached_writer.ed_scalar_mul_windowed_accumulators(&gadget, &table, &scalar_value, 0);
+        (0..num_rows).into_par_iter().for_each(|i| {
+            cached_writer.write_ed_scalar_mul_windowed(&gadget, &accumulators, i);
+            cached_writer.write_row_instructions(&cached_generator.air_data, i);
+        });
+
+        let expected = table.scalar_mul(&scalar_value);
+        for i in (nb_bits / width - 1..num_rows).step_by(nb_bits / width) {
+            let value: AffinePoint<E> = cached_writer.read_ec_point(&gadget.result, i);
+            assert_eq!(value, expected);
+        }
+        assert_eq!(
+            generator.trace_clone().values,
+            cached_generator.trace_clone().values
+        );
+
+        let stark = Starky::new(air);
+        let config = SC::standard_fast_config(num_rows);
+        let public = cached_writer.public().unwrap().clone();
+
+        // Generate proof and verify as a stark
+        test_starky(&stark, &config, &cached_generator, &public);
+    }
+
+    #[test]
+    #[should_panic(expected = "the base of the table is not the point of the gadget")]
+    fn test_ed25519_scalar_mul_windowed_fixed_base_mismatch() {
+        type L = Ed25519WindowedScalarMulTest;
+        type E = Ed25519;
+
+        let nb_bits = E::nb_scalar_bits();
+
+        let mut builder = AirBuilder::<L>::new();
+        let point = builder.alloc_public_ec_point();
+        let scalar = builder.alloc_array_public::<BitRegister>(nb_bits);
+        let gadget = builder.ed_scalar_mul_windowed_gadget(&scalar, &point, 2);
+
+        let num_rows = 1 << 8;
+        let (_, trace_data) = builder.build();
+        let generator = ArithmeticGenerator::<L>::new(trace_data, num_rows);
+        let writer = generator.new_writer();
+
+        let table = FixedBaseTable::<E>::generator();
+        let other_base = table.power(1).clone();
+        writer.write_ec_point(&point, &other_base, 0);
+        writer.ed_scalar_mul_windowed_accumulators(&gadget, &table, &BigUint::one(), 0);
+    }
+}
@@ -6,12 +6,15 @@ use crate::air::AirConstraint;
 use crate::chip::field::add::FpAddInstruction;
 use crate::chip::field::den::FpDenInstruction;
 use crate::chip::field::div::FpDivInstruction;
+use crate::chip::field::div_or_zero::FpDivOrZeroInstruction;
 use crate::chip::field::inner_product::FpInnerProductInstruction;
 use crate::chip::field::instruction::{FpInstruction, FromFieldInstruction};
 use crate::chip::field::mul::FpMulInstruction;
 use crate::chip::field::mul_const::FpMulConstInstruction;
+use crate::chip::field::nonzero::FpNonZeroInstruction;
 use crate::chip::field::sub::FpSubInstruction;
 use crate::chip::instruction::Instruction;
+use crate::chip::register::memory::MemorySlice;
 use crate::chip::trace::writer::{AirWriter, TraceWriter};
 use crate::math::field::PrimeField64;
 use crate::polynomial::parser::PolynomialParser;
@@ -56,6 +59,20 @@ impl<E: EllipticCurve, F: PrimeField64> Instruction<F> for ECInstruction<E> {
             Self::LimbBit(i) => i.write_to_air(writer),
         }
     }
+
+    fn trace_layout(&self) -> Vec<MemorySlice> {
+        match self {
+            Self::Fp(i) => Instruction::<F>::trace_layout(i),
+            Self::LimbBit(i) => Instruction::<F>::trace_layout(i),
+        }
+    }
+
+    fn name(&self) -> &'static str {
+        match self {
+            Self::Fp(i) => Instruction::<F>::name(i),
+            Self::LimbBit(i) => Instruction::<F>::name(i),
+        }
+    }
 }
 
 impl<E: EllipticCurve> FromFieldInstruction<E::BaseField> for ECInstruction<E> {}
@@ -90,6 +107,12 @@ impl<E: EllipticCurve> From<FpDivInstruction<E::BaseField>> for ECInstruction<E>
     }
 }
 
+impl<E: EllipticCurve> From<FpDivOrZeroInstruction<E::BaseField>> for ECInstruction<E> {
+    fn from(i: FpDivOrZeroInstruction<E::BaseField>) -> Self {
+        Self::Fp(i.into())
+    }
+}
+
 impl<E: EllipticCurve> From<FpDenInstruction<E::BaseField>> for ECInstruction<E> {
     fn from(i: FpDenInstruction<E::BaseField>) -> Self {
         Self::Fp(i.into())
@@ -107,3 +130,9 @@ impl<E: EllipticCurve> From<FpMulConstInstruction<E::BaseField>> for ECInstructi
         Self::Fp(i.into())
     }
 }
+
+impl<E: EllipticCurve> From<FpNonZeroInstruction<E::BaseField>> for ECInstruction<E> {
+    fn from(i: FpNonZeroInstruction<E::BaseField>) -> Self {
+        Self::Fp(i.into())
+    }
+}
@@ -13,6 +13,7 @@ use crate::machine::builder::Builder;
 pub mod edwards;
 pub mod gadget;
 mod instruction_set;
+pub mod montgomery;
 pub mod point;
 pub mod scalar;
 pub mod scalar_mul;
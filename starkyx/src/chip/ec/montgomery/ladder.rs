@@ -0,0 +1,273 @@
+use num::{BigUint, One, Zero};
+
+use super::{Curve25519, MontgomeryParameters};
+use crate::chip::arithmetic::expression::ArithmeticExpression;
+use crate::chip::builder::AirBuilder;
+use crate::chip::ec::edwards::ed25519::params::Ed25519BaseField;
+use crate::chip::field::instruction::FromFieldInstruction;
+use crate::chip::field::parameters::FieldParameters;
+use crate::chip::field::register::FieldRegister;
+use crate::chip::register::array::ArrayRegister;
+use crate::chip::register::bit::BitRegister;
+use crate::chip::register::element::ElementRegister;
+use crate::chip::register::u16::U16Register;
+use crate::chip::register::{Register, RegisterSerializable};
+use crate::chip::AirParameters;
+use crate::math::prelude::*;
+use crate::polynomial::Polynomial;
+
+impl<L: AirParameters> AirBuilder<L> {
+    /// Computes the u-coordinate of `s * P` on a Montgomery curve from the u-coordinate `u` of `P`,
+    /// using the x-only Montgomery ladder of RFC 7748.
+    ///
+    /// The scalar is given by its little-endian bits, and every row performs one step of the
+    /// ladder for one bit, starting with the most significant one. The two points of the ladder
+    /// are kept in projective coordinates and swapped according to the bit before and after each
+    /// step. The computation repeats every `scalar.len()` rows, and on the last row of each cycle
+    /// the returned register holds `x * z^(p - 2)` for the projective coordinates `(x : z)` of the
+    /// first point, which is the result of the scalar multiplication. In particular the result is
+    /// zero when `z` vanishes, as for the point at infinity. The returned register is only written
+    /// on the last row of each cycle.
+    pub fn montgomery_ladder<M: MontgomeryParameters>(
+        &mut self,
+        scalar: &ArrayRegister<BitRegister>,
+        u: &FieldRegister<M::BaseField>,
+    ) -> FieldRegister<M::BaseField>
+    where
+        L::Instruction: FromFieldInstruction<M::BaseField>,
+    {
+        let nb_bits = scalar.len();
+        assert!(nb_bits > 0, "the scalar must have at least one bit");
+
+        let constant_expr = |value: BigUint| {
+            let limbs = Polynomial::<L::Field>::from_biguint_field(
+                &value,
+                M::BaseField::NB_BITS_PER_LIMB,
+                M::BaseField::NB_LIMBS,
+            );
+            ArithmeticExpression::from_constant_vec(limbs.coefficients)
+        };
+        let zero_expr = constant_expr(BigUint::zero());
+        let one_expr = constant_expr(BigUint::one());
+
+        // A rotating one-hot selector picks the bit of the scalar processed at each row. The
+        // first row of each cycle processes the most significant bit.
+        let selectors = self.loop_instr(nb_bits);
+        let bit_expr = (0..nb_bits).fold(ArithmeticExpression::zero(), |acc, i| {
+            acc + selectors.get_iteration_reg(i).expr() * scalar.get(nb_bits - 1 - i).expr()
+        });
+        let bit = self.bit_from_expr(bit_expr);
+
+        // The ladder starts every cycle at `(x_2 : z_2) = (1 : 0)` and `(x_3 : z_3) = (u : 1)`.
+        let x_2 = self.alloc::<FieldRegister<M::BaseField>>();
+        let z_2 = self.alloc::<FieldRegister<M::BaseField>>();
+        let x_3 = self.alloc::<FieldRegister<M::BaseField>>();
+        let z_3 = self.alloc::<FieldRegister<M::BaseField>>();
+        self.set_to_expression_first_row(&x_2, one_expr.clone());
+        self.set_to_expression_first_row(&z_2, zero_expr.clone());
+        self.set_to_expression_first_row(&x_3, u.expr());
+        self.set_to_expression_first_row(&z_3, one_expr.clone());
+
+        // Swap the points if the bit is set.
+        let sx_2 = self.select(&bit, &x_3, &x_2);
+        let sz_2 = self.select(&bit, &z_3, &z_2);
+        let sx_3 = self.select(&bit, &x_2, &x_3);
+        let sz_3 = self.select(&bit, &z_2, &z_3);
+
+        // Double the first point and add the two points, whose difference is `P`.
+        let a = self.fp_add(&sx_2, &sz_2);
+        let aa = self.fp_mul(&a, &a);
+        let b = self.fp_sub(&sx_2, &sz_2);
+        let bb = self.fp_mul(&b, &b);
+        let e = self.fp_sub(&aa, &bb);
+        let c = self.fp_add(&sx_3, &sz_3);
+        let d = self.fp_sub(&sx_3, &sz_3);
+        let da = self.fp_mul(&d, &a);
+        let cb = self.fp_mul(&c, &b);
+
+        let da_plus_cb = self.fp_add(&da, &cb);
+        let nx_3 = self.fp_mul(&da_plus_cb, &da_plus_cb);
+        let da_minus_cb = self.fp_sub(&da, &cb);
+        let da_minus_cb_sq = self.fp_mul(&da_minus_cb, &da_minus_cb);
+        let nz_3 = self.fp_mul(u, &da_minus_cb_sq);
+
+        let nx_2 = self.fp_mul(&aa, &bb);
+        let a24_e = self.fp_mul_const(&e, M::A24);
+        let aa_plus_a24_e = self.fp_add(&aa, &a24_e);
+        let nz_2 = self.fp_mul(&e, &aa_plus_a24_e);
+
+        // Swap the points back.
+        let rx_2 = self.select(&bit, &nx_3, &nx_2);
+        let rz_2 = self.select(&bit, &nz_3, &nz_2);
+        let rx_3 = self.select(&bit, &nx_2, &nx_3);
+        let rz_3 = self.select(&bit, &nz_2, &nz_3);
+
+        // Carry the points to the next row, resetting them at the end of each cycle.
+        let end_bit = selectors.get_iteration_reg(nb_bits - 1);
+        for (register, reset, value) in [
+            (x_2, one_expr.clone(), rx_2),
+            (z_2, zero_expr, rz_2),
+            (x_3, u.expr(), rx_3),
+            (z_3, one_expr, rz_3),
+        ] {
+            self.set_to_expression_transition(
+                &register.next(),
+                end_bit.expr() * reset + end_bit.not_expr() * value.expr(),
+            );
+        }
+
+        // Compute the affine coordinate once, at the end of each cycle.
+        let affine = self.alloc_fp_div_or_zero_instruction(&rx_2, &rz_2, true);
+        self.register_instruction_with_filter(affine, end_bit.expr());
+        affine.result()
+    }
+
+    /// Computes the X25519 function of RFC 7748 on the u-coordinate `u` of a point of Curve25519.
+    ///
+    /// The scalar is given by its little-endian bits, and is expected to be clamped as in
+    /// RFC 7748, in which case only its 255 least significant bits need to be given. As in
+    /// RFC 7748, the most significant bit of `u` is masked before running the ladder. See
+    /// [`Self::montgomery_ladder`] for the layout of the computation.
+    pub fn x25519_ladder(
+        &mut self,
+        scalar_bits: &ArrayRegister<BitRegister>,
+        u: &FieldRegister<Ed25519BaseField>,
+    ) -> FieldRegister<Ed25519BaseField>
+    where
+        L::Instruction: FromFieldInstruction<Ed25519BaseField>,
+    {
+        let nb_limbs = Ed25519BaseField::NB_LIMBS;
+        let nb_bits_per_limb = Ed25519BaseField::NB_BITS_PER_LIMB;
+
+        // Decompose the most significant limb of `u` to mask its top bit.
+        let limbs = ArrayRegister::<U16Register>::from_register_unsafe(*u.register());
+        let top_limb = ElementRegister::from_register_unsafe(*limbs.get(nb_limbs - 1).register());
+        let top_bits = self.range_check_bits(&top_limb, nb_bits_per_limb);
+
+        let mut top_bit_weight = vec![L::Field::ZERO; nb_limbs];
+        top_bit_weight[nb_limbs - 1] = L::Field::from_canonical_u32(1 << (nb_bits_per_limb - 1));
+        let masked_u = self.alloc::<FieldRegister<Ed25519BaseField>>();
+        self.set_to_expression(
+            &masked_u,
+            u.expr()
+                - top_bits.get(nb_bits_per_limb - 1).expr()
+                    * ArithmeticExpression::from_constant_vec(top_bit_weight),
+        );
+
+        self.montgomery_ladder::<Curve25519>(scalar_bits, &masked_u)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use curve25519_dalek::constants::X25519_BASEPOINT;
+    use curve25519_dalek::montgomery::MontgomeryPoint;
+    use rand::{thread_rng, Rng};
+    use serde::{Deserialize, Serialize};
+
+    use super::*;
+    use crate::chip::builder::tests::*;
+    use crate::chip::field::instruction::FpInstruction;
+    use crate::chip::utils::{biguint_to_bits_le, field_limbs_to_biguint};
+    use crate::polynomial::to_u16_le_limbs_polynomial;
+
+    #[derive(Clone, Debug, Copy, Serialize, Deserialize)]
+    pub struct X25519LadderTest;
+
+    impl AirParameters for X25519LadderTest {
+        type Field = GoldilocksField;
+        type CubicParams = GoldilocksCubicParameters;
+
+        const NUM_ARITHMETIC_COLUMNS: usize = 2400;
+        const NUM_FREE_COLUMNS: usize = 320;
+        const EXTENDED_COLUMNS: usize = 3700;
+        type Instruction = FpInstruction<Ed25519BaseField>;
+    }
+
+    fn prove_x25519(u_bytes: [u8; 32], scalar_bytes: [u8; 32]) {
+        type F = GoldilocksField;
+        type L = X25519LadderTest;
+        type SC = PoseidonGoldilocksStarkConfig;
+
+        let nb_bits = 255;
+
+        let mut builder = AirBuilder::<L>::new();
+        let u = builder.alloc_public::<FieldRegister<Ed25519BaseField>>();
+        let scalar = builder.alloc_array_public::<BitRegister>(nb_bits);
+        let result = builder.x25519_ladder(&scalar, &u);
+
+        let num_rows = 1 << 16;
+        let (air, trace_data) = builder.build();
+        let generator = ArithmeticGenerator::<L>::new(trace_data, num_rows);
+
+        // Compute the shared secret with the reference implementation.
+        let expected = BigUint::from_bytes_le(
+            &MontgomeryPoint(u_bytes)
+                .mul_clamped(scalar_bytes)
+                .to_bytes(),
+        );
+
+        // Clamp the scalar as in RFC 7748.
+        let mut clamped_bytes = scalar_bytes;
+        clamped_bytes[0] &= 248;
+        clamped_bytes[31] &= 127;
+        clamped_bytes[31] |= 64;
+        let scalar_value = BigUint::from_bytes_le(&clamped_bytes);
+        let u_value = BigUint::from_bytes_le(&u_bytes);
+
+        let writer = generator.new_writer();
+        writer.write(
+            &u,
+            &to_u16_le_limbs_polynomial::<F, Ed25519BaseField>(&u_value),
+            0,
+        );
+        for (bit, value) in scalar
+            .iter()
+            .zip(biguint_to_bits_le(&scalar_value, nb_bits))
+        {
+            writer.write(&bit, &F::from_canonical_u8(value as u8), 0);
+        }
+        writer.write_global_instructions(&generator.air_data);
+
+        // The points of the ladder are carried between rows, so the trace is written sequentially.
+        for i in 0..num_rows {
+            writer.write_row_instructions(&generator.air_data, i);
+        }
+
+        for i in (nb_bits - 1..num_rows).step_by(nb_bits) {
+            let value = field_limbs_to_biguint(writer.read(&result, i).coefficients());
+            assert_eq!(value, expected);
+        }
+
+        let stark = Starky::new(air);
+        let config = SC::standard_fast_config(num_rows);
+        let public = writer.public().unwrap().clone();
+
+        // Generate proof and verify as a stark
+        test_starky(&stark, &config, &generator, &public);
+    }
+
+    #[test]
+    fn test_x25519_ladder() {
+        let mut rng = thread_rng();
+        let public_key = X25519_BASEPOINT.mul_clamped(rng.gen::<[u8; 32]>());
+        prove_x25519(public_key.to_bytes(), rng.gen::<[u8; 32]>());
+    }
+
+    #[test]
+    fn test_x25519_ladder_masks_top_bit() {
+        let mut rng = thread_rng();
+        let mut u_bytes = X25519_BASEPOINT
+            .mul_clamped(rng.gen::<[u8; 32]>())
+            .to_bytes();
+        u_bytes[31] |= 128;
+        prove_x25519(u_bytes, rng.gen::<[u8; 32]>());
+    }
+
+    #[test]
+    fn test_x25519_ladder_zero_u() {
+        // The point `u = 0` has order two, so the ladder ends at the point at infinity, whose
+        // projective coordinate `z` vanishes, and the result is zero.
+        prove_x25519([0u8; 32], thread_rng().gen::<[u8; 32]>());
+    }
+}
@@ -0,0 +1,33 @@
+use core::fmt::Debug;
+
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+
+use crate::chip::ec::edwards::ed25519::params::Ed25519BaseField;
+use crate::chip::field::parameters::{FieldParameters, MAX_NB_LIMBS};
+
+pub mod ladder;
+
+/// A Montgomery curve `B * v^2 = u^3 + A * u^2 + u`.
+pub trait MontgomeryParameters:
+    Debug + Send + Sync + Copy + Serialize + DeserializeOwned + 'static
+{
+    type BaseField: FieldParameters;
+
+    /// The constant `(A - 2) / 4` used in the doubling formula of the ladder.
+    const A24: [u16; MAX_NB_LIMBS];
+}
+
+/// Curve25519, the Montgomery curve `v^2 = u^3 + 486662 * u^2 + u` used by X25519.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct Curve25519;
+
+impl MontgomeryParameters for Curve25519 {
+    type BaseField = Ed25519BaseField;
+
+    // 121665 = 65536 + 56129.
+    const A24: [u16; MAX_NB_LIMBS] = [
+        56129, 1, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0,
+        0, 0, 0,
+    ];
+}
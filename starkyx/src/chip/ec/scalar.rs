@@ -10,6 +10,7 @@ use crate::chip::instruction::Instruction;
 use crate::chip::register::array::ArrayRegister;
 use crate::chip::register::bit::BitRegister;
 use crate::chip::register::element::ElementRegister;
+use crate::chip::register::memory::MemorySlice;
 use crate::chip::register::{Register, RegisterSerializable};
 use crate::chip::trace::writer::{AirWriter, TraceWriter};
 use crate::chip::AirParameters;
@@ -164,6 +165,10 @@ impl<F: PrimeField64> Instruction<F> for LimbBitInstruction {
             writer.write(&self.bit_accumulator.next(), &next_value);
         }
     }
+
+    fn trace_layout(&self) -> Vec<MemorySlice> {
+        vec![*self.bit.register(), *self.bit_accumulator.register()]
+    }
 }
 
 #[cfg(test)]
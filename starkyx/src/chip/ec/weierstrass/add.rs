@@ -0,0 +1,225 @@
+use serde::{Deserialize, Serialize};
+
+use super::{SWCurve, WeierstrassParameters};
+use crate::chip::builder::AirBuilder;
+use crate::chip::ec::gadget::EllipticCurveWriter;
+use crate::chip::ec::point::{AffinePoint, AffinePointRegister};
+use crate::chip::field::div::FpDivInstruction;
+use crate::chip::field::instruction::FromFieldInstruction;
+use crate::chip::field::mul::FpMulInstruction;
+use crate::chip::field::register::FieldRegister;
+use crate::chip::field::sub::FpSubInstruction;
+use crate::chip::instruction::Instruction;
+use crate::chip::register::Register;
+use crate::chip::trace::writer::TraceWriter;
+use crate::chip::AirParameters;
+use crate::math::prelude::*;
+
+/// The addition of two points `p` and `q` on a short Weierstrass curve, using the affine formulas
+///
+/// `lambda = (y_2 - y_1) / (x_2 - x_1)`,
+/// `x_3 = lambda^2 - x_1 - x_2`,
+/// `y_3 = lambda * (x_1 - x_3) - y_1`.
+///
+/// The slope `lambda` is not defined when `x_1 == x_2`, so the gadget only adds different points
+/// that are not opposite of each other. In particular, doubling a point must be done with a
+/// separate gadget, such as [`AirBuilder::sw_double`].
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+#[serde(bound = "")]
+pub struct SWAddGadget<E: WeierstrassParameters> {
+    pub p: AffinePointRegister<SWCurve<E>>,
+    pub q: AffinePointRegister<SWCurve<E>>,
+    pub slope: FieldRegister<E::BaseField>,
+    pub result: AffinePointRegister<SWCurve<E>>,
+    slope_numerator: FpSubInstruction<E::BaseField>,
+    slope_denominator: FpSubInstruction<E::BaseField>,
+    slope_quotient: FpDivInstruction<E::BaseField>,
+    slope_squared: FpMulInstruction<E::BaseField>,
+    x_3_partial: FpSubInstruction<E::BaseField>,
+    x_3: FpSubInstruction<E::BaseField>,
+    x_1_minus_x_3: FpSubInstruction<E::BaseField>,
+    y_3_partial: FpMulInstruction<E::BaseField>,
+    y_3: FpSubInstruction<E::BaseField>,
+}
+
+impl<L: AirParameters> AirBuilder<L> {
+    /// Allocates the registers and instructions of the addition of two different points `p` and
+    /// `q` on a short Weierstrass curve, see [`SWAddGadget`].
+    pub fn sw_add_gadget<E: WeierstrassParameters>(
+        &mut self,
+        p: &AffinePointRegister<SWCurve<E>>,
+        q: &AffinePointRegister<SWCurve<E>>,
+    ) -> SWAddGadget<E>
+    where
+        L::Instruction: FromFieldInstruction<E::BaseField>,
+    {
+        let is_trace = [p.x, p.y, q.x, q.y].iter().any(|r| r.is_trace());
+        let alloc = |builder: &mut Self| {
+            if is_trace {
+                builder.alloc::<FieldRegister<E::BaseField>>()
+            } else {
+                builder.alloc_public::<FieldRegister<E::BaseField>>()
+            }
+        };
+
+        // lambda = (y_2 - y_1) / (x_2 - x_1).
+        let numerator = alloc(self);
+        let slope_numerator = self.set_fp_sub(&q.y, &p.y, &numerator);
+        let denominator = alloc(self);
+        let slope_denominator = self.set_fp_sub(&q.x, &p.x, &denominator);
+        let slope = alloc(self);
+        let slope_quotient = self.set_fp_div(&numerator, &denominator, &slope);
+
+        // x_3 = lambda^2 - x_1 - x_2.
+        let slope_sq = alloc(self);
+        let slope_squared = self.set_fp_mul(&slope, &slope, &slope_sq);
+        let x_3_minus_x_2 = alloc(self);
+        let x_3_partial = self.set_fp_sub(&slope_sq, &p.x, &x_3_minus_x_2);
+        let result_x = alloc(self);
+        let x_3 = self.set_fp_sub(&x_3_minus_x_2, &q.x, &result_x);
+
+        // y_3 = lambda * (x_1 - x_3) - y_1.
+        let x_diff = alloc(self);
+        let x_1_minus_x_3 = self.set_fp_sub(&p.x, &result_x, &x_diff);
+        let y_3_plus_y_1 = alloc(self);
+        let y_3_partial = self.set_fp_mul(&slope, &x_diff, &y_3_plus_y_1);
+        let result_y = alloc(self);
+        let y_3 = self.set_fp_sub(&y_3_plus_y_1, &p.y, &result_y);
+
+        SWAddGadget {
+            p: *p,
+            q: *q,
+            slope,
+            result: AffinePointRegister::new(result_x, result_y),
+            slope_numerator,
+            slope_denominator,
+            slope_quotient,
+            slope_squared,
+            x_3_partial,
+            x_3,
+            x_1_minus_x_3,
+            y_3_partial,
+            y_3,
+        }
+    }
+
+    /// Add two different points `p` and `q` on a short Weierstrass curve.
+    ///
+    /// The points must not be equal nor opposite, see [`SWAddGadget`].
+    pub fn sw_add<E: WeierstrassParameters>(
+        &mut self,
+        p: &AffinePointRegister<SWCurve<E>>,
+        q: &AffinePointRegister<SWCurve<E>>,
+    ) -> AffinePointRegister<SWCurve<E>>
+    where
+        L::Instruction: FromFieldInstruction<E::BaseField>,
+    {
+        self.sw_add_gadget(p, q).result
+    }
+}
+
+impl<F: PrimeField64> TraceWriter<F> {
+    /// Writes the intermediate registers and the result of an addition gadget at `row_index`,
+    /// given that the input points are already written, and returns the sum.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the input points have the same x-coordinate, in which case the addition formula
+    /// does not apply.
+    pub fn write_sw_add<E: WeierstrassParameters>(
+        &self,
+        gadget: &SWAddGadget<E>,
+        row_index: usize,
+    ) -> AffinePoint<SWCurve<E>> {
+        let p = self.read_ec_point(&gadget.p, row_index);
+        let q = self.read_ec_point(&gadget.q, row_index);
+        assert_ne!(
+            p.x, q.x,
+            "Cannot add points with the same x-coordinate, use a doubling gadget instead"
+        );
+
+        gadget.slope_numerator.write(self, row_index);
+        gadget.slope_denominator.write(self, row_index);
+        gadget.slope_quotient.write(self, row_index);
+        gadget.slope_squared.write(self, row_index);
+        gadget.x_3_partial.write(self, row_index);
+        gadget.x_3.write(self, row_index);
+        gadget.x_1_minus_x_3.write(self, row_index);
+        gadget.y_3_partial.write(self, row_index);
+        gadget.y_3.write(self, row_index);
+
+        self.read_ec_point(&gadget.result, row_index)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use num::bigint::RandBigInt;
+    use rand::thread_rng;
+
+    use super::*;
+    use crate::chip::builder::tests::*;
+    use crate::chip::ec::gadget::EllipticCurveGadget;
+    use crate::chip::ec::weierstrass::secp256k1::{Secp256k1, Secp256k1BaseField};
+    use crate::chip::field::instruction::FpInstruction;
+
+    #[derive(Clone, Debug, Copy, Serialize, Deserialize)]
+    pub struct Secp256k1AddTest;
+
+    impl AirParameters for Secp256k1AddTest {
+        type Field = GoldilocksField;
+        type CubicParams = GoldilocksCubicParameters;
+
+        const NUM_ARITHMETIC_COLUMNS: usize = 1152;
+        const NUM_FREE_COLUMNS: usize = 2;
+        const EXTENDED_COLUMNS: usize = 1737;
+        type Instruction = FpInstruction<Secp256k1BaseField>;
+    }
+
+    #[test]
+    fn test_secp256k1_add_gadget() {
+        type L = Secp256k1AddTest;
+        type SC = PoseidonGoldilocksStarkConfig;
+        type E = Secp256k1;
+
+        let mut builder = AirBuilder::<L>::new();
+
+        let p = builder.alloc_ec_point();
+        let q = builder.alloc_ec_point();
+
+        let gadget = builder.sw_add_gadget(&p, &q);
+
+        let num_rows = 1 << 16;
+        let (air, trace_data) = builder.build();
+        let generator = ArithmeticGenerator::<L>::new(trace_data, num_rows);
+
+        let base = E::generator();
+        let mut rng = thread_rng();
+        let a = rng.gen_biguint(256);
+        let b = rng.gen_biguint(256);
+        let p_int = base.sw_scalar_mul(&a);
+        let q_int = base.sw_scalar_mul(&b);
+        let expected = p_int.sw_add(&q_int);
+
+        let writer = generator.new_writer();
+        writer.write_global_instructions(&generator.air_data);
+        (0..num_rows).for_each(|i| {
+            writer.write_ec_point(&p, &p_int, i);
+            writer.write_ec_point(&q, &q_int, i);
+            let sum = writer.write_sw_add(&gadget, i);
+            assert_eq!(sum, expected);
+            writer.write_row_instructions(&generator.air_data, i);
+        });
+
+        let stark = Starky::new(air);
+        let config = SC::standard_fast_config(num_rows);
+
+        let public_inputs = writer.0.public.read().unwrap().clone();
+
+        // Generate proof and verify as a stark
+        test_starky(&stark, &config, &generator, &public_inputs);
+
+        // Test the recursive proof.
+        test_recursive_starky(stark, config, generator, &public_inputs);
+    }
+}
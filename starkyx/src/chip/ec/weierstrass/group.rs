@@ -31,19 +31,6 @@ impl<L: AirParameters> AirBuilder<L> {
         AffinePointRegister::<SWCurve<E>> { x: x_3, y: y_3 }
     }
 
-    /// Add two different points `p` and `q` on a short Weierstrass curve.
-    pub fn sw_add<E: WeierstrassParameters>(
-        &mut self,
-        p: &AffinePointRegister<SWCurve<E>>,
-        q: &AffinePointRegister<SWCurve<E>>,
-    ) -> AffinePointRegister<SWCurve<E>>
-    where
-        L::Instruction: FromFieldInstruction<E::BaseField>,
-    {
-        let slope = self.sw_slope_different(p, q);
-        self.sw_add_with_slope(p, q, &slope)
-    }
-
     /// Doubles a point `p` on a short Weierstrass curve.
     pub fn sw_double<E: WeierstrassParameters>(
         &mut self,
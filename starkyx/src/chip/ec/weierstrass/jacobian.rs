@@ -0,0 +1,254 @@
+use num::Zero;
+use serde::{Deserialize, Serialize};
+
+use super::{SWCurve, WeierstrassParameters};
+use crate::chip::builder::AirBuilder;
+use crate::chip::ec::point::AffinePointRegister;
+use crate::chip::ec::EllipticCurve;
+use crate::chip::field::instruction::FromFieldInstruction;
+use crate::chip::field::register::FieldRegister;
+use crate::chip::AirParameters;
+
+/// A point in Jacobian coordinates `(X, Y, Z)`, representing the affine point `(X/Z^2, Y/Z^3)`.
+///
+/// The addition and doubling formulas in Jacobian coordinates do not need any inversion, and a
+/// single pair of divisions converts the result back to affine coordinates at the end.
+///
+/// Note that in a chip an inverse is a witness checked by a single multiplication, so the affine
+/// formulas are not more expensive than the Jacobian ones: each Jacobian operation uses more
+/// columns than its affine counterpart. The Jacobian formulas are useful when the result is only
+/// needed up to the projective equivalence, deferring the conversion to affine coordinates.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub struct JacobianPointRegister<E: EllipticCurve> {
+    pub x: FieldRegister<E::BaseField>,
+    pub y: FieldRegister<E::BaseField>,
+    pub z: FieldRegister<E::BaseField>,
+}
+
+impl<E: EllipticCurve> JacobianPointRegister<E> {
+    pub fn new(
+        x: FieldRegister<E::BaseField>,
+        y: FieldRegister<E::BaseField>,
+        z: FieldRegister<E::BaseField>,
+    ) -> Self {
+        Self { x, y, z }
+    }
+}
+
+impl<L: AirParameters> AirBuilder<L> {
+    /// Converts an affine point to Jacobian coordinates by setting `Z = 1`.
+    pub fn sw_to_jacobian<E: WeierstrassParameters>(
+        &mut self,
+        p: &AffinePointRegister<SWCurve<E>>,
+    ) -> JacobianPointRegister<SWCurve<E>> {
+        let z = self.fp_one();
+        JacobianPointRegister::new(p.x, p.y, z)
+    }
+
+    /// Converts a point in Jacobian coordinates to affine coordinates `(X/Z^2, Y/Z^3)`.
+    pub fn sw_jacobian_to_affine<E: WeierstrassParameters>(
+        &mut self,
+        p: &JacobianPointRegister<SWCurve<E>>,
+    ) -> AffinePointRegister<SWCurve<E>>
+    where
+        L::Instruction: FromFieldInstruction<E::BaseField>,
+    {
+        let z_2 = self.fp_mul(&p.z, &p.z);
+        let z_3 = self.fp_mul(&z_2, &p.z);
+        let x = self.fp_div(&p.x, &z_2);
+        let y = self.fp_div(&p.y, &z_3);
+        AffinePointRegister::new(x, y)
+    }
+
+    /// Doubles a point `p` in Jacobian coordinates on a short Weierstrass curve.
+    ///
+    /// Uses the formulas `S = 4XY^2`, `M = 3X^2 + aZ^4`, `X' = M^2 - 2S`,
+    /// `Y' = M(S - X') - 8Y^4` and `Z' = 2YZ`. The term `aZ^4` is skipped for curves with `a = 0`.
+    pub fn sw_jacobian_double<E: WeierstrassParameters>(
+        &mut self,
+        p: &JacobianPointRegister<SWCurve<E>>,
+    ) -> JacobianPointRegister<SWCurve<E>>
+    where
+        L::Instruction: FromFieldInstruction<E::BaseField>,
+    {
+        let (x, y, z) = (p.x, p.y, p.z);
+
+        let x_2 = self.fp_mul(&x, &x);
+        let y_2 = self.fp_mul(&y, &y);
+        let y_4 = self.fp_mul(&y_2, &y_2);
+
+        // S = 4 * X * Y^2.
+        let x_y_2 = self.fp_mul(&x, &y_2);
+        let s = self.fp_add(&x_y_2, &x_y_2);
+        let s = self.fp_add(&s, &s);
+
+        // M = 3 * X^2 + a * Z^4.
+        let m = self.fp_add(&x_2, &x_2);
+        let mut m = self.fp_add(&m, &x_2);
+        let a_int = E::a_int();
+        if !a_int.is_zero() {
+            let a = self.fp_constant(&a_int);
+            let z_2 = self.fp_mul(&z, &z);
+            let z_4 = self.fp_mul(&z_2, &z_2);
+            let a_z_4 = self.fp_mul(&a, &z_4);
+            m = self.fp_add(&m, &a_z_4);
+        }
+
+        // X' = M^2 - 2 * S.
+        let m_2 = self.fp_mul(&m, &m);
+        let x_3 = self.fp_sub(&m_2, &s);
+        let x_3 = self.fp_sub(&x_3, &s);
+
+        // Y' = M * (S - X') - 8 * Y^4.
+        let s_minus_x_3 = self.fp_sub(&s, &x_3);
+        let y_3 = self.fp_mul(&m, &s_minus_x_3);
+        let y_4_8 = self.fp_add(&y_4, &y_4);
+        let y_4_8 = self.fp_add(&y_4_8, &y_4_8);
+        let y_4_8 = self.fp_add(&y_4_8, &y_4_8);
+        let y_3 = self.fp_sub(&y_3, &y_4_8);
+
+        // Z' = 2 * Y * Z.
+        let y_z = self.fp_mul(&y, &z);
+        let z_3 = self.fp_add(&y_z, &y_z);
+
+        JacobianPointRegister::new(x_3, y_3, z_3)
+    }
+
+    /// Adds two different points `p` and `q` in Jacobian coordinates on a short Weierstrass curve.
+    ///
+    /// As for [`Self::sw_add`], the points are assumed to be different and not opposite.
+    pub fn sw_jacobian_add<E: WeierstrassParameters>(
+        &mut self,
+        p: &JacobianPointRegister<SWCurve<E>>,
+        q: &JacobianPointRegister<SWCurve<E>>,
+    ) -> JacobianPointRegister<SWCurve<E>>
+    where
+        L::Instruction: FromFieldInstruction<E::BaseField>,
+    {
+        let (x_1, y_1, z_1) = (p.x, p.y, p.z);
+        let (x_2, y_2, z_2) = (q.x, q.y, q.z);
+
+        // U1 = X1 * Z2^2, U2 = X2 * Z1^2.
+        let z_1_2 = self.fp_mul(&z_1, &z_1);
+        let z_2_2 = self.fp_mul(&z_2, &z_2);
+        let u_1 = self.fp_mul(&x_1, &z_2_2);
+        let u_2 = self.fp_mul(&x_2, &z_1_2);
+
+        // S1 = Y1 * Z2^3, S2 = Y2 * Z1^3.
+        let y_1_z_2 = self.fp_mul(&y_1, &z_2);
+        let s_1 = self.fp_mul(&y_1_z_2, &z_2_2);
+        let y_2_z_1 = self.fp_mul(&y_2, &z_1);
+        let s_2 = self.fp_mul(&y_2_z_1, &z_1_2);
+
+        // H = U2 - U1, R = S2 - S1.
+        let h = self.fp_sub(&u_2, &u_1);
+        let r = self.fp_sub(&s_2, &s_1);
+
+        let h_2 = self.fp_mul(&h, &h);
+        let h_3 = self.fp_mul(&h, &h_2);
+        let v = self.fp_mul(&u_1, &h_2);
+
+        // X3 = R^2 - H^3 - 2 * V.
+        let r_2 = self.fp_mul(&r, &r);
+        let x_3 = self.fp_sub(&r_2, &h_3);
+        let v_2 = self.fp_add(&v, &v);
+        let x_3 = self.fp_sub(&x_3, &v_2);
+
+        // Y3 = R * (V - X3) - S1 * H^3.
+        let v_minus_x_3 = self.fp_sub(&v, &x_3);
+        let y_3 = self.fp_mul(&r, &v_minus_x_3);
+        let s_1_h_3 = self.fp_mul(&s_1, &h_3);
+        let y_3 = self.fp_sub(&y_3, &s_1_h_3);
+
+        // Z3 = Z1 * Z2 * H.
+        let z_1_z_2 = self.fp_mul(&z_1, &z_2);
+        let z_3 = self.fp_mul(&z_1_z_2, &h);
+
+        JacobianPointRegister::new(x_3, y_3, z_3)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use num::bigint::RandBigInt;
+    use num::BigUint;
+    use rand::thread_rng;
+
+    use super::*;
+    use crate::chip::builder::tests::*;
+    use crate::chip::ec::gadget::{EllipticCurveGadget, EllipticCurveWriter};
+    use crate::chip::ec::weierstrass::secp256k1::{
+        Secp256k1, Secp256k1BaseField, Secp256k1Parameters,
+    };
+    use crate::chip::field::instruction::FpInstruction;
+
+    #[derive(Clone, Debug, Copy, Serialize, Deserialize)]
+    pub struct JacobianTest;
+
+    impl AirParameters for JacobianTest {
+        type Field = GoldilocksField;
+        type CubicParams = GoldilocksCubicParameters;
+
+        const NUM_ARITHMETIC_COLUMNS: usize = 6100;
+        const NUM_FREE_COLUMNS: usize = 2;
+        const EXTENDED_COLUMNS: usize = 9200;
+        type Instruction = FpInstruction<Secp256k1BaseField>;
+    }
+
+    #[test]
+    fn test_secp256k1_jacobian_scalar_mul() {
+        type L = JacobianTest;
+        type E = Secp256k1;
+
+        // Computes `5 * p = 2 * (2 * p) + p` in Jacobian coordinates.
+        let mut builder = AirBuilder::<L>::new();
+        let p = builder.alloc_ec_point();
+        let p_jacobian = builder.sw_to_jacobian::<Secp256k1Parameters>(&p);
+        let p_2 = builder.sw_jacobian_double(&p_jacobian);
+        let p_4 = builder.sw_jacobian_double(&p_2);
+        let p_5 = builder.sw_jacobian_add(&p_4, &p_jacobian);
+        let result = builder.sw_jacobian_to_affine(&p_5);
+        let (_, trace_data, jacobian_report) = builder.finalize();
+
+        // The same computation in affine coordinates.
+        let mut affine_builder = AirBuilder::<L>::new();
+        let q: AffinePointRegister<E> = affine_builder.alloc_ec_point();
+        let q_2 = affine_builder.ec_double(&q);
+        let q_4 = affine_builder.ec_double(&q_2);
+        let affine_result = affine_builder.ec_add(&q_4, &q);
+        let (_, affine_trace_data, affine_report) = affine_builder.finalize();
+
+        // An inverse costs a single multiplication check in a chip, so the affine formulas use
+        // fewer columns than the inversion-free ones.
+        assert!(
+            affine_report.arithmetic_used < jacobian_report.arithmetic_used,
+            "affine: {:?}, jacobian: {:?}",
+            affine_report,
+            jacobian_report
+        );
+
+        let num_rows = 1 << 4;
+        let generator = ArithmeticGenerator::<L>::new(trace_data, num_rows);
+        let writer = generator.new_writer();
+        writer.write_global_instructions(&generator.air_data);
+
+        let affine_generator = ArithmeticGenerator::<L>::new(affine_trace_data, num_rows);
+        let affine_writer = affine_generator.new_writer();
+        affine_writer.write_global_instructions(&affine_generator.air_data);
+
+        let base = E::generator();
+        let mut rng = thread_rng();
+        for i in 0..num_rows {
+            let p_int = base.sw_scalar_mul(&rng.gen_biguint(256));
+            writer.write_ec_point(&p, &p_int, i);
+            writer.write_row_instructions(&generator.air_data, i);
+
+            affine_writer.write_ec_point(&q, &p_int, i);
+            affine_writer.write_row_instructions(&affine_generator.air_data, i);
+
+            let expected = p_int.sw_scalar_mul(&BigUint::from(5u32));
+            assert_eq!(affine_writer.read_ec_point(&affine_result, i), expected);
+            assert_eq!(writer.read_ec_point(&result, i), expected);
+        }
+    }
+}
@@ -8,9 +8,12 @@ use crate::chip::field::instruction::FromFieldInstruction;
 use crate::chip::field::parameters::{FieldParameters, MAX_NB_LIMBS};
 use crate::chip::AirParameters;
 
+pub mod add;
 pub mod biguint_operations;
 pub mod bn254;
 pub mod group;
+pub mod jacobian;
+pub mod secp256k1;
 pub mod slope;
 
 /// Parameters that specify a short Weierstrass curve : y^2 = x^3 + ax + b.
@@ -0,0 +1,79 @@
+use num::{BigUint, Num, Zero};
+use serde::{Deserialize, Serialize};
+
+use super::{SWCurve, WeierstrassParameters};
+use crate::chip::ec::EllipticCurveParameters;
+use crate::chip::field::parameters::FieldParameters;
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+/// Secp256k1 curve parameter
+pub struct Secp256k1Parameters;
+
+pub type Secp256k1 = SWCurve<Secp256k1Parameters>;
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Serialize, Deserialize)]
+/// Secp256k1 base field parameter
+pub struct Secp256k1BaseField;
+
+impl FieldParameters for Secp256k1BaseField {
+    const NB_BITS_PER_LIMB: usize = 16;
+
+    const NB_LIMBS: usize = 16;
+
+    const NB_WITNESS_LIMBS: usize = 2 * Self::NB_LIMBS - 2;
+
+    // Base field modulus:
+    //  115792089237316195423570985008687907853269984665640564039457584007908834671663
+    const MODULUS: [u16; crate::chip::field::parameters::MAX_NB_LIMBS] = [
+        64559, 65535, 65534, 65535, 65535, 65535, 65535, 65535, 65535, 65535, 65535, 65535, 65535,
+        65535, 65535, 65535, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0,
+    ];
+
+    const WITNESS_OFFSET: usize = 1usize << 21;
+}
+
+impl EllipticCurveParameters for Secp256k1Parameters {
+    type BaseField = Secp256k1BaseField;
+}
+
+impl WeierstrassParameters for Secp256k1Parameters {
+    const A: [u16; crate::chip::field::parameters::MAX_NB_LIMBS] = [
+        0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0,
+        0, 0,
+    ];
+
+    const B: [u16; crate::chip::field::parameters::MAX_NB_LIMBS] = [
+        7, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0,
+        0, 0,
+    ];
+
+    fn generator() -> (BigUint, BigUint) {
+        let x = BigUint::from_str_radix(
+            "55066263022277343669578718895168534326250603453777594175500187360389116729240",
+            10,
+        )
+        .unwrap();
+        let y = BigUint::from_str_radix(
+            "32670510020758816978083085130507043184471273380659243275938904335757337482424",
+            10,
+        )
+        .unwrap();
+        (x, y)
+    }
+
+    fn prime_group_order() -> num::BigUint {
+        BigUint::from_str_radix(
+            "115792089237316195423570985008687907852837564279074904382605163141518161494337",
+            10,
+        )
+        .unwrap()
+    }
+
+    fn a_int() -> BigUint {
+        BigUint::zero()
+    }
+
+    fn b_int() -> BigUint {
+        BigUint::from(7u32)
+    }
+}
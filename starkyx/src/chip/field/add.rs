@@ -4,9 +4,12 @@ use super::parameters::FieldParameters;
 use super::register::FieldRegister;
 use super::util;
 use crate::air::AirConstraint;
+use crate::chip::arithmetic::expression::ArithmeticExpression;
 use crate::chip::builder::AirBuilder;
 use crate::chip::instruction::Instruction;
 use crate::chip::register::array::ArrayRegister;
+use crate::chip::register::bit::BitRegister;
+use crate::chip::register::memory::MemorySlice;
 use crate::chip::register::u16::U16Register;
 use crate::chip::register::{Register, RegisterSerializable};
 use crate::chip::trace::writer::{AirWriter, TraceWriter};
@@ -14,13 +17,15 @@ use crate::chip::utils::{digits_to_biguint, split_u32_limbs_to_u16_limbs};
 use crate::chip::AirParameters;
 use crate::math::prelude::*;
 use crate::polynomial::parser::PolynomialParser;
-use crate::polynomial::{to_u16_le_limbs_polynomial, Polynomial};
+use crate::polynomial::Polynomial;
 
 #[derive(Debug, Clone, Copy, Serialize, Deserialize)]
 #[serde(bound = "")]
 pub struct FpAddInstruction<P: FieldParameters> {
     pub a: FieldRegister<P>,
     pub b: FieldRegister<P>,
+    /// An optional third summand, see [`AirBuilder::fp_add3`].
+    pub c: Option<FieldRegister<P>>,
     pub result: FieldRegister<P>,
     pub(crate) carry: FieldRegister<P>,
     pub(crate) witness_low: ArrayRegister<U16Register>,
@@ -55,6 +60,88 @@ impl<L: AirParameters> AirBuilder<L> {
         result
     }
 
+    /// Given two field elements `a` and `b`, computes the sum `a + b = c` and returns `c` together
+    /// with the carry bit of the addition.
+    ///
+    /// For an honest prover, the carry bit is equal to `1` if and only if `a + b >= p`. The
+    /// constraints only enforce `a + b = c + carry * p` with `c` range checked to its limbs and
+    /// do not enforce `c < p`, so a malicious prover may instead set the carry to `0` and write
+    /// `c = a + b` whenever `a + b` fits in the limbs of `c`. Callers that rely on the carry bit
+    /// must constrain `c < p` themselves.
+    pub fn fp_add_carry<P: FieldParameters>(
+        &mut self,
+        a: &FieldRegister<P>,
+        b: &FieldRegister<P>,
+    ) -> (FieldRegister<P>, BitRegister)
+    where
+        L::Instruction: From<FpAddInstruction<P>>,
+    {
+        let is_trace = a.is_trace() || b.is_trace();
+        let (result, carry_bit) = if is_trace {
+            (
+                self.alloc::<FieldRegister<P>>(),
+                self.alloc::<BitRegister>(),
+            )
+        } else {
+            (
+                self.alloc_public::<FieldRegister<P>>(),
+                self.alloc_public::<BitRegister>(),
+            )
+        };
+        let instr = self.set_fp_add_internal(a, b, None, &result);
+
+        // The carry of the addition is at most one, so only its first limb can be non-zero.
+        let carry = ArrayRegister::<U16Register>::from_register_unsafe(*instr.carry.register());
+        if is_trace {
+            self.set_to_expression(&carry_bit, carry.get(0).expr());
+        } else {
+            self.set_to_expression_public(&carry_bit, carry.get(0).expr());
+            self.assert_expression_zero(
+                carry_bit.expr() * (carry_bit.expr() - ArithmeticExpression::one()),
+            );
+        }
+        for limb in carry.iter().skip(1) {
+            self.assert_expression_zero(limb.expr());
+        }
+
+        (result, carry_bit)
+    }
+
+    /// Given three field elements `a`, `b` and `c`, computes the sum `a + b + c`.
+    ///
+    /// The sum is checked by a single field operation whose carry is constrained to `{0, 1, 2}`,
+    /// which is cheaper than two chained calls to [`Self::fp_add`].
+    pub fn fp_add3<P: FieldParameters>(
+        &mut self,
+        a: &FieldRegister<P>,
+        b: &FieldRegister<P>,
+        c: &FieldRegister<P>,
+    ) -> FieldRegister<P>
+    where
+        L::Instruction: From<FpAddInstruction<P>>,
+    {
+        let is_trace = a.is_trace() || b.is_trace() || c.is_trace();
+        let result = if is_trace {
+            self.alloc::<FieldRegister<P>>()
+        } else {
+            self.alloc_public::<FieldRegister<P>>()
+        };
+        let instr = self.set_fp_add_internal(a, b, Some(c), &result);
+
+        // The carry of the addition is at most two, so only its first limb can be non-zero.
+        let carry = ArrayRegister::<U16Register>::from_register_unsafe(*instr.carry.register());
+        let carry_0 = carry.get(0).expr();
+        let two = L::Field::from_canonical_u8(2);
+        self.assert_expression_zero(
+            carry_0.clone() * (carry_0.clone() - ArithmeticExpression::one()) * (carry_0 - two),
+        );
+        for limb in carry.iter().skip(1) {
+            self.assert_expression_zero(limb.expr());
+        }
+
+        result
+    }
+
     pub fn set_fp_add<P: FieldParameters>(
         &mut self,
         a: &FieldRegister<P>,
@@ -63,7 +150,21 @@ impl<L: AirParameters> AirBuilder<L> {
     ) where
         L::Instruction: From<FpAddInstruction<P>>,
     {
-        let is_trace = a.is_trace() || b.is_trace() || result.is_trace();
+        self.set_fp_add_internal(a, b, None, result);
+    }
+
+    fn set_fp_add_internal<P: FieldParameters>(
+        &mut self,
+        a: &FieldRegister<P>,
+        b: &FieldRegister<P>,
+        c: Option<&FieldRegister<P>>,
+        result: &FieldRegister<P>,
+    ) -> FpAddInstruction<P>
+    where
+        L::Instruction: From<FpAddInstruction<P>>,
+    {
+        let is_trace =
+            a.is_trace() || b.is_trace() || c.map_or(false, |c| c.is_trace()) || result.is_trace();
         let carry: FieldRegister<P>;
         let witness_low: ArrayRegister<U16Register>;
         let witness_high: ArrayRegister<U16Register>;
@@ -79,6 +180,7 @@ impl<L: AirParameters> AirBuilder<L> {
         let instr = FpAddInstruction {
             a: *a,
             b: *b,
+            c: c.copied(),
             result: *result,
             carry,
             witness_low,
@@ -89,6 +191,7 @@ impl<L: AirParameters> AirBuilder<L> {
         } else {
             self.register_global_instruction(instr);
         }
+        instr
     }
 }
 
@@ -100,7 +203,11 @@ impl<AP: PolynomialParser, P: FieldParameters> AirConstraint<AP> for FpAddInstru
         let p_result = self.result.eval(parser);
         let p_carry = self.carry.eval(parser);
 
-        let p_a_plus_b = parser.poly_add(&p_a, &p_b);
+        let mut p_a_plus_b = parser.poly_add(&p_a, &p_b);
+        if let Some(c) = self.c {
+            let p_c = c.eval(parser);
+            p_a_plus_b = parser.poly_add(&p_a_plus_b, &p_c);
+        }
         let p_a_plus_b_minus_result = parser.poly_sub(&p_a_plus_b, &p_result);
         let p_limbs = parser.constant_poly(&Polynomial::from_iter(util::modulus_field_iter::<
             AP::Field,
@@ -110,53 +217,73 @@ impl<AP: PolynomialParser, P: FieldParameters> AirConstraint<AP> for FpAddInstru
         let p_mul_times_carry = parser.poly_mul(&p_carry, &p_limbs);
         let p_vanishing = parser.poly_sub(&p_a_plus_b_minus_result, &p_mul_times_carry);
 
-        let p_witness_low = Polynomial::from_coefficients(self.witness_low.eval_vec(parser));
-        let p_witness_high = Polynomial::from_coefficients(self.witness_high.eval_vec(parser));
+        let p_witness_low =
+            Polynomial::from_coefficients(self.witness_low.packed_slice(parser).to_vec());
+        let p_witness_high =
+            Polynomial::from_coefficients(self.witness_high.packed_slice(parser).to_vec());
 
         util::eval_field_operation::<AP, P>(parser, &p_vanishing, &p_witness_low, &p_witness_high)
     }
 }
 
+/// Computes the result, the carry and the low and high limbs of the witness of the sum of `p_a`,
+/// `p_b` and `p_c`.
+///
+/// The result and carry are computed by [`util::reduce_sum_limbs`], without branching on the
+/// values of the summands.
+#[allow(clippy::type_complexity)]
+fn fp_add_witness<F: PrimeField64, P: FieldParameters>(
+    p_a: &Polynomial<F>,
+    p_b: &Polynomial<F>,
+    p_c: Option<&Polynomial<F>>,
+) -> (Polynomial<F>, Polynomial<F>, Vec<F>, Vec<F>) {
+    let to_digits = |p: &Polynomial<F>| {
+        p.coefficients
+            .iter()
+            .map(|x| x.as_canonical_u64() as u16)
+            .collect::<Vec<_>>()
+    };
+    let a_digits = to_digits(p_a);
+    let b_digits = to_digits(p_b);
+    let c_digits = p_c.map(to_digits).unwrap_or_default();
+
+    // Compute field addition in the integers.
+    let (result_digits, carry_digits) =
+        util::reduce_sum_limbs::<P>(&[&a_digits, &b_digits, &c_digits]);
+    debug_assert_eq!(
+        digits_to_biguint(&carry_digits) * P::modulus() + digits_to_biguint(&result_digits),
+        digits_to_biguint(&a_digits) + digits_to_biguint(&b_digits) + digits_to_biguint(&c_digits)
+    );
+
+    // Make little endian polynomial limbs.
+    let p_modulus = Polynomial::from_iter(util::modulus_field_iter::<F, P>());
+    let p_result = Polynomial::from_iter(result_digits.iter().map(|x| F::from_canonical_u16(*x)));
+    let p_carry = Polynomial::from_iter(carry_digits.iter().map(|x| F::from_canonical_u16(*x)));
+
+    // Compute the vanishing polynomial.
+    let p_sum = match p_c {
+        Some(p_c) => &(p_a + p_b) + p_c,
+        None => p_a + p_b,
+    };
+    let p_vanishing = &p_sum - &p_result - &p_carry * &p_modulus;
+    debug_assert_eq!(p_vanishing.degree(), P::NB_WITNESS_LIMBS);
+
+    // Compute the witness.
+    let p_witness = util::compute_root_quotient_and_shift(&p_vanishing, P::WITNESS_OFFSET);
+    let (p_witness_low, p_witness_high) = split_u32_limbs_to_u16_limbs(&p_witness);
+
+    (p_result, p_carry, p_witness_low, p_witness_high)
+}
+
 // Instruction trait
 impl<F: PrimeField64, P: FieldParameters> Instruction<F> for FpAddInstruction<P> {
     fn write(&self, writer: &TraceWriter<F>, row_index: usize) {
         let p_a = writer.read(&self.a, row_index);
         let p_b = writer.read(&self.b, row_index);
+        let p_c = self.c.map(|c| writer.read(&c, row_index));
 
-        let a_digits = p_a
-            .coefficients
-            .iter()
-            .map(|x| x.as_canonical_u64() as u16)
-            .collect::<Vec<_>>();
-        let b_digits = p_b
-            .coefficients
-            .iter()
-            .map(|x| x.as_canonical_u64() as u16)
-            .collect::<Vec<_>>();
-
-        let a = digits_to_biguint(&a_digits);
-        let b = digits_to_biguint(&b_digits);
-
-        // Compute field addition in the integers.
-        let modulus = P::modulus();
-        let result = (&a + &b) % &modulus;
-        let carry = (&a + &b - &result) / &modulus;
-        debug_assert!(result < modulus);
-        debug_assert!(carry < modulus);
-        debug_assert_eq!(&carry * &modulus, a + b - &result);
-
-        // Make little endian polynomial limbs.
-        let p_modulus = to_u16_le_limbs_polynomial::<F, P>(&modulus);
-        let p_result = to_u16_le_limbs_polynomial::<F, P>(&result);
-        let p_carry = to_u16_le_limbs_polynomial::<F, P>(&carry);
-
-        // Compute the vanishing polynomial.
-        let p_vanishing = &p_a + &p_b - &p_result - &p_carry * &p_modulus;
-        debug_assert_eq!(p_vanishing.degree(), P::NB_WITNESS_LIMBS);
-
-        // Compute the witness.
-        let p_witness = util::compute_root_quotient_and_shift(&p_vanishing, P::WITNESS_OFFSET);
-        let (p_witness_low, p_witness_high) = split_u32_limbs_to_u16_limbs(&p_witness);
+        let (p_result, p_carry, p_witness_low, p_witness_high) =
+            fp_add_witness::<F, P>(&p_a, &p_b, p_c.as_ref());
 
         writer.write(&self.result, &p_result, row_index);
         writer.write(&self.carry, &p_carry, row_index);
@@ -167,58 +294,37 @@ impl<F: PrimeField64, P: FieldParameters> Instruction<F> for FpAddInstruction<P>
     fn write_to_air(&self, writer: &mut impl AirWriter<Field = F>) {
         let p_a = writer.read(&self.a);
         let p_b = writer.read(&self.b);
+        let p_c = self.c.map(|c| writer.read(&c));
 
-        let a_digits = p_a
-            .coefficients
-            .iter()
-            .map(|x| x.as_canonical_u64() as u16)
-            .collect::<Vec<_>>();
-        let b_digits = p_b
-            .coefficients
-            .iter()
-            .map(|x| x.as_canonical_u64() as u16)
-            .collect::<Vec<_>>();
-
-        let a = digits_to_biguint(&a_digits);
-        let b = digits_to_biguint(&b_digits);
-
-        // Compute field addition in the integers.
-        let modulus = P::modulus();
-        let result = (&a + &b) % &modulus;
-        let carry = (&a + &b - &result) / &modulus;
-        debug_assert!(result < modulus);
-        debug_assert!(carry < modulus);
-        debug_assert_eq!(&carry * &modulus, a + b - &result);
-
-        // Make little endian polynomial limbs.
-        let p_modulus = to_u16_le_limbs_polynomial::<F, P>(&modulus);
-        let p_result = to_u16_le_limbs_polynomial::<F, P>(&result);
-        let p_carry = to_u16_le_limbs_polynomial::<F, P>(&carry);
-
-        // Compute the vanishing polynomial.
-        let p_vanishing = &p_a + &p_b - &p_result - &p_carry * &p_modulus;
-        debug_assert_eq!(p_vanishing.degree(), P::NB_WITNESS_LIMBS);
-
-        // Compute the witness.
-        let p_witness = util::compute_root_quotient_and_shift(&p_vanishing, P::WITNESS_OFFSET);
-        let (p_witness_low, p_witness_high) = split_u32_limbs_to_u16_limbs(&p_witness);
+        let (p_result, p_carry, p_witness_low, p_witness_high) =
+            fp_add_witness::<F, P>(&p_a, &p_b, p_c.as_ref());
 
         writer.write(&self.result, &p_result);
         writer.write(&self.carry, &p_carry);
         writer.write_array(&self.witness_low, &p_witness_low);
         writer.write_array(&self.witness_high, &p_witness_high);
     }
+
+    fn trace_layout(&self) -> Vec<MemorySlice> {
+        vec![
+            *self.result.register(),
+            *self.carry.register(),
+            *self.witness_low.register(),
+            *self.witness_high.register(),
+        ]
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use num::bigint::RandBigInt;
-    use num::BigUint;
-    use rand::thread_rng;
+    use num::{BigUint, Zero};
+    use rand::{thread_rng, Rng};
 
     use super::*;
     use crate::chip::builder::tests::*;
     use crate::chip::field::parameters::tests::Fp25519;
+    use crate::polynomial::to_u16_le_limbs_polynomial;
 
     #[derive(Clone, Debug, Copy, Serialize, Deserialize)]
     struct FpAddTest;
@@ -234,6 +340,21 @@ mod tests {
         type Instruction = FpAddInstruction<Fp25519>;
     }
 
+    #[test]
+    fn test_fp_add_constrained_registers() {
+        type L = FpAddTest;
+        type P = Fp25519;
+
+        let mut builder = AirBuilder::<L>::new();
+        builder.set_strict(true);
+        let a = builder.alloc::<FieldRegister<P>>();
+        let b = builder.alloc::<FieldRegister<P>>();
+        let _ = builder.fp_add(&a, &b);
+
+        assert!(builder.unconstrained_registers().is_empty());
+        builder.build();
+    }
+
     #[test]
     fn test_fpadd() {
         type F = GoldilocksField;
@@ -299,4 +420,270 @@ mod tests {
         // Test the recursive proof.
         test_recursive_starky(stark, config, generator, &public);
     }
+
+    /// Computes the witness of a sum of field elements by division in the integers.
+    #[allow(clippy::type_complexity)]
+    fn fp_add_witness_biguint<F: PrimeField64, P: FieldParameters>(
+        summands: &[&BigUint],
+    ) -> (Polynomial<F>, Polynomial<F>, Vec<F>, Vec<F>) {
+        let modulus = P::modulus();
+        let sum = summands.iter().copied().sum::<BigUint>();
+        let result = &sum % &modulus;
+        let carry = (&sum - &result) / &modulus;
+
+        let p_modulus = to_u16_le_limbs_polynomial::<F, P>(&modulus);
+        let p_result = to_u16_le_limbs_polynomial::<F, P>(&result);
+        let p_carry = to_u16_le_limbs_polynomial::<F, P>(&carry);
+        let p_sum = summands
+            .iter()
+            .map(|x| to_u16_le_limbs_polynomial::<F, P>(x))
+            .reduce(|acc, x| &acc + &x)
+            .unwrap();
+        let p_vanishing = &p_sum - &p_result - &p_carry * &p_modulus;
+        let p_witness = util::compute_root_quotient_and_shift(&p_vanishing, P::WITNESS_OFFSET);
+        let (p_witness_low, p_witness_high) = split_u32_limbs_to_u16_limbs(&p_witness);
+
+        (p_result, p_carry, p_witness_low, p_witness_high)
+    }
+
+    #[test]
+    fn test_fp_add_witness_matches_biguint() {
+        type F = GoldilocksField;
+        type P = Fp25519;
+
+        let p = P::modulus();
+        let max = (BigUint::from(1u32) << 256) - 1u32;
+        let mut rng = thread_rng();
+
+        let mut values = vec![
+            BigUint::zero(),
+            BigUint::from(1u32),
+            &p - 1u32,
+            p.clone(),
+            max.clone(),
+        ];
+        values.extend((0..10).map(|_| rng.gen_biguint(256) % &p));
+        values.extend((0..10).map(|_| rng.gen_biguint(256)));
+
+        for a in values.iter() {
+            for b in values.iter() {
+                let p_a = to_u16_le_limbs_polynomial::<F, P>(a);
+                let p_b = to_u16_le_limbs_polynomial::<F, P>(b);
+                assert_eq!(
+                    fp_add_witness::<F, P>(&p_a, &p_b, None),
+                    fp_add_witness_biguint::<F, P>(&[a, b])
+                );
+
+                let c = &values[rng.gen_range(0..values.len())];
+                let p_c = to_u16_le_limbs_polynomial::<F, P>(c);
+                assert_eq!(
+                    fp_add_witness::<F, P>(&p_a, &p_b, Some(&p_c)),
+                    fp_add_witness_biguint::<F, P>(&[a, b, c])
+                );
+            }
+        }
+    }
+
+    #[derive(Clone, Debug, Copy, Serialize, Deserialize)]
+    struct FpAddComposedTest;
+
+    impl AirParameters for FpAddComposedTest {
+        type Field = GoldilocksField;
+        type CubicParams = GoldilocksCubicParameters;
+
+        const NUM_ARITHMETIC_COLUMNS: usize = 248;
+        const NUM_FREE_COLUMNS: usize = 2;
+        const EXTENDED_COLUMNS: usize = 381;
+
+        type Instruction = FpAddInstruction<Fp25519>;
+    }
+
+    /// A gadget allocating two field elements and their sum.
+    fn fp_add_gadget<L: AirParameters>(builder: &mut AirBuilder<L>) -> [FieldRegister<Fp25519>; 3]
+    where
+        L::Instruction: From<FpAddInstruction<Fp25519>>,
+    {
+        let a = builder.alloc::<FieldRegister<Fp25519>>();
+        let b = builder.alloc::<FieldRegister<Fp25519>>();
+        let c = builder.fp_add(&a, &b);
+        [a, b, c]
+    }
+
+    #[test]
+    fn test_fp_add_composed_gadgets() {
+        type F = GoldilocksField;
+        type L = FpAddComposedTest;
+        type SC = PoseidonGoldilocksStarkConfig;
+
+        let p = Fp25519::modulus();
+
+        // Two independent gadgets composed in a single chip.
+        let mut builder = AirBuilder::<L>::new();
+        let gadgets = [fp_add_gadget(&mut builder), fp_add_gadget(&mut builder)];
+
+        let (air, trace_data) = builder.build();
+        let num_rows = 1 << 16;
+        let generator = ArithmeticGenerator::<L>::new(trace_data, num_rows);
+
+        (0..num_rows).into_par_iter().for_each(|i| {
+            let mut rng = thread_rng();
+            let writer = generator.new_writer();
+            let mut expected = Vec::new();
+            for [a, b, _] in gadgets.iter() {
+                let a_int: BigUint = rng.gen_biguint(256) % &p;
+                let b_int = rng.gen_biguint(256) % &p;
+                writer.write(a, &Polynomial::<F>::from_biguint_field(&a_int, 16, 16), i);
+                writer.write(b, &Polynomial::<F>::from_biguint_field(&b_int, 16, 16), i);
+                expected.push((a_int + b_int) % &p);
+            }
+            writer.write_row_instructions(&generator.air_data, i);
+
+            for ([_, _, c], expected) in gadgets.iter().zip(expected) {
+                assert_eq!(
+                    writer.read(c, i),
+                    Polynomial::<F>::from_biguint_field(&expected, 16, 16)
+                );
+            }
+        });
+
+        let stark = Starky::new(air);
+        let config = SC::standard_fast_config(num_rows);
+
+        // Generate proof and verify as a stark
+        test_starky(&stark, &config, &generator, &[]);
+    }
+
+    #[derive(Clone, Debug, Copy, Serialize, Deserialize)]
+    struct FpAddCarryTest;
+
+    impl AirParameters for FpAddCarryTest {
+        type Field = GoldilocksField;
+        type CubicParams = GoldilocksCubicParameters;
+
+        const NUM_ARITHMETIC_COLUMNS: usize = 124;
+        const NUM_FREE_COLUMNS: usize = 3;
+        const EXTENDED_COLUMNS: usize = 195;
+
+        type Instruction = FpAddInstruction<Fp25519>;
+    }
+
+    #[test]
+    fn test_fp_add_carry() {
+        type F = GoldilocksField;
+        type L = FpAddCarryTest;
+        type SC = PoseidonGoldilocksStarkConfig;
+        type P = Fp25519;
+
+        let p = Fp25519::modulus();
+
+        let mut builder = AirBuilder::<L>::new();
+
+        let a = builder.alloc::<FieldRegister<P>>();
+        let b = builder.alloc::<FieldRegister<P>>();
+        let (_, carry) = builder.fp_add_carry(&a, &b);
+
+        let (air, trace_data) = builder.build();
+        let num_rows = 1 << 16;
+        let generator = ArithmeticGenerator::<L>::new(trace_data, num_rows);
+
+        let writer = generator.new_writer();
+        writer.write_global_instructions(&generator.air_data);
+
+        (0..num_rows).into_par_iter().for_each(|i| {
+            let mut rng = thread_rng();
+            let a_int: BigUint = rng.gen_biguint(256) % &p;
+            let b_int = rng.gen_biguint(256) % &p;
+            let p_a = Polynomial::<F>::from_biguint_field(&a_int, 16, 16);
+            let p_b = Polynomial::<F>::from_biguint_field(&b_int, 16, 16);
+
+            writer.write(&a, &p_a, i);
+            writer.write(&b, &p_b, i);
+            writer.write_row_instructions(&generator.air_data, i);
+
+            let expected_carry = F::from_canonical_u8((a_int + b_int >= p) as u8);
+            assert_eq!(writer.read(&carry, i), expected_carry);
+        });
+
+        let stark = Starky::new(air);
+        let config = SC::standard_fast_config(num_rows);
+
+        let public = writer.public().unwrap().clone();
+        // Generate proof and verify as a stark
+        test_starky(&stark, &config, &generator, &public);
+    }
+
+    #[derive(Clone, Debug, Copy, Serialize, Deserialize)]
+    struct FpAdd3Test;
+
+    impl AirParameters for FpAdd3Test {
+        type Field = GoldilocksField;
+        type CubicParams = GoldilocksCubicParameters;
+
+        const NUM_ARITHMETIC_COLUMNS: usize = 140;
+        const NUM_FREE_COLUMNS: usize = 2;
+        const EXTENDED_COLUMNS: usize = 219;
+
+        type Instruction = FpAddInstruction<Fp25519>;
+    }
+
+    #[test]
+    fn test_fp_add3() {
+        type F = GoldilocksField;
+        type L = FpAdd3Test;
+        type SC = PoseidonGoldilocksStarkConfig;
+        type P = Fp25519;
+
+        let p = Fp25519::modulus();
+
+        let mut builder = AirBuilder::<L>::new();
+
+        let a = builder.alloc::<FieldRegister<P>>();
+        let b = builder.alloc::<FieldRegister<P>>();
+        let c = builder.alloc::<FieldRegister<P>>();
+        let sum = builder.fp_add3(&a, &b, &c);
+
+        let (air, trace_data) = builder.build();
+        let num_rows = 1 << 16;
+        let generator = ArithmeticGenerator::<L>::new(trace_data, num_rows);
+
+        let writer = generator.new_writer();
+        writer.write_global_instructions(&generator.air_data);
+
+        (0..num_rows).into_par_iter().for_each(|i| {
+            // The first row has the largest possible carry of two.
+            let (a_int, b_int, c_int) = if i == 0 {
+                (&p - 1u32, &p - 1u32, &p - 1u32)
+            } else {
+                let mut rng = thread_rng();
+                (
+                    rng.gen_biguint(256) % &p,
+                    rng.gen_biguint(256) % &p,
+                    rng.gen_biguint(256) % &p,
+                )
+            };
+            let p_a = Polynomial::<F>::from_biguint_field(&a_int, 16, 16);
+            let p_b = Polynomial::<F>::from_biguint_field(&b_int, 16, 16);
+            let p_c = Polynomial::<F>::from_biguint_field(&c_int, 16, 16);
+
+            writer.write(&a, &p_a, i);
+            writer.write(&b, &p_b, i);
+            writer.write(&c, &p_c, i);
+            writer.write_row_instructions(&generator.air_data, i);
+
+            let expected = (a_int + b_int + c_int) % &p;
+            assert_eq!(
+                writer.read(&sum, i),
+                Polynomial::<F>::from_biguint_field(&expected, 16, 16)
+            );
+        });
+
+        let stark = Starky::new(air);
+        let config = SC::standard_fast_config(num_rows);
+
+        // Generate proof and verify as a stark
+        test_starky(&stark, &config, &generator, &[]);
+
+        // Test the recursive proof.
+        test_recursive_starky(stark, config, generator, &[]);
+    }
 }
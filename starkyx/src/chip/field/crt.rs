@@ -0,0 +1,314 @@
+//! Multiplication modulo the product of two primes, using the Chinese remainder theorem.
+//!
+//! A value `x` modulo `N = p * q` is represented by its residues `x mod p` and `x mod q`. A
+//! product modulo `N` is then computed as two field multiplications, one in each residue channel,
+//! whose carries and witnesses are allocated in a single block of range checked limbs. The value
+//! modulo `N` is reconstructed from the residues by the trace writer.
+
+use num::BigUint;
+use serde::{Deserialize, Serialize};
+
+use super::mul::FpMulInstruction;
+use super::parameters::FieldParameters;
+use super::register::FieldRegister;
+use crate::air::AirConstraint;
+use crate::chip::builder::AirBuilder;
+use crate::chip::instruction::Instruction;
+use crate::chip::register::memory::MemorySlice;
+use crate::chip::register::u16::U16Register;
+use crate::chip::register::RegisterSerializable;
+use crate::chip::trace::writer::{AirWriter, TraceWriter};
+use crate::chip::utils::field_limbs_to_biguint;
+use crate::chip::AirParameters;
+use crate::math::prelude::*;
+use crate::polynomial::parser::PolynomialParser;
+use crate::polynomial::to_u16_le_limbs_polynomial;
+
+/// A value modulo `P::modulus() * Q::modulus()`, represented by its residues modulo the two
+/// moduli.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+#[serde(bound = "")]
+pub struct CrtRegister<P: FieldParameters, Q: FieldParameters> {
+    pub p: FieldRegister<P>,
+    pub q: FieldRegister<Q>,
+}
+
+/// The product of two values in both residue channels.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+#[serde(bound = "")]
+pub struct FpCrtMulInstruction<P: FieldParameters, Q: FieldParameters> {
+    pub mul_p: FpMulInstruction<P>,
+    pub mul_q: FpMulInstruction<Q>,
+}
+
+impl<P: FieldParameters, Q: FieldParameters> CrtRegister<P, Q> {
+    pub fn new(p: FieldRegister<P>, q: FieldRegister<Q>) -> Self {
+        Self { p, q }
+    }
+
+    pub fn is_trace(&self) -> bool {
+        self.p.is_trace() || self.q.is_trace()
+    }
+}
+
+/// Reconstructs the value modulo `P::modulus() * Q::modulus()` with residues `r_p` and `r_q`.
+///
+/// The moduli must be distinct primes.
+pub fn crt_reconstruct<P: FieldParameters, Q: FieldParameters>(
+    r_p: &BigUint,
+    r_q: &BigUint,
+) -> BigUint {
+    let p = P::modulus();
+    let q = Q::modulus();
+    debug_assert!(r_p < &p && r_q < &q);
+
+    // x = r_p + p * ((r_q - r_p) * p^{-1} mod q)
+    let p_inv = (&p % &q).modpow(&(&q - 2u32), &q);
+    let diff = (r_q + &q - r_p % &q) % &q;
+    r_p + &p * ((diff * p_inv) % &q)
+}
+
+impl<L: AirParameters> AirBuilder<L> {
+    pub fn alloc_crt<P: FieldParameters, Q: FieldParameters>(&mut self) -> CrtRegister<P, Q> {
+        let p = self.alloc::<FieldRegister<P>>();
+        let q = self.alloc::<FieldRegister<Q>>();
+        CrtRegister::new(p, q)
+    }
+
+    pub fn alloc_public_crt<P: FieldParameters, Q: FieldParameters>(
+        &mut self,
+    ) -> CrtRegister<P, Q> {
+        let p = self.alloc_public::<FieldRegister<P>>();
+        let q = self.alloc_public::<FieldRegister<Q>>();
+        CrtRegister::new(p, q)
+    }
+
+    /// Given two values `a` and `b` modulo `N = P::modulus() * Q::modulus()`, computes the
+    /// product `a * b mod N`.
+    pub fn fp_mul_crt<P: FieldParameters, Q: FieldParameters>(
+        &mut self,
+        a: &CrtRegister<P, Q>,
+        b: &CrtRegister<P, Q>,
+    ) -> CrtRegister<P, Q>
+    where
+        L::Instruction: From<FpCrtMulInstruction<P, Q>>,
+    {
+        let is_trace = a.is_trace() || b.is_trace();
+
+        // The carries and witnesses of both channels share one block of range checked limbs.
+        let num_p_limbs = FpMulInstruction::<P>::NUM_RANGE_CHECKED_LIMBS;
+        let num_limbs = num_p_limbs + FpMulInstruction::<Q>::NUM_RANGE_CHECKED_LIMBS;
+        let (result, limbs) = if is_trace {
+            (
+                self.alloc_crt::<P, Q>(),
+                self.alloc_array::<U16Register>(num_limbs),
+            )
+        } else {
+            (
+                self.alloc_public_crt::<P, Q>(),
+                self.alloc_array_public::<U16Register>(num_limbs),
+            )
+        };
+
+        let instr = FpCrtMulInstruction {
+            mul_p: FpMulInstruction::from_limbs(
+                a.p,
+                b.p,
+                result.p,
+                limbs.get_subarray(0..num_p_limbs),
+            ),
+            mul_q: FpMulInstruction::from_limbs(
+                a.q,
+                b.q,
+                result.q,
+                limbs.get_subarray(num_p_limbs..num_limbs),
+            ),
+        };
+
+        if is_trace {
+            self.register_instruction(instr);
+        } else {
+            self.register_global_instruction(instr);
+        }
+        result
+    }
+}
+
+impl<AP: PolynomialParser, P: FieldParameters, Q: FieldParameters> AirConstraint<AP>
+    for FpCrtMulInstruction<P, Q>
+{
+    fn eval(&self, parser: &mut AP) {
+        AirConstraint::<AP>::eval(&self.mul_p, parser);
+        AirConstraint::<AP>::eval(&self.mul_q, parser);
+    }
+}
+
+impl<F: PrimeField64, P: FieldParameters, Q: FieldParameters> Instruction<F>
+    for FpCrtMulInstruction<P, Q>
+{
+    fn write(&self, writer: &TraceWriter<F>, row_index: usize) {
+        Instruction::<F>::write(&self.mul_p, writer, row_index);
+        Instruction::<F>::write(&self.mul_q, writer, row_index);
+    }
+
+    fn write_to_air(&self, writer: &mut impl AirWriter<Field = F>) {
+        Instruction::<F>::write_to_air(&self.mul_p, writer);
+        Instruction::<F>::write_to_air(&self.mul_q, writer);
+    }
+
+    fn trace_layout(&self) -> Vec<MemorySlice> {
+        let mut layout = Instruction::<F>::trace_layout(&self.mul_p);
+        layout.extend(Instruction::<F>::trace_layout(&self.mul_q));
+        layout
+    }
+}
+
+impl<F: PrimeField64> TraceWriter<F> {
+    /// Writes the residues of `value` modulo `P::modulus()` and `Q::modulus()`.
+    pub fn write_crt<P: FieldParameters, Q: FieldParameters>(
+        &self,
+        data: &CrtRegister<P, Q>,
+        value: &BigUint,
+        row_index: usize,
+    ) {
+        self.write_crt_p(data, &(value % P::modulus()), row_index);
+        self.write_crt_q(data, &(value % Q::modulus()), row_index);
+    }
+
+    /// Writes the residue modulo `P::modulus()` of a value.
+    pub fn write_crt_p<P: FieldParameters, Q: FieldParameters>(
+        &self,
+        data: &CrtRegister<P, Q>,
+        residue: &BigUint,
+        row_index: usize,
+    ) {
+        let p_residue = to_u16_le_limbs_polynomial::<F, P>(residue);
+        self.write(&data.p, &p_residue, row_index);
+    }
+
+    /// Writes the residue modulo `Q::modulus()` of a value.
+    pub fn write_crt_q<P: FieldParameters, Q: FieldParameters>(
+        &self,
+        data: &CrtRegister<P, Q>,
+        residue: &BigUint,
+        row_index: usize,
+    ) {
+        let q_residue = to_u16_le_limbs_polynomial::<F, Q>(residue);
+        self.write(&data.q, &q_residue, row_index);
+    }
+
+    /// Reads the residues of a value and reconstructs it modulo `P::modulus() * Q::modulus()`.
+    pub fn read_crt<P: FieldParameters, Q: FieldParameters>(
+        &self,
+        data: &CrtRegister<P, Q>,
+        row_index: usize,
+    ) -> BigUint {
+        let r_p = field_limbs_to_biguint(self.read(&data.p, row_index).coefficients());
+        let r_q = field_limbs_to_biguint(self.read(&data.q, row_index).coefficients());
+        crt_reconstruct::<P, Q>(&r_p, &r_q)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use num::bigint::RandBigInt;
+    use rand::thread_rng;
+
+    use super::*;
+    use crate::chip::builder::tests::*;
+    use crate::chip::field::parameters::MAX_NB_LIMBS;
+
+    /// The prime `2^32 - 5`.
+    #[derive(Debug, Default, Clone, Copy, PartialEq, Serialize, Deserialize)]
+    struct Fp32A;
+
+    impl FieldParameters for Fp32A {
+        const NB_BITS_PER_LIMB: usize = 16;
+        const NB_LIMBS: usize = 2;
+        const NB_WITNESS_LIMBS: usize = 2 * Self::NB_LIMBS - 2;
+        const MODULUS: [u16; MAX_NB_LIMBS] = [
+            65531, 65535, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0,
+            0, 0, 0, 0, 0, 0,
+        ];
+        const WITNESS_OFFSET: usize = 1usize << 20;
+    }
+
+    /// The prime `2^32 - 17`.
+    #[derive(Debug, Default, Clone, Copy, PartialEq, Serialize, Deserialize)]
+    struct Fp32B;
+
+    impl FieldParameters for Fp32B {
+        const NB_BITS_PER_LIMB: usize = 16;
+        const NB_LIMBS: usize = 2;
+        const NB_WITNESS_LIMBS: usize = 2 * Self::NB_LIMBS - 2;
+        const MODULUS: [u16; MAX_NB_LIMBS] = [
+            65519, 65535, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0,
+            0, 0, 0, 0, 0, 0,
+        ];
+        const WITNESS_OFFSET: usize = 1usize << 20;
+    }
+
+    #[derive(Clone, Debug, Copy, Serialize, Deserialize)]
+    struct FpCrtMulTest;
+
+    impl AirParameters for FpCrtMulTest {
+        type Field = GoldilocksField;
+        type CubicParams = GoldilocksCubicParameters;
+
+        const NUM_ARITHMETIC_COLUMNS: usize = 24;
+        const NUM_FREE_COLUMNS: usize = 2;
+        const EXTENDED_COLUMNS: usize = 45;
+
+        type Instruction = FpCrtMulInstruction<Fp32A, Fp32B>;
+    }
+
+    #[test]
+    fn test_crt_reconstruct() {
+        let n = Fp32A::modulus() * Fp32B::modulus();
+        let mut rng = thread_rng();
+        for _ in 0..100 {
+            let x = rng.gen_biguint_below(&n);
+            let r_p = &x % Fp32A::modulus();
+            let r_q = &x % Fp32B::modulus();
+            assert_eq!(crt_reconstruct::<Fp32A, Fp32B>(&r_p, &r_q), x);
+        }
+    }
+
+    #[test]
+    fn test_fp_mul_crt() {
+        type L = FpCrtMulTest;
+        type SC = PoseidonGoldilocksStarkConfig;
+
+        let n = Fp32A::modulus() * Fp32B::modulus();
+
+        let mut builder = AirBuilder::<L>::new();
+        let a = builder.alloc_crt::<Fp32A, Fp32B>();
+        let b = builder.alloc_crt::<Fp32A, Fp32B>();
+        let result = builder.fp_mul_crt(&a, &b);
+
+        let (air, trace_data) = builder.build();
+        let num_rows = 1 << 16;
+        let generator = ArithmeticGenerator::<L>::new(trace_data, num_rows);
+
+        let writer = generator.new_writer();
+        (0..num_rows).into_par_iter().for_each(|i| {
+            let mut rng = thread_rng();
+            let a_int = rng.gen_biguint_below(&n);
+            let b_int = rng.gen_biguint_below(&n);
+            writer.write_crt(&a, &a_int, i);
+            writer.write_crt(&b, &b_int, i);
+            writer.write_row_instructions(&generator.air_data, i);
+
+            assert_eq!(writer.read_crt(&result, i), (&a_int * &b_int) % &n);
+        });
+
+        let stark = Starky::new(air);
+        let config = SC::standard_fast_config(num_rows);
+
+        // Generate proof and verify as a stark
+        test_starky(&stark, &config, &generator, &[]);
+
+        // Test the recursive proof.
+        test_recursive_starky(stark, config, generator, &[]);
+    }
+}
@@ -8,6 +8,7 @@ use crate::air::AirConstraint;
 use crate::chip::builder::AirBuilder;
 use crate::chip::instruction::Instruction;
 use crate::chip::register::array::ArrayRegister;
+use crate::chip::register::memory::MemorySlice;
 use crate::chip::register::u16::U16Register;
 use crate::chip::register::{Register, RegisterSerializable};
 use crate::chip::trace::writer::{AirWriter, TraceWriter};
@@ -112,8 +113,10 @@ impl<AP: PolynomialParser, P: FieldParameters> AirConstraint<AP> for FpDenInstru
         let mul_times_carry = parser.poly_mul(&p_carry, &p_limbs);
         let p_vanishing = parser.poly_sub(&p_lhs_minus_rhs, &mul_times_carry);
 
-        let p_witness_low = Polynomial::from_coefficients(self.witness_low.eval_vec(parser));
-        let p_witness_high = Polynomial::from_coefficients(self.witness_high.eval_vec(parser));
+        let p_witness_low =
+            Polynomial::from_coefficients(self.witness_low.packed_slice(parser).to_vec());
+        let p_witness_high =
+            Polynomial::from_coefficients(self.witness_high.packed_slice(parser).to_vec());
 
         util::eval_field_operation::<AP, P>(parser, &p_vanishing, &p_witness_low, &p_witness_high)
     }
@@ -221,6 +224,15 @@ impl<F: PrimeField64, P: FieldParameters> Instruction<F> for FpDenInstruction<P>
         writer.write_array(&self.witness_low, &p_witness_low);
         writer.write_array(&self.witness_high, &p_witness_high);
     }
+
+    fn trace_layout(&self) -> Vec<MemorySlice> {
+        vec![
+            *self.result.register(),
+            *self.carry.register(),
+            *self.witness_low.register(),
+            *self.witness_high.register(),
+        ]
+    }
 }
 
 #[cfg(test)]
@@ -8,6 +8,7 @@ use crate::air::AirConstraint;
 use crate::chip::builder::AirBuilder;
 use crate::chip::instruction::Instruction;
 use crate::chip::register::array::ArrayRegister;
+use crate::chip::register::memory::MemorySlice;
 use crate::chip::register::u16::U16Register;
 use crate::chip::register::RegisterSerializable;
 use crate::chip::trace::writer::TraceWriter;
@@ -49,12 +50,14 @@ impl<L: AirParameters> AirBuilder<L> {
         result
     }
 
+    /// Sets `result` to the quotient `a / b`, returning the registered instruction.
     pub fn set_fp_div<P: FieldParameters>(
         &mut self,
         a: &FieldRegister<P>,
         b: &FieldRegister<P>,
         result: &FieldRegister<P>,
-    ) where
+    ) -> FpDivInstruction<P>
+    where
         L::Instruction: From<FpDivInstruction<P>>,
     {
         let is_trace = a.is_trace() || b.is_trace() || result.is_trace();
@@ -120,6 +123,7 @@ impl<L: AirParameters> AirBuilder<L> {
         } else {
             self.register_global_instruction(instr);
         }
+        instr
     }
 }
 
@@ -176,6 +180,13 @@ impl<F: PrimeField64, P: FieldParameters> Instruction<F> for FpDivInstruction<P>
         self.denominator.write_to_air(writer);
         self.multiplication.write_to_air(writer);
     }
+
+    fn trace_layout(&self) -> Vec<MemorySlice> {
+        let mut layout = Instruction::<F>::trace_layout(&self.denominator);
+        layout.push(*self.denominator.b.register());
+        layout.extend(Instruction::<F>::trace_layout(&self.multiplication));
+        layout
+    }
 }
 
 #[cfg(test)]
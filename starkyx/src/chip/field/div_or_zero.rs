@@ -0,0 +1,258 @@
+use num::BigUint;
+use serde::{Deserialize, Serialize};
+
+use super::mul::FpMulInstruction;
+use super::parameters::FieldParameters;
+use super::register::FieldRegister;
+use crate::air::AirConstraint;
+use crate::chip::builder::AirBuilder;
+use crate::chip::instruction::Instruction;
+use crate::chip::register::array::ArrayRegister;
+use crate::chip::register::memory::MemorySlice;
+use crate::chip::register::u16::U16Register;
+use crate::chip::register::{Register, RegisterSerializable};
+use crate::chip::trace::writer::{AirWriter, TraceWriter};
+use crate::chip::utils::digits_to_biguint;
+use crate::chip::AirParameters;
+use crate::math::prelude::*;
+use crate::polynomial::parser::PolynomialParser;
+use crate::polynomial::{to_u16_le_limbs_polynomial, Polynomial};
+
+/// Fp division with zero handling. Computes `a * b^(p - 2) = result`.
+///
+/// The value `b^(p - 2)` is the inverse of `b` if `b` is non-zero and zero otherwise, so that the
+/// result is `a / b` for a non-zero `b` and zero for `b = 0`. The prover supplies a hint `b_inv`
+/// and the instruction constrains `b * b_inv = t`, `b * t = b` and `b_inv * t = b_inv`. If `b` is
+/// non-zero the second constraint forces `t = 1`, so `b_inv` is the inverse of `b`. If `b` is zero
+/// then `t = 0` and the third constraint forces `b_inv = 0`.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+#[serde(bound = "")]
+pub struct FpDivOrZeroInstruction<P: FieldParameters> {
+    /// a `FpMulInstruction` to compute `b * b_inv = t`.
+    inverse: FpMulInstruction<P>,
+    /// a `FpMulInstruction` to check `b * t = b`.
+    check_b: FpMulInstruction<P>,
+    /// a `FpMulInstruction` to check `b_inv * t = b_inv`.
+    check_b_inv: FpMulInstruction<P>,
+    /// a `FpMulInstruction` to compute `a * b_inv = result`.
+    multiplication: FpMulInstruction<P>,
+}
+
+impl<P: FieldParameters> FpDivOrZeroInstruction<P> {
+    /// The register holding `a * b^(p - 2)`.
+    pub fn result(&self) -> FieldRegister<P> {
+        self.multiplication.result
+    }
+}
+
+impl<L: AirParameters> AirBuilder<L> {
+    /// Given two field elements `a` and `b`, computes `a * b^(p - 2)`, which is the quotient
+    /// `a / b` if `b` is non-zero and zero otherwise.
+    pub fn fp_div_or_zero<P: FieldParameters>(
+        &mut self,
+        a: &FieldRegister<P>,
+        b: &FieldRegister<P>,
+    ) -> FieldRegister<P>
+    where
+        L::Instruction: From<FpDivOrZeroInstruction<P>>,
+    {
+        let is_trace = a.is_trace() || b.is_trace();
+        let instr = self.alloc_fp_div_or_zero_instruction(a, b, is_trace);
+
+        if is_trace {
+            self.register_instruction(instr);
+        } else {
+            self.register_global_instruction(instr);
+        }
+        instr.result()
+    }
+
+    /// Allocates the registers of the instruction computing `a * b^(p - 2)` without registering
+    /// it, so that it can be registered with a filter.
+    pub(crate) fn alloc_fp_div_or_zero_instruction<P: FieldParameters>(
+        &mut self,
+        a: &FieldRegister<P>,
+        b: &FieldRegister<P>,
+        is_trace: bool,
+    ) -> FpDivOrZeroInstruction<P> {
+        let alloc_field = |builder: &mut Self| {
+            if is_trace {
+                builder.alloc::<FieldRegister<P>>()
+            } else {
+                builder.alloc_public::<FieldRegister<P>>()
+            }
+        };
+        let b_inv = alloc_field(self);
+        let t = alloc_field(self);
+        let result = alloc_field(self);
+
+        let mul = |builder: &mut Self,
+                   a: FieldRegister<P>,
+                   b: FieldRegister<P>,
+                   result: FieldRegister<P>| {
+            let carry: FieldRegister<P>;
+            let witness_low: ArrayRegister<U16Register>;
+            let witness_high: ArrayRegister<U16Register>;
+            if is_trace {
+                carry = builder.alloc::<FieldRegister<P>>();
+                witness_low = builder.alloc_array::<U16Register>(P::NB_WITNESS_LIMBS);
+                witness_high = builder.alloc_array::<U16Register>(P::NB_WITNESS_LIMBS);
+            } else {
+                carry = builder.alloc_public::<FieldRegister<P>>();
+                witness_low = builder.alloc_array_public::<U16Register>(P::NB_WITNESS_LIMBS);
+                witness_high = builder.alloc_array_public::<U16Register>(P::NB_WITNESS_LIMBS);
+            }
+            FpMulInstruction {
+                a,
+                b,
+                result,
+                carry,
+                witness_low,
+                witness_high,
+            }
+        };
+
+        FpDivOrZeroInstruction {
+            inverse: mul(self, *b, b_inv, t),
+            check_b: mul(self, *b, t, *b),
+            check_b_inv: mul(self, b_inv, t, b_inv),
+            multiplication: mul(self, *a, b_inv, result),
+        }
+    }
+}
+
+impl<AP: PolynomialParser, P: FieldParameters> AirConstraint<AP> for FpDivOrZeroInstruction<P> {
+    fn eval(&self, parser: &mut AP) {
+        self.inverse.eval(parser);
+        self.check_b.eval(parser);
+        self.check_b_inv.eval(parser);
+        self.multiplication.eval(parser);
+    }
+}
+
+impl<P: FieldParameters> FpDivOrZeroInstruction<P> {
+    /// Computes `b^(p - 2)`, which is zero if `b` is zero.
+    fn inverse_or_zero<F: PrimeField64>(p_b: &Polynomial<F>) -> Polynomial<F> {
+        let b_digits = p_b
+            .coefficients
+            .iter()
+            .map(|x| x.as_canonical_u64() as u16)
+            .collect::<Vec<_>>();
+        let b = digits_to_biguint(&b_digits);
+
+        let modulus = P::modulus();
+        let b_inv = b.modpow(&(&modulus - BigUint::from(2u64)), &modulus);
+        to_u16_le_limbs_polynomial::<F, P>(&b_inv)
+    }
+}
+
+impl<F: PrimeField64, P: FieldParameters> Instruction<F> for FpDivOrZeroInstruction<P> {
+    fn write(&self, writer: &TraceWriter<F>, row_index: usize) {
+        let p_b = writer.read(&self.inverse.a, row_index);
+        writer.write(&self.inverse.b, &Self::inverse_or_zero(&p_b), row_index);
+
+        // The checks only write their witnesses, as their results are the inputs `b` and `b_inv`.
+        self.inverse.write(writer, row_index);
+        self.check_b.write_witness(writer, row_index);
+        self.check_b_inv.write_witness(writer, row_index);
+        self.multiplication.write(writer, row_index);
+    }
+
+    fn write_to_air(&self, writer: &mut impl AirWriter<Field = F>) {
+        let p_b = writer.read(&self.inverse.a);
+        writer.write(&self.inverse.b, &Self::inverse_or_zero(&p_b));
+
+        self.inverse.write_to_air(writer);
+        self.check_b.write_witness_to_air(writer);
+        self.check_b_inv.write_witness_to_air(writer);
+        self.multiplication.write_to_air(writer);
+    }
+
+    fn trace_layout(&self) -> Vec<MemorySlice> {
+        let mut layout = Instruction::<F>::trace_layout(&self.inverse);
+        layout.push(*self.inverse.b.register());
+        for check in [&self.check_b, &self.check_b_inv] {
+            layout.extend([
+                *check.carry.register(),
+                *check.witness_low.register(),
+                *check.witness_high.register(),
+            ]);
+        }
+        layout.extend(Instruction::<F>::trace_layout(&self.multiplication));
+        layout
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use num::bigint::RandBigInt;
+    use num::Zero;
+    use rand::thread_rng;
+
+    use super::*;
+    use crate::chip::builder::tests::*;
+    use crate::chip::field::parameters::tests::Fp25519;
+    use crate::chip::utils::field_limbs_to_biguint;
+
+    #[derive(Clone, Debug, Copy, Serialize, Deserialize)]
+    struct FpDivOrZeroTest;
+
+    impl AirParameters for FpDivOrZeroTest {
+        type Field = GoldilocksField;
+        type CubicParams = GoldilocksCubicParameters;
+
+        const NUM_ARITHMETIC_COLUMNS: usize = 400;
+        const NUM_FREE_COLUMNS: usize = 2;
+        const EXTENDED_COLUMNS: usize = 630;
+
+        type Instruction = FpDivOrZeroInstruction<Fp25519>;
+    }
+
+    #[test]
+    fn test_fp_div_or_zero() {
+        type F = GoldilocksField;
+        type L = FpDivOrZeroTest;
+        type SC = PoseidonGoldilocksStarkConfig;
+        type P = Fp25519;
+
+        let p = Fp25519::modulus();
+        let mut builder = AirBuilder::<L>::new();
+
+        let a = builder.alloc::<FieldRegister<P>>();
+        let b = builder.alloc::<FieldRegister<P>>();
+        let result = builder.fp_div_or_zero(&a, &b);
+
+        let (air, trace_data) = builder.build();
+        let num_rows = 1 << 16;
+        let generator = ArithmeticGenerator::<L>::new(trace_data, num_rows);
+
+        let writer = generator.new_writer();
+        writer.write_global_instructions(&generator.air_data);
+        let mut rng = thread_rng();
+        for i in 0..num_rows {
+            let a_int = rng.gen_biguint(256) % &p;
+            // Every other row divides by zero.
+            let b_int = if i % 2 == 0 {
+                BigUint::zero()
+            } else {
+                rng.gen_biguint(256) % &p
+            };
+            writer.write(&a, &to_u16_le_limbs_polynomial::<F, P>(&a_int), i);
+            writer.write(&b, &to_u16_le_limbs_polynomial::<F, P>(&b_int), i);
+            writer.write_row_instructions(&generator.air_data, i);
+
+            let value = field_limbs_to_biguint(writer.read(&result, i).coefficients());
+            let expected = (&a_int * b_int.modpow(&(&p - 2u32), &p)) % &p;
+            assert_eq!(value, expected);
+        }
+
+        let stark = Starky::new(air);
+        let config = SC::standard_fast_config(num_rows);
+
+        // Generate proof and verify as a stark
+        test_starky(&stark, &config, &generator, &[]);
+
+        // Test the recursive proof.
+        test_recursive_starky(stark, config, generator, &[]);
+    }
+}
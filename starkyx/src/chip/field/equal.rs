@@ -0,0 +1,112 @@
+use super::parameters::FieldParameters;
+use super::register::FieldRegister;
+use crate::chip::builder::AirBuilder;
+use crate::chip::register::array::ArrayRegister;
+use crate::chip::AirParameters;
+
+impl<L: AirParameters> AirBuilder<L> {
+    /// Asserts that `a` and `b` are equal.
+    ///
+    /// The limbs are compared one by one, so both registers are expected to hold reduced values,
+    /// as written by the field instructions.
+    pub fn assert_fp_equal<P: FieldParameters>(
+        &mut self,
+        a: &FieldRegister<P>,
+        b: &FieldRegister<P>,
+    ) {
+        self.assert_equal(a, b);
+    }
+
+    /// Asserts that the arrays `a` and `b` are equal element-wise.
+    pub fn assert_fp_array_equal<P: FieldParameters>(
+        &mut self,
+        a: &ArrayRegister<FieldRegister<P>>,
+        b: &ArrayRegister<FieldRegister<P>>,
+    ) {
+        assert_eq!(a.len(), b.len(), "arrays must have the same length");
+        for (a_i, b_i) in a.iter().zip(b.iter()) {
+            self.assert_fp_equal(&a_i, &b_i);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use num::bigint::RandBigInt;
+    use num::BigUint;
+    use rand::thread_rng;
+    use serde::{Deserialize, Serialize};
+
+    use super::*;
+    use crate::chip::builder::tests::*;
+    use crate::chip::field::parameters::tests::Fp25519;
+    use crate::polynomial::Polynomial;
+
+    #[derive(Clone, Debug, Copy, Serialize, Deserialize)]
+    struct FpArrayEqualTest;
+
+    impl AirParameters for FpArrayEqualTest {
+        type Field = GoldilocksField;
+        type CubicParams = GoldilocksCubicParameters;
+
+        const NUM_ARITHMETIC_COLUMNS: usize = 64;
+        const NUM_FREE_COLUMNS: usize = 2;
+        const EXTENDED_COLUMNS: usize = 105;
+
+        type Instruction = EmptyInstruction<GoldilocksField>;
+    }
+
+    /// Proves that two arrays holding the coordinates of a point are equal, with the
+    /// `y`-coordinate of the second array shifted by `y_offset` on row `bad_row`.
+    fn prove_array_equal(bad_row: usize, y_offset: u32) {
+        type F = GoldilocksField;
+        type L = FpArrayEqualTest;
+        type SC = PoseidonGoldilocksStarkConfig;
+        type P = Fp25519;
+
+        let mut builder = AirBuilder::<L>::new();
+        let a = builder.alloc_array::<FieldRegister<P>>(2);
+        let b = builder.alloc_array::<FieldRegister<P>>(2);
+        builder.assert_fp_array_equal(&a, &b);
+
+        let (air, trace_data) = builder.build();
+        let num_rows = 1 << 16;
+        let generator = ArithmeticGenerator::<L>::new(trace_data, num_rows);
+        let writer = generator.new_writer();
+
+        let p = P::modulus();
+        let to_poly = |x: &BigUint| Polynomial::<F>::from_biguint_field(x, 16, 16);
+        (0..num_rows).into_par_iter().for_each(|i| {
+            let mut rng = thread_rng();
+            let x = rng.gen_biguint_below(&(&p - 1u32));
+            let y = rng.gen_biguint_below(&(&p - 1u32));
+            let y_b = if i == bad_row {
+                &y + y_offset
+            } else {
+                y.clone()
+            };
+            writer.write(&a.get(0), &to_poly(&x), i);
+            writer.write(&a.get(1), &to_poly(&y), i);
+            writer.write(&b.get(0), &to_poly(&x), i);
+            writer.write(&b.get(1), &to_poly(&y_b), i);
+            writer.write_row_instructions(&generator.air_data, i);
+        });
+
+        let stark = Starky::new(air);
+        let config = SC::standard_fast_config(num_rows);
+
+        // Generate proof and verify as a stark
+        test_starky(&stark, &config, &generator, &[]);
+    }
+
+    #[test]
+    fn test_fp_array_equal() {
+        prove_array_equal(0, 0);
+    }
+
+    #[test]
+    #[should_panic(expected = "the vanishing polynomial is not divisible by Z_H")]
+    fn test_fp_array_equal_fails_on_different_coordinate() {
+        prove_array_equal(17, 1);
+    }
+}
@@ -2,16 +2,17 @@
 //!
 //! To understand the implementation, it may be useful to refer to `mod.rs`.
 
-use num::{BigUint, Zero};
+use num::{BigUint, One, Zero};
 use serde::{Deserialize, Serialize};
 
-use super::parameters::FieldParameters;
+use super::parameters::{FieldParameters, LIMB};
 use super::register::FieldRegister;
 use super::util;
 use crate::air::AirConstraint;
 use crate::chip::builder::AirBuilder;
 use crate::chip::instruction::Instruction;
 use crate::chip::register::array::ArrayRegister;
+use crate::chip::register::memory::MemorySlice;
 use crate::chip::register::u16::U16Register;
 use crate::chip::register::{Register, RegisterSerializable};
 use crate::chip::trace::writer::{AirWriter, TraceWriter};
@@ -77,6 +78,63 @@ impl<L: AirParameters> AirBuilder<L> {
         }
         result
     }
+
+    /// Computes the sum of products `sum(a_i * b_i) mod p` of the given pairs.
+    ///
+    /// The sum is computed by a single inner product instruction, so it uses one result, carry
+    /// and witness set for any number of pairs instead of one for each multiplication and
+    /// addition of a chained computation. The carry of the reduction is less than `n * p` for `n`
+    /// pairs and must fit in the limbs of a field element, and the coefficients of the witness
+    /// must be less than the witness offset of `P`, which bounds the number of pairs.
+    pub fn fp_sum_of_products<P: FieldParameters>(
+        &mut self,
+        pairs: &[(FieldRegister<P>, FieldRegister<P>)],
+    ) -> FieldRegister<P>
+    where
+        L::Instruction: From<FpInnerProductInstruction<P>>,
+    {
+        assert!(!pairs.is_empty(), "cannot compute an empty sum of products");
+        let modulus = P::modulus();
+        let max_carry =
+            BigUint::from(pairs.len()) * (&modulus - 1u32) * (&modulus - 1u32) / &modulus;
+        assert!(
+            max_carry < carry_bound::<P>(),
+            "too many pairs in a sum of products: the carry of {} pairs does not fit in {} limbs",
+            pairs.len(),
+            P::NB_LIMBS
+        );
+        assert!(
+            vanishing_bound::<P>(pairs.len())
+                <= BigUint::from(P::WITNESS_OFFSET) * BigUint::from(LIMB - 1),
+            "too many pairs in a sum of products: the witness of {} pairs exceeds the witness \
+             offset {}",
+            pairs.len(),
+            P::WITNESS_OFFSET
+        );
+
+        let (a, b): (Vec<_>, Vec<_>) = pairs.iter().copied().unzip();
+        self.fp_inner_product(&a, &b)
+    }
+}
+
+/// The bound on the carry of an inner product, which must fit in the limbs of a field element.
+fn carry_bound<P: FieldParameters>() -> BigUint {
+    BigUint::one() << (P::NB_BITS_PER_LIMB * P::NB_LIMBS)
+}
+
+/// A bound on the absolute values of the coefficients of the vanishing polynomial of an inner
+/// product of `nb_pairs` pairs.
+///
+/// The products of the limbs of the pairs add up to at most `nb_pairs * NB_LIMBS * (2^16 - 1)^2`
+/// in each coefficient, and the limbs of the result and of the carry times the modulus to at most
+/// `NB_LIMBS * (2^16 - 1)^2 + 2^16 - 1`. The witness is the quotient of the vanishing polynomial
+/// by `x - 2^16`, whose coefficients are less than this bound divided by `2^16 - 1`.
+fn vanishing_bound<P: FieldParameters>(nb_pairs: usize) -> BigUint {
+    let limb_max = BigUint::from(LIMB - 1);
+    let limb_products = BigUint::from(P::NB_LIMBS) * &limb_max * &limb_max;
+    let products_bound = BigUint::from(nb_pairs) * &limb_products;
+    let reduction_bound = limb_products + limb_max;
+    products_bound.max(reduction_bound)
 }
 
 impl<AP: PolynomialParser, P: FieldParameters> AirConstraint<AP> for FpInnerProductInstruction<P> {
@@ -105,8 +163,10 @@ impl<AP: PolynomialParser, P: FieldParameters> AirConstraint<AP> for FpInnerProd
         let p_carry_mul_modulus = parser.poly_mul(&p_carry, &p_limbs);
         let p_vanishing = parser.poly_sub(&p_inner_product_minus_result, &p_carry_mul_modulus);
 
-        let p_witness_low = Polynomial::from_coefficients(self.witness_low.eval_vec(parser));
-        let p_witness_high = Polynomial::from_coefficients(self.witness_high.eval_vec(parser));
+        let p_witness_low =
+            Polynomial::from_coefficients(self.witness_low.packed_slice(parser).to_vec());
+        let p_witness_high =
+            Polynomial::from_coefficients(self.witness_high.packed_slice(parser).to_vec());
 
         util::eval_field_operation::<AP, P>(parser, &p_vanishing, &p_witness_low, &p_witness_high)
     }
@@ -141,7 +201,7 @@ impl<F: PrimeField64, P: FieldParameters> Instruction<F> for FpInnerProductInstr
         let result = &(&inner_product % modulus);
         let carry = &((&inner_product - result) / modulus);
         assert!(result < modulus);
-        assert!(carry < &(2u32 * modulus));
+        assert!(carry < &carry_bound::<P>());
         assert_eq!(carry * modulus, inner_product - result);
 
         let p_modulus = to_u16_le_limbs_polynomial::<F, P>(modulus);
@@ -194,7 +254,7 @@ impl<F: PrimeField64, P: FieldParameters> Instruction<F> for FpInnerProductInstr
         let result = &(&inner_product % modulus);
         let carry = &((&inner_product - result) / modulus);
         assert!(result < modulus);
-        assert!(carry < &(2u32 * modulus));
+        assert!(carry < &carry_bound::<P>());
         assert_eq!(carry * modulus, inner_product - result);
 
         let p_modulus = to_u16_le_limbs_polynomial::<F, P>(modulus);
@@ -218,6 +278,15 @@ impl<F: PrimeField64, P: FieldParameters> Instruction<F> for FpInnerProductInstr
         writer.write_array(&self.witness_low, &p_witness_low);
         writer.write_array(&self.witness_high, &p_witness_high);
     }
+
+    fn trace_layout(&self) -> Vec<MemorySlice> {
+        vec![
+            *self.result.register(),
+            *self.carry.register(),
+            *self.witness_low.register(),
+            *self.witness_high.register(),
+        ]
+    }
 }
 
 #[cfg(test)]
@@ -227,7 +296,10 @@ mod tests {
 
     use super::*;
     use crate::chip::builder::tests::*;
+    use crate::chip::field::instruction::FpInstruction;
     use crate::chip::field::parameters::tests::Fp25519;
+    use crate::chip::field::parameters::MAX_NB_LIMBS;
+    use crate::chip::trace::data::AirTraceData;
 
     #[derive(Clone, Debug, Copy, Serialize, Deserialize)]
     struct FpInnerProductTest;
@@ -309,4 +381,174 @@ mod tests {
         // Test the recursive proof.
         test_recursive_starky(stark, config, generator, &public);
     }
+
+    /// The modulus `2^29 - 3`, small enough for the carry of a few products to fit in two limbs.
+    #[derive(Debug, Default, Clone, Copy, PartialEq, Serialize, Deserialize)]
+    struct Fp29;
+
+    impl FieldParameters for Fp29 {
+        const NB_BITS_PER_LIMB: usize = 16;
+        const NB_LIMBS: usize = 2;
+        const NB_WITNESS_LIMBS: usize = 2 * Self::NB_LIMBS - 2;
+        const MODULUS: [u16; MAX_NB_LIMBS] = [
+            65533, 8191, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0,
+            0, 0, 0, 0, 0,
+        ];
+        const WITNESS_OFFSET: usize = 1usize << 20;
+    }
+
+    #[derive(Clone, Debug, Copy, Serialize, Deserialize)]
+    struct FpSumOfProductsTest;
+
+    impl AirParameters for FpSumOfProductsTest {
+        type Field = GoldilocksField;
+        type CubicParams = GoldilocksCubicParameters;
+
+        const NUM_ARITHMETIC_COLUMNS: usize = 20;
+        const NUM_FREE_COLUMNS: usize = 2;
+        const EXTENDED_COLUMNS: usize = 39;
+
+        type Instruction = FpInstruction<Fp29>;
+    }
+
+    #[derive(Clone, Debug, Copy, Serialize, Deserialize)]
+    struct FpChainedProductsTest;
+
+    impl AirParameters for FpChainedProductsTest {
+        type Field = GoldilocksField;
+        type CubicParams = GoldilocksCubicParameters;
+
+        const NUM_ARITHMETIC_COLUMNS: usize = 52;
+        const NUM_FREE_COLUMNS: usize = 2;
+        const EXTENDED_COLUMNS: usize = 87;
+
+        type Instruction = FpInstruction<Fp29>;
+    }
+
+    /// The number of columns written by the instructions of a chip.
+    fn num_instruction_columns<L: AirParameters>(trace_data: &AirTraceData<L>) -> usize {
+        trace_data
+            .instructions
+            .iter()
+            .flat_map(|instruction| instruction.trace_layout())
+            .map(|slice| slice.len())
+            .sum()
+    }
+
+    #[test]
+    fn test_fp_sum_of_products() {
+        type F = GoldilocksField;
+        type L = FpSumOfProductsTest;
+        type SC = PoseidonGoldilocksStarkConfig;
+        type P = Fp29;
+        type Fp = FieldRegister<P>;
+
+        let p = P::modulus();
+        let num_pairs = 3;
+
+        let mut builder = AirBuilder::<L>::new();
+        let pairs = (0..num_pairs)
+            .map(|_| (builder.alloc::<Fp>(), builder.alloc::<Fp>()))
+            .collect::<Vec<_>>();
+        let result = builder.fp_sum_of_products(&pairs);
+
+        let (air, trace_data) = builder.build();
+        let num_sum_columns = num_instruction_columns(&trace_data);
+        let num_rows = 1 << 16;
+        let generator = ArithmeticGenerator::<L>::new(trace_data, num_rows);
+
+        let writer = generator.new_writer();
+        (0..num_rows).into_par_iter().for_each(|i| {
+            let mut rng = thread_rng();
+            let mut expected = BigUint::zero();
+            for (a, b) in pairs.iter() {
+                let a_int = rng.gen_biguint(29) % &p;
+                let b_int = rng.gen_biguint(29) % &p;
+                expected += &a_int * &b_int;
+                writer.write(a, &Polynomial::from_biguint_field(&a_int, 16, 2), i);
+                writer.write(b, &Polynomial::from_biguint_field(&b_int, 16, 2), i);
+            }
+            writer.write_row_instructions(&generator.air_data, i);
+
+            let value = writer.read(&result, i);
+            let expected = Polynomial::<F>::from_biguint_field(&(expected % &p), 16, 2);
+            assert_eq!(value, expected);
+        });
+
+        let stark = Starky::new(air);
+        let config = SC::standard_fast_config(num_rows);
+
+        // Generate proof and verify as a stark
+        test_starky(&stark, &config, &generator, &[]);
+
+        // Chaining the multiplications and additions uses one set of columns for each of them.
+        let mut builder = AirBuilder::<FpChainedProductsTest>::new();
+        let pairs = (0..num_pairs)
+            .map(|_| (builder.alloc::<Fp>(), builder.alloc::<Fp>()))
+            .collect::<Vec<_>>();
+        let products = pairs
+            .iter()
+            .map(|(a, b)| builder.fp_mul(a, b))
+            .collect::<Vec<_>>();
+        let _ = products[1..]
+            .iter()
+            .fold(products[0], |acc, product| builder.fp_add(&acc, product));
+        let (_, trace_data) = builder.build();
+        let num_chained_columns = num_instruction_columns(&trace_data);
+
+        assert_eq!(num_sum_columns, 8);
+        assert_eq!(num_chained_columns, 5 * num_sum_columns);
+    }
+
+    #[test]
+    #[should_panic(expected = "too many pairs in a sum of products")]
+    fn test_fp_sum_of_products_carry_overflow() {
+        type Fp = FieldRegister<Fp25519>;
+
+        let mut builder = AirBuilder::<FpInnerProductTest>::new();
+        let pairs = (0..3)
+            .map(|_| (builder.alloc::<Fp>(), builder.alloc::<Fp>()))
+            .collect::<Vec<_>>();
+        builder.fp_sum_of_products(&pairs);
+    }
+
+    /// The modulus of [`Fp29`] with a witness offset of `2^19`, which bounds the number of pairs
+    /// of a sum of products before its carry does.
+    #[derive(Debug, Default, Clone, Copy, PartialEq, Serialize, Deserialize)]
+    struct Fp29NarrowWitness;
+
+    impl FieldParameters for Fp29NarrowWitness {
+        const NB_BITS_PER_LIMB: usize = 16;
+        const NB_LIMBS: usize = 2;
+        const NB_WITNESS_LIMBS: usize = 2 * Self::NB_LIMBS - 2;
+        const MODULUS: [u16; MAX_NB_LIMBS] = Fp29::MODULUS;
+        const WITNESS_OFFSET: usize = 1usize << 19;
+    }
+
+    #[derive(Clone, Debug, Copy, Serialize, Deserialize)]
+    struct FpNarrowWitnessTest;
+
+    impl AirParameters for FpNarrowWitnessTest {
+        type Field = GoldilocksField;
+        type CubicParams = GoldilocksCubicParameters;
+
+        const NUM_ARITHMETIC_COLUMNS: usize = 20;
+        const NUM_FREE_COLUMNS: usize = 2;
+        const EXTENDED_COLUMNS: usize = 39;
+
+        type Instruction = FpInstruction<Fp29NarrowWitness>;
+    }
+
+    #[test]
+    #[should_panic(expected = "exceeds the witness offset")]
+    fn test_fp_sum_of_products_witness_overflow() {
+        type Fp = FieldRegister<Fp29NarrowWitness>;
+
+        // The carry of 5 pairs fits in two limbs, but their witness exceeds `2^19`.
+        let mut builder = AirBuilder::<FpNarrowWitnessTest>::new();
+        let pairs = (0..5)
+            .map(|_| (builder.alloc::<Fp>(), builder.alloc::<Fp>()))
+            .collect::<Vec<_>>();
+        builder.fp_sum_of_products(&pairs);
+    }
 }
@@ -3,13 +3,16 @@ use serde::{Deserialize, Serialize};
 use super::add::FpAddInstruction;
 use super::den::FpDenInstruction;
 use super::div::FpDivInstruction;
+use super::div_or_zero::FpDivOrZeroInstruction;
 use super::inner_product::FpInnerProductInstruction;
 use super::mul::FpMulInstruction;
 use super::mul_const::FpMulConstInstruction;
+use super::nonzero::FpNonZeroInstruction;
 use super::parameters::FieldParameters;
 use super::sub::FpSubInstruction;
 use crate::air::AirConstraint;
 use crate::chip::instruction::Instruction;
+use crate::chip::register::memory::MemorySlice;
 use crate::chip::trace::writer::{AirWriter, TraceWriter};
 use crate::math::prelude::*;
 use crate::polynomial::parser::PolynomialParser;
@@ -24,6 +27,8 @@ pub enum FpInstruction<P: FieldParameters> {
     Den(FpDenInstruction<P>),
     Sub(FpSubInstruction<P>),
     Div(FpDivInstruction<P>),
+    DivOrZero(FpDivOrZeroInstruction<P>),
+    NonZero(FpNonZeroInstruction<P>),
 }
 
 pub trait FromFieldInstruction<P: FieldParameters>:
@@ -31,9 +36,11 @@ pub trait FromFieldInstruction<P: FieldParameters>:
     + From<FpMulInstruction<P>>
     + From<FpSubInstruction<P>>
     + From<FpDivInstruction<P>>
+    + From<FpDivOrZeroInstruction<P>>
     + From<FpMulConstInstruction<P>>
     + From<FpInnerProductInstruction<P>>
     + From<FpDenInstruction<P>>
+    + From<FpNonZeroInstruction<P>>
 {
 }
 
@@ -49,6 +56,8 @@ impl<AP: PolynomialParser, P: FieldParameters> AirConstraint<AP> for FpInstructi
             FpInstruction::Den(instruction) => AirConstraint::<AP>::eval(instruction, parser),
             FpInstruction::Sub(instruction) => AirConstraint::<AP>::eval(instruction, parser),
             FpInstruction::Div(instruction) => AirConstraint::<AP>::eval(instruction, parser),
+            FpInstruction::DivOrZero(instruction) => AirConstraint::<AP>::eval(instruction, parser),
+            FpInstruction::NonZero(instruction) => AirConstraint::<AP>::eval(instruction, parser),
         }
     }
 }
@@ -77,6 +86,12 @@ impl<F: PrimeField64, P: FieldParameters> Instruction<F> for FpInstruction<P> {
             FpInstruction::Div(instruction) => {
                 Instruction::<F>::write(instruction, writer, row_index)
             }
+            FpInstruction::DivOrZero(instruction) => {
+                Instruction::<F>::write(instruction, writer, row_index)
+            }
+            FpInstruction::NonZero(instruction) => {
+                Instruction::<F>::write(instruction, writer, row_index)
+            }
         }
     }
 
@@ -93,6 +108,40 @@ impl<F: PrimeField64, P: FieldParameters> Instruction<F> for FpInstruction<P> {
             FpInstruction::Den(instruction) => Instruction::<F>::write_to_air(instruction, writer),
             FpInstruction::Sub(instruction) => Instruction::<F>::write_to_air(instruction, writer),
             FpInstruction::Div(instruction) => Instruction::<F>::write_to_air(instruction, writer),
+            FpInstruction::DivOrZero(instruction) => {
+                Instruction::<F>::write_to_air(instruction, writer)
+            }
+            FpInstruction::NonZero(instruction) => {
+                Instruction::<F>::write_to_air(instruction, writer)
+            }
+        }
+    }
+
+    fn name(&self) -> &'static str {
+        match self {
+            FpInstruction::Add(_) => "FpAdd",
+            FpInstruction::Mul(_) => "FpMul",
+            FpInstruction::MulConst(_) => "FpMulConst",
+            FpInstruction::Inner(_) => "FpInnerProduct",
+            FpInstruction::Den(_) => "FpDen",
+            FpInstruction::Sub(_) => "FpSub",
+            FpInstruction::Div(_) => "FpDiv",
+            FpInstruction::DivOrZero(_) => "FpDivOrZero",
+            FpInstruction::NonZero(_) => "FpNonZero",
+        }
+    }
+
+    fn trace_layout(&self) -> Vec<MemorySlice> {
+        match self {
+            FpInstruction::Add(instruction) => Instruction::<F>::trace_layout(instruction),
+            FpInstruction::Mul(instruction) => Instruction::<F>::trace_layout(instruction),
+            FpInstruction::MulConst(instruction) => Instruction::<F>::trace_layout(instruction),
+            FpInstruction::Inner(instruction) => Instruction::<F>::trace_layout(instruction),
+            FpInstruction::Den(instruction) => Instruction::<F>::trace_layout(instruction),
+            FpInstruction::Sub(instruction) => Instruction::<F>::trace_layout(instruction),
+            FpInstruction::Div(instruction) => Instruction::<F>::trace_layout(instruction),
+            FpInstruction::DivOrZero(instruction) => Instruction::<F>::trace_layout(instruction),
+            FpInstruction::NonZero(instruction) => Instruction::<F>::trace_layout(instruction),
         }
     }
 }
@@ -138,3 +187,15 @@ impl<P: FieldParameters> From<FpDivInstruction<P>> for FpInstruction<P> {
         FpInstruction::Div(instr)
     }
 }
+
+impl<P: FieldParameters> From<FpDivOrZeroInstruction<P>> for FpInstruction<P> {
+    fn from(instr: FpDivOrZeroInstruction<P>) -> Self {
+        FpInstruction::DivOrZero(instr)
+    }
+}
+
+impl<P: FieldParameters> From<FpNonZeroInstruction<P>> for FpInstruction<P> {
+    fn from(instr: FpNonZeroInstruction<P>) -> Self {
+        FpInstruction::NonZero(instr)
+    }
+}
@@ -29,12 +29,17 @@
 
 pub mod add;
 pub mod constants;
+pub mod crt;
 pub mod den;
 pub mod div;
+pub mod div_or_zero;
+pub mod equal;
 pub mod inner_product;
 pub mod instruction;
 pub mod mul;
 pub mod mul_const;
+pub mod negate;
+pub mod nonzero;
 pub mod ops;
 pub mod parameters;
 pub mod register;
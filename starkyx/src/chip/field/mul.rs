@@ -7,6 +7,7 @@ use crate::air::AirConstraint;
 use crate::chip::builder::AirBuilder;
 use crate::chip::instruction::Instruction;
 use crate::chip::register::array::ArrayRegister;
+use crate::chip::register::memory::MemorySlice;
 use crate::chip::register::u16::U16Register;
 use crate::chip::register::{Register, RegisterSerializable};
 use crate::chip::trace::writer::{AirWriter, TraceWriter};
@@ -38,38 +39,77 @@ impl<L: AirParameters> AirBuilder<L> {
         L::Instruction: From<FpMulInstruction<P>>,
     {
         let is_trace = a.is_trace() || b.is_trace();
-
-        let result: FieldRegister<P>;
-        let carry: FieldRegister<P>;
-        let witness_low: ArrayRegister<U16Register>;
-        let witness_high: ArrayRegister<U16Register>;
-
-        if is_trace {
-            result = self.alloc::<FieldRegister<P>>();
-            carry = self.alloc::<FieldRegister<P>>();
-            witness_low = self.alloc_array::<U16Register>(P::NB_WITNESS_LIMBS);
-            witness_high = self.alloc_array::<U16Register>(P::NB_WITNESS_LIMBS);
+        let result = if is_trace {
+            self.alloc::<FieldRegister<P>>()
         } else {
-            result = self.alloc_public::<FieldRegister<P>>();
-            carry = self.alloc_public::<FieldRegister<P>>();
-            witness_low = self.alloc_array_public::<U16Register>(P::NB_WITNESS_LIMBS);
-            witness_high = self.alloc_array_public::<U16Register>(P::NB_WITNESS_LIMBS);
-        }
-        let instr = FpMulInstruction {
-            a: *a,
-            b: *b,
-            result,
-            carry,
-            witness_low,
-            witness_high,
+            self.alloc_public::<FieldRegister<P>>()
         };
+        self.set_fp_mul(a, b, &result);
+        result
+    }
+
+    /// Sets `result` to the product `a * b`, returning the registered instruction.
+    pub fn set_fp_mul<P: FieldParameters>(
+        &mut self,
+        a: &FieldRegister<P>,
+        b: &FieldRegister<P>,
+        result: &FieldRegister<P>,
+    ) -> FpMulInstruction<P>
+    where
+        L::Instruction: From<FpMulInstruction<P>>,
+    {
+        let is_trace = a.is_trace() || b.is_trace() || result.is_trace();
+        let instr = self.alloc_fp_mul_instruction(a, b, result);
 
         if is_trace {
             self.register_instruction(instr);
         } else {
             self.register_global_instruction(instr);
         }
-        result
+        instr
+    }
+
+    /// Allocates the carry and witness limbs of the instruction computing `a * b = result`
+    /// without registering it.
+    pub(crate) fn alloc_fp_mul_instruction<P: FieldParameters>(
+        &mut self,
+        a: &FieldRegister<P>,
+        b: &FieldRegister<P>,
+        result: &FieldRegister<P>,
+    ) -> FpMulInstruction<P> {
+        let num_columns = FpMulInstruction::<P>::NUM_RANGE_CHECKED_LIMBS;
+        let limbs = if a.is_trace() || b.is_trace() || result.is_trace() {
+            self.alloc_array::<U16Register>(num_columns)
+        } else {
+            self.alloc_array_public::<U16Register>(num_columns)
+        };
+        FpMulInstruction::from_limbs(*a, *b, *result, limbs)
+    }
+}
+
+impl<P: FieldParameters> FpMulInstruction<P> {
+    /// The number of range checked `u16` limbs holding the carry and the witness.
+    pub(crate) const NUM_RANGE_CHECKED_LIMBS: usize = P::NB_LIMBS + 2 * P::NB_WITNESS_LIMBS;
+
+    /// The instruction computing `a * b = result`, with the carry and the witness stored in
+    /// `limbs`, which must hold `Self::NUM_RANGE_CHECKED_LIMBS` range checked limbs.
+    pub(crate) fn from_limbs(
+        a: FieldRegister<P>,
+        b: FieldRegister<P>,
+        result: FieldRegister<P>,
+        limbs: ArrayRegister<U16Register>,
+    ) -> Self {
+        assert_eq!(limbs.len(), Self::NUM_RANGE_CHECKED_LIMBS);
+        let witness_start = P::NB_LIMBS;
+        let witness_mid = witness_start + P::NB_WITNESS_LIMBS;
+        Self {
+            a,
+            b,
+            result,
+            carry: FieldRegister::from_register(*limbs.get_subarray(0..witness_start).register()),
+            witness_low: limbs.get_subarray(witness_start..witness_mid),
+            witness_high: limbs.get_subarray(witness_mid..limbs.len()),
+        }
     }
 }
 
@@ -91,114 +131,225 @@ impl<AP: PolynomialParser, P: FieldParameters> AirConstraint<AP> for FpMulInstru
         let p_mul_times_carry = parser.poly_mul(&p_carry, &p_limbs);
         let p_vanishing = parser.poly_sub(&p_a_mul_b_minus_result, &p_mul_times_carry);
 
-        let p_witness_low = Polynomial::from_coefficients(self.witness_low.eval_vec(parser));
-        let p_witness_high = Polynomial::from_coefficients(self.witness_high.eval_vec(parser));
+        let p_witness_low =
+            Polynomial::from_coefficients(self.witness_low.packed_slice(parser).to_vec());
+        let p_witness_high =
+            Polynomial::from_coefficients(self.witness_high.packed_slice(parser).to_vec());
 
         util::eval_field_operation::<AP, P>(parser, &p_vanishing, &p_witness_low, &p_witness_high)
     }
 }
 
-impl<F: PrimeField64, P: FieldParameters> Instruction<F> for FpMulInstruction<P> {
-    fn write(&self, writer: &TraceWriter<F>, row_index: usize) {
-        let p_a = writer.read(&self.a, row_index);
-        let p_b = writer.read(&self.b, row_index);
-
-        let a_digits = p_a
-            .coefficients
-            .iter()
-            .map(|x| x.as_canonical_u64() as u16)
-            .collect::<Vec<_>>();
-        let b_digits = p_b
-            .coefficients
-            .iter()
-            .map(|x| x.as_canonical_u64() as u16)
-            .collect::<Vec<_>>();
+impl<P: FieldParameters> FpMulInstruction<P> {
+    /// Computes the product `a * b` reduced modulo `p`.
+    fn product<F: PrimeField64>(p_a: &Polynomial<F>, p_b: &Polynomial<F>) -> Polynomial<F> {
+        let a = digits_to_biguint(&to_u16_digits(p_a));
+        let b = digits_to_biguint(&to_u16_digits(p_b));
+        let result = (&a * &b) % &P::modulus();
+        to_u16_le_limbs_polynomial::<F, P>(&result)
+    }
 
-        let a = digits_to_biguint(&a_digits);
-        let b = digits_to_biguint(&b_digits);
+    /// Computes the carry and the witness limbs of `a * b = result`, where `result` is any value
+    /// congruent to `a * b` modulo `p` and not greater than `a * b`.
+    pub(crate) fn carry_and_witness<F: PrimeField64>(
+        p_a: &Polynomial<F>,
+        p_b: &Polynomial<F>,
+        p_result: &Polynomial<F>,
+    ) -> (Polynomial<F>, Vec<F>, Vec<F>) {
+        let a = digits_to_biguint(&to_u16_digits(p_a));
+        let b = digits_to_biguint(&to_u16_digits(p_b));
+        let result = digits_to_biguint(&to_u16_digits(p_result));
 
         // Compute field multiplication in the integers.
         let modulus = P::modulus();
-        let result = (&a * &b) % &modulus;
         let carry = (&a * &b - &result) / &modulus;
-        debug_assert!(result < modulus);
         debug_assert!(carry < modulus);
         debug_assert_eq!(&carry * &modulus, a * b - &result);
 
         // Make little endian polynomial limbs.
         let p_modulus = to_u16_le_limbs_polynomial::<F, P>(&modulus);
-        let p_result = to_u16_le_limbs_polynomial::<F, P>(&result);
         let p_carry = to_u16_le_limbs_polynomial::<F, P>(&carry);
 
         // Compute the vanishing polynomial.
-        let p_vanishing = &p_a * &p_b - &p_result - &p_carry * &p_modulus;
+        util::debug_assert_mul_in_range(p_a, p_b, p_result, &p_carry, &p_modulus);
+        let p_vanishing = &(&(p_a * p_b) - p_result) - &(&p_carry * &p_modulus);
         debug_assert_eq!(p_vanishing.degree(), P::NB_WITNESS_LIMBS);
 
         // Compute the witness.
         let p_witness = util::compute_root_quotient_and_shift(&p_vanishing, P::WITNESS_OFFSET);
         let (p_witness_low, p_witness_high) = split_u32_limbs_to_u16_limbs(&p_witness);
 
-        writer.write(&self.result, &p_result, row_index);
+        (p_carry, p_witness_low, p_witness_high)
+    }
+
+    /// Writes the carry and the witness of `a * b = result` at row `row_index`, reading `result`
+    /// from the trace instead of computing it.
+    pub(crate) fn write_witness<F: PrimeField64>(&self, writer: &TraceWriter<F>, row_index: usize) {
+        let p_a = writer.read(&self.a, row_index);
+        let p_b = writer.read(&self.b, row_index);
+        let p_result = writer.read(&self.result, row_index);
+
+        let (p_carry, p_witness_low, p_witness_high) =
+            Self::carry_and_witness(&p_a, &p_b, &p_result);
+
         writer.write(&self.carry, &p_carry, row_index);
         writer.write_array(&self.witness_low, &p_witness_low, row_index);
         writer.write_array(&self.witness_high, &p_witness_high, row_index);
     }
 
-    fn write_to_air(&self, writer: &mut impl AirWriter<Field = F>) {
+    /// Writes the carry and the witness of `a * b = result` to the current row of `writer`,
+    /// reading `result` from the trace instead of computing it.
+    pub(crate) fn write_witness_to_air<F: PrimeField64>(
+        &self,
+        writer: &mut impl AirWriter<Field = F>,
+    ) {
         let p_a = writer.read(&self.a);
         let p_b = writer.read(&self.b);
+        let p_result = writer.read(&self.result);
 
-        let a_digits = p_a
-            .coefficients
-            .iter()
-            .map(|x| x.as_canonical_u64() as u16)
-            .collect::<Vec<_>>();
-        let b_digits = p_b
-            .coefficients
-            .iter()
-            .map(|x| x.as_canonical_u64() as u16)
-            .collect::<Vec<_>>();
+        let (p_carry, p_witness_low, p_witness_high) =
+            Self::carry_and_witness(&p_a, &p_b, &p_result);
 
-        let a = digits_to_biguint(&a_digits);
-        let b = digits_to_biguint(&b_digits);
+        writer.write(&self.carry, &p_carry);
+        writer.write_array(&self.witness_low, &p_witness_low);
+        writer.write_array(&self.witness_high, &p_witness_high);
+    }
+}
 
-        // Compute field multiplication in the integers.
-        let modulus = P::modulus();
-        let result = (&a * &b) % &modulus;
-        let carry = (&a * &b - &result) / &modulus;
-        debug_assert!(result < modulus);
-        debug_assert!(carry < modulus);
-        debug_assert_eq!(&carry * &modulus, a * b - &result);
+fn to_u16_digits<F: PrimeField64>(p: &Polynomial<F>) -> Vec<u16> {
+    p.coefficients
+        .iter()
+        .map(|x| x.as_canonical_u64() as u16)
+        .collect()
+}
 
-        // Make little endian polynomial limbs.
-        let p_modulus = to_u16_le_limbs_polynomial::<F, P>(&modulus);
-        let p_result = to_u16_le_limbs_polynomial::<F, P>(&result);
-        let p_carry = to_u16_le_limbs_polynomial::<F, P>(&carry);
+impl<F: PrimeField64, P: FieldParameters> Instruction<F> for FpMulInstruction<P> {
+    fn write(&self, writer: &TraceWriter<F>, row_index: usize) {
+        let p_a = writer.read(&self.a, row_index);
+        let p_b = writer.read(&self.b, row_index);
 
-        // Compute the vanishing polynomial.
-        let p_vanishing = &p_a * &p_b - &p_result - &p_carry * &p_modulus;
-        debug_assert_eq!(p_vanishing.degree(), P::NB_WITNESS_LIMBS);
+        writer.write(&self.result, &Self::product(&p_a, &p_b), row_index);
+        self.write_witness(writer, row_index);
+    }
 
-        // Compute the witness.
-        let p_witness = util::compute_root_quotient_and_shift(&p_vanishing, P::WITNESS_OFFSET);
-        let (p_witness_low, p_witness_high) = split_u32_limbs_to_u16_limbs(&p_witness);
+    fn write_to_air(&self, writer: &mut impl AirWriter<Field = F>) {
+        let p_a = writer.read(&self.a);
+        let p_b = writer.read(&self.b);
 
-        writer.write(&self.result, &p_result);
-        writer.write(&self.carry, &p_carry);
-        writer.write_array(&self.witness_low, &p_witness_low);
-        writer.write_array(&self.witness_high, &p_witness_high);
+        writer.write(&self.result, &Self::product(&p_a, &p_b));
+        self.write_witness_to_air(writer);
+    }
+
+    fn trace_layout(&self) -> Vec<MemorySlice> {
+        vec![
+            *self.result.register(),
+            *self.carry.register(),
+            *self.witness_low.register(),
+            *self.witness_high.register(),
+        ]
     }
 }
 
 #[cfg(test)]
 mod tests {
     use num::bigint::RandBigInt;
-    use num::BigUint;
+    use num::{BigUint, One};
     use rand::thread_rng;
 
     use super::*;
     use crate::chip::builder::tests::*;
     use crate::chip::field::parameters::tests::Fp25519;
+    use crate::chip::field::parameters::MAX_NB_LIMBS;
+    use crate::chip::Chip;
+
+    /// The base field of BLS12-381, whose 24 limbs make the witness coefficients of a product
+    /// exceed `2^20` for some inputs.
+    #[derive(Debug, Default, Clone, Copy, PartialEq, Serialize, Deserialize)]
+    struct Bls12381BaseField<const WITNESS_OFFSET_BITS: usize>;
+
+    impl<const WITNESS_OFFSET_BITS: usize> FieldParameters for Bls12381BaseField<WITNESS_OFFSET_BITS> {
+        const NB_BITS_PER_LIMB: usize = 16;
+        const NB_LIMBS: usize = 24;
+        const NB_WITNESS_LIMBS: usize = 2 * Self::NB_LIMBS - 2;
+        const MODULUS: [u16; MAX_NB_LIMBS] = [
+            43691, 65535, 65535, 47614, 65535, 45395, 65534, 7851, 63012, 63152, 53920, 26416,
+            4799, 62341, 19332, 25719, 44247, 17227, 42934, 19227, 59034, 14719, 4586, 6657, 0, 0,
+            0, 0, 0, 0, 0, 0,
+        ];
+        const WITNESS_OFFSET: usize = 1usize << WITNESS_OFFSET_BITS;
+    }
+
+    #[derive(Clone, Debug, Copy, Serialize, Deserialize)]
+    struct Bls12381MulTest<const WITNESS_OFFSET_BITS: usize>;
+
+    impl<const WITNESS_OFFSET_BITS: usize> AirParameters for Bls12381MulTest<WITNESS_OFFSET_BITS> {
+        type Field = GoldilocksField;
+        type CubicParams = GoldilocksCubicParameters;
+
+        const NUM_ARITHMETIC_COLUMNS: usize = 188;
+        const NUM_FREE_COLUMNS: usize = 2;
+        const EXTENDED_COLUMNS: usize = 291;
+
+        type Instruction = FpMulInstruction<Bls12381BaseField<WITNESS_OFFSET_BITS>>;
+    }
+
+    /// Writes the product of `a` and `b` on the first row of the trace and returns the generator.
+    fn write_bls12381_mul<const WITNESS_OFFSET_BITS: usize>(
+        a_int: &BigUint,
+        b_int: &BigUint,
+    ) -> (
+        Chip<Bls12381MulTest<WITNESS_OFFSET_BITS>>,
+        ArithmeticGenerator<Bls12381MulTest<WITNESS_OFFSET_BITS>>,
+    ) {
+        type F = GoldilocksField;
+
+        let mut builder = AirBuilder::<Bls12381MulTest<WITNESS_OFFSET_BITS>>::new();
+        let a = builder.alloc::<FieldRegister<Bls12381BaseField<WITNESS_OFFSET_BITS>>>();
+        let b = builder.alloc::<FieldRegister<Bls12381BaseField<WITNESS_OFFSET_BITS>>>();
+        let result = builder.fp_mul(&a, &b);
+
+        let (air, trace_data) = builder.build();
+        let num_rows = 1 << 16;
+        let generator = ArithmeticGenerator::new(trace_data, num_rows);
+
+        let p_a = Polynomial::<F>::from_biguint_field(a_int, 16, 24);
+        let p_b = Polynomial::<F>::from_biguint_field(b_int, 16, 24);
+        let writer = generator.new_writer();
+        for i in 0..num_rows {
+            writer.write(&a, &p_a, i);
+            writer.write(&b, &p_b, i);
+            writer.write_row_instructions(&generator.air_data, i);
+        }
+
+        let modulus = Bls12381BaseField::<WITNESS_OFFSET_BITS>::modulus();
+        let expected = Polynomial::<F>::from_biguint_field(&((a_int * b_int) % modulus), 16, 24);
+        assert_eq!(writer.read(&result, 0), expected);
+
+        (air, generator)
+    }
+
+    #[test]
+    fn test_fpmul_bls12381() {
+        type SC = PoseidonGoldilocksStarkConfig;
+
+        // The limbs of `2^376 - 1` are all maximal, so the witness of its square is close to the
+        // bound of the offset.
+        let a_int = (BigUint::one() << 376) - 1u32;
+        let (air, generator) = write_bls12381_mul::<22>(&a_int, &a_int);
+
+        let stark = Starky::new(air);
+        let config = SC::standard_fast_config(generator.num_rows);
+        test_starky(&stark, &config, &generator, &[]);
+    }
+
+    #[test]
+    #[cfg(debug_assertions)]
+    #[should_panic(expected = "witness coefficient out of range")]
+    fn test_fpmul_bls12381_witness_overflow() {
+        // With an offset of `2^20`, the shifted witness of the square of `2^376 - 1` is negative.
+        let a_int = (BigUint::one() << 376) - 1u32;
+        write_bls12381_mul::<20>(&a_int, &a_int);
+    }
 
     #[derive(Clone, Debug, Copy, Serialize, Deserialize)]
     struct FpMulTest;
@@ -0,0 +1,111 @@
+use super::parameters::FieldParameters;
+use super::register::FieldRegister;
+use super::sub::FpSubInstruction;
+use crate::chip::builder::AirBuilder;
+use crate::chip::register::bit::BitRegister;
+use crate::chip::AirParameters;
+
+impl<L: AirParameters> AirBuilder<L> {
+    /// Computes `-x mod p` if `sign` is set and `x` otherwise.
+    ///
+    /// The negation is computed as the field subtraction `0 - x`, so that `-0 = 0`, and the result
+    /// is selected by the sign bit.
+    pub fn fp_conditional_negate<P: FieldParameters>(
+        &mut self,
+        x: &FieldRegister<P>,
+        sign: &BitRegister,
+    ) -> FieldRegister<P>
+    where
+        L::Instruction: From<FpSubInstruction<P>>,
+    {
+        let zero = self.fp_zero::<P>();
+        let neg_x = self.fp_sub(&zero, x);
+        self.select(sign, &neg_x, x)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use num::bigint::RandBigInt;
+    use num::{BigUint, Zero};
+    use rand::thread_rng;
+    use serde::{Deserialize, Serialize};
+
+    use super::*;
+    use crate::chip::builder::tests::*;
+    use crate::chip::field::parameters::tests::Fp25519;
+    use crate::polynomial::Polynomial;
+
+    #[derive(Clone, Debug, Copy, Serialize, Deserialize)]
+    struct FpConditionalNegateTest;
+
+    impl AirParameters for FpConditionalNegateTest {
+        type Field = GoldilocksField;
+        type CubicParams = GoldilocksCubicParameters;
+
+        const NUM_ARITHMETIC_COLUMNS: usize = 124;
+        const NUM_FREE_COLUMNS: usize = 3;
+        const EXTENDED_COLUMNS: usize = 195;
+
+        type Instruction = FpSubInstruction<Fp25519>;
+    }
+
+    #[test]
+    fn test_fp_conditional_negate() {
+        type F = GoldilocksField;
+        type L = FpConditionalNegateTest;
+        type SC = PoseidonGoldilocksStarkConfig;
+        type P = Fp25519;
+
+        let p = Fp25519::modulus();
+
+        let mut builder = AirBuilder::<L>::new();
+
+        let x = builder.alloc::<FieldRegister<P>>();
+        let sign = builder.alloc::<BitRegister>();
+        let result = builder.fp_conditional_negate(&x, &sign);
+
+        let (air, trace_data) = builder.build();
+        let num_rows = 1 << 16;
+        let generator = ArithmeticGenerator::<L>::new(trace_data, num_rows);
+
+        let writer = generator.new_writer();
+        writer.write_global_instructions(&generator.air_data);
+
+        (0..num_rows).into_par_iter().for_each(|i| {
+            let mut rng = thread_rng();
+            let writer = generator.new_writer();
+            // Negating zero is checked on the first rows.
+            let x_int = if i < 2 {
+                BigUint::zero()
+            } else {
+                rng.gen_biguint(256) % &p
+            };
+            let sign_value = i % 2 == 1;
+            let expected = if sign_value {
+                (&p - &x_int) % &p
+            } else {
+                x_int.clone()
+            };
+
+            writer.write(&x, &Polynomial::from_biguint_field(&x_int, 16, 16), i);
+            writer.write(&sign, &F::from_canonical_u8(sign_value as u8), i);
+            writer.write_row_instructions(&generator.air_data, i);
+
+            assert_eq!(
+                writer.read(&result, i),
+                Polynomial::<F>::from_biguint_field(&expected, 16, 16)
+            );
+        });
+
+        let stark = Starky::new(air);
+        let config = SC::standard_fast_config(num_rows);
+        let public = writer.public().unwrap().clone();
+
+        // Generate proof and verify as a stark
+        test_starky(&stark, &config, &generator, &public);
+
+        // Test the recursive proof.
+        test_recursive_starky(stark, config, generator, &public);
+    }
+}
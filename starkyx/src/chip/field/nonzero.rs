@@ -0,0 +1,429 @@
+use anyhow::{anyhow, Result};
+use num::{BigUint, One, Zero};
+use serde::{Deserialize, Serialize};
+
+use super::mul::FpMulInstruction;
+use super::parameters::FieldParameters;
+use super::register::FieldRegister;
+use crate::air::AirConstraint;
+use crate::chip::builder::AirBuilder;
+use crate::chip::instruction::Instruction;
+use crate::chip::register::array::ArrayRegister;
+use crate::chip::register::memory::MemorySlice;
+use crate::chip::register::u16::U16Register;
+use crate::chip::register::RegisterSerializable;
+use crate::chip::trace::writer::{AirWriter, TraceWriter};
+use crate::chip::utils::digits_to_biguint;
+use crate::chip::AirParameters;
+use crate::math::prelude::*;
+use crate::polynomial::parser::PolynomialParser;
+use crate::polynomial::{to_u16_le_limbs_polynomial, Polynomial};
+
+/// Computes the inverse `a_inv` of a field element `a`, asserting that `a` is non-zero.
+///
+/// The prover supplies a hint `a_inv` and the instruction constrains `a * a_inv = 1`. If `a = 0`
+/// there is no such hint and the constraint cannot be satisfied.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+#[serde(bound = "")]
+pub struct FpNonZeroInstruction<P: FieldParameters> {
+    /// a `FpMulInstruction` to check `a * a_inv = 1`.
+    inverse: FpMulInstruction<P>,
+}
+
+impl<L: AirParameters> AirBuilder<L> {
+    /// Asserts that the field element `a` is non-zero by constraining the existence of an inverse.
+    pub fn assert_nonzero<P: FieldParameters>(&mut self, a: &FieldRegister<P>)
+    where
+        L::Instruction: From<FpNonZeroInstruction<P>>,
+    {
+        self.fp_inverse(a);
+    }
+
+    /// Given a non-zero field element `a`, computes its inverse `a_inv` such that `a * a_inv = 1`.
+    pub fn fp_inverse<P: FieldParameters>(&mut self, a: &FieldRegister<P>) -> FieldRegister<P>
+    where
+        L::Instruction: From<FpNonZeroInstruction<P>>,
+    {
+        let is_trace = a.is_trace();
+
+        let carry: FieldRegister<P>;
+        let witness_low: ArrayRegister<U16Register>;
+        let witness_high: ArrayRegister<U16Register>;
+        let one = self.fp_one();
+        let a_inv: FieldRegister<P>;
+
+        if is_trace {
+            carry = self.alloc::<FieldRegister<P>>();
+            witness_low = self.alloc_array::<U16Register>(P::NB_WITNESS_LIMBS);
+            witness_high = self.alloc_array::<U16Register>(P::NB_WITNESS_LIMBS);
+            a_inv = self.alloc::<FieldRegister<P>>();
+        } else {
+            carry = self.alloc_public::<FieldRegister<P>>();
+            witness_low = self.alloc_array_public::<U16Register>(P::NB_WITNESS_LIMBS);
+            witness_high = self.alloc_array_public::<U16Register>(P::NB_WITNESS_LIMBS);
+            a_inv = self.alloc_public::<FieldRegister<P>>();
+        }
+
+        // check that a * a_inv = one.
+        let inverse = FpMulInstruction {
+            a: *a,
+            b: a_inv,
+            result: one,
+            carry,
+            witness_low,
+            witness_high,
+        };
+
+        let instr = FpNonZeroInstruction { inverse };
+
+        if is_trace {
+            self.register_instruction(instr);
+        } else {
+            self.register_global_instruction(instr);
+        }
+        a_inv
+    }
+}
+
+impl<AP: PolynomialParser, P: FieldParameters> AirConstraint<AP> for FpNonZeroInstruction<P> {
+    fn eval(&self, parser: &mut AP) {
+        self.inverse.eval(parser);
+    }
+}
+
+impl<P: FieldParameters> FpNonZeroInstruction<P> {
+    /// Computes the inverse hint of `a`, or `None` if `a` is zero.
+    fn inverse_hint<F: PrimeField64>(p_a: &[F]) -> Option<BigUint> {
+        let a_digits = p_a
+            .iter()
+            .map(|x| x.as_canonical_u64() as u16)
+            .collect::<Vec<_>>();
+        let a = digits_to_biguint(&a_digits);
+
+        let modulus = P::modulus();
+        if (&a % &modulus).is_zero() {
+            return None;
+        }
+        Some(a.modpow(&(&modulus - BigUint::from(2u64)), &modulus))
+    }
+
+    /// Computes the carry and the witness limbs of `a * a_inv = 1`.
+    ///
+    /// The result of the product is the interned public constant one, which is never written by
+    /// the instruction.
+    fn carry_and_witness<F: PrimeField64>(
+        p_a: &Polynomial<F>,
+        p_a_inv: &Polynomial<F>,
+    ) -> (Polynomial<F>, Vec<F>, Vec<F>) {
+        let p_one = to_u16_le_limbs_polynomial::<F, P>(&BigUint::one());
+        FpMulInstruction::<P>::carry_and_witness(p_a, p_a_inv, &p_one)
+    }
+
+    /// Writes the inverse of `a` at row `row_index`, returning an error if `a` is zero.
+    pub fn try_write<F: PrimeField64>(
+        &self,
+        writer: &TraceWriter<F>,
+        row_index: usize,
+    ) -> Result<()> {
+        let p_a = writer.read(&self.inverse.a, row_index);
+        let a_inv_int = Self::inverse_hint(&p_a.coefficients)
+            .ok_or_else(|| anyhow!("Cannot invert zero at row {}", row_index))?;
+        let p_a_inv = to_u16_le_limbs_polynomial::<F, P>(&a_inv_int);
+        let (p_carry, p_witness_low, p_witness_high) = Self::carry_and_witness(&p_a, &p_a_inv);
+
+        writer.write(&self.inverse.b, &p_a_inv, row_index);
+        writer.write(&self.inverse.carry, &p_carry, row_index);
+        writer.write_array(&self.inverse.witness_low, &p_witness_low, row_index);
+        writer.write_array(&self.inverse.witness_high, &p_witness_high, row_index);
+        Ok(())
+    }
+
+    /// Writes the inverse of `a` to the current row of `writer`, returning an error if `a` is
+    /// zero.
+    pub fn try_write_to_air<F: PrimeField64>(
+        &self,
+        writer: &mut impl AirWriter<Field = F>,
+    ) -> Result<()> {
+        let p_a = writer.read(&self.inverse.a);
+        let a_inv_int =
+            Self::inverse_hint(&p_a.coefficients).ok_or_else(|| anyhow!("Cannot invert zero"))?;
+        let p_a_inv = to_u16_le_limbs_polynomial::<F, P>(&a_inv_int);
+        let (p_carry, p_witness_low, p_witness_high) = Self::carry_and_witness(&p_a, &p_a_inv);
+
+        writer.write(&self.inverse.b, &p_a_inv);
+        writer.write(&self.inverse.carry, &p_carry);
+        writer.write_array(&self.inverse.witness_low, &p_witness_low);
+        writer.write_array(&self.inverse.witness_high, &p_witness_high);
+        Ok(())
+    }
+}
+
+impl<F: PrimeField64, P: FieldParameters> Instruction<F> for FpNonZeroInstruction<P> {
+    fn write(&self, writer: &TraceWriter<F>, row_index: usize) {
+        // If `a` is zero there is no inverse, and we leave the witness unassigned so that the
+        // constraint `a * a_inv = 1` fails. Use `try_write` to handle this case.
+        if let Err(e) = self.try_write(writer, row_index) {
+            log::warn!("{}, the trace will not satisfy the constraints", e);
+        }
+    }
+
+    fn write_to_air(&self, writer: &mut impl AirWriter<Field = F>) {
+        if let Err(e) = self.try_write_to_air(writer) {
+            log::warn!("{}, the trace will not satisfy the constraints", e);
+        }
+    }
+
+    fn trace_layout(&self) -> Vec<MemorySlice> {
+        vec![
+            *self.inverse.b.register(),
+            *self.inverse.carry.register(),
+            *self.inverse.witness_low.register(),
+            *self.inverse.witness_high.register(),
+        ]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use num::bigint::RandBigInt;
+    use rand::thread_rng;
+
+    use super::*;
+    use crate::chip::builder::tests::*;
+    use crate::chip::field::parameters::tests::Fp25519;
+    use crate::chip::instruction::set::AirInstruction;
+    use crate::chip::trace::data::AirTraceData;
+    use crate::chip::trace::writer::data::AirWriterData;
+    use crate::chip::utils::field_limbs_to_biguint;
+
+    #[derive(Clone, Debug, Copy, Serialize, Deserialize)]
+    struct FpNonZeroTest;
+
+    impl AirParameters for FpNonZeroTest {
+        type Field = GoldilocksField;
+        type CubicParams = GoldilocksCubicParameters;
+
+        const NUM_ARITHMETIC_COLUMNS: usize = 108;
+        const NUM_FREE_COLUMNS: usize = 2;
+        const EXTENDED_COLUMNS: usize = 171;
+
+        type Instruction = FpNonZeroInstruction<Fp25519>;
+    }
+
+    fn prove_nonzero(values: impl Fn(usize) -> BigUint + Sync) {
+        type F = GoldilocksField;
+        type L = FpNonZeroTest;
+        type SC = PoseidonGoldilocksStarkConfig;
+        type P = Fp25519;
+
+        let mut builder = AirBuilder::<L>::new();
+
+        let a = builder.alloc::<FieldRegister<P>>();
+        builder.assert_nonzero(&a);
+
+        let (air, trace_data) = builder.build();
+        let num_rows = 1 << 16;
+        let generator = ArithmeticGenerator::<L>::new(trace_data, num_rows);
+
+        let writer = generator.new_writer();
+        writer.write_global_instructions(&generator.air_data);
+
+        (0..num_rows).into_par_iter().for_each(|i| {
+            let p_a = Polynomial::<F>::from_biguint_field(&values(i), 16, 16);
+            writer.write(&a, &p_a, i);
+            writer.write_row_instructions(&generator.air_data, i);
+        });
+
+        let stark = Starky::new(air);
+        let config = SC::standard_fast_config(num_rows);
+        let public = writer.public().unwrap().clone();
+
+        // Generate proof and verify as a stark
+        test_starky(&stark, &config, &generator, &public);
+    }
+
+    /// Fills the trace of `a` and its instructions through the chunks of an `AirWriterData` and
+    /// returns the generator holding that trace.
+    fn write_with_air_writer(
+        trace_data: AirTraceData<FpNonZeroTest>,
+        a: &FieldRegister<Fp25519>,
+        values: &[BigUint],
+    ) -> ArithmeticGenerator<FpNonZeroTest> {
+        type F = GoldilocksField;
+
+        let num_rows = values.len();
+        let mut writer_data = AirWriterData::new(&trace_data, num_rows);
+        trace_data.write_global_instructions(&mut writer_data.public_writer());
+
+        let chunk_size = 1 << 8;
+        for mut chunk in writer_data.chunks(chunk_size) {
+            for i in 0..chunk_size {
+                let mut writer = chunk.row_writer(i);
+                let row_index = writer.row_index().unwrap();
+                let p_a = Polynomial::<F>::from_biguint_field(&values[row_index], 16, 16);
+                writer.write(a, &p_a);
+                trace_data.write_trace_instructions(&mut writer);
+            }
+        }
+
+        let generator = ArithmeticGenerator::<FpNonZeroTest>::new(trace_data, num_rows);
+        let writer = generator.new_writer();
+        let mut trace = writer.write_trace().unwrap();
+        for (i, row) in writer_data.trace.rows().enumerate() {
+            trace.row_mut(i)[..row.len()].copy_from_slice(row);
+        }
+        drop(trace);
+        writer
+            .public_mut()
+            .unwrap()
+            .copy_from_slice(&writer_data.public);
+        generator
+    }
+
+    #[test]
+    fn test_fp_assert_nonzero_air_writer() {
+        type L = FpNonZeroTest;
+        type SC = PoseidonGoldilocksStarkConfig;
+        type P = Fp25519;
+
+        let mut builder = AirBuilder::<L>::new();
+        let a = builder.alloc::<FieldRegister<P>>();
+        builder.assert_nonzero(&a);
+
+        let (air, trace_data) = builder.build();
+        let num_rows = 1 << 16;
+        let values = (0..num_rows)
+            .map(|i| BigUint::from(i as u64 + 1))
+            .collect::<Vec<_>>();
+        let generator = write_with_air_writer(trace_data, &a, &values);
+
+        let stark = Starky::new(air);
+        let config = SC::standard_fast_config(num_rows);
+        let public = generator.new_writer().public().unwrap().clone();
+
+        // Generate proof and verify as a stark
+        test_starky(&stark, &config, &generator, &public);
+    }
+
+    #[test]
+    fn test_fp_inverse_air_writer() {
+        type L = FpNonZeroTest;
+        type SC = PoseidonGoldilocksStarkConfig;
+        type P = Fp25519;
+
+        let p = Fp25519::modulus();
+
+        let mut builder = AirBuilder::<L>::new();
+        let a = builder.alloc::<FieldRegister<P>>();
+        let a_inv = builder.fp_inverse(&a);
+
+        let (air, trace_data) = builder.build();
+        let num_rows = 1 << 16;
+        let values = (0..num_rows)
+            .map(|_| {
+                let mut rng = thread_rng();
+                rng.gen_biguint_range(&BigUint::from(1u32), &p)
+            })
+            .collect::<Vec<_>>();
+        let generator = write_with_air_writer(trace_data, &a, &values);
+
+        let writer = generator.new_writer();
+        for (i, value) in values.iter().enumerate().take(16) {
+            let a_inv_value = field_limbs_to_biguint(writer.read(&a_inv, i).coefficients());
+            assert_eq!((value * a_inv_value) % &p, BigUint::from(1u32));
+        }
+
+        let stark = Starky::new(air);
+        let config = SC::standard_fast_config(num_rows);
+        let public = writer.public().unwrap().clone();
+
+        // Generate proof and verify as a stark
+        test_starky(&stark, &config, &generator, &public);
+    }
+
+    #[test]
+    fn test_fp_assert_nonzero() {
+        let p = Fp25519::modulus();
+        prove_nonzero(|_| {
+            let mut rng = thread_rng();
+            rng.gen_biguint_range(&BigUint::from(1u32), &p)
+        });
+    }
+
+    #[test]
+    fn test_fp_inverse() {
+        type F = GoldilocksField;
+        type L = FpNonZeroTest;
+        type SC = PoseidonGoldilocksStarkConfig;
+        type P = Fp25519;
+
+        let p = Fp25519::modulus();
+
+        let mut builder = AirBuilder::<L>::new();
+        let a = builder.alloc::<FieldRegister<P>>();
+        let a_inv = builder.fp_inverse(&a);
+
+        let (air, trace_data) = builder.build();
+        let num_rows = 1 << 16;
+        let generator = ArithmeticGenerator::<L>::new(trace_data, num_rows);
+
+        let writer = generator.new_writer();
+        writer.write_global_instructions(&generator.air_data);
+
+        let values = (0..num_rows)
+            .into_par_iter()
+            .map(|_| {
+                let mut rng = thread_rng();
+                rng.gen_biguint_range(&BigUint::from(1u32), &p)
+            })
+            .collect::<Vec<_>>();
+        (0..num_rows).into_par_iter().for_each(|i| {
+            let p_a = Polynomial::<F>::from_biguint_field(&values[i], 16, 16);
+            writer.write(&a, &p_a, i);
+            writer.write_row_instructions(&generator.air_data, i);
+        });
+
+        for (i, value) in values.iter().enumerate().take(16) {
+            let a_inv_value = field_limbs_to_biguint(writer.read(&a_inv, i).coefficients());
+            assert_eq!((value * a_inv_value) % &p, BigUint::from(1u32));
+        }
+
+        // Writing the inverse of zero is an error.
+        let instruction = generator
+            .air_data
+            .instructions
+            .iter()
+            .find_map(|instruction| match instruction {
+                AirInstruction::CustomInstruction(instruction) => Some(*instruction),
+                _ => None,
+            })
+            .unwrap();
+        let zero_writer = TraceWriter::new(&generator.air_data, num_rows);
+        zero_writer.write(
+            &a,
+            &Polynomial::<F>::from_biguint_field(&BigUint::zero(), 16, 16),
+            0,
+        );
+        let err = instruction.try_write(&zero_writer, 0).unwrap_err();
+        assert!(err.to_string().contains("Cannot invert zero"));
+
+        let stark = Starky::new(air);
+        let config = SC::standard_fast_config(num_rows);
+        let public = writer.public().unwrap().clone();
+
+        // Generate proof and verify as a stark
+        test_starky(&stark, &config, &generator, &public);
+    }
+
+    #[test]
+    #[should_panic(expected = "the vanishing polynomial is not divisible by Z_H")]
+    fn test_fp_assert_nonzero_fails_on_zero() {
+        prove_nonzero(|i| {
+            if i == 42 {
+                BigUint::zero()
+            } else {
+                BigUint::from(i as u64 + 1)
+            }
+        });
+    }
+}
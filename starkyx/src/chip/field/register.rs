@@ -1,6 +1,7 @@
 use core::iter::once;
 use core::marker::PhantomData;
 
+use num::BigUint;
 use serde::{Deserialize, Serialize};
 
 use super::parameters::FieldParameters;
@@ -14,8 +15,11 @@ use crate::chip::register::cubic::CubicRegister;
 use crate::chip::register::memory::MemorySlice;
 use crate::chip::register::u16::U16Register;
 use crate::chip::register::{Register, RegisterSerializable, RegisterSized};
+use crate::chip::trace::writer::TraceWriter;
+use crate::chip::utils::field_limbs_to_biguint;
+use crate::chip::AirParameters;
 use crate::math::prelude::*;
-use crate::polynomial::Polynomial;
+use crate::polynomial::{to_u16_le_limbs_polynomial, Polynomial};
 
 /// A register for representing a field element. The value is decomposed into a series of U16 limbs
 /// which is controlled by `NB_LIMBS` in FieldParameters. Each limb is range checked using a lookup.
@@ -64,7 +68,7 @@ impl<P: FieldParameters> MemoryValue for FieldRegister<P> {
         P::NB_LIMBS / 2 + 1
     }
 
-    fn compress<L: crate::chip::AirParameters>(
+    fn compress<L: AirParameters>(
         &self,
         builder: &mut AirBuilder<L>,
         ptr: RawPointer,
@@ -91,6 +95,46 @@ impl<P: FieldParameters> MemoryValue for FieldRegister<P> {
     }
 }
 
+impl<L: AirParameters> AirBuilder<L> {
+    /// Allocates a pair of public field registers, such as the coordinates of a point.
+    ///
+    /// The two registers are contiguous in the public inputs, `x` first.
+    pub fn alloc_public_point<P: FieldParameters>(
+        &mut self,
+    ) -> (FieldRegister<P>, FieldRegister<P>) {
+        let x = self.alloc_public::<FieldRegister<P>>();
+        let y = self.alloc_public::<FieldRegister<P>>();
+        (x, y)
+    }
+}
+
+impl<F: PrimeField64> TraceWriter<F> {
+    /// Reads a pair of public field registers allocated by [`AirBuilder::alloc_public_point`].
+    pub fn read_public_point<P: FieldParameters>(
+        &self,
+        point: &(FieldRegister<P>, FieldRegister<P>),
+    ) -> (BigUint, BigUint) {
+        let p_x = self.read(&point.0, 0);
+        let p_y = self.read(&point.1, 0);
+        (
+            field_limbs_to_biguint(p_x.coefficients()),
+            field_limbs_to_biguint(p_y.coefficients()),
+        )
+    }
+
+    /// Writes a pair of public field registers allocated by [`AirBuilder::alloc_public_point`].
+    pub fn write_public_point<P: FieldParameters>(
+        &self,
+        point: &(FieldRegister<P>, FieldRegister<P>),
+        value: &(BigUint, BigUint),
+    ) {
+        let p_x = to_u16_le_limbs_polynomial::<F, P>(&value.0);
+        let p_y = to_u16_le_limbs_polynomial::<F, P>(&value.1);
+        self.write(&point.0, &p_x, 0);
+        self.write(&point.1, &p_y, 0);
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use num::bigint::RandBigInt;
@@ -115,6 +159,33 @@ mod tests {
     use crate::plonky2::stark::Starky;
     use crate::polynomial::Polynomial;
 
+    #[test]
+    fn test_public_point() {
+        type F = GoldilocksField;
+        type L = FpMemAddTest;
+        type P = Fp25519;
+
+        let mut builder = AirBuilder::<L>::new();
+        let point = builder.alloc_public_point::<P>();
+        let (_, trace_data) = builder.build();
+        let generator = ArithmeticGenerator::<L>::new(trace_data, 1 << 4);
+
+        let mut rng = thread_rng();
+        let p = Fp25519::modulus();
+        let value = (rng.gen_biguint(256) % &p, rng.gen_biguint(256) % &p);
+
+        let writer = generator.new_writer();
+        writer.write_public_point(&point, &value);
+        assert_eq!(writer.read_public_point(&point), value);
+
+        // The coordinates are contiguous in the public inputs.
+        let public = writer.public().unwrap().clone();
+        let p_x = Polynomial::<F>::from_biguint_field(&value.0, 16, 16);
+        let p_y = Polynomial::<F>::from_biguint_field(&value.1, 16, 16);
+        assert_eq!(&public[..16], p_x.coefficients());
+        assert_eq!(&public[16..32], p_y.coefficients());
+    }
+
     #[derive(Clone, Debug, Copy, Serialize, Deserialize)]
     struct FpMemAddTest;
 
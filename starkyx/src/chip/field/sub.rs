@@ -7,6 +7,7 @@ use crate::air::AirConstraint;
 use crate::chip::builder::AirBuilder;
 use crate::chip::instruction::Instruction;
 use crate::chip::register::array::ArrayRegister;
+use crate::chip::register::memory::MemorySlice;
 use crate::chip::register::u16::U16Register;
 use crate::chip::register::RegisterSerializable;
 use crate::chip::trace::writer::{AirWriter, TraceWriter};
@@ -45,12 +46,14 @@ impl<L: AirParameters> AirBuilder<L> {
         result
     }
 
+    /// Sets `result` to the difference `a - b`, returning the registered instruction.
     pub fn set_fp_sub<P: FieldParameters>(
         &mut self,
         a: &FieldRegister<P>,
         b: &FieldRegister<P>,
         result: &FieldRegister<P>,
-    ) where
+    ) -> FpSubInstruction<P>
+    where
         L::Instruction: From<FpSubInstruction<P>>,
     {
         let is_trace = a.is_trace() || b.is_trace() || result.is_trace();
@@ -70,6 +73,7 @@ impl<L: AirParameters> AirBuilder<L> {
         let inner_instr = FpAddInstruction {
             a: *result,
             b: *b,
+            c: None,
             result: *a,
             carry,
             witness_low,
@@ -82,6 +86,7 @@ impl<L: AirParameters> AirBuilder<L> {
         } else {
             self.register_global_instruction(instr);
         }
+        instr
     }
 }
 
@@ -148,6 +153,16 @@ impl<F: PrimeField64, P: FieldParameters> Instruction<F> for FpSubInstruction<P>
 
         self.inner.write_to_air(writer);
     }
+
+    fn trace_layout(&self) -> Vec<MemorySlice> {
+        // The inner addition rewrites its result, the minuend, with its own value.
+        vec![
+            *self.inner.a.register(),
+            *self.inner.carry.register(),
+            *self.inner.witness_low.register(),
+            *self.inner.witness_high.register(),
+        ]
+    }
 }
 
 #[cfg(test)]
@@ -1,6 +1,7 @@
 use num::BigUint;
 
-use super::parameters::FieldParameters;
+use super::parameters::{FieldParameters, LIMB};
+use crate::chip::utils::bigint_into_u16_digits;
 use crate::math::prelude::*;
 use crate::polynomial::parser::PolynomialParser;
 use crate::polynomial::Polynomial;
@@ -41,8 +42,123 @@ pub fn modulus_field_iter<F: Field, P: FieldParameters>() -> impl Iterator<Item
         .take(P::NB_LIMBS)
 }
 
+/// Checks that the coefficients of the vanishing polynomial `a(x) * b(x) - result(x) -
+/// carry(x) * p(x)` of a product do not wrap around `F`.
+///
+/// The coefficients are accumulated as integers from the `u16` limbs of the operands, and each of
+/// them is represented faithfully in `F` only if its absolute value is below half of the order of `F`.
 #[inline]
-pub fn compute_root_quotient_and_shift<F: Field>(
+pub fn debug_assert_mul_in_range<F: PrimeField64>(
+    p_a: &Polynomial<F>,
+    p_b: &Polynomial<F>,
+    p_result: &Polynomial<F>,
+    p_carry: &Polynomial<F>,
+    p_modulus: &Polynomial<F>,
+) {
+    if !cfg!(debug_assertions) {
+        return;
+    }
+    let limbs = |p: &Polynomial<F>| {
+        p.coefficients()
+            .iter()
+            .map(|x| {
+                let limb = x.as_canonical_u64();
+                debug_assert!(limb < LIMB as u64, "limb {} is not a u16", limb);
+                limb as i128
+            })
+            .collect::<Vec<_>>()
+    };
+    let product = |x: &[i128], y: &[i128]| {
+        let mut z = vec![0i128; x.len() + y.len()];
+        for (i, x_i) in x.iter().enumerate() {
+            for (j, y_j) in y.iter().enumerate() {
+                z[i + j] += x_i * y_j;
+            }
+        }
+        z
+    };
+
+    let mut vanishing = product(&limbs(p_a), &limbs(p_b));
+    let carry_mul_modulus = product(&limbs(p_carry), &limbs(p_modulus));
+    for (i, coefficient) in carry_mul_modulus.into_iter().enumerate() {
+        vanishing[i] -= coefficient;
+    }
+    for (i, coefficient) in limbs(p_result).into_iter().enumerate() {
+        vanishing[i] -= coefficient;
+    }
+
+    let half_order = (F::order() / 2) as i128;
+    for coefficient in vanishing {
+        debug_assert!(
+            coefficient.abs() < half_order,
+            "vanishing polynomial coefficient {} overflows the field",
+            coefficient
+        );
+    }
+}
+
+/// Reduces a sum of field elements given by their `u16` limbs, returning the limbs of `result`
+/// and `carry` such that `sum = carry * p + result` with `result < p`.
+///
+/// The reduction avoids control flow that depends on the summands: the carry is computed by a
+/// restoring division whose number of steps only depends on the modulus and on the number of
+/// summands, and each step subtracts a shifted modulus and selects the outcome with a mask.
+///
+/// This is the only reduction of the field chips that avoids data-dependent branching. The
+/// witness computations of the other field operations still divide with `BigUint`, whose running
+/// time depends on the size of the operands, and the conversions to and from canonical field
+/// elements may branch on their values.
+pub fn reduce_sum_limbs<P: FieldParameters>(summands: &[&[u16]]) -> (Vec<u16>, Vec<u16>) {
+    // Any sum of limbs fits in one more limb than the modulus.
+    let width = P::NB_LIMBS + 1;
+
+    let mut sum = vec![0u16; width];
+    let mut carry_limb = 0u32;
+    for (i, limb) in sum.iter_mut().enumerate() {
+        let mut acc = carry_limb;
+        for summand in summands {
+            acc += summand.get(i).copied().unwrap_or(0) as u32;
+        }
+        *limb = acc as u16;
+        carry_limb = acc >> 16;
+    }
+
+    // The number of steps is determined by the largest possible quotient.
+    let max_sum =
+        BigUint::from(summands.len()) * ((BigUint::from(1u32) << (16 * P::NB_LIMBS)) - 1u32);
+    let modulus = P::modulus();
+    let nb_steps = (&max_sum / &modulus).bits() as usize;
+
+    let mut remainder = sum;
+    let mut quotient = vec![0u16; P::NB_LIMBS];
+    for k in (0..nb_steps).rev() {
+        let shifted_modulus = bigint_into_u16_digits(&(&modulus << k), width);
+
+        let mut difference = vec![0u16; width];
+        let mut borrow = 0u32;
+        for i in 0..width {
+            let diff = (remainder[i] as u32)
+                .wrapping_sub(shifted_modulus[i] as u32)
+                .wrapping_sub(borrow);
+            difference[i] = diff as u16;
+            borrow = diff >> 31;
+        }
+
+        // Keep the difference if and only if the subtraction did not underflow.
+        let bit = (borrow ^ 1) as u16;
+        let mask = bit.wrapping_neg();
+        for (r, d) in remainder.iter_mut().zip(difference.iter()) {
+            *r = (d & mask) | (*r & !mask);
+        }
+        quotient[k / 16] |= bit << (k % 16);
+    }
+
+    remainder.truncate(P::NB_LIMBS);
+    (remainder, quotient)
+}
+
+#[inline]
+pub fn compute_root_quotient_and_shift<F: PrimeField64>(
     p_vanishing: &Polynomial<F>,
     offset: usize,
 ) -> Vec<F> {
@@ -60,8 +176,21 @@ pub fn compute_root_quotient_and_shift<F: Field>(
     let p_quotient = p_vanishing.root_quotient(root_monomial);
     debug_assert_eq!(p_quotient.degree(), p_vanishing.degree() - 1);
 
-    // Sanity Check #1: For all i, |w_i| < 2^20 to prevent overflows.
+    // Sanity Check #1: For all i, |w_i| < offset, so the shifted witness is positive and fits in
+    // the two `u16` limbs it is split into.
     let offset_u64 = offset as u64;
+    debug_assert!(
+        offset_u64 <= 1 << 31,
+        "witness offset {} is too large",
+        offset
+    );
+    for c in p_quotient.coefficients().iter() {
+        debug_assert!(
+            c.as_canonical_u64() < offset_u64 || c.neg().as_canonical_u64() <= offset_u64,
+            "witness coefficient out of range of the witness offset {}",
+            offset
+        );
+    }
 
     // Sanity Check #2: w(x) * (x - 2^16) = vanishing(x).
     let x_minus_root = Polynomial::<F>::from_coefficients_slice(&[-root_monomial, F::ONE]);
@@ -119,4 +119,8 @@ impl<F: Field> Instruction<F> for AssignInstruction<F> {
             _ => {}
         }
     }
+
+    fn trace_layout(&self) -> Vec<MemorySlice> {
+        vec![self.target]
+    }
 }
@@ -0,0 +1,148 @@
+use serde::{Deserialize, Serialize};
+
+use super::set::AirInstruction;
+use super::Instruction;
+use crate::air::parser::AirParser;
+use crate::air::AirConstraint;
+use crate::chip::builder::AirBuilder;
+use crate::chip::register::array::ArrayRegister;
+use crate::chip::register::bit::BitRegister;
+use crate::chip::register::element::ElementRegister;
+use crate::chip::register::memory::MemorySlice;
+use crate::chip::register::{Register, RegisterSerializable};
+use crate::chip::trace::writer::{AirWriter, TraceWriter};
+use crate::chip::AirParameters;
+use crate::math::prelude::*;
+
+/// Constrains a value to lie in the range `[0, 2^n)`.
+///
+/// The value is decomposed into `n` bits, each constrained by `b * (b - 1) = 0` as a
+/// `BitRegister`, whose weighted sum `sum_i b_i * 2^i` must equal the value.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BitRangeInstruction {
+    value: ElementRegister,
+    bits: ArrayRegister<BitRegister>,
+}
+
+impl<L: AirParameters> AirBuilder<L> {
+    /// Constrains the value of `reg` to lie in the range `[0, 2^num_bits)` and returns its
+    /// little-endian bit decomposition.
+    ///
+    /// Uses `num_bits` free columns, for any `num_bits` between 1 and 63.
+    pub fn range_check_bits(
+        &mut self,
+        reg: &ElementRegister,
+        num_bits: usize,
+    ) -> ArrayRegister<BitRegister> {
+        assert!(
+            (1..=63).contains(&num_bits),
+            "range check of {} bits is not supported, expected between 1 and 63 bits",
+            num_bits
+        );
+        let bits = self.alloc_array::<BitRegister>(num_bits);
+
+        let instr = BitRangeInstruction { value: *reg, bits };
+        self.register_air_instruction_internal(AirInstruction::BitRange(instr));
+
+        bits
+    }
+}
+
+impl BitRangeInstruction {
+    fn bit_values<F: PrimeField64>(&self, value: F) -> Vec<F> {
+        let value = value.as_canonical_u64();
+        (0..self.bits.len())
+            .map(|i| F::from_canonical_u64((value >> i) & 1))
+            .collect()
+    }
+}
+
+impl<AP: AirParser> AirConstraint<AP> for BitRangeInstruction {
+    fn eval(&self, parser: &mut AP) {
+        let value = self.value.eval(parser);
+        let bits = self.bits.eval_vec(parser);
+
+        // value = sum_i bit_i * 2^i.
+        let mut constraint = value;
+        for (i, bit) in bits.iter().enumerate() {
+            let bit_times_power = parser.mul_const(*bit, AP::Field::from_canonical_u64(1 << i));
+            constraint = parser.sub(constraint, bit_times_power);
+        }
+        parser.constraint(constraint);
+    }
+}
+
+impl<F: PrimeField64> Instruction<F> for BitRangeInstruction {
+    fn write(&self, writer: &TraceWriter<F>, row_index: usize) {
+        let value = writer.read(&self.value, row_index);
+        writer.write_array(&self.bits, self.bit_values(value), row_index);
+    }
+
+    fn write_to_air(&self, writer: &mut impl AirWriter<Field = F>) {
+        let value = writer.read(&self.value);
+        writer.write_array(&self.bits, self.bit_values(value));
+    }
+
+    fn trace_layout(&self) -> Vec<MemorySlice> {
+        vec![*self.bits.register()]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::chip::builder::tests::*;
+
+    #[derive(Debug, Clone, Serialize, Deserialize)]
+    pub struct BitRangeTest;
+
+    impl AirParameters for BitRangeTest {
+        type Field = GoldilocksField;
+        type CubicParams = GoldilocksCubicParameters;
+        type Instruction = EmptyInstruction<GoldilocksField>;
+
+        const NUM_FREE_COLUMNS: usize = 13;
+    }
+
+    fn prove_bit_range(values: impl Fn(usize) -> u64) {
+        type F = GoldilocksField;
+        type L = BitRangeTest;
+        type SC = PoseidonGoldilocksStarkConfig;
+
+        let mut builder = AirBuilder::<L>::new();
+        let value = builder.alloc::<ElementRegister>();
+        builder.range_check_bits(&value, 12);
+
+        let (air, trace_data) = builder.build();
+
+        let num_rows = 1 << 12;
+        let generator = ArithmeticGenerator::<L>::new(trace_data, num_rows);
+        let writer = generator.new_writer();
+
+        for i in 0..num_rows {
+            writer.write(&value, &F::from_canonical_u64(values(i)), i);
+            writer.write_row_instructions(&generator.air_data, i);
+        }
+
+        let stark = Starky::new(air);
+        let config = SC::standard_fast_config(num_rows);
+
+        // Generate proof and verify as a stark
+        test_starky(&stark, &config, &generator, &[]);
+    }
+
+    #[test]
+    fn test_range_check_bits() {
+        prove_bit_range(|i| match i {
+            0 => 0,
+            1 => (1 << 12) - 1,
+            _ => (i as u64 * 37) % (1 << 12),
+        });
+    }
+
+    #[test]
+    #[should_panic(expected = "the vanishing polynomial is not divisible by Z_H")]
+    fn test_range_check_bits_out_of_range() {
+        prove_bit_range(|i| if i == 7 { 1 << 12 } else { i as u64 });
+    }
+}
@@ -4,6 +4,7 @@ use super::Instruction;
 use crate::air::parser::AirParser;
 use crate::air::AirConstraint;
 use crate::chip::register::element::ElementRegister;
+use crate::chip::register::memory::MemorySlice;
 use crate::chip::register::{Register, RegisterSerializable};
 use crate::chip::trace::writer::{AirWriter, TraceWriter};
 use crate::math::prelude::*;
@@ -36,4 +37,8 @@ impl<F: Field> Instruction<F> for ClockInstruction {
         let value = F::from_canonical_usize(writer.row_index().unwrap());
         writer.write(&self.clk, &value);
     }
+
+    fn trace_layout(&self) -> Vec<MemorySlice> {
+        vec![*self.clk.register()]
+    }
 }
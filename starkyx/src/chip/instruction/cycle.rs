@@ -1,3 +1,5 @@
+use core::borrow::Borrow;
+
 use serde::{Deserialize, Serialize};
 
 use super::set::AirInstruction;
@@ -9,6 +11,7 @@ use crate::chip::builder::AirBuilder;
 use crate::chip::register::array::ArrayRegister;
 use crate::chip::register::bit::BitRegister;
 use crate::chip::register::element::ElementRegister;
+use crate::chip::register::memory::MemorySlice;
 use crate::chip::register::{Register, RegisterSerializable};
 use crate::chip::trace::writer::{AirWriter, TraceWriter};
 use crate::chip::AirParameters;
@@ -46,6 +49,40 @@ impl Loop {
     }
 }
 
+/// An array of registers holding one group of values per cycle.
+///
+/// The values are constrained to stay the same on all the rows of a cycle, so each group can be
+/// read from any row of its cycle, as in [`TraceWriter::read_cycle_array`].
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub struct CycleArray<T> {
+    values: ArrayRegister<T>,
+    cycle_length: usize,
+}
+
+impl<T: Register> CycleArray<T> {
+    /// The registers of the group of the current cycle.
+    pub fn values(&self) -> ArrayRegister<T> {
+        self.values
+    }
+
+    /// The number of rows of a cycle.
+    pub fn cycle_length(&self) -> usize {
+        self.cycle_length
+    }
+
+    /// The first row of the cycle with index `cycle_index`.
+    pub fn first_row(&self, cycle_index: usize) -> usize {
+        cycle_index * self.cycle_length
+    }
+}
+
+impl<F> Cycle<F> {
+    /// The number of rows of the cycle.
+    pub fn length(&self) -> usize {
+        self.group.len()
+    }
+}
+
 impl<L: AirParameters> AirBuilder<L> {
     pub fn cycle(&mut self, length_log: usize) -> Cycle<L::Field> {
         let start_bit = self.alloc::<BitRegister>();
@@ -111,6 +148,50 @@ impl<L: AirParameters> AirBuilder<L> {
             iterations_registers,
         }
     }
+
+    /// Allocates an array of `per_row` registers holding one group of values per cycle of `cycle`.
+    pub fn alloc_cycle_array<T: Register>(
+        &mut self,
+        cycle: &Cycle<L::Field>,
+        per_row: usize,
+    ) -> CycleArray<T> {
+        let values = self.alloc_array::<T>(per_row);
+
+        // The values are copied forward to the next row unless the cycle ends.
+        for value in values.iter() {
+            self.assert_expression_zero_transition(
+                cycle.end_bit.not_expr() * (value.next().expr() - value.expr()),
+            );
+        }
+
+        CycleArray {
+            values,
+            cycle_length: cycle.length(),
+        }
+    }
+
+    /// Allocates a column whose value on row `i` is `values[i % values.len()]`.
+    ///
+    /// The period is enforced by a rotating one-hot selector (see [`Self::loop_instr`]), so the
+    /// values are fixed by the constraints and do not occupy any public inputs.
+    pub fn periodic_constant(&mut self, values: &[L::Field]) -> ElementRegister {
+        assert!(
+            !values.is_empty(),
+            "periodic constant must have a non-empty period"
+        );
+        let selectors = self.loop_instr(values.len());
+        let periodic_constant = self.alloc::<ElementRegister>();
+
+        let expression = values
+            .iter()
+            .enumerate()
+            .fold(ArithmeticExpression::zero(), |acc, (i, value)| {
+                acc + selectors.get_iteration_reg(i).expr() * *value
+            });
+        self.set_to_expression(&periodic_constant, expression);
+
+        periodic_constant
+    }
 }
 
 impl<AP: AirParser<Field = F>, F: Field> AirConstraint<AP> for Cycle<F> {
@@ -232,6 +313,16 @@ impl<F: Field> Instruction<F> for Cycle<F> {
             writer.write(&self.end_bit_witness, &(element - gen_inverse).inverse());
         }
     }
+
+    fn trace_layout(&self) -> Vec<MemorySlice> {
+        vec![
+            *self.element.register(),
+            *self.start_bit.register(),
+            *self.end_bit.register(),
+            *self.start_bit_witness.register(),
+            *self.end_bit_witness.register(),
+        ]
+    }
 }
 
 impl<AP: AirParser<Field = F>, F: Field> AirConstraint<AP> for ProcessIdInstruction {
@@ -258,6 +349,39 @@ impl<F: Field> Instruction<F> for ProcessIdInstruction {
         let process_id = F::from_canonical_usize(row_index / self.size);
         writer.write(&self.process_id, &process_id);
     }
+
+    fn trace_layout(&self) -> Vec<MemorySlice> {
+        vec![*self.process_id.register()]
+    }
+}
+
+impl<F: Field> TraceWriter<F> {
+    /// Writes the group of values of the cycle with index `cycle_index` to all its rows.
+    pub fn write_cycle_array<T: Register, I>(
+        &self,
+        array: &CycleArray<T>,
+        values: I,
+        cycle_index: usize,
+    ) where
+        I: IntoIterator,
+        I::Item: Borrow<T::Value<F>>,
+        I::IntoIter: Clone,
+    {
+        let values = values.into_iter();
+        let first_row = array.first_row(cycle_index);
+        for row_index in first_row..first_row + array.cycle_length {
+            self.write_array(&array.values, values.clone(), row_index);
+        }
+    }
+
+    /// Reads the group of values of the cycle with index `cycle_index`.
+    pub fn read_cycle_array<T: Register>(
+        &self,
+        array: &CycleArray<T>,
+        cycle_index: usize,
+    ) -> Vec<T::Value<F>> {
+        self.read_vec(&array.values, array.first_row(cycle_index))
+    }
 }
 
 #[cfg(test)]
@@ -316,4 +440,108 @@ mod tests {
             air.eval(&mut window_parser);
         }
     }
+
+    #[derive(Clone, Debug, Serialize, Deserialize)]
+    pub struct PeriodicConstantTest;
+
+    impl AirParameters for PeriodicConstantTest {
+        type Field = GoldilocksField;
+        type CubicParams = GoldilocksCubicParameters;
+
+        type Instruction = EmptyInstruction<GoldilocksField>;
+
+        const NUM_ARITHMETIC_COLUMNS: usize = 0;
+        const NUM_FREE_COLUMNS: usize = 5;
+    }
+
+    #[test]
+    fn test_periodic_constant() {
+        type F = GoldilocksField;
+        type L = PeriodicConstantTest;
+        type SC = PoseidonGoldilocksStarkConfig;
+
+        let mut builder = AirBuilder::<L>::new();
+
+        let values = [7u32, 11, 13, 17].map(F::from_canonical_u32);
+        let periodic = builder.periodic_constant(&values);
+
+        let (air, trace_data) = builder.build();
+
+        let num_rows = 1 << 8;
+        let generator = ArithmeticGenerator::<L>::new(trace_data, num_rows);
+        let writer = generator.new_writer();
+        writer.write_global_instructions(&generator.air_data);
+        for i in 0..num_rows {
+            writer.write_row_instructions(&generator.air_data, i);
+        }
+
+        for i in 0..num_rows {
+            assert_eq!(writer.read(&periodic, i), values[i % values.len()]);
+        }
+
+        let stark = Starky::new(air);
+        let config = SC::standard_fast_config(num_rows);
+        let public = writer.public().unwrap().clone();
+
+        // Generate proof and verify as a stark
+        test_starky(&stark, &config, &generator, &public);
+    }
+
+    #[derive(Clone, Debug, Serialize, Deserialize)]
+    pub struct CycleArrayTest;
+
+    impl AirParameters for CycleArrayTest {
+        type Field = GoldilocksField;
+        type CubicParams = GoldilocksCubicParameters;
+
+        type Instruction = EmptyInstruction<GoldilocksField>;
+
+        const NUM_ARITHMETIC_COLUMNS: usize = 0;
+        const NUM_FREE_COLUMNS: usize = 8;
+    }
+
+    #[test]
+    fn test_cycle_array() {
+        type F = GoldilocksField;
+        type L = CycleArrayTest;
+        type SC = PoseidonGoldilocksStarkConfig;
+
+        let mut builder = AirBuilder::<L>::new();
+        let cycle = builder.cycle(2);
+        let array = builder.alloc_cycle_array::<ElementRegister>(&cycle, 3);
+
+        let (air, trace_data) = builder.build();
+
+        let num_rows = 1 << 8;
+        let num_cycles = num_rows / cycle.length();
+        let generator = ArithmeticGenerator::<L>::new(trace_data, num_rows);
+        let writer = generator.new_writer();
+
+        let group = |cycle_index: usize| {
+            (0..3)
+                .map(|j| F::from_canonical_usize(3 * cycle_index + j))
+                .collect::<Vec<_>>()
+        };
+        for cycle_index in 0..num_cycles {
+            writer.write_cycle_array(&array, &group(cycle_index), cycle_index);
+        }
+        writer.write_global_instructions(&generator.air_data);
+        for i in 0..num_rows {
+            writer.write_row_instructions(&generator.air_data, i);
+        }
+
+        for cycle_index in 0..num_cycles {
+            assert_eq!(
+                writer.read_cycle_array(&array, cycle_index),
+                group(cycle_index)
+            );
+        }
+
+        let stark = Starky::new(air);
+        let config = SC::standard_fast_config(num_rows);
+        let public = writer.public().unwrap().clone();
+
+        // Generate proof and verify as a stark
+        test_starky(&stark, &config, &generator, &public);
+    }
 }
@@ -3,6 +3,7 @@ use serde::{Deserialize, Serialize};
 use super::Instruction;
 use crate::air::parser::AirParser;
 use crate::air::AirConstraint;
+use crate::chip::register::memory::MemorySlice;
 use crate::chip::trace::writer::{AirWriter, TraceWriter};
 use crate::math::prelude::*;
 
@@ -16,6 +17,10 @@ impl<F: Field> Instruction<F> for EmptyInstruction<F> {
     fn write(&self, _writer: &TraceWriter<F>, _row_index: usize) {}
 
     fn write_to_air(&self, _writer: &mut impl AirWriter<Field = F>) {}
+
+    fn trace_layout(&self) -> Vec<MemorySlice> {
+        Vec::new()
+    }
 }
 
 impl<F: Field, AP: AirParser<Field = F>> AirConstraint<AP> for EmptyInstruction<F> {
@@ -2,16 +2,20 @@ use core::fmt::Debug;
 
 use serde::{Deserialize, Serialize};
 
+use super::register::memory::MemorySlice;
+use super::register::relocation::memory_slices;
 use super::trace::writer::AirWriter;
 use crate::chip::trace::writer::TraceWriter;
 use crate::math::prelude::*;
 
 pub mod assign;
 pub mod bit;
+pub mod bit_range;
 pub mod clock;
 pub mod cycle;
 pub mod empty;
 pub mod set;
+pub mod signed_range;
 
 pub trait Instruction<F: Field>:
     'static + Send + Sync + Clone + Debug + Serialize + for<'de> Deserialize<'de>
@@ -22,6 +26,29 @@ pub trait Instruction<F: Field>:
     #[allow(unused_variables)]
     // Writes the instruction to a general AirWriter.
     fn write_to_air(&self, writer: &mut impl AirWriter<Field = F>);
+
+    /// A name for the type of the instruction, used to group instructions when profiling the
+    /// trace generation.
+    fn name(&self) -> &'static str {
+        core::any::type_name::<Self>()
+    }
+
+    /// The registers written by the instruction.
+    ///
+    /// This is used by [`AirTraceData::validate_trace`] to check that the instructions of a chip
+    /// write to disjoint columns of the trace. Inputs that an instruction rewrites with their own
+    /// value are not part of its layout.
+    ///
+    /// [`AirTraceData::validate_trace`]: crate::chip::trace::data::AirTraceData::validate_trace
+    fn trace_layout(&self) -> Vec<MemorySlice>;
+
+    /// The registers the constraints of the instruction depend on, including both its inputs and
+    /// the registers of its trace layout.
+    ///
+    /// By default, these are all the registers contained in the instruction.
+    fn constrained_registers(&self) -> Vec<MemorySlice> {
+        memory_slices(self)
+    }
 }
 
 /// An instruction that only consists of constraints
@@ -34,4 +61,8 @@ impl<F: Field, C: ConstraintInstruction> Instruction<F> for C {
     fn write(&self, _writer: &TraceWriter<F>, _row_index: usize) {}
 
     fn write_to_air(&self, _writer: &mut impl AirWriter<Field = F>) {}
+
+    fn trace_layout(&self) -> Vec<MemorySlice> {
+        Vec::new()
+    }
 }
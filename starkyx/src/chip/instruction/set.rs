@@ -1,12 +1,15 @@
 use alloc::sync::Arc;
+use core::fmt::Debug;
 
 use log::debug;
 use serde::{Deserialize, Serialize};
 
 use super::assign::AssignInstruction;
 use super::bit::BitConstraint;
+use super::bit_range::BitRangeInstruction;
 use super::clock::ClockInstruction;
 use super::cycle::{Cycle, ProcessIdInstruction};
+use super::signed_range::SignedRangeInstruction;
 use super::Instruction;
 use crate::air::parser::{AirParser, MulParser};
 use crate::air::AirConstraint;
@@ -28,9 +31,12 @@ pub enum AirInstruction<F, I> {
     Cycle(Cycle<F>),
     Clock(ClockInstruction),
     ProcessId(ProcessIdInstruction),
+    SignedRange(SignedRangeInstruction),
+    BitRange(BitRangeInstruction),
     Filtered(ArithmeticExpression<F>, Arc<Self>),
     Mem(MemoryInstruction<F>),
     Watch(String, ArrayRegister<ElementRegister>),
+    WatchExpression(String, ArithmeticExpression<F>),
 }
 
 impl<F: Field, AP: AirParser<Field = F>, I> AirConstraint<AP> for AirInstruction<F, I>
@@ -46,6 +52,8 @@ where
             AirInstruction::Cycle(i) => AirConstraint::<AP>::eval(i, parser),
             AirInstruction::Clock(i) => AirConstraint::<AP>::eval(i, parser),
             AirInstruction::ProcessId(i) => AirConstraint::<AP>::eval(i, parser),
+            AirInstruction::SignedRange(i) => AirConstraint::<AP>::eval(i, parser),
+            AirInstruction::BitRange(i) => AirConstraint::<AP>::eval(i, parser),
             AirInstruction::Filtered(expression, instr) => {
                 assert_eq!(
                     expression.size, 1,
@@ -63,11 +71,12 @@ where
             }
             AirInstruction::Mem(i) => AirConstraint::<AP>::eval(i, parser),
             AirInstruction::Watch(_, _) => {}
+            AirInstruction::WatchExpression(_, _) => {}
         }
     }
 }
 
-impl<F: Field, I: Instruction<F>> Instruction<F> for AirInstruction<F, I> {
+impl<F: PrimeField64, I: Instruction<F>> Instruction<F> for AirInstruction<F, I> {
     fn write(&self, writer: &TraceWriter<F>, row_index: usize) {
         match self {
             AirInstruction::CustomInstruction(i) => i.write(writer, row_index),
@@ -77,6 +86,8 @@ impl<F: Field, I: Instruction<F>> Instruction<F> for AirInstruction<F, I> {
             AirInstruction::Cycle(i) => Instruction::<F>::write(i, writer, row_index),
             AirInstruction::Clock(i) => Instruction::<F>::write(i, writer, row_index),
             AirInstruction::ProcessId(i) => Instruction::<F>::write(i, writer, row_index),
+            AirInstruction::SignedRange(i) => Instruction::<F>::write(i, writer, row_index),
+            AirInstruction::BitRange(i) => Instruction::<F>::write(i, writer, row_index),
             AirInstruction::Filtered(expression, i) => {
                 let filter = writer.read_expression(expression, row_index)[0];
                 if filter == F::ONE {
@@ -86,7 +97,11 @@ impl<F: Field, I: Instruction<F>> Instruction<F> for AirInstruction<F, I> {
             AirInstruction::Mem(i) => Instruction::<F>::write(i, writer, row_index),
             AirInstruction::Watch(name, register) => {
                 let value = writer.read_vec(register, row_index);
-                debug!("row {}: , {}: {:?}", row_index, name, value);
+                debug!("{}", watch_message(name, Some(row_index), &value));
+            }
+            AirInstruction::WatchExpression(name, expression) => {
+                let value = writer.read_expression(expression, row_index);
+                debug!("{}", watch_message(name, Some(row_index), &value));
             }
         }
     }
@@ -100,6 +115,8 @@ impl<F: Field, I: Instruction<F>> Instruction<F> for AirInstruction<F, I> {
             AirInstruction::Cycle(i) => i.write_to_air(writer),
             AirInstruction::Clock(i) => i.write_to_air(writer),
             AirInstruction::ProcessId(i) => i.write_to_air(writer),
+            AirInstruction::SignedRange(i) => i.write_to_air(writer),
+            AirInstruction::BitRange(i) => i.write_to_air(writer),
             AirInstruction::Filtered(expression, i) => {
                 let filter = writer.read_expression(expression)[0];
                 if filter == F::ONE {
@@ -109,15 +126,58 @@ impl<F: Field, I: Instruction<F>> Instruction<F> for AirInstruction<F, I> {
             AirInstruction::Mem(i) => i.write_to_air(writer),
             AirInstruction::Watch(name, register) => {
                 let value = writer.read_vec(register);
-                let row_index = writer.row_index();
-                if let Some(index) = row_index {
-                    debug!("row {}: , {}: {:?}", index, name, value);
-                } else {
-                    debug!("{}: {:?}", name, value);
-                }
+                debug!("{}", watch_message(name, writer.row_index(), &value));
             }
+            AirInstruction::WatchExpression(name, expression) => {
+                let value = writer.read_expression(expression);
+                debug!("{}", watch_message(name, writer.row_index(), &value));
+            }
+        }
+    }
+
+    fn name(&self) -> &'static str {
+        match self {
+            AirInstruction::CustomInstruction(i) => i.name(),
+            AirInstruction::BitConstraint(_) => "BitConstraint",
+            AirInstruction::Select(_) => "Select",
+            AirInstruction::Assign(_) => "Assign",
+            AirInstruction::Cycle(_) => "Cycle",
+            AirInstruction::Clock(_) => "Clock",
+            AirInstruction::ProcessId(_) => "ProcessId",
+            AirInstruction::SignedRange(_) => "SignedRange",
+            AirInstruction::BitRange(_) => "BitRange",
+            AirInstruction::Filtered(_, _) => "Filtered",
+            AirInstruction::Mem(_) => "Mem",
+            AirInstruction::Watch(_, _) => "Watch",
+            AirInstruction::WatchExpression(_, _) => "WatchExpression",
         }
     }
+
+    fn trace_layout(&self) -> Vec<MemorySlice> {
+        match self {
+            AirInstruction::CustomInstruction(i) => i.trace_layout(),
+            AirInstruction::Select(i) => Instruction::<F>::trace_layout(i),
+            AirInstruction::Assign(i) => Instruction::<F>::trace_layout(i),
+            AirInstruction::Cycle(i) => Instruction::<F>::trace_layout(i),
+            AirInstruction::Clock(i) => Instruction::<F>::trace_layout(i),
+            AirInstruction::ProcessId(i) => Instruction::<F>::trace_layout(i),
+            AirInstruction::SignedRange(i) => Instruction::<F>::trace_layout(i),
+            AirInstruction::BitRange(i) => Instruction::<F>::trace_layout(i),
+            AirInstruction::Filtered(_, i) => i.trace_layout(),
+            AirInstruction::Mem(i) => Instruction::<F>::trace_layout(i),
+            AirInstruction::BitConstraint(_)
+            | AirInstruction::Watch(_, _)
+            | AirInstruction::WatchExpression(_, _) => Vec::new(),
+        }
+    }
+}
+
+/// The message logged by a watch instruction for a value on the row `row_index`, if any.
+pub(crate) fn watch_message<T: Debug>(name: &str, row_index: Option<usize>, value: &T) -> String {
+    match row_index {
+        Some(index) => format!("row {}: , {}: {:?}", index, name, value),
+        None => format!("{}: {:?}", name, value),
+    }
 }
 
 impl<F, I> From<I> for AirInstruction<F, I> {
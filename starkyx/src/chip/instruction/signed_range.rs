@@ -0,0 +1,315 @@
+use serde::{Deserialize, Serialize};
+
+use super::set::AirInstruction;
+use super::Instruction;
+use crate::air::parser::AirParser;
+use crate::air::AirConstraint;
+use crate::chip::builder::AirBuilder;
+use crate::chip::register::array::ArrayRegister;
+use crate::chip::register::element::ElementRegister;
+use crate::chip::register::memory::MemorySlice;
+use crate::chip::register::u16::U16Register;
+use crate::chip::register::{Register, RegisterSerializable};
+use crate::chip::trace::writer::{AirWriter, TraceWriter};
+use crate::chip::AirParameters;
+use crate::math::prelude::*;
+
+/// Constrains a value to lie in the signed range `[-2^k, 2^k)`.
+///
+/// The shifted value `value + 2^k` is decomposed into `u16` limbs which are range checked by the
+/// builder. If the most significant limb holds `r < 16` bits, it is also multiplied by `2^(16 - r)`
+/// into a range checked column, which bounds it by `2^r`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SignedRangeInstruction {
+    value: ElementRegister,
+    limbs: ArrayRegister<U16Register>,
+    top_limb_scaled: Option<U16Register>,
+    nb_bits: usize,
+}
+
+impl<L: AirParameters> AirBuilder<L> {
+    /// Allocates a register whose value is constrained to lie in the signed range `[-2^k, 2^k)`.
+    ///
+    /// Negative values are represented by their field negation. Uses `ceil((k + 1) / 16)`
+    /// arithmetic columns, and one more if `k + 1` is not a multiple of 16.
+    pub fn alloc_signed_range(&mut self, k: usize) -> ElementRegister {
+        assert!(
+            k < 63,
+            "signed range of {} bits does not fit in a field element",
+            k
+        );
+        let value = self.alloc::<ElementRegister>();
+
+        let nb_limbs = (k + 1).div_ceil(16);
+        let limbs = self.alloc_array::<U16Register>(nb_limbs);
+        let top_limb_bits = k + 1 - 16 * (nb_limbs - 1);
+        let top_limb_scaled = (top_limb_bits < 16).then(|| self.alloc::<U16Register>());
+
+        let instr = SignedRangeInstruction {
+            value,
+            limbs,
+            top_limb_scaled,
+            nb_bits: k,
+        };
+        self.register_air_instruction_internal(AirInstruction::SignedRange(instr));
+
+        value
+    }
+}
+
+impl SignedRangeInstruction {
+    fn top_limb_shift(&self) -> usize {
+        16 * self.limbs.len() - (self.nb_bits + 1)
+    }
+
+    fn limb_values<F: PrimeField64>(&self, value: F) -> (Vec<F>, Option<F>) {
+        let shifted = (value + F::from_canonical_u64(1 << self.nb_bits)).as_canonical_u64();
+        let limbs = (0..self.limbs.len())
+            .map(|i| F::from_canonical_u64((shifted >> (16 * i)) & 0xFFFF))
+            .collect::<Vec<_>>();
+        let top_limb_scaled = self.top_limb_scaled.map(|_| {
+            let top_limb = shifted >> (16 * (self.limbs.len() - 1));
+            F::from_canonical_u64(top_limb << self.top_limb_shift())
+        });
+        (limbs, top_limb_scaled)
+    }
+}
+
+impl<AP: AirParser> AirConstraint<AP> for SignedRangeInstruction {
+    fn eval(&self, parser: &mut AP) {
+        let value = self.value.eval(parser);
+        let limbs = self.limbs.eval_vec(parser);
+
+        // value + 2^k = sum_i limb_i * 2^(16 * i).
+        let mut shifted = parser.add_const(value, AP::Field::from_canonical_u64(1 << self.nb_bits));
+        for (i, limb) in limbs.iter().enumerate() {
+            let limb_times_power =
+                parser.mul_const(*limb, AP::Field::from_canonical_u64(1 << (16 * i)));
+            shifted = parser.sub(shifted, limb_times_power);
+        }
+        parser.constraint(shifted);
+
+        // top_limb_scaled = top_limb * 2^(16 - r).
+        if let Some(top_limb_scaled) = self.top_limb_scaled {
+            let top_limb_scaled = top_limb_scaled.eval(parser);
+            let top_limb = *limbs.last().unwrap();
+            let scale = AP::Field::from_canonical_u64(1 << self.top_limb_shift());
+            let top_limb_times_scale = parser.mul_const(top_limb, scale);
+            let constraint = parser.sub(top_limb_scaled, top_limb_times_scale);
+            parser.constraint(constraint);
+        }
+    }
+}
+
+impl<F: PrimeField64> Instruction<F> for SignedRangeInstruction {
+    fn write(&self, writer: &TraceWriter<F>, row_index: usize) {
+        let value = writer.read(&self.value, row_index);
+        let (limbs, top_limb_scaled) = self.limb_values(value);
+        writer.write_array(&self.limbs, limbs, row_index);
+        if let (Some(register), Some(scaled)) = (self.top_limb_scaled, top_limb_scaled) {
+            writer.write(&register, &scaled, row_index);
+        }
+    }
+
+    fn write_to_air(&self, writer: &mut impl AirWriter<Field = F>) {
+        let value = writer.read(&self.value);
+        let (limbs, top_limb_scaled) = self.limb_values(value);
+        writer.write_array(&self.limbs, limbs);
+        if let (Some(register), Some(scaled)) = (self.top_limb_scaled, top_limb_scaled) {
+            writer.write(&register, &scaled);
+        }
+    }
+
+    fn trace_layout(&self) -> Vec<MemorySlice> {
+        let mut layout = vec![*self.limbs.register()];
+        if let Some(register) = self.top_limb_scaled {
+            layout.push(*register.register());
+        }
+        layout
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use anyhow::Result;
+
+    use super::*;
+    use crate::chip::builder::tests::*;
+    use crate::chip::Chip;
+    use crate::plonky2::stark::config::CurtaPoseidonGoldilocksConfig;
+    use crate::plonky2::stark::prover::StarkyProver;
+    use crate::plonky2::stark::verifier::StarkyVerifier;
+    use crate::trace::generator::TraceGenerator;
+    use crate::trace::AirTrace;
+
+    #[derive(Debug, Clone, Serialize, Deserialize)]
+    pub struct SignedRangeTest;
+
+    impl AirParameters for SignedRangeTest {
+        type Field = GoldilocksField;
+        type CubicParams = GoldilocksCubicParameters;
+        type Instruction = EmptyInstruction<GoldilocksField>;
+
+        const NUM_ARITHMETIC_COLUMNS: usize = 3;
+        const NUM_FREE_COLUMNS: usize = 3;
+        const EXTENDED_COLUMNS: usize = 12;
+    }
+
+    const K: usize = 20;
+    const NUM_ROWS: usize = 1 << 16;
+
+    fn to_field(x: i64) -> GoldilocksField {
+        if x >= 0 {
+            GoldilocksField::from_canonical_u64(x as u64)
+        } else {
+            -GoldilocksField::from_canonical_u64(x.unsigned_abs())
+        }
+    }
+
+    /// Builds a signed range chip for `K` bits and writes `values` into its trace.
+    fn signed_range_generator(
+        values: impl Fn(usize) -> i64,
+    ) -> (
+        Chip<SignedRangeTest>,
+        ArithmeticGenerator<SignedRangeTest>,
+        SignedRangeInstruction,
+    ) {
+        type L = SignedRangeTest;
+
+        let mut builder = AirBuilder::<L>::new();
+        let value = builder.alloc_signed_range(K);
+        let instr = match builder.instructions.last() {
+            Some(AirInstruction::SignedRange(instr)) => instr.clone(),
+            _ => unreachable!("the last instruction is the signed range check"),
+        };
+
+        let (air, trace_data) = builder.build();
+
+        let generator = ArithmeticGenerator::<L>::new(trace_data, NUM_ROWS);
+        let writer = generator.new_writer();
+        for i in 0..NUM_ROWS {
+            writer.write(&value, &to_field(values(i)), i);
+            writer.write_row_instructions(&generator.air_data, i);
+        }
+
+        (air, generator, instr)
+    }
+
+    fn prove_signed_range(values: impl Fn(usize) -> i64) {
+        type SC = PoseidonGoldilocksStarkConfig;
+
+        let (air, generator, _) = signed_range_generator(values);
+
+        let stark = Starky::new(air);
+        let config = SC::standard_fast_config(NUM_ROWS);
+
+        // Generate proof and verify as a stark
+        test_starky(&stark, &config, &generator, &[]);
+    }
+
+    #[test]
+    fn test_signed_range() {
+        let bound = 1i64 << K;
+        prove_signed_range(|i| match i {
+            0 => -bound,
+            1 => bound - 1,
+            2 => 0,
+            3 => -1,
+            _ => (i as i64 * 31 - bound) % bound,
+        });
+    }
+
+    /// A dishonest generator which writes the range check multiplicities of an honest trace, and
+    /// then replaces the value of one row by an out of range value with limbs consistent with it.
+    ///
+    /// The limbs are not reduced to 16 bits, so the honest generator could not count them in the
+    /// range check table.
+    #[derive(Debug, Serialize, Deserialize)]
+    struct OutOfRangeGenerator {
+        inner: ArithmeticGenerator<SignedRangeTest>,
+        instr: SignedRangeInstruction,
+        row: usize,
+        value: i64,
+    }
+
+    impl TraceGenerator<GoldilocksField, Chip<SignedRangeTest>> for OutOfRangeGenerator {
+        type Error = anyhow::Error;
+
+        fn generate_round(
+            &self,
+            air: &Chip<SignedRangeTest>,
+            round: usize,
+            challenges: &[GoldilocksField],
+            global_values: &mut [GoldilocksField],
+            public_inputs: &[GoldilocksField],
+        ) -> Result<AirTrace<GoldilocksField>> {
+            type F = GoldilocksField;
+
+            let trace =
+                self.inner
+                    .generate_round(air, round, challenges, global_values, public_inputs)?;
+            if round != 0 {
+                return Ok(trace);
+            }
+
+            // value + 2^k = limb_0 + limb_1 * 2^16, with `limb_1` of more than 16 bits.
+            assert_eq!(self.instr.limbs.len(), 2);
+            let value = to_field(self.value);
+            let shifted = (value + F::from_canonical_u64(1 << K)).as_canonical_u64();
+            let limbs = [shifted & 0xFFFF, shifted >> 16].map(F::from_canonical_u64);
+            let scale = F::from_canonical_u64(1 << self.instr.top_limb_shift());
+
+            let writer = self.inner.new_writer();
+            writer.write(&self.instr.value, &value, self.row);
+            writer.write_array(&self.instr.limbs, limbs, self.row);
+            writer.write(
+                &self.instr.top_limb_scaled.unwrap(),
+                &(limbs[1] * scale),
+                self.row,
+            );
+
+            let trace = self.inner.trace_clone();
+            Ok(AirTrace {
+                values: trace
+                    .rows_par()
+                    .flat_map(|row| row[..air.execution_trace_length].to_vec())
+                    .collect(),
+                width: air.execution_trace_length,
+            })
+        }
+    }
+
+    fn prove_out_of_range(value: i64) {
+        type F = GoldilocksField;
+        type C = CurtaPoseidonGoldilocksConfig;
+        type SC = PoseidonGoldilocksStarkConfig;
+        const D: usize = 2;
+
+        let (air, inner, instr) = signed_range_generator(|i| -(i as i64));
+        let generator = OutOfRangeGenerator {
+            inner,
+            instr,
+            row: 7,
+            value,
+        };
+
+        let stark = Starky::new(air);
+        let config = SC::standard_fast_config(NUM_ROWS);
+
+        let proof = StarkyProver::<F, C, D>::prove(&config, &stark, &generator, &[]).unwrap();
+        // Only reachable if the prover does not catch the out of range value.
+        StarkyVerifier::verify(&config, &stark, proof, &[]).unwrap();
+    }
+
+    #[test]
+    #[should_panic(expected = "the vanishing polynomial is not divisible by Z_H")]
+    fn test_signed_range_out_of_bounds() {
+        prove_out_of_range(1 << K);
+    }
+
+    #[test]
+    #[should_panic(expected = "the vanishing polynomial is not divisible by Z_H")]
+    fn test_signed_range_negative_out_of_bounds() {
+        prove_out_of_range(-(1 << K) - 1);
+    }
+}
@@ -131,6 +131,10 @@ impl<F: Field> Instruction<F> for GetInstruction<F> {
 
         writer.write_slice(&self.register, &value);
     }
+
+    fn trace_layout(&self) -> Vec<MemorySlice> {
+        vec![self.register]
+    }
 }
 
 impl<F: Field> GetInstruction<F> {
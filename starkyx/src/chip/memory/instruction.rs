@@ -8,6 +8,7 @@ use crate::air::parser::AirParser;
 use crate::air::AirConstraint;
 use crate::chip::instruction::Instruction;
 use crate::chip::register::element::ElementRegister;
+use crate::chip::register::memory::MemorySlice;
 use crate::chip::trace::writer::{AirWriter, TraceWriter};
 use crate::math::field::Field;
 
@@ -59,4 +60,12 @@ impl<F: Field> Instruction<F> for MemoryInstruction<F> {
             Self::Watch(instr) => instr.write_to_air(writer),
         }
     }
+
+    fn trace_layout(&self) -> Vec<MemorySlice> {
+        match self {
+            Self::Get(instr) => instr.trace_layout(),
+            Self::Set(instr) => instr.trace_layout(),
+            Self::Watch(instr) => instr.trace_layout(),
+        }
+    }
 }
@@ -162,6 +162,10 @@ impl<F: Field> Instruction<F> for SetInstruction<F> {
                 multiplicity,
             });
     }
+
+    fn trace_layout(&self) -> Vec<MemorySlice> {
+        Vec::new()
+    }
 }
 
 impl<F: Field> SetInstruction<F> {
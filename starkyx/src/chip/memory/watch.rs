@@ -5,6 +5,7 @@ use super::pointer::raw::RawPointer;
 use crate::air::parser::AirParser;
 use crate::air::AirConstraint;
 use crate::chip::instruction::Instruction;
+use crate::chip::register::memory::MemorySlice;
 use crate::chip::trace::writer::{AirWriter, TraceWriter};
 use crate::math::prelude::*;
 
@@ -59,6 +60,10 @@ impl<F: Field> Instruction<F> for WatchInstruction {
             )
         };
     }
+
+    fn trace_layout(&self) -> Vec<MemorySlice> {
+        Vec::new()
+    }
 }
 
 impl WatchInstruction {
@@ -3,6 +3,7 @@ use serde::{Deserialize, Serialize};
 
 use self::constraint::Constraint;
 use self::instruction::Instruction;
+use self::trace::data::AirTraceData;
 use crate::math::prelude::*;
 use crate::plonky2::stark::Starky;
 
@@ -61,12 +62,36 @@ pub trait AirParameters:
 pub struct Chip<L: AirParameters> {
     constraints: Vec<Constraint<L>>,
     global_constraints: Vec<Constraint<L>>,
+    unused_columns: Vec<usize>,
     pub execution_trace_length: usize,
     pub num_challenges: usize,
     pub num_public_values: usize,
     pub num_global_values: usize,
 }
 
+impl<L: AirParameters> Chip<L> {
+    /// Sets the number of public values of the chip and the number of public inputs of its trace
+    /// data `air_data` to `num_public_values`.
+    ///
+    /// The chip and the trace data must agree on the number of public values before the update,
+    /// and the new count cannot be smaller than the number of public values already allocated.
+    pub fn set_public_values(&mut self, air_data: &mut AirTraceData<L>, num_public_values: usize) {
+        assert_eq!(
+            self.num_public_values, air_data.num_public_inputs,
+            "Mismatched public value counts: the chip has {} public values and the trace data {}",
+            self.num_public_values, air_data.num_public_inputs
+        );
+        assert!(
+            num_public_values >= self.num_public_values,
+            "Cannot set the number of public values to {}, {} are already allocated",
+            num_public_values,
+            self.num_public_values
+        );
+        self.num_public_values = num_public_values;
+        air_data.num_public_inputs = num_public_values;
+    }
+}
+
 impl<L: AirParameters> Starky<Chip<L>> {
     pub fn from_chip(chip: Chip<L>) -> Self {
         Self::new(chip)
@@ -185,6 +185,19 @@ impl<T: Register> ArrayRegister<T> {
         (0..self.len()).map(elem_fn).collect()
     }
 
+    /// Evaluates an array of single-cell registers as a slice of the variables of the parser.
+    ///
+    /// Arrays are allocated contiguously, so the values are read as one slice instead of element
+    /// by element as in [`Self::eval_vec`]. With the packed parser of the prover, each variable
+    /// holds `P::WIDTH` rows of the trace.
+    #[inline]
+    pub fn packed_slice<'a, AP: AirParser>(&self, parser: &'a AP) -> &'a [AP::Var]
+    where
+        T: Register<Value<AP::Var> = AP::Var>,
+    {
+        self.register.eval_slice(parser)
+    }
+
     #[inline]
     pub fn eval_array<AP: AirParser, const N: usize>(&self, parser: &AP) -> [T::Value<AP::Var>; N] {
         assert!(
@@ -240,3 +253,40 @@ impl<T: Register> IntoIterator for ArrayRegister<T> {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use plonky2::field::goldilocks_field::GoldilocksField;
+    use plonky2::field::types::Sample;
+
+    use super::*;
+    use crate::chip::register::element::ElementRegister;
+    use crate::chip::register::u16::U16Register;
+    use crate::trace::window_parser::TraceWindowParser;
+    use crate::trace::AirTrace;
+
+    #[test]
+    fn test_packed_slice() {
+        type F = GoldilocksField;
+
+        let (width, num_rows) = (16, 8);
+        let values = (0..width * num_rows).map(|_| F::rand()).collect::<Vec<_>>();
+        let trace = AirTrace::from_rows(values, width);
+
+        let elements =
+            ArrayRegister::<ElementRegister>::from_register_unsafe(MemorySlice::Local(0, 5));
+        let limbs = ArrayRegister::<U16Register>::from_register_unsafe(MemorySlice::Local(5, 11));
+        let next_limbs =
+            ArrayRegister::<U16Register>::from_register_unsafe(MemorySlice::Next(5, 11));
+
+        for window in trace.windows() {
+            let parser = TraceWindowParser::new(window, &[], &[], &[]);
+            assert_eq!(elements.packed_slice(&parser), elements.eval_vec(&parser));
+            assert_eq!(limbs.packed_slice(&parser), limbs.eval_vec(&parser));
+            assert_eq!(
+                next_limbs.packed_slice(&parser),
+                next_limbs.eval_vec(&parser)
+            );
+        }
+    }
+}
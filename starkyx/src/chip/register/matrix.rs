@@ -0,0 +1,205 @@
+use serde::{Deserialize, Serialize};
+
+use super::array::ArrayRegister;
+use super::strided::StridedArrayRegister;
+use super::{Register, RegisterSerializable};
+use crate::air::parser::AirParser;
+
+/// A two-dimensional view of an array of registers, stored in row-major order.
+///
+/// The element at row `i` and column `j` of a matrix with `num_cols` columns is the register at
+/// index `i * num_cols + j` of the underlying array, so that each row is a contiguous array and
+/// each column is a strided array.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub struct MatrixRegister<T> {
+    array: ArrayRegister<T>,
+    num_rows: usize,
+    num_cols: usize,
+}
+
+impl<T: Register> MatrixRegister<T> {
+    pub(crate) fn new(array: ArrayRegister<T>, num_rows: usize, num_cols: usize) -> Self {
+        assert_eq!(
+            array.len(),
+            num_rows * num_cols,
+            "Cannot reshape an array of length {} into a {}x{} matrix",
+            array.len(),
+            num_rows,
+            num_cols
+        );
+        Self {
+            array,
+            num_rows,
+            num_cols,
+        }
+    }
+
+    pub fn num_rows(&self) -> usize {
+        self.num_rows
+    }
+
+    pub fn num_cols(&self) -> usize {
+        self.num_cols
+    }
+
+    /// The underlying array of the matrix, in row-major order.
+    pub fn as_array(&self) -> ArrayRegister<T> {
+        self.array
+    }
+
+    #[inline]
+    pub fn get(&self, row: usize, col: usize) -> T {
+        if row >= self.num_rows || col >= self.num_cols {
+            panic!(
+                "Index ({}, {}) out of bounds for a {}x{} matrix",
+                row, col, self.num_rows, self.num_cols
+            );
+        }
+        self.array.get(row * self.num_cols + col)
+    }
+
+    /// The row at index `row`, as a contiguous array.
+    #[inline]
+    pub fn row(&self, row: usize) -> ArrayRegister<T> {
+        if row >= self.num_rows {
+            panic!(
+                "Row {} out of bounds for a matrix with {} rows",
+                row, self.num_rows
+            );
+        }
+        self.array
+            .get_subarray(row * self.num_cols..(row + 1) * self.num_cols)
+    }
+
+    /// The column at index `col`, as a strided array.
+    #[inline]
+    pub fn column(&self, col: usize) -> StridedArrayRegister<T> {
+        StridedArrayRegister::new(self.array, self.num_cols).lane(col)
+    }
+
+    #[inline]
+    pub fn rows(&self) -> impl Iterator<Item = ArrayRegister<T>> {
+        let matrix = *self;
+        (0..self.num_rows).map(move |i| matrix.row(i))
+    }
+
+    /// Returns the matrix but in the next row of the trace.
+    pub fn next(&self) -> Self {
+        Self {
+            array: self.array.next(),
+            ..*self
+        }
+    }
+
+    #[inline]
+    pub fn eval_vec<AP: AirParser>(&self, parser: &AP) -> Vec<Vec<T::Value<AP::Var>>> {
+        self.rows().map(|row| row.eval_vec(parser)).collect()
+    }
+}
+
+impl<T: Register> ArrayRegister<T> {
+    /// Views the array as a matrix with `num_rows` rows and `num_cols` columns, in row-major
+    /// order, without allocating new registers.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the length of the array is not `num_rows * num_cols`.
+    pub fn reshape(&self, num_rows: usize, num_cols: usize) -> MatrixRegister<T> {
+        MatrixRegister::new(*self, num_rows, num_cols)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::chip::arithmetic::expression::ArithmeticExpression;
+    use crate::chip::builder::tests::*;
+    use crate::chip::builder::AirBuilder;
+    use crate::chip::register::element::ElementRegister;
+    use crate::chip::AirParameters;
+    use crate::math::prelude::*;
+
+    #[derive(Debug, Clone, Serialize, Deserialize)]
+    struct MatrixTest;
+
+    impl AirParameters for MatrixTest {
+        type Field = GoldilocksField;
+        type CubicParams = GoldilocksCubicParameters;
+        type Instruction = EmptyInstruction<GoldilocksField>;
+
+        const NUM_FREE_COLUMNS: usize = 12;
+    }
+
+    #[test]
+    fn test_reshape() {
+        type F = GoldilocksField;
+        type L = MatrixTest;
+        type SC = PoseidonGoldilocksStarkConfig;
+
+        let mut builder = AirBuilder::<L>::new();
+        let array = builder.alloc_array::<ElementRegister>(9);
+        let matrix = array.reshape(3, 3);
+        assert_eq!(matrix.num_rows(), 3);
+        assert_eq!(matrix.num_cols(), 3);
+        for i in 0..3 {
+            for j in 0..3 {
+                assert_eq!(matrix.get(i, j).register(), array.get(3 * i + j).register());
+                assert_eq!(matrix.row(i).get(j).register(), matrix.get(i, j).register());
+                assert_eq!(
+                    matrix.column(j).get(i).register(),
+                    matrix.get(i, j).register()
+                );
+            }
+        }
+
+        // Multiply the matrix by the vector `(1, 2, 3)`.
+        let product = builder.alloc_array::<ElementRegister>(3);
+        for (row, product_i) in matrix.rows().zip(product) {
+            let expression = row
+                .iter()
+                .enumerate()
+                .fold(ArithmeticExpression::zero(), |acc, (j, x)| {
+                    acc + x.expr() * F::from_canonical_usize(j + 1)
+                });
+            builder.set_to_expression(&product_i, expression);
+        }
+
+        let (air, trace_data) = builder.build();
+        let num_rows = 1 << 5;
+        let generator = ArithmeticGenerator::<L>::new(trace_data, num_rows);
+        let writer = generator.new_writer();
+        for row in 0..num_rows {
+            for (k, x) in array.iter().enumerate() {
+                writer.write(&x, &F::from_canonical_usize(row * 9 + k), row);
+            }
+            writer.write_row_instructions(&generator.air_data, row);
+
+            for i in 0..3 {
+                for j in 0..3 {
+                    assert_eq!(
+                        writer.read(&matrix.get(i, j), row),
+                        F::from_canonical_usize(row * 9 + 3 * i + j)
+                    );
+                }
+                let expected = (0..3)
+                    .map(|j| F::from_canonical_usize((row * 9 + 3 * i + j) * (j + 1)))
+                    .sum::<F>();
+                assert_eq!(writer.read(&product.get(i), row), expected);
+            }
+        }
+
+        let stark = Starky::new(air);
+        let config = SC::standard_fast_config(num_rows);
+
+        // Generate proof and verify as a stark
+        test_starky(&stark, &config, &generator, &[]);
+    }
+
+    #[test]
+    #[should_panic(expected = "Cannot reshape an array of length 9 into a 2x4 matrix")]
+    fn test_reshape_length_mismatch() {
+        let mut builder = AirBuilder::<MatrixTest>::new();
+        let array = builder.alloc_array::<ElementRegister>(9);
+        array.reshape(2, 4);
+    }
+}
@@ -2,13 +2,19 @@ use core::hash::Hash;
 
 use serde::{Deserialize, Serialize};
 
+use super::relocation::SerializedMemorySlice;
 use crate::air::parser::AirParser;
 use crate::trace::view::{TraceView, TraceViewMut};
 
 /// A contiguous chunk of memory in the trace and Stark data.
 /// Corresponds to a slice in vars.local_values, vars.next_values, vars.public_inputs,
 /// or vars.challenges.
+///
+/// Memory slices are relocated when deserialized inside [`MemoryRelocation::relocate`].
+///
+/// [`MemoryRelocation::relocate`]: super::relocation::MemoryRelocation::relocate
 #[derive(Debug, Clone, Copy, PartialEq, PartialOrd, Eq, Ord, Serialize, Deserialize)]
+#[serde(from = "SerializedMemorySlice")]
 pub enum MemorySlice {
     /// A slice of the current row.
     Local(usize, usize),
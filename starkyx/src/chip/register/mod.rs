@@ -14,8 +14,11 @@ pub mod bit;
 pub mod cell;
 pub mod cubic;
 pub mod element;
+pub mod matrix;
 pub mod memory;
+pub mod relocation;
 pub mod slice;
+pub mod strided;
 pub mod u16;
 
 /// Adds serialization and deserialization to a register for converting between the canonical type
@@ -0,0 +1,207 @@
+use core::cell::RefCell;
+
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+
+use super::memory::MemorySlice;
+
+thread_local! {
+    /// The relocation applied to the memory slices deserialized in [`MemoryRelocation::relocate`].
+    static RELOCATION: RefCell<Option<MemoryRelocation>> = RefCell::new(None);
+
+    /// The memory slices deserialized in [`memory_slices`].
+    static COLLECTED: RefCell<Option<Vec<MemorySlice>>> = RefCell::new(None);
+}
+
+/// Returns all the memory slices contained in `value`, e.g. the registers of an instruction.
+///
+/// As in [`MemoryRelocation::relocate`], the value is serialized and deserialized back, and the
+/// memory slices are recorded as they are deserialized.
+pub fn memory_slices<T: Serialize + DeserializeOwned>(value: &T) -> Vec<MemorySlice> {
+    let bytes = bincode::serialize(value).expect("Failed to serialize the value");
+    COLLECTED.with(|collected| *collected.borrow_mut() = Some(Vec::new()));
+    let _guard = RelocationGuard;
+    let _: T = bincode::deserialize(&bytes).expect("Failed to deserialize the value");
+    COLLECTED.with(|collected| collected.borrow_mut().take().unwrap_or_default())
+}
+
+/// A map from the memory of a chip to the memory of a larger chip containing it.
+///
+/// The trace columns are moved by segments, so that e.g. the arithmetic, free and extended columns
+/// of the chip land in the corresponding regions of the larger chip. Public values, global values
+/// and challenges are moved by a constant offset.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MemoryRelocation {
+    /// The segments `(start, end, new_start)` moving the trace columns in `start..end` to
+    /// `new_start..new_start + end - start`.
+    pub trace_segments: Vec<(usize, usize, usize)>,
+    pub public_offset: usize,
+    pub global_offset: usize,
+    pub challenge_offset: usize,
+}
+
+impl MemoryRelocation {
+    /// The new index of the trace column `column`.
+    pub fn trace_column(&self, column: usize) -> usize {
+        self.trace_segments
+            .iter()
+            .find(|(start, end, _)| (*start..*end).contains(&column))
+            .map(|(start, _, new_start)| new_start + column - start)
+            .unwrap_or_else(|| panic!("Trace column {} is not relocated", column))
+    }
+
+    /// The relocation of the memory slice `slice`.
+    ///
+    /// A trace slice must lie within a single segment.
+    pub fn apply(&self, slice: MemorySlice) -> MemorySlice {
+        let trace_index = |index: usize, length: usize| {
+            let new_index = self.trace_column(index);
+            if length > 0 {
+                assert_eq!(
+                    self.trace_column(index + length - 1),
+                    new_index + length - 1,
+                    "The trace slice {:?} spans several relocation segments",
+                    slice
+                );
+            }
+            new_index
+        };
+        match slice {
+            MemorySlice::Local(index, length) => {
+                MemorySlice::Local(trace_index(index, length), length)
+            }
+            MemorySlice::Next(index, length) => {
+                MemorySlice::Next(trace_index(index, length), length)
+            }
+            MemorySlice::Public(index, length) => {
+                MemorySlice::Public(index + self.public_offset, length)
+            }
+            MemorySlice::Global(index, length) => {
+                MemorySlice::Global(index + self.global_offset, length)
+            }
+            MemorySlice::Challenge(index, length) => {
+                MemorySlice::Challenge(index + self.challenge_offset, length)
+            }
+        }
+    }
+
+    /// Returns `value` as a `U` with all its memory slices relocated.
+    ///
+    /// The value is serialized and deserialized back, so that the relocation reaches the memory
+    /// slices of any serializable type, including registers, constraints and custom instructions.
+    /// The types `T` and `U` must have the same serialized form, e.g. the same type over air
+    /// parameters that only differ by their number of columns.
+    pub fn relocate<T: Serialize, U: DeserializeOwned>(&self, value: &T) -> U {
+        let bytes = bincode::serialize(value).expect("Failed to serialize the relocated value");
+        RELOCATION.with(|relocation| *relocation.borrow_mut() = Some(self.clone()));
+        let _guard = RelocationGuard;
+        bincode::deserialize(&bytes).expect("Failed to deserialize the relocated value")
+    }
+}
+
+/// Clears the current relocation and collection when dropped, including on a panic during
+/// deserialization.
+struct RelocationGuard;
+
+impl Drop for RelocationGuard {
+    fn drop(&mut self) {
+        RELOCATION.with(|relocation| *relocation.borrow_mut() = None);
+        COLLECTED.with(|collected| *collected.borrow_mut() = None);
+    }
+}
+
+/// The serialized form of a [`MemorySlice`], which is relocated when deserialized inside
+/// [`MemoryRelocation::relocate`].
+#[derive(Deserialize)]
+#[serde(rename = "MemorySlice")]
+pub(crate) enum SerializedMemorySlice {
+    Local(usize, usize),
+    Next(usize, usize),
+    Public(usize, usize),
+    Global(usize, usize),
+    Challenge(usize, usize),
+}
+
+impl From<SerializedMemorySlice> for MemorySlice {
+    fn from(slice: SerializedMemorySlice) -> Self {
+        let slice = match slice {
+            SerializedMemorySlice::Local(index, length) => MemorySlice::Local(index, length),
+            SerializedMemorySlice::Next(index, length) => MemorySlice::Next(index, length),
+            SerializedMemorySlice::Public(index, length) => MemorySlice::Public(index, length),
+            SerializedMemorySlice::Global(index, length) => MemorySlice::Global(index, length),
+            SerializedMemorySlice::Challenge(index, length) => {
+                MemorySlice::Challenge(index, length)
+            }
+        };
+        let slice = RELOCATION.with(|relocation| match relocation.borrow().as_ref() {
+            Some(relocation) => relocation.apply(slice),
+            None => slice,
+        });
+        COLLECTED.with(|collected| {
+            if let Some(collected) = collected.borrow_mut().as_mut() {
+                collected.push(slice);
+            }
+        });
+        slice
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::chip::register::array::ArrayRegister;
+    use crate::chip::register::element::ElementRegister;
+    use crate::chip::register::RegisterSerializable;
+
+    #[test]
+    fn test_memory_relocation() {
+        let relocation = MemoryRelocation {
+            trace_segments: vec![(0, 4, 10), (4, 6, 20)],
+            public_offset: 3,
+            global_offset: 0,
+            challenge_offset: 1,
+        };
+
+        let array =
+            ArrayRegister::<ElementRegister>::from_register_unsafe(MemorySlice::Local(1, 3));
+        let relocated: ArrayRegister<ElementRegister> = relocation.relocate(&array);
+        assert_eq!(*relocated.register(), MemorySlice::Local(11, 3));
+
+        let slices = vec![
+            MemorySlice::Next(5, 1),
+            MemorySlice::Public(2, 2),
+            MemorySlice::Challenge(0, 3),
+        ];
+        let relocated: Vec<MemorySlice> = relocation.relocate(&slices);
+        assert_eq!(
+            relocated,
+            vec![
+                MemorySlice::Next(21, 1),
+                MemorySlice::Public(5, 2),
+                MemorySlice::Challenge(1, 3),
+            ]
+        );
+
+        assert_eq!(memory_slices(&slices), slices);
+        assert_eq!(memory_slices(&array), vec![MemorySlice::Local(1, 3)]);
+
+        // Outside of a relocation, slices are deserialized unchanged.
+        let bytes = bincode::serialize(&slices).unwrap();
+        assert_eq!(
+            bincode::deserialize::<Vec<MemorySlice>>(&bytes).unwrap(),
+            slices
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "spans several relocation segments")]
+    fn test_memory_relocation_across_segments() {
+        let relocation = MemoryRelocation {
+            trace_segments: vec![(0, 4, 10), (4, 6, 20)],
+            public_offset: 0,
+            global_offset: 0,
+            challenge_offset: 0,
+        };
+        relocation.apply(MemorySlice::Local(3, 2));
+    }
+}
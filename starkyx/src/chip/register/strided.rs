@@ -0,0 +1,178 @@
+use serde::{Deserialize, Serialize};
+
+use super::array::ArrayRegister;
+use super::{Register, RegisterSerializable};
+use crate::air::parser::AirParser;
+
+/// An array of registers laid out at a fixed stride in a block of columns.
+///
+/// A block of `length * stride` registers holds `stride` interleaved arrays, called lanes, so
+/// that the `i`-th registers of all the lanes are next to each other in the trace. The lane at
+/// `offset` holds the registers `offset, offset + stride, offset + 2 * stride, ...` of the block.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub struct StridedArrayRegister<T> {
+    block: ArrayRegister<T>,
+    offset: usize,
+    stride: usize,
+}
+
+impl<T: Register> StridedArrayRegister<T> {
+    pub(crate) fn new(block: ArrayRegister<T>, stride: usize) -> Self {
+        assert!(stride > 0, "the stride of an array must be positive");
+        assert_eq!(
+            block.len() % stride,
+            0,
+            "the block length must be a multiple of the stride"
+        );
+        Self {
+            block,
+            offset: 0,
+            stride,
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.block.len() / self.stride
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    pub fn stride(&self) -> usize {
+        self.stride
+    }
+
+    /// The lane of the same block starting at `offset`, interleaved with this array.
+    pub fn lane(&self, offset: usize) -> Self {
+        assert!(
+            offset < self.stride,
+            "lane {} out of bounds for a stride of {}",
+            offset,
+            self.stride
+        );
+        Self { offset, ..*self }
+    }
+
+    #[inline]
+    pub fn get(&self, idx: usize) -> T {
+        if idx >= self.len() {
+            panic!(
+                "Index {} out of bounds for an array of length {}",
+                idx,
+                self.len()
+            );
+        }
+        self.block.get(self.offset + idx * self.stride)
+    }
+
+    #[inline]
+    pub fn iter(&self) -> impl Iterator<Item = T> {
+        let array = *self;
+        (0..self.len()).map(move |i| array.get(i))
+    }
+
+    /// Returns the array but in the next row.
+    pub fn next(&self) -> Self {
+        Self {
+            block: self.block.next(),
+            ..*self
+        }
+    }
+
+    #[inline]
+    pub fn eval_vec<AP: AirParser>(&self, parser: &AP) -> Vec<T::Value<AP::Var>> {
+        self.iter().map(|register| register.eval(parser)).collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::chip::builder::tests::*;
+    use crate::chip::builder::AirBuilder;
+    use crate::chip::register::element::ElementRegister;
+    use crate::chip::AirParameters;
+    use crate::math::prelude::*;
+
+    #[derive(Debug, Clone, Serialize, Deserialize)]
+    struct StridedArrayTest;
+
+    impl AirParameters for StridedArrayTest {
+        type Field = GoldilocksField;
+        type CubicParams = GoldilocksCubicParameters;
+        type Instruction = EmptyInstruction<GoldilocksField>;
+
+        const NUM_FREE_COLUMNS: usize = 8;
+    }
+
+    /// Constrains `y[i] = x[i]^2 + i`, proves the chip and returns the values of `y`.
+    fn square_chip(
+        mut builder: AirBuilder<StridedArrayTest>,
+        x: &[ElementRegister],
+        y: &[ElementRegister],
+    ) -> Vec<Vec<GoldilocksField>> {
+        type F = GoldilocksField;
+        type SC = PoseidonGoldilocksStarkConfig;
+
+        for (i, (x_i, y_i)) in x.iter().zip(y.iter()).enumerate() {
+            builder.set_to_expression(y_i, x_i.expr() * x_i.expr() + F::from_canonical_usize(i));
+        }
+
+        let (air, trace_data) = builder.build();
+        let num_rows = 1 << 5;
+        let generator = ArithmeticGenerator::<StridedArrayTest>::new(trace_data, num_rows);
+        let writer = generator.new_writer();
+        for row in 0..num_rows {
+            for (i, x_i) in x.iter().enumerate() {
+                writer.write(x_i, &F::from_canonical_usize(row * x.len() + i), row);
+            }
+            writer.write_row_instructions(&generator.air_data, row);
+        }
+
+        let values = (0..num_rows)
+            .map(|row| y.iter().map(|y_i| writer.read(y_i, row)).collect())
+            .collect();
+
+        let stark = Starky::new(air);
+        let config = SC::standard_fast_config(num_rows);
+
+        // Generate proof and verify as a stark
+        test_starky(&stark, &config, &generator, &[]);
+
+        values
+    }
+
+    #[test]
+    fn test_strided_array() {
+        // The arrays `x` and `y` interleaved as `x[0], y[0], x[1], y[1], ...`.
+        let mut builder = AirBuilder::<StridedArrayTest>::new();
+        let x = builder.alloc_array_strided::<ElementRegister>(4, 2);
+        let y = x.lane(1);
+        assert_eq!(x.len(), 4);
+        assert_eq!(y.len(), 4);
+        for i in 0..4 {
+            assert_eq!(
+                x.get(i).register().get_range().0 + 1,
+                y.get(i).register().get_range().0
+            );
+        }
+        let strided_values = square_chip(
+            builder,
+            &x.iter().collect::<Vec<_>>(),
+            &y.iter().collect::<Vec<_>>(),
+        );
+
+        // The same arrays laid out contiguously.
+        let mut builder = AirBuilder::<StridedArrayTest>::new();
+        let x = builder.alloc_array::<ElementRegister>(4);
+        let y = builder.alloc_array::<ElementRegister>(4);
+        let contiguous_values = square_chip(
+            builder,
+            &x.iter().collect::<Vec<_>>(),
+            &y.iter().collect::<Vec<_>>(),
+        );
+
+        assert_eq!(strided_values, contiguous_values);
+    }
+}
@@ -7,14 +7,17 @@ pub mod constraint;
 pub mod trace;
 
 use core::marker::PhantomData;
+use core::ops::Range;
 
 use serde::{Deserialize, Serialize};
 
 use crate::chip::arithmetic::expression::ArithmeticExpression;
+use crate::chip::arithmetic::expression_slice::ArithmeticExpressionSlice;
 use crate::chip::builder::AirBuilder;
 use crate::chip::register::array::ArrayRegister;
 use crate::chip::register::cubic::CubicRegister;
-use crate::chip::register::Register;
+use crate::chip::register::memory::MemorySlice;
+use crate::chip::register::{Register, RegisterSerializable};
 use crate::chip::AirParameters;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -35,6 +38,18 @@ impl<L: AirParameters> AirBuilder<L> {
         self.accumulate_expressions(challenges, &values)
     }
 
+    /// Accumulates the values of an array register.
+    ///
+    /// The array occupies a contiguous range of columns, which the trace writer reads as a single
+    /// slice of each row instead of evaluating the values one by one.
+    pub fn accumulate_array<T: Register>(
+        &mut self,
+        challenges: &ArrayRegister<CubicRegister>,
+        values: &ArrayRegister<T>,
+    ) -> CubicRegister {
+        self.accumulate_expressions(challenges, &[values.expr()])
+    }
+
     pub fn set_accumulate_expressions(
         &mut self,
         challenges: &ArrayRegister<CubicRegister>,
@@ -99,6 +114,41 @@ impl<L: AirParameters> AirBuilder<L> {
 
         digest
     }
+
+    /// Computes the digest `sum_i challenge^i * values[i]` of public or global values.
+    ///
+    /// The digest and the powers of `challenge` are computed once, in the global region, so the
+    /// digest does not depend on the trace and can be used to bind the same public values across
+    /// starks.
+    pub fn global_evaluation<T: Register>(
+        &mut self,
+        values: &[T],
+        challenge: &CubicRegister,
+    ) -> CubicRegister {
+        assert!(
+            values.iter().all(|value| !value.is_trace()),
+            "a global evaluation can only depend on public and global values"
+        );
+        let values = values.iter().map(|data| data.expr()).collect::<Vec<_>>();
+        let total_length = values.iter().map(|data| data.size).sum::<usize>();
+        let powers = self.element_powers(challenge, total_length);
+        self.accumulate_public_expressions(&powers, &values)
+    }
+}
+
+impl<F, E> Accumulator<F, E> {
+    /// Returns the column ranges of the values if all of them are local trace registers.
+    pub(crate) fn local_value_columns(&self) -> Option<Vec<Range<usize>>> {
+        self.values
+            .iter()
+            .map(|value| match value.expression {
+                ArithmeticExpressionSlice::Input(MemorySlice::Local(index, length)) => {
+                    Some(index..index + length)
+                }
+                _ => None,
+            })
+            .collect()
+    }
 }
 
 #[cfg(test)]
@@ -210,6 +260,63 @@ pub mod tests {
         test_recursive_starky(stark, config, generator, &[]);
     }
 
+    #[derive(Debug, Clone, Serialize, Deserialize)]
+    struct ArrayAccumulatorTest;
+
+    impl AirParameters for ArrayAccumulatorTest {
+        type Field = GoldilocksField;
+        type CubicParams = GoldilocksCubicParameters;
+
+        const NUM_FREE_COLUMNS: usize = 32;
+        const EXTENDED_COLUMNS: usize = 6;
+
+        type Instruction = EmptyInstruction<GoldilocksField>;
+    }
+
+    #[test]
+    fn test_array_accumulation() {
+        type L = ArrayAccumulatorTest;
+        type F = GoldilocksField;
+        type SC = PoseidonGoldilocksStarkConfig;
+
+        let mut builder = AirBuilder::<L>::new();
+        let array = builder.alloc_array::<ElementRegister>(32);
+        let challenges = builder.alloc_array_challenge(32);
+
+        // The array is read from the trace columns, while the scaled expression is evaluated
+        // value by value.
+        let digest = builder.accumulate_array(&challenges, &array);
+        let digest_expected = builder.accumulate_expressions(&challenges, &[array.expr() * F::ONE]);
+
+        let (air, trace_data) = builder.build();
+        assert!(trace_data.accumulators[0].local_value_columns().is_some());
+        assert!(trace_data.accumulators[1].local_value_columns().is_none());
+
+        let num_rows = 1 << 10;
+        let generator = ArithmeticGenerator::<L>::new(trace_data, num_rows);
+
+        let writer = generator.new_writer();
+        for i in 0..num_rows {
+            for element in array.iter() {
+                writer.write(&element, &GoldilocksField::rand(), i);
+            }
+        }
+
+        let stark = Starky::from_chip(air);
+
+        let config = SC::standard_fast_config(num_rows);
+
+        // Generate proof and verify as a stark
+        test_starky(&stark, &config, &generator, &[]);
+
+        for i in 0..num_rows {
+            assert_eq!(writer.read(&digest, i), writer.read(&digest_expected, i));
+        }
+
+        // Test the recursive proof.
+        test_recursive_starky(stark, config, generator, &[]);
+    }
+
     #[test]
     fn test_public_accumulation() {
         type L = AccumulatorTest;
@@ -279,4 +386,50 @@ pub mod tests {
         // Test the recursive proof.
         test_recursive_starky(stark, config, generator, &public_inputs);
     }
+
+    #[test]
+    fn test_global_evaluation() {
+        type L = AccumulatorTest;
+        type F = GoldilocksField;
+        type SC = PoseidonGoldilocksStarkConfig;
+
+        let mut builder = AirBuilder::<L>::new();
+        let values = builder.alloc_array_public::<ElementRegister>(3);
+        let _ = builder.clock();
+
+        let challenge = builder.alloc_challenge::<CubicRegister>();
+        let digest = builder.global_evaluation(&values.iter().collect::<Vec<_>>(), &challenge);
+
+        let (air, trace_data) = builder.build();
+
+        let num_rows = 1 << 10;
+        let generator = ArithmeticGenerator::<L>::new(trace_data, num_rows);
+
+        let writer = generator.new_writer();
+        for i in 0..num_rows {
+            writer.write_row_instructions(&generator.air_data, i);
+        }
+
+        let public_inputs = (0..3).map(|_| F::rand()).collect::<Vec<_>>();
+
+        let stark = Starky::from_chip(air);
+
+        let config = SC::standard_fast_config(num_rows);
+
+        // Generate proof and verify as a stark
+        test_starky(&stark, &config, &generator, &public_inputs);
+
+        // The digest matches the random linear combination of the public values.
+        let gamma = writer.read(&challenge, 0);
+        let mut power = CubicElement::<F>::ONE;
+        let mut expected = CubicElement::<F>::ZERO;
+        for value in public_inputs.iter() {
+            expected += power * CubicElement::from_base(*value, F::ZERO);
+            power *= gamma;
+        }
+        assert_eq!(writer.read(&digest, 0), expected);
+
+        // Test the recursive proof.
+        test_recursive_starky(stark, config, generator, &public_inputs);
+    }
 }
@@ -1,9 +1,12 @@
+use core::ops::Range;
+
 use super::Accumulator;
 use crate::chip::register::memory::MemorySlice;
 use crate::chip::register::RegisterSerializable;
 use crate::chip::trace::writer::TraceWriter;
 use crate::math::extension::cubic::element::CubicElement;
 use crate::math::prelude::*;
+use crate::maybe_rayon::*;
 
 impl<F: PrimeField> TraceWriter<F> {
     pub(crate) fn write_accumulation<E: CubicParameters<F>>(
@@ -25,6 +28,10 @@ impl<F: PrimeField> TraceWriter<F> {
                 self.write(&accumulator.digest, &acc, 0);
             }
             _ => {
+                if let Some(columns) = accumulator.local_value_columns() {
+                    self.write_local_accumulation(accumulator, &challenges, &columns);
+                    return;
+                }
                 let num_rows = self.height;
                 (0..num_rows).for_each(|row| {
                     let acc = accumulator
@@ -39,4 +46,31 @@ impl<F: PrimeField> TraceWriter<F> {
             }
         }
     }
+
+    /// Writes the accumulation of values that are all local trace registers, reading the values
+    /// of each row directly from the columns of the trace.
+    fn write_local_accumulation<E: CubicParameters<F>>(
+        &self,
+        accumulator: &Accumulator<F, E>,
+        challenges: &[CubicElement<F>],
+        columns: &[Range<usize>],
+    ) {
+        let trace = self.read_trace().unwrap();
+        let digests = trace
+            .rows_par()
+            .map(|row| {
+                columns
+                    .iter()
+                    .flat_map(|range| row[range.clone()].iter())
+                    .zip(challenges.iter())
+                    .map(|(val, alpha)| *alpha * *val)
+                    .sum::<CubicElement<F>>()
+            })
+            .collect::<Vec<_>>();
+        drop(trace);
+
+        for (row, digest) in digests.iter().enumerate() {
+            self.write(&accumulator.digest, digest, row);
+        }
+    }
 }
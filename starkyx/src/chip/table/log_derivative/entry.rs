@@ -39,6 +39,23 @@ impl<F: Field> LogEntryValue<F> {
     ) -> CubicExtension<F, E> {
         CubicExtension::from_base_field(self.multiplier) / (beta - CubicExtension::from(self.value))
     }
+
+    /// Evaluates a batch of entries at `beta` using a single field inversion.
+    #[inline]
+    pub fn evaluate_batch<E: CubicParameters<F>>(
+        values: &[Self],
+        beta: CubicExtension<F, E>,
+    ) -> Vec<CubicExtension<F, E>> {
+        let denominators = values
+            .iter()
+            .map(|v| beta - CubicExtension::from(v.value))
+            .collect::<Vec<_>>();
+        CubicExtension::batch_inverse(&denominators)
+            .into_iter()
+            .zip(values.iter())
+            .map(|(inverse, v)| inverse * v.multiplier)
+            .collect()
+    }
 }
 
 impl<T: EvalCubic> LogEntry<T> {
@@ -1,4 +1,4 @@
-use super::entry::LogEntry;
+use super::entry::{LogEntry, LogEntryValue};
 use crate::chip::register::cubic::{CubicRegister, EvalCubic};
 use crate::chip::register::slice::RegisterSlice;
 use crate::chip::register::Register;
@@ -7,6 +7,9 @@ use crate::math::prelude::cubic::extension::CubicExtension;
 use crate::math::prelude::*;
 use crate::maybe_rayon::*;
 
+/// The number of rows of the trace whose log-derivative denominators are inverted together.
+pub(crate) const BATCH_INVERSE_ROWS: usize = 1 << 8;
+
 impl<F: PrimeField> TraceWriter<F> {
     pub fn write_log_trace_accumulation<T: EvalCubic, E: CubicParameters<F>>(
         &self,
@@ -15,31 +18,44 @@ impl<F: PrimeField> TraceWriter<F> {
         intermediate_values: &impl RegisterSlice<CubicRegister>,
         trace_accumulator: CubicRegister,
     ) -> CubicExtension<F, E> {
-        // Accumulate lookup values in the trace
-        let accumulators = self
-            .write_trace()
-            .unwrap()
-            .rows_par_mut()
-            .map(|row| {
-                let entry_chunks = entries.chunks_exact(2);
-                let last_element = entry_chunks
-                    .remainder()
-                    .first()
-                    .map(|reg| reg.read_from_slice(row).evaluate(beta))
-                    .unwrap_or(CubicExtension::ZERO);
-                let mut accumumulator = CubicExtension::ZERO;
-                let accumulators = intermediate_values;
-                for (k, pair) in entry_chunks.enumerate() {
-                    let a = pair[0].read_from_slice(row);
-                    let b = pair[1].read_from_slice(row);
-                    accumumulator += a.evaluate(beta) + b.evaluate(beta);
-                    accumulators
-                        .get_value(k)
-                        .assign_to_raw_slice(row, &accumumulator.0);
-                }
-                accumumulator + last_element
+        // Accumulate lookup values in the trace, inverting the denominators of
+        // `BATCH_INVERSE_ROWS` rows at a time.
+        let num_entries = entries.len();
+        let mut trace = self.write_trace().unwrap();
+        let width = trace.width;
+        let accumulators = trace
+            .values
+            .par_chunks_mut(BATCH_INVERSE_ROWS * width)
+            .flat_map_iter(|rows| {
+                let entry_values = rows
+                    .chunks_exact(width)
+                    .flat_map(|row| entries.iter().map(|entry| entry.read_from_slice(row)))
+                    .collect::<Vec<_>>();
+                let evaluations = LogEntryValue::evaluate_batch(&entry_values, beta);
+                rows.chunks_exact_mut(width)
+                    .enumerate()
+                    .map(|(i, row)| {
+                        let row_evaluations = &evaluations[i * num_entries..(i + 1) * num_entries];
+                        let entry_chunks = row_evaluations.chunks_exact(2);
+                        let last_element = entry_chunks
+                            .remainder()
+                            .first()
+                            .copied()
+                            .unwrap_or(CubicExtension::ZERO);
+                        let mut accumumulator = CubicExtension::ZERO;
+                        let accumulators = intermediate_values;
+                        for (k, pair) in entry_chunks.enumerate() {
+                            accumumulator += pair[0] + pair[1];
+                            accumulators
+                                .get_value(k)
+                                .assign_to_raw_slice(row, &accumumulator.0);
+                        }
+                        accumumulator + last_element
+                    })
+                    .collect::<Vec<_>>()
             })
             .collect::<Vec<_>>();
+        drop(trace);
 
         let mut value = CubicExtension::ZERO;
         for (i, acc) in accumulators.into_iter().enumerate() {
@@ -6,6 +6,7 @@ use self::table::LogLookupTable;
 use self::values::LogLookupValues;
 
 pub mod constraint;
+pub mod set;
 pub mod table;
 pub mod trace;
 pub mod values;
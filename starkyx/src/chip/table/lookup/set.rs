@@ -0,0 +1,245 @@
+use std::collections::HashMap;
+
+use serde::{Deserialize, Serialize};
+
+use super::table::LogLookupTable;
+use crate::chip::builder::AirBuilder;
+use crate::chip::memory::time::Time;
+use crate::chip::register::array::ArrayRegister;
+use crate::chip::register::element::ElementRegister;
+use crate::chip::register::memory::MemorySlice;
+use crate::chip::register::{Register, RegisterSerializable};
+use crate::chip::trace::writer::TraceWriter;
+use crate::chip::AirParameters;
+use crate::math::prelude::*;
+
+/// A lookup of element values into an arbitrary, not necessarily contiguous, set of constants.
+///
+/// The allowed values are stored in memory, and the table is a single column reading the entry
+/// of the set at a prover chosen index on every row. Since the memory only holds the allowed
+/// values, every row of the table is in the set regardless of the index. The table entries and
+/// their multiplicities are written by [`TraceWriter::write_set_lookup_table`] and
+/// [`TraceWriter::write_set_lookup_multiplicities`].
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(bound = "")]
+pub struct SetLookup<F, E> {
+    pub(crate) table: LogLookupTable<ElementRegister, F, E>,
+    pub(crate) allowed: Vec<F>,
+    pub(crate) values: Vec<ElementRegister>,
+    /// The index of the set entry read by each row of the table.
+    pub(crate) index: ElementRegister,
+    /// The number of rows of the table reading each entry of the set.
+    pub(crate) reads: ArrayRegister<ElementRegister>,
+}
+
+impl<F, E> SetLookup<F, E> {
+    pub fn allowed(&self) -> &[F] {
+        &self.allowed
+    }
+}
+
+impl<L: AirParameters> AirBuilder<L> {
+    /// Constrains every register in `values` to take a value in the set `allowed`.
+    ///
+    /// Uses three free columns regardless of the size of `allowed`, and initializes the memory
+    /// bus if it is not already initialized. The table must be written with
+    /// [`TraceWriter::write_set_lookup_table`] before the global instructions, and the
+    /// multiplicities with [`TraceWriter::write_set_lookup_multiplicities`] once the values have
+    /// been written.
+    pub fn lookup_into_set(
+        &mut self,
+        values: &[ElementRegister],
+        allowed: &[L::Field],
+    ) -> SetLookup<L::Field, L::CubicParams> {
+        assert!(!allowed.is_empty(), "cannot lookup into an empty set");
+        assert!(!values.is_empty(), "no values to lookup");
+
+        if self.buses.is_empty() {
+            self.init_local_memory();
+        }
+
+        let reads = self.alloc_array_public::<ElementRegister>(allowed.len());
+        let set = self.uninit_slice();
+        for ((i, value), multiplicity) in allowed.iter().enumerate().zip(reads.iter()) {
+            let constant = self.constant::<ElementRegister>(value);
+            self.set(
+                &set.get(i),
+                constant,
+                &Time::zero(),
+                Some(multiplicity),
+                None,
+                None,
+            );
+        }
+
+        let index = self.alloc::<ElementRegister>();
+        let entry = self.get(&set.get_at(index), &Time::zero(), None, None);
+
+        let multiplicities = self.alloc_array::<ElementRegister>(1);
+        let mut table_data = self.new_lookup(&[entry], &multiplicities);
+        table_data.register_lookup_values(self, values);
+        self.constrain_element_lookup_table(table_data.clone());
+
+        SetLookup {
+            table: table_data,
+            allowed: allowed.to_vec(),
+            values: values.to_vec(),
+            index,
+            reads,
+        }
+    }
+}
+
+impl<F: PrimeField> TraceWriter<F> {
+    /// Writes the index of the set entry read by each row of the table, cycling through the
+    /// allowed values, together with the number of reads of each entry.
+    ///
+    /// The number of reads is used by the memory instructions, so this must be called before
+    /// the global instructions are written.
+    pub fn write_set_lookup_table<E: CubicParameters<F>>(&self, set_lookup: &SetLookup<F, E>) {
+        let num_rows = self.height();
+        let set_len = set_lookup.allowed.len();
+        assert!(
+            num_rows >= set_len,
+            "the trace has {} rows, fewer than the {} allowed values",
+            num_rows,
+            set_len
+        );
+
+        for i in 0..num_rows {
+            self.write(&set_lookup.index, &F::from_canonical_usize(i % set_len), i);
+        }
+        let reads = (0..set_len).map(|i| F::from_canonical_usize((num_rows - i).div_ceil(set_len)));
+        self.write_array(&set_lookup.reads, reads, 0);
+    }
+
+    /// Counts the occurrences of each allowed value among the looked up values and writes them
+    /// to the multiplicity column of the set lookup.
+    ///
+    /// The count of the `i`-th allowed value is written in row `i`, the first row of the table
+    /// holding that value. Values outside of the set are not counted, which makes the lookup
+    /// constraints fail.
+    pub fn write_set_lookup_multiplicities<E: CubicParameters<F>>(
+        &self,
+        set_lookup: &SetLookup<F, E>,
+    ) {
+        let indices = set_lookup
+            .allowed
+            .iter()
+            .enumerate()
+            .map(|(i, value)| (*value, i))
+            .collect::<HashMap<_, _>>();
+        let mut multiplicities = vec![F::ZERO; set_lookup.allowed.len()];
+        let mut count = |value: F| {
+            if let Some(&index) = indices.get(&value) {
+                multiplicities[index] += F::ONE;
+            }
+        };
+
+        let (trace_values, public_values): (Vec<_>, Vec<_>) = set_lookup
+            .values
+            .iter()
+            .partition(|value| matches!(value.register(), MemorySlice::Local(..)));
+
+        let trace = self.read_trace().unwrap();
+        for row in trace.rows() {
+            for value in trace_values.iter() {
+                count(value.read_from_slice(row));
+            }
+        }
+        drop(trace);
+
+        for value in public_values.iter() {
+            count(self.read(*value, 0));
+        }
+
+        // Row `i` of the table holds the `i`-th allowed value, so its count is written there.
+        let multiplicity = set_lookup.table.multiplicities.get(0);
+        for (i, count) in multiplicities.into_iter().enumerate() {
+            self.write(&multiplicity, &count, i);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::chip::builder::tests::*;
+
+    #[derive(Debug, Clone, Serialize, Deserialize)]
+    pub struct SetLookupTest;
+
+    impl AirParameters for SetLookupTest {
+        type Field = GoldilocksField;
+        type CubicParams = GoldilocksCubicParameters;
+        type Instruction = EmptyInstruction<GoldilocksField>;
+
+        const NUM_ARITHMETIC_COLUMNS: usize = 0;
+        const NUM_FREE_COLUMNS: usize = 5;
+        const EXTENDED_COLUMNS: usize = 18;
+    }
+
+    fn allowed_set() -> Vec<GoldilocksField> {
+        (0..50u64)
+            .map(|i| GoldilocksField::from_canonical_u64(3 * i * i + 1))
+            .collect()
+    }
+
+    fn prove_set_lookup(values: impl Fn(usize) -> GoldilocksField) {
+        type L = SetLookupTest;
+        type SC = PoseidonGoldilocksStarkConfig;
+
+        let allowed = allowed_set();
+
+        let mut builder = AirBuilder::<L>::new();
+        let a = builder.alloc::<ElementRegister>();
+        let b = builder.alloc::<ElementRegister>();
+        let set_lookup = builder.lookup_into_set(&[a, b], &allowed);
+
+        let (air, trace_data) = builder.build();
+
+        let num_rows = 1 << 10;
+        let generator = ArithmeticGenerator::<L>::new(trace_data, num_rows);
+        let writer = generator.new_writer();
+
+        writer.write_set_lookup_table(&set_lookup);
+        writer.write_global_instructions(&generator.air_data);
+        for i in 0..num_rows {
+            writer.write(&a, &values(i), i);
+            writer.write(&b, &allowed[(7 * i) % allowed.len()], i);
+            writer.write_row_instructions(&generator.air_data, i);
+        }
+        writer.write_set_lookup_multiplicities(&set_lookup);
+
+        let stark = Starky::new(air);
+        let config = SC::standard_fast_config(num_rows);
+
+        let public_inputs = writer.0.public.read().unwrap().clone();
+
+        // Generate proof and verify as a stark
+        test_starky(&stark, &config, &generator, &public_inputs);
+
+        // Test the recursive proof.
+        test_recursive_starky(stark, config, generator, &public_inputs);
+    }
+
+    #[test]
+    fn test_set_lookup() {
+        let allowed = allowed_set();
+        prove_set_lookup(|i| allowed[i % allowed.len()]);
+    }
+
+    #[test]
+    #[should_panic(expected = "global constraint is not satisfied")]
+    fn test_set_lookup_value_not_in_set() {
+        let allowed = allowed_set();
+        // 2 = 3 * i^2 + 1 has no integer solution, so it is not in the set.
+        prove_set_lookup(|i| {
+            if i == 13 {
+                GoldilocksField::from_canonical_u64(2)
+            } else {
+                allowed[i % allowed.len()]
+            }
+        });
+    }
+}
@@ -3,6 +3,8 @@ use itertools::Itertools;
 use super::{LogLookupTable, LookupTable};
 use crate::chip::register::cubic::EvalCubic;
 use crate::chip::register::Register;
+use crate::chip::table::log_derivative::entry::LogEntryValue;
+use crate::chip::table::log_derivative::trace::BATCH_INVERSE_ROWS;
 use crate::chip::trace::writer::TraceWriter;
 use crate::math::prelude::cubic::extension::CubicExtension;
 use crate::math::prelude::*;
@@ -20,29 +22,45 @@ impl<F: PrimeField> TraceWriter<F> {
             table_data.multiplicities_table_log.len()
         );
         assert_eq!(table_data.table.len(), table_data.multiplicities.len());
-        let mult_table_log_entries = self
-            .write_trace()
-            .unwrap()
-            .rows_par_mut()
-            .map(|row| {
-                let mut sum = CubicExtension::ZERO;
-                for ((table, multiplicity), table_log_register) in table_data
-                    .table
-                    .iter()
-                    .zip_eq(table_data.multiplicities.iter())
-                    .zip_eq(table_data.multiplicities_table_log.iter())
-                {
-                    let table_val = table.read_from_slice(row);
-                    let mult_val = multiplicity.read_from_slice(row);
-                    let table = CubicExtension::from(T::trace_value_as_cubic(table_val));
-                    let mult = CubicExtension::from(mult_val);
-                    let table_log = mult / (beta - table);
-                    table_log_register.assign_to_raw_slice(row, &table_log.0);
-                    sum += table_log;
-                }
-                sum
+        // Invert the denominators of `BATCH_INVERSE_ROWS` rows at a time.
+        let num_columns = table_data.table.len();
+        let mut trace = self.write_trace().unwrap();
+        let width = trace.width;
+        let mult_table_log_entries = trace
+            .values
+            .par_chunks_mut(BATCH_INVERSE_ROWS * width)
+            .flat_map_iter(|rows| {
+                let entry_values = rows
+                    .chunks_exact(width)
+                    .flat_map(|row| {
+                        table_data
+                            .table
+                            .iter()
+                            .zip_eq(table_data.multiplicities.iter())
+                            .map(|(table, multiplicity)| LogEntryValue {
+                                value: T::trace_value_as_cubic(table.read_from_slice(row)),
+                                multiplier: multiplicity.read_from_slice(row),
+                            })
+                    })
+                    .collect::<Vec<_>>();
+                let table_logs = LogEntryValue::evaluate_batch(&entry_values, beta);
+                rows.chunks_exact_mut(width)
+                    .zip_eq(table_logs.chunks_exact(num_columns))
+                    .map(|(row, row_table_logs)| {
+                        let mut sum = CubicExtension::ZERO;
+                        for (table_log, table_log_register) in row_table_logs
+                            .iter()
+                            .zip_eq(table_data.multiplicities_table_log.iter())
+                        {
+                            table_log_register.assign_to_raw_slice(row, &table_log.0);
+                            sum += *table_log;
+                        }
+                        sum
+                    })
+                    .collect::<Vec<_>>()
             })
             .collect::<Vec<_>>();
+        drop(trace);
 
         // Write accumulation
         let mut acc = CubicExtension::ZERO;
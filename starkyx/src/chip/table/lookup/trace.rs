@@ -9,29 +9,27 @@ use crate::math::prelude::*;
 use crate::maybe_rayon::*;
 use crate::trace::AirTrace;
 
+/// The number of rows of the trace counted together when computing multiplicities.
+const MULTIPLICITY_CHUNK_ROWS: usize = 1 << 12;
+
 impl<F: PrimeField> TraceWriter<F> {
     pub fn write_multiplicities_from_fn<E: CubicParameters<F>, T: Register>(
         &self,
         num_rows: usize,
         table_data: &LogLookupTable<T, F, E>,
-        table_index: impl Fn(T::Value<F>) -> usize,
+        table_index: impl Fn(T::Value<F>) -> usize + Sync,
         trace_values: &[T],
         public_values: &[T],
     ) {
-        // Calculate multiplicities
-        let mut multiplicities = vec![F::ZERO; num_rows];
-
         // Count the multiplicities in the trace
-        let trace = self.read_trace().unwrap();
-        for row in trace.rows() {
+        let mut multiplicities = self.count_trace_multiplicities(1, num_rows, |row, counts| {
             for value in trace_values.iter() {
                 let val = value.read_from_slice(row);
                 let index = table_index(val);
                 assert!(index < num_rows);
-                multiplicities[index] += F::ONE;
+                counts[index] += 1;
             }
-        }
-        drop(trace);
+        });
 
         // Count the multiplicities in the public values
         let public_slice = self.public.read().unwrap();
@@ -39,37 +37,107 @@ impl<F: PrimeField> TraceWriter<F> {
             let val = value.read_from_slice(&public_slice);
             let index = table_index(val);
             assert!(index < num_rows);
-            multiplicities[index] += F::ONE;
+            multiplicities[index] += 1;
         }
+        drop(public_slice);
 
         // Write multiplicities into the trace
         let multiplicity = table_data.multiplicities.get(0);
         for (i, mult) in multiplicities.iter().enumerate() {
-            self.write(&multiplicity, mult, i);
+            self.write(&multiplicity, &F::from_canonical_u32(*mult), i);
         }
     }
 
+    /// Counts the table entries looked up by the rows of the trace, in parallel.
+    ///
+    /// The rows are split into chunks of `MULTIPLICITY_CHUNK_ROWS` rows, which are folded into one
+    /// vector of counts per parallel job, and the vectors of the jobs are added up at the end. The
+    /// function `count_row` increments the counts of the entries looked up by a row. The counts
+    /// are indexed by `row * num_table_columns + column`, as in an `AirTrace` of the table.
+    fn count_trace_multiplicities(
+        &self,
+        num_table_columns: usize,
+        num_rows: usize,
+        count_row: impl Fn(&[F], &mut [u32]) + Sync,
+    ) -> Vec<u32> {
+        let num_counts = num_table_columns * num_rows;
+        let trace = self.read_trace().unwrap();
+        let width = trace.width;
+        let count_chunk = |mut counts: Vec<u32>, chunk: &[F]| {
+            for row in chunk.chunks_exact(width) {
+                count_row(row, &mut counts);
+            }
+            counts
+        };
+
+        #[cfg(feature = "parallel")]
+        let counts = trace
+            .values
+            .par_chunks(MULTIPLICITY_CHUNK_ROWS * width)
+            .fold(|| vec![0u32; num_counts], count_chunk)
+            .reduce(
+                || vec![0u32; num_counts],
+                |mut counts, other| {
+                    for (count, other_count) in counts.iter_mut().zip(other) {
+                        *count += other_count;
+                    }
+                    counts
+                },
+            );
+        #[cfg(not(feature = "parallel"))]
+        let counts = trace
+            .values
+            .chunks(MULTIPLICITY_CHUNK_ROWS * width)
+            .fold(vec![0u32; num_counts], count_chunk);
+        drop(trace);
+
+        counts
+    }
+
+    /// Writes a precomputed vector of multiplicities to the column of `multiplicity`.
+    ///
+    /// The entry at index `i` is the multiplicity of the table entry at row `i`. This is meant for
+    /// multiplicities computed out of band, e.g. from a log of the operations performed, instead
+    /// of by reading the looked up values back from the trace.
+    pub fn write_multiplicities_direct(&self, multiplicity: &ElementRegister, values: &[F]) {
+        assert_eq!(
+            values.len(),
+            self.height(),
+            "the number of multiplicities must be equal to the number of rows"
+        );
+        let column = multiplicity.register().get_range().0;
+        let mut trace = self.write_trace().unwrap();
+        trace
+            .rows_par_mut()
+            .zip(values.par_iter())
+            .for_each(|(row, value)| row[column] = *value);
+    }
+
+    /// Computes the multiplicities of the entries of a table spread over `num_table_columns`
+    /// columns, without writing them to the trace.
+    ///
+    /// The function `table_index` maps a looked up value to the `(row, column)` position of its
+    /// entry in the table. The returned trace has one column per table column and can be written
+    /// to the multiplicity registers with [`Self::write_lookup_multiplicities`].
     pub fn get_multiplicities_from_fn<T: EvalCubic>(
         &self,
         num_table_columns: usize,
         num_rows: usize,
         trace_entries: &[LogEntry<T>],
         public_entries: &[LogEntry<T>],
-        table_index: impl Fn(T::Value<F>) -> (usize, usize),
+        table_index: impl Fn(T::Value<F>) -> (usize, usize) + Sync,
     ) -> AirTrace<F> {
-        let mut multiplicities_trace = AirTrace::new_with_value(num_table_columns, num_rows, 0u32);
-
         // Count the multiplicities in the trace
-        let trace = self.read_trace().unwrap();
-        for row in trace.rows() {
-            for entry in trace_entries.iter() {
-                let value = entry.value().read_from_slice(row);
-                let (row_index, col_index) = table_index(value);
-                assert!(col_index < num_table_columns);
-                assert!(row_index < num_rows);
-                multiplicities_trace.row_mut(row_index)[col_index] += 1;
-            }
-        }
+        let mut multiplicities =
+            self.count_trace_multiplicities(num_table_columns, num_rows, |row, counts| {
+                for entry in trace_entries.iter() {
+                    let value = entry.value().read_from_slice(row);
+                    let (row_index, col_index) = table_index(value);
+                    assert!(col_index < num_table_columns);
+                    assert!(row_index < num_rows);
+                    counts[row_index * num_table_columns + col_index] += 1;
+                }
+            });
 
         // Count the multiplicities in public inputs
         let public_slice = self.public.read().unwrap();
@@ -78,12 +146,11 @@ impl<F: PrimeField> TraceWriter<F> {
             let (row_index, col_index) = table_index(value);
             assert!(col_index < num_table_columns);
             assert!(row_index < num_rows);
-            multiplicities_trace.row_mut(row_index)[col_index] += 1;
+            multiplicities[row_index * num_table_columns + col_index] += 1;
         }
 
         AirTrace::from_rows(
-            multiplicities_trace
-                .values
+            multiplicities
                 .into_par_iter()
                 .map(F::from_canonical_u32)
                 .collect(),
@@ -233,3 +300,134 @@ impl<F: PrimeField> TraceWriter<F> {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use serde::{Deserialize, Serialize};
+
+    use super::*;
+    use crate::chip::arithmetic::expression::ArithmeticExpression;
+    use crate::chip::builder::tests::*;
+    use crate::chip::builder::AirBuilder;
+    use crate::chip::register::memory::MemorySlice;
+    use crate::chip::AirParameters;
+
+    #[derive(Debug, Clone, Serialize, Deserialize)]
+    pub struct DirectMultiplicityTest;
+
+    impl AirParameters for DirectMultiplicityTest {
+        type Field = GoldilocksField;
+        type CubicParams = GoldilocksCubicParameters;
+        type Instruction = EmptyInstruction<GoldilocksField>;
+
+        const NUM_ARITHMETIC_COLUMNS: usize = 0;
+        const NUM_FREE_COLUMNS: usize = 4;
+        const EXTENDED_COLUMNS: usize = 12;
+    }
+
+    #[test]
+    fn test_write_multiplicities_direct() {
+        type F = GoldilocksField;
+        type L = DirectMultiplicityTest;
+        type SC = PoseidonGoldilocksStarkConfig;
+
+        let num_rows = 1 << 10;
+
+        // A table holding the row index, and two values looked up in it.
+        let mut builder = AirBuilder::<L>::new();
+        let table = builder.alloc::<ElementRegister>();
+        builder.set_to_expression_first_row(&table, ArithmeticExpression::zero());
+        builder.set_to_expression_transition(
+            &table.next(),
+            table.expr() + ArithmeticExpression::one(),
+        );
+        let a = builder.alloc::<ElementRegister>();
+        let b = builder.alloc::<ElementRegister>();
+        let multiplicities = builder.alloc_array::<ElementRegister>(1);
+        let mut table_data = builder.new_lookup(&[table], &multiplicities);
+        table_data.register_lookup_values(&mut builder, &[a, b]);
+        builder.constrain_element_lookup_table(table_data.clone());
+
+        let (air, trace_data) = builder.build();
+
+        let value_a = |i: usize| (3 * i + 1) % num_rows;
+        let value_b = |i: usize| (i * i) % num_rows;
+
+        // The multiplicities computed from a log of the looked up values.
+        let mut counts = vec![F::ZERO; num_rows];
+        for i in 0..num_rows {
+            counts[value_a(i)] += F::ONE;
+            counts[value_b(i)] += F::ONE;
+        }
+
+        let computed = ArithmeticGenerator::<L>::new(trace_data.clone(), num_rows);
+        let direct = ArithmeticGenerator::<L>::new(trace_data, num_rows);
+        for generator in [&computed, &direct] {
+            let writer = generator.new_writer();
+            for i in 0..num_rows {
+                writer.write(&a, &F::from_canonical_usize(value_a(i)), i);
+                writer.write(&b, &F::from_canonical_usize(value_b(i)), i);
+                writer.write_row_instructions(&generator.air_data, i);
+            }
+        }
+
+        computed.new_writer().write_multiplicities_from_fn(
+            num_rows,
+            &table_data,
+            |value| value.as_canonical_u64() as usize,
+            &[a, b],
+            &[],
+        );
+        direct
+            .new_writer()
+            .write_multiplicities_direct(&multiplicities.get(0), &counts);
+
+        assert_eq!(computed.trace_clone().values, direct.trace_clone().values);
+
+        let stark = Starky::new(air);
+        let config = SC::standard_fast_config(num_rows);
+
+        // Generate proofs and verify as a stark
+        test_starky(&stark, &config, &computed, &[]);
+        test_starky(&stark, &config, &direct, &[]);
+    }
+
+    #[test]
+    fn test_get_multiplicities_from_fn_parallel() {
+        type F = GoldilocksField;
+
+        // More rows than a single chunk, and a table of 2 columns of 2^8 rows.
+        let num_rows = 4 * MULTIPLICITY_CHUNK_ROWS + 17;
+        let num_table_rows = 1 << 8;
+        let writer = TraceWriter::new_with_value(F::ZERO, 2, num_rows, 0, 0);
+        let a = ElementRegister::from_register(MemorySlice::Local(0, 1));
+        let b = ElementRegister::from_register(MemorySlice::Local(1, 1));
+        for i in 0..num_rows {
+            writer.write(&a, &F::from_canonical_usize((7 * i + 3) % 512), i);
+            writer.write(&b, &F::from_canonical_usize((i * i) % 512), i);
+        }
+
+        let table_index = |value: F| {
+            let value = value.as_canonical_u64() as usize;
+            (value % num_table_rows, value / num_table_rows)
+        };
+        let multiplicities = writer.get_multiplicities_from_fn(
+            2,
+            num_table_rows,
+            &[LogEntry::Input(a), LogEntry::Input(b)],
+            &[],
+            table_index,
+        );
+
+        // Count the multiplicities serially.
+        let mut expected = AirTrace::new_with_value(2, num_table_rows, F::ZERO);
+        for i in 0..num_rows {
+            for register in [a, b] {
+                let (row, col) = table_index(writer.read(&register, i));
+                expected.row_mut(row)[col] += F::ONE;
+            }
+        }
+
+        assert_eq!(multiplicities.values, expected.values);
+    }
+}
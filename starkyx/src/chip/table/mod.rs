@@ -18,3 +18,4 @@ pub mod bus;
 pub mod log_derivative;
 pub mod lookup;
 pub mod powers;
+pub mod rlc;
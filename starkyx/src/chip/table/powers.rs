@@ -4,7 +4,7 @@ use crate::air::extension::cubic::CubicParser;
 use crate::chip::builder::AirBuilder;
 use crate::chip::register::array::ArrayRegister;
 use crate::chip::register::cubic::CubicRegister;
-use crate::chip::register::Register;
+use crate::chip::register::{Register, RegisterSerializable};
 use crate::chip::trace::writer::TraceWriter;
 use crate::math::prelude::*;
 use crate::prelude::cubic::element::CubicElement;
@@ -25,10 +25,24 @@ impl<L: AirParameters> AirBuilder<L> {
     /// Get an array of powers 1, `gamma`,..., `gamma^{len-1}` of a verifier challenge.
     pub fn challenge_powers(&mut self, len: usize) -> ArrayRegister<CubicRegister> {
         let challenge = self.alloc_challenge();
+        self.element_powers(&challenge, len)
+    }
+
+    /// Get an array of powers 1, `element`,..., `element^{len-1}` of a challenge, global or
+    /// public element, stored in the global region.
+    pub fn element_powers(
+        &mut self,
+        element: &CubicRegister,
+        len: usize,
+    ) -> ArrayRegister<CubicRegister> {
+        assert!(
+            !element.is_trace(),
+            "the powers of a trace element cannot be stored in the global region"
+        );
         let power_values = self.alloc_array_global(len);
 
         let powers = Powers {
-            element: challenge,
+            element: *element,
             values: power_values,
             _marker: std::marker::PhantomData,
         };
@@ -0,0 +1,181 @@
+use core::marker::PhantomData;
+
+use serde::{Deserialize, Serialize};
+
+use crate::air::extension::cubic::CubicParser;
+use crate::air::AirConstraint;
+use crate::chip::builder::AirBuilder;
+use crate::chip::register::array::ArrayRegister;
+use crate::chip::register::cubic::CubicRegister;
+use crate::chip::register::Register;
+use crate::chip::trace::writer::TraceWriter;
+use crate::chip::AirParameters;
+use crate::math::prelude::*;
+
+/// The random linear combination `sum_i challenge^i * values[i]` of cubic values.
+///
+/// The combination is computed with Horner's rule, `acc_i = acc_{i+1} * challenge + values[i]`
+/// with `acc_{n-1} = values[n-1]`, where the partial sums `acc_0, ..., acc_{n-2}` are stored in
+/// the extended trace and `acc_0` is the result.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RandomLinearCombination<F, E> {
+    challenge: CubicRegister,
+    values: Vec<CubicRegister>,
+    partial_sums: ArrayRegister<CubicRegister>,
+    _marker: PhantomData<(F, E)>,
+}
+
+impl<L: AirParameters> AirBuilder<L> {
+    /// Allocates an array of `length` cubic registers in the execution trace.
+    pub fn alloc_cubic_array(&mut self, length: usize) -> ArrayRegister<CubicRegister> {
+        self.alloc_array::<CubicRegister>(length)
+    }
+
+    /// Computes the random linear combination `sum_i challenge^i * values[i]`.
+    ///
+    /// Uses `3 * (n - 1)` extended columns for `n` values, which are written with the rest of the
+    /// extended trace, so `challenge` can be a verifier challenge.
+    pub fn random_linear_combination(
+        &mut self,
+        challenge: &CubicRegister,
+        values: &[CubicRegister],
+    ) -> CubicRegister {
+        assert!(!values.is_empty(), "no values to combine");
+        if values.len() == 1 {
+            return values[0];
+        }
+
+        let partial_sums = self.alloc_array_extended::<CubicRegister>(values.len() - 1);
+        let rlc = RandomLinearCombination {
+            challenge: *challenge,
+            values: values.to_vec(),
+            partial_sums,
+            _marker: PhantomData,
+        };
+
+        self.random_linear_combinations.push(rlc.clone());
+        self.constraints.push(rlc.into());
+
+        partial_sums.get(0)
+    }
+}
+
+impl<E: CubicParameters<AP::Field>, AP: CubicParser<E>> AirConstraint<AP>
+    for RandomLinearCombination<AP::Field, E>
+{
+    fn eval(&self, parser: &mut AP) {
+        let challenge = self.challenge.eval(parser);
+        let (last, values) = self.values.split_last().unwrap();
+
+        let mut acc = last.eval(parser);
+        for (value, partial_sum) in values.iter().zip(self.partial_sums.iter()).rev() {
+            let value = value.eval(parser);
+            let partial_sum = partial_sum.eval(parser);
+            let acc_times_challenge = parser.mul_extension(acc, challenge);
+            let expected = parser.add_extension(acc_times_challenge, value);
+            parser.assert_eq_extension(expected, partial_sum);
+            acc = partial_sum;
+        }
+    }
+}
+
+impl<F: PrimeField> TraceWriter<F> {
+    pub(crate) fn write_random_linear_combination<E: CubicParameters<F>>(
+        &self,
+        rlc: &RandomLinearCombination<F, E>,
+    ) {
+        let (last, values) = rlc.values.split_last().unwrap();
+        for row in 0..self.height {
+            let challenge = self.read(&rlc.challenge, row);
+            let mut acc = self.read(last, row);
+            for (value, partial_sum) in values.iter().zip(rlc.partial_sums.iter()).rev() {
+                acc = acc * challenge + self.read(value, row);
+                self.write(&partial_sum, &acc, row);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use plonky2::field::types::Sample;
+
+    use super::*;
+    use crate::chip::builder::tests::*;
+    use crate::math::extension::cubic::element::CubicElement;
+
+    /// Computes `sum_i challenge^i * values[i]`.
+    fn host_rlc<F: Field>(
+        challenge: CubicElement<F>,
+        values: &[CubicElement<F>],
+    ) -> CubicElement<F> {
+        values
+            .iter()
+            .rev()
+            .fold(CubicElement::ZERO, |acc, value| acc * challenge + *value)
+    }
+
+    #[derive(Debug, Clone, Serialize, Deserialize)]
+    struct RandomLinearCombinationTest;
+
+    impl AirParameters for RandomLinearCombinationTest {
+        type Field = GoldilocksField;
+        type CubicParams = GoldilocksCubicParameters;
+
+        const NUM_FREE_COLUMNS: usize = 15;
+        const EXTENDED_COLUMNS: usize = 18;
+
+        type Instruction = EmptyInstruction<GoldilocksField>;
+    }
+
+    #[test]
+    fn test_random_linear_combination() {
+        type L = RandomLinearCombinationTest;
+        type F = GoldilocksField;
+        type SC = PoseidonGoldilocksStarkConfig;
+
+        let mut builder = AirBuilder::<L>::new();
+        let gamma = builder.alloc::<CubicRegister>();
+        let values = builder.alloc_cubic_array(4);
+        let value_registers = values.iter().collect::<Vec<_>>();
+
+        // A combination with a value of the trace and one with a verifier challenge.
+        let rlc = builder.random_linear_combination(&gamma, &value_registers);
+        let beta = builder.alloc_challenge::<CubicRegister>();
+        let rlc_challenge = builder.random_linear_combination(&beta, &value_registers);
+
+        let (air, trace_data) = builder.build();
+
+        let num_rows = 1 << 10;
+        let generator = ArithmeticGenerator::<L>::new(trace_data, num_rows);
+
+        let rand_cubic = || CubicElement([F::rand(), F::rand(), F::rand()]);
+        let writer = generator.new_writer();
+        for i in 0..num_rows {
+            writer.write(&gamma, &rand_cubic(), i);
+            for value in values.iter() {
+                writer.write(&value, &rand_cubic(), i);
+            }
+        }
+
+        let stark = Starky::from_chip(air);
+        let config = SC::standard_fast_config(num_rows);
+
+        // Generate proof and verify as a stark
+        test_starky(&stark, &config, &generator, &[]);
+
+        let beta_value = writer.read(&beta, 0);
+        for i in 0..num_rows {
+            let value_elements = writer.read_vec(&values, i);
+            let gamma_value = writer.read(&gamma, i);
+            assert_eq!(writer.read(&rlc, i), host_rlc(gamma_value, &value_elements));
+            assert_eq!(
+                writer.read(&rlc_challenge, i),
+                host_rlc(beta_value, &value_elements)
+            );
+        }
+
+        // Test the recursive proof.
+        test_recursive_starky(stark, config, generator, &[]);
+    }
+}
@@ -1,17 +1,33 @@
+use anyhow::{ensure, Result};
 use serde::{Deserialize, Serialize};
 
 use super::writer::{AirWriter, TraceWriter};
+use crate::chip::constraint::Constraint;
+use crate::chip::instruction::assign::AssignType;
 use crate::chip::instruction::set::AirInstruction;
+use crate::chip::instruction::Instruction;
 use crate::chip::memory::pointer::accumulate::PointerAccumulator;
 use crate::chip::register::cubic::CubicRegister;
+use crate::chip::register::memory::MemorySlice;
+use crate::chip::register::relocation::MemoryRelocation;
 use crate::chip::table::accumulator::Accumulator;
 use crate::chip::table::bus::channel::BusChannel;
 use crate::chip::table::bus::global::Bus;
 use crate::chip::table::lookup::table::LookupTable;
 use crate::chip::table::lookup::values::LookupValues;
 use crate::chip::table::powers::Powers;
-use crate::chip::AirParameters;
+use crate::chip::table::rlc::RandomLinearCombination;
+use crate::chip::{AirParameters, Chip};
+use crate::trace::AirTrace;
 
+/// The data needed to generate the trace of a chip built by an [`AirBuilder`].
+///
+/// The instructions and constraints refer to the columns by their position in the layout given
+/// by `L`. To compose gadgets into a single stark, either build them with the same builder, e.g. by
+/// writing each gadget as a function taking `&mut AirBuilder<L>`, or build them separately and
+/// relocate their columns with [`AirTraceData::merge`].
+///
+/// [`AirBuilder`]: crate::chip::builder::AirBuilder
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[allow(clippy::type_complexity)]
 pub struct AirTraceData<L: AirParameters> {
@@ -23,6 +39,7 @@ pub struct AirTraceData<L: AirParameters> {
     pub global_instructions: Vec<AirInstruction<L::Field, L::Instruction>>,
     pub powers: Vec<Powers<L::Field, L::CubicParams>>,
     pub accumulators: Vec<Accumulator<L::Field, L::CubicParams>>,
+    pub random_linear_combinations: Vec<RandomLinearCombination<L::Field, L::CubicParams>>,
     pub pointer_row_accumulators: Vec<PointerAccumulator<L::Field, L::CubicParams>>,
     pub pointer_global_accumulators: Vec<PointerAccumulator<L::Field, L::CubicParams>>,
     pub bus_channels: Vec<BusChannel<CubicRegister, L::CubicParams>>,
@@ -33,13 +50,275 @@ pub struct AirTraceData<L: AirParameters> {
         LookupTable<L::Field, L::CubicParams>,
         LookupValues<L::Field, L::CubicParams>,
     )>,
+    /// The descriptions of the documented registers, see [`AirBuilder::document`].
+    ///
+    /// [`AirBuilder::document`]: crate::chip::builder::AirBuilder::document
+    pub register_docs: Vec<(MemorySlice, String)>,
+    /// The range checks of the chips merged into this one by [`AirTraceData::merge`], in addition
+    /// to `range_data`. See [`AirTraceData::range_checks`].
+    #[serde(default)]
+    pub merged_range_data: Vec<(
+        LookupTable<L::Field, L::CubicParams>,
+        LookupValues<L::Field, L::CubicParams>,
+    )>,
 }
 
 impl<L: AirParameters> AirTraceData<L> {
+    /// Merges the chip `chip` with trace data `self` and the chip `other_chip` with trace data
+    /// `other` into a single chip with parameters `M`.
+    ///
+    /// The arithmetic, free and extended columns of the two chips are placed side by side in the
+    /// corresponding regions of `M`, which must have as many columns of each kind as the two chips
+    /// together. The public values, global values and challenges of the second chip follow those
+    /// of the first. All the registers of the instructions, constraints and shared memory are
+    /// relocated accordingly. The range check of `self` stays in `range_data`, and those of
+    /// `other` are added to `merged_range_data`.
+    ///
+    /// Returns the merged chip and trace data, along with the relocation of each chip, which maps
+    /// the registers of a chip to their position in the merged chip.
+    #[allow(clippy::type_complexity)]
+    pub fn merge<L2, M>(
+        &self,
+        chip: &Chip<L>,
+        other: &AirTraceData<L2>,
+        other_chip: &Chip<L2>,
+    ) -> (Chip<M>, AirTraceData<M>, [MemoryRelocation; 2])
+    where
+        L2: AirParameters<
+            Field = L::Field,
+            CubicParams = L::CubicParams,
+            Instruction = L::Instruction,
+        >,
+        M: AirParameters<
+            Field = L::Field,
+            CubicParams = L::CubicParams,
+            Instruction = L::Instruction,
+        >,
+    {
+        let (arithmetic, free) = (M::NUM_ARITHMETIC_COLUMNS, M::NUM_FREE_COLUMNS);
+        assert_eq!(
+            arithmetic,
+            L::NUM_ARITHMETIC_COLUMNS + L2::NUM_ARITHMETIC_COLUMNS,
+            "The merged chip must have the arithmetic columns of both chips"
+        );
+        assert_eq!(
+            free,
+            L::NUM_FREE_COLUMNS + L2::NUM_FREE_COLUMNS,
+            "The merged chip must have the free columns of both chips"
+        );
+        assert_eq!(
+            M::EXTENDED_COLUMNS,
+            L::EXTENDED_COLUMNS + L2::EXTENDED_COLUMNS,
+            "The merged chip must have the extended columns of both chips"
+        );
+        assert_eq!(chip.num_public_values, self.num_public_inputs);
+        assert_eq!(other_chip.num_public_values, other.num_public_inputs);
+
+        let relocation = MemoryRelocation {
+            trace_segments: vec![
+                (0, L::NUM_ARITHMETIC_COLUMNS, 0),
+                (
+                    L::NUM_ARITHMETIC_COLUMNS,
+                    L::NUM_ARITHMETIC_COLUMNS + L::NUM_FREE_COLUMNS,
+                    arithmetic,
+                ),
+                (
+                    L::NUM_ARITHMETIC_COLUMNS + L::NUM_FREE_COLUMNS,
+                    L::num_columns(),
+                    arithmetic + free,
+                ),
+            ],
+            public_offset: 0,
+            global_offset: 0,
+            challenge_offset: 0,
+        };
+        let other_relocation = MemoryRelocation {
+            trace_segments: vec![
+                (0, L2::NUM_ARITHMETIC_COLUMNS, L::NUM_ARITHMETIC_COLUMNS),
+                (
+                    L2::NUM_ARITHMETIC_COLUMNS,
+                    L2::NUM_ARITHMETIC_COLUMNS + L2::NUM_FREE_COLUMNS,
+                    arithmetic + L::NUM_FREE_COLUMNS,
+                ),
+                (
+                    L2::NUM_ARITHMETIC_COLUMNS + L2::NUM_FREE_COLUMNS,
+                    L2::num_columns(),
+                    arithmetic + free + L::EXTENDED_COLUMNS,
+                ),
+            ],
+            public_offset: self.num_public_inputs,
+            global_offset: self.num_global_values,
+            challenge_offset: self.num_challenges,
+        };
+
+        // The execution trace ends with the free columns used by the second chip, and contains
+        // the free columns of the first chip.
+        let execution_trace_length =
+            arithmetic + L::NUM_FREE_COLUMNS + other.execution_trace_length
+                - L2::NUM_ARITHMETIC_COLUMNS;
+
+        let mut constraints: Vec<Constraint<M>> = relocation.relocate(&chip.constraints);
+        constraints
+            .extend(other_relocation.relocate::<_, Vec<Constraint<M>>>(&other_chip.constraints));
+        let mut global_constraints: Vec<Constraint<M>> =
+            relocation.relocate(&chip.global_constraints);
+        global_constraints.extend(
+            other_relocation.relocate::<_, Vec<Constraint<M>>>(&other_chip.global_constraints),
+        );
+        let mut unused_columns = chip
+            .unused_columns
+            .iter()
+            .map(|column| relocation.trace_column(*column))
+            .chain(
+                other_chip
+                    .unused_columns
+                    .iter()
+                    .map(|column| other_relocation.trace_column(*column)),
+            )
+            .collect::<Vec<_>>();
+        unused_columns.sort_unstable();
+
+        let merged_chip = Chip {
+            constraints,
+            global_constraints,
+            unused_columns,
+            execution_trace_length,
+            num_challenges: chip.num_challenges + other_chip.num_challenges,
+            num_public_values: chip.num_public_values + other_chip.num_public_values,
+            num_global_values: chip.num_global_values + other_chip.num_global_values,
+        };
+
+        let mut air_data: AirTraceData<M> = relocation.relocate(self);
+        let other_data: AirTraceData<M> = other_relocation.relocate(other);
+        air_data.num_challenges += other_data.num_challenges;
+        air_data.num_public_inputs += other_data.num_public_inputs;
+        air_data.num_global_values += other_data.num_global_values;
+        air_data.execution_trace_length = execution_trace_length;
+        air_data.instructions.extend(other_data.instructions);
+        air_data
+            .global_instructions
+            .extend(other_data.global_instructions);
+        air_data.powers.extend(other_data.powers);
+        air_data.accumulators.extend(other_data.accumulators);
+        air_data
+            .random_linear_combinations
+            .extend(other_data.random_linear_combinations);
+        air_data
+            .pointer_row_accumulators
+            .extend(other_data.pointer_row_accumulators);
+        air_data
+            .pointer_global_accumulators
+            .extend(other_data.pointer_global_accumulators);
+        air_data.bus_channels.extend(other_data.bus_channels);
+        air_data.buses.extend(other_data.buses);
+        air_data.lookup_values.extend(other_data.lookup_values);
+        air_data.lookup_tables.extend(other_data.lookup_tables);
+        air_data.register_docs.extend(other_data.register_docs);
+        air_data.merged_range_data.extend(other_data.range_data);
+        air_data
+            .merged_range_data
+            .extend(other_data.merged_range_data);
+
+        (merged_chip, air_data, [relocation, other_relocation])
+    }
+
+    /// Returns the range checks of the arithmetic columns, those of the chip itself followed by
+    /// those of the chips merged into it.
+    #[allow(clippy::type_complexity)]
+    pub fn range_checks(
+        &self,
+    ) -> impl Iterator<
+        Item = &(
+            LookupTable<L::Field, L::CubicParams>,
+            LookupValues<L::Field, L::CubicParams>,
+        ),
+    > {
+        self.range_data.iter().chain(self.merged_range_data.iter())
+    }
+
+    /// Sets the number of public inputs of the trace data and the number of public values of
+    /// `chip` to `num_public_inputs`. See [`Chip::set_public_values`].
+    pub fn set_public_inputs(&mut self, chip: &mut Chip<L>, num_public_inputs: usize) {
+        chip.set_public_values(self, num_public_inputs);
+    }
+
+    /// Returns the description of the documented register containing the trace column `column`.
+    pub fn column_doc(&self, column: usize) -> Option<&str> {
+        self.register_docs
+            .iter()
+            .find(|(register, _)| {
+                let (start, end) = register.get_range();
+                register.is_trace() && (start..end).contains(&column)
+            })
+            .map(|(_, doc)| doc.as_str())
+    }
+
+    /// Checks the layout of the instructions against a trace of the chip.
+    ///
+    /// Every column written by an instruction, as given by [`Instruction::trace_layout`], must be
+    /// within the width of `trace`, and no two instructions writing on every row may write to the
+    /// same column. Instructions that only write some of the rows, such as filtered instructions
+    /// and first row or transition assignments, are allowed to share columns.
+    pub fn validate_trace(&self, trace: &AirTrace<L::Field>) -> Result<()> {
+        let mut regions = Vec::new();
+        for (index, instruction) in self.instructions.iter().enumerate() {
+            for slice in instruction.trace_layout() {
+                let (start, length) = match slice {
+                    MemorySlice::Local(start, length) | MemorySlice::Next(start, length) => {
+                        (start, length)
+                    }
+                    _ => continue,
+                };
+                let end = start + length;
+                ensure!(
+                    end <= trace.width,
+                    "Instruction {} ({}) writes to columns {}..{} outside of a trace of width {}",
+                    index,
+                    instruction.name(),
+                    start,
+                    end,
+                    trace.width
+                );
+                if writes_every_row(instruction) {
+                    regions.push((start, end, index));
+                }
+            }
+        }
+
+        regions.sort();
+        let mut last_region: Option<(usize, usize)> = None;
+        for (start, end, index) in regions {
+            if let Some((last_end, last_index)) = last_region {
+                ensure!(
+                    start >= last_end || index == last_index,
+                    "Instructions {} ({}) and {} ({}) both write to column {}{}",
+                    last_index,
+                    self.instructions[last_index].name(),
+                    index,
+                    self.instructions[index].name(),
+                    start,
+                    self.column_doc(start)
+                        .map(|doc| format!(" ({})", doc))
+                        .unwrap_or_default()
+                );
+                if end <= last_end {
+                    continue;
+                }
+            }
+            last_region = Some((end, index));
+        }
+
+        Ok(())
+    }
+
     #[inline]
     pub fn write_trace_instructions(&self, writer: &mut impl AirWriter<Field = L::Field>) {
         for instruction in self.instructions.iter() {
+            #[cfg(feature = "profile-trace")]
+            let start = std::time::Instant::now();
             writer.write_instruction(instruction);
+            #[cfg(feature = "profile-trace")]
+            super::profile::record(instruction.name(), start.elapsed());
         }
     }
 
@@ -63,6 +342,11 @@ impl<L: AirParameters> AirTraceData<L> {
             writer.write_accumulation(acc);
         }
 
+        // Write random linear combinations.
+        for rlc in self.random_linear_combinations.iter() {
+            writer.write_random_linear_combination(rlc);
+        }
+
         // Write pointer accumulations.
         for acc in self.pointer_global_accumulators.iter() {
             writer.write_ptr_accumulation(acc, 0);
@@ -95,3 +379,238 @@ impl<L: AirParameters> AirTraceData<L> {
         }
     }
 }
+
+/// Whether an instruction writes to its layout on every row of the trace.
+fn writes_every_row<F, I>(instruction: &AirInstruction<F, I>) -> bool {
+    match instruction {
+        AirInstruction::Filtered(_, _) => false,
+        AirInstruction::Assign(assign) => assign.kind == AssignType::All,
+        _ => true,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use num::bigint::RandBigInt;
+    use num::BigUint;
+    use rand::thread_rng;
+
+    use super::*;
+    use crate::chip::arithmetic::expression::ArithmeticExpression;
+    use crate::chip::builder::tests::*;
+    use crate::chip::builder::AirBuilder;
+    use crate::chip::field::instruction::FpInstruction;
+    use crate::chip::field::parameters::tests::Fp25519;
+    use crate::chip::field::parameters::{FieldParameters, MAX_NB_LIMBS};
+    use crate::chip::field::register::FieldRegister;
+    use crate::chip::register::bit::BitRegister;
+    use crate::chip::register::element::ElementRegister;
+    use crate::chip::register::Register;
+    use crate::math::prelude::*;
+    use crate::polynomial::Polynomial;
+
+    #[derive(Debug, Clone, Serialize, Deserialize)]
+    struct ValidateTraceTest;
+
+    impl AirParameters for ValidateTraceTest {
+        type Field = GoldilocksField;
+        type CubicParams = GoldilocksCubicParameters;
+        type Instruction = EmptyInstruction<GoldilocksField>;
+
+        const NUM_FREE_COLUMNS: usize = 6;
+    }
+
+    #[test]
+    fn test_validate_trace() {
+        type F = GoldilocksField;
+        type L = ValidateTraceTest;
+
+        let mut builder = AirBuilder::<L>::new();
+        let bit = builder.alloc::<BitRegister>();
+        let a = builder.alloc::<ElementRegister>();
+        let b = builder.alloc::<ElementRegister>();
+        let x_0 = builder.alloc::<ElementRegister>();
+        let x_1 = builder.alloc::<ElementRegister>();
+        builder.set_to_expression_first_row(&x_0, ArithmeticExpression::zero());
+        builder.set_to_expression_first_row(&x_1, ArithmeticExpression::one());
+        builder.set_to_expression_transition(&x_0.next(), x_1.expr());
+        builder.set_to_expression_transition(&x_1.next(), x_0.expr() + x_1.expr());
+        builder.select(&bit, &a, &b);
+        let (_, trace_data) = builder.build();
+
+        // The assignments to the first row and the transitions share columns.
+        let trace = AirTrace::new_with_value(L::num_columns(), 1 << 4, F::ZERO);
+        trace_data.validate_trace(&trace).unwrap();
+
+        // The trace is too narrow for the layout.
+        let narrow_trace = AirTrace::new_with_value(4, 1 << 4, F::ZERO);
+        let err = trace_data.validate_trace(&narrow_trace).unwrap_err();
+        assert!(err.to_string().contains("outside of a trace of width 4"));
+
+        // Two selections write to the same register.
+        let mut builder = AirBuilder::<L>::new();
+        let bit = builder.alloc::<BitRegister>();
+        let a = builder.alloc::<ElementRegister>();
+        let b = builder.alloc::<ElementRegister>();
+        let result = builder.select(&bit, &a, &b);
+        builder.document(&result, "selected value");
+        builder.set_select(&bit, &b, &a, &result);
+        let (_, trace_data) = builder.build();
+
+        let err = trace_data.validate_trace(&trace).unwrap_err();
+        assert!(err.to_string().contains(&format!(
+            "both write to column {} (selected value)",
+            result.register().get_range().0
+        )));
+    }
+
+    #[derive(Debug, Clone, Serialize, Deserialize)]
+    struct FieldLayoutTest;
+
+    impl AirParameters for FieldLayoutTest {
+        type Field = GoldilocksField;
+        type CubicParams = GoldilocksCubicParameters;
+        type Instruction = FpInstruction<Fp25519>;
+
+        const NUM_ARITHMETIC_COLUMNS: usize = 600;
+        const NUM_FREE_COLUMNS: usize = 2;
+        const EXTENDED_COLUMNS: usize = 900;
+    }
+
+    #[test]
+    fn test_field_instructions_trace_layout() {
+        type F = GoldilocksField;
+        type L = FieldLayoutTest;
+        type P = Fp25519;
+
+        let mut builder = AirBuilder::<L>::new();
+        let a = builder.alloc::<FieldRegister<P>>();
+        let b = builder.alloc::<FieldRegister<P>>();
+        let c = builder.fp_sub(&a, &b);
+        let d = builder.fp_div(&c, &b);
+        let mut constant = [0u16; MAX_NB_LIMBS];
+        constant[0] = 3;
+        let e = builder.fp_mul_const(&d, constant);
+        builder.assert_nonzero(&e);
+        builder.fp_den(&a, &e, true);
+        let (_, trace_data, report) = builder.finalize();
+
+        let trace = AirTrace::new_with_value(L::num_columns(), 1 << 4, F::ZERO);
+        trace_data.validate_trace(&trace).unwrap();
+
+        // Apart from the inputs, every arithmetic column is written by exactly one instruction.
+        let mut written = vec![0; report.arithmetic_used];
+        for slice in trace_data
+            .instructions
+            .iter()
+            .flat_map(|i| i.trace_layout())
+        {
+            if let MemorySlice::Local(start, length) = slice {
+                for count in written.iter_mut().skip(start).take(length) {
+                    *count += 1;
+                }
+            }
+        }
+        let num_inputs = 2 * P::NB_LIMBS;
+        assert!(written[..num_inputs].iter().all(|count| *count == 0));
+        assert!(written[num_inputs..].iter().all(|count| *count == 1));
+    }
+
+    #[derive(Clone, Debug, Copy, Serialize, Deserialize)]
+    struct MergeTest;
+
+    impl AirParameters for MergeTest {
+        type Field = GoldilocksField;
+        type CubicParams = GoldilocksCubicParameters;
+
+        const NUM_ARITHMETIC_COLUMNS: usize = 124;
+        const NUM_FREE_COLUMNS: usize = 2;
+        const EXTENDED_COLUMNS: usize = 195;
+
+        type Instruction = FpInstruction<Fp25519>;
+    }
+
+    #[derive(Clone, Debug, Copy, Serialize, Deserialize)]
+    struct MergedTest;
+
+    impl AirParameters for MergedTest {
+        type Field = GoldilocksField;
+        type CubicParams = GoldilocksCubicParameters;
+
+        const NUM_ARITHMETIC_COLUMNS: usize = 2 * MergeTest::NUM_ARITHMETIC_COLUMNS;
+        const NUM_FREE_COLUMNS: usize = 2 * MergeTest::NUM_FREE_COLUMNS;
+        const EXTENDED_COLUMNS: usize = 2 * MergeTest::EXTENDED_COLUMNS;
+
+        type Instruction = FpInstruction<Fp25519>;
+    }
+
+    #[test]
+    fn test_merge() {
+        type F = GoldilocksField;
+        type L = MergeTest;
+        type M = MergedTest;
+        type SC = PoseidonGoldilocksStarkConfig;
+        type P = Fp25519;
+
+        let p = Fp25519::modulus();
+
+        let build = || {
+            let mut builder = AirBuilder::<L>::new();
+            let a = builder.alloc::<FieldRegister<P>>();
+            let b = builder.alloc::<FieldRegister<P>>();
+            let c = builder.fp_add(&a, &b);
+            let (air, trace_data) = builder.build();
+            (air, trace_data, [a, b, c])
+        };
+        let (air_0, trace_data_0, registers_0) = build();
+        let (air_1, trace_data_1, registers_1) = build();
+
+        let (air, trace_data, relocations) =
+            trace_data_0.merge::<L, M>(&air_0, &trace_data_1, &air_1);
+        let registers: [[FieldRegister<P>; 3]; 2] = [
+            relocations[0].relocate(&registers_0),
+            relocations[1].relocate(&registers_1),
+        ];
+        assert_ne!(registers[0][0].register(), registers[1][0].register());
+
+        // Each chip keeps its own range check.
+        assert!(trace_data.range_data.is_some());
+        assert_eq!(trace_data.merged_range_data.len(), 1);
+
+        let num_rows = 1 << 16;
+        let generator = ArithmeticGenerator::<M>::new(trace_data, num_rows);
+        let writer = generator.new_writer();
+
+        let mut rng = thread_rng();
+        for i in 0..num_rows {
+            let inputs = registers
+                .iter()
+                .map(|[a, b, _]| {
+                    let a_int: BigUint = rng.gen_biguint(256) % &p;
+                    let b_int = rng.gen_biguint(256) % &p;
+                    let p_a = Polynomial::<F>::from_biguint_field(&a_int, 16, 16);
+                    let p_b = Polynomial::<F>::from_biguint_field(&b_int, 16, 16);
+                    writer.write_slice(a, p_a.coefficients(), i);
+                    writer.write_slice(b, p_b.coefficients(), i);
+                    (a_int, b_int)
+                })
+                .collect::<Vec<_>>();
+            writer.write_row_instructions(&generator.air_data, i);
+
+            for ([_, _, c], (a_int, b_int)) in registers.iter().zip(inputs) {
+                let c_int = (a_int + b_int) % &p;
+                assert_eq!(
+                    writer.read(c, i),
+                    Polynomial::<F>::from_biguint_field(&c_int, 16, 16)
+                );
+            }
+        }
+        writer.write_global_instructions(&generator.air_data);
+
+        let stark = Starky::new(air);
+        let config = SC::standard_fast_config(num_rows);
+
+        // Generate proof and verify as a stark
+        test_starky(&stark, &config, &generator, &[]);
+    }
+}
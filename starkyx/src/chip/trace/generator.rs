@@ -1,10 +1,17 @@
 use alloc::sync::Arc;
+use core::fmt::Debug;
+use std::ffi::OsString;
+use std::fs::{self, File};
+use std::io::{BufReader, BufWriter, Write};
+use std::path::Path;
+use std::sync::{Mutex, RwLock};
 
-use anyhow::{Error, Result};
-use serde::{Deserialize, Serialize};
+use anyhow::{anyhow, ensure, Error, Result};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
 
 use super::data::AirTraceData;
-use super::writer::TraceWriter;
+use super::writer::{TraceWriter, WriterData};
+use crate::chip::memory::map::MemoryMap;
 use crate::chip::table::log_derivative::entry::LogEntry;
 use crate::chip::table::lookup::table::LookupTable;
 use crate::chip::table::lookup::values::LookupValues;
@@ -74,6 +81,94 @@ impl<L: AirParameters> ArithmeticGenerator<L> {
     pub fn strong_count(&self) -> usize {
         Arc::strong_count(&self.writer.0)
     }
+
+    /// Saves the partially written trace to `path`, so that trace generation can be resumed from
+    /// row `next_row` with [`Self::resume`].
+    ///
+    /// The checkpoint holds the trace, the global and public values, the challenges and the
+    /// memory of the writer. It is first written to a temporary file next to `path` which is then
+    /// renamed, so that an interruption while saving leaves any previous checkpoint intact.
+    pub fn checkpoint(&self, path: impl AsRef<Path>, next_row: usize) -> Result<()> {
+        ensure!(
+            next_row <= self.num_rows,
+            "Cannot checkpoint at row {} of a trace of {} rows",
+            next_row,
+            self.num_rows
+        );
+        let path = path.as_ref();
+        let mut tmp_path = OsString::from(path);
+        tmp_path.push(".tmp");
+
+        let mut file = BufWriter::new(File::create(&tmp_path)?);
+        bincode::serialize_into(&mut file, &(self.num_rows, next_row))?;
+        bincode::serialize_into(&mut file, &*self.writer.read_trace().unwrap())?;
+        bincode::serialize_into(&mut file, &*self.writer.global().unwrap())?;
+        bincode::serialize_into(&mut file, &*self.writer.public().unwrap())?;
+        bincode::serialize_into(&mut file, &*self.writer.challenges.read().unwrap())?;
+        bincode::serialize_into(&mut file, &*self.writer.memory().unwrap())?;
+        file.flush()?;
+        file.get_ref().sync_all()?;
+        drop(file);
+
+        fs::rename(&tmp_path, path)?;
+        Ok(())
+    }
+
+    /// Restores a generator from a checkpoint saved by [`Self::checkpoint`], returning it with
+    /// the index of the next row to write.
+    ///
+    /// Returns an error if the checkpoint does not match the dimensions of `air_data`.
+    pub fn resume(path: impl AsRef<Path>, air_data: AirTraceData<L>) -> Result<(Self, usize)> {
+        let mut file = BufReader::new(File::open(path)?);
+        let (num_rows, next_row): (usize, usize) = bincode::deserialize_from(&mut file)?;
+        let trace: AirTrace<L::Field> = bincode::deserialize_from(&mut file)?;
+        let global: Vec<L::Field> = bincode::deserialize_from(&mut file)?;
+        let public: Vec<L::Field> = bincode::deserialize_from(&mut file)?;
+        let challenges: Vec<L::Field> = bincode::deserialize_from(&mut file)?;
+        let memory: MemoryMap<L::Field> = bincode::deserialize_from(&mut file)?;
+
+        ensure!(
+            trace.width == L::num_columns() && trace.height() == num_rows,
+            "The checkpoint trace has dimensions {}x{}, expected {}x{}",
+            trace.width,
+            trace.height(),
+            L::num_columns(),
+            num_rows
+        );
+        ensure!(
+            public.len() == air_data.num_public_inputs,
+            "The checkpoint has {} public inputs, expected {}",
+            public.len(),
+            air_data.num_public_inputs
+        );
+        ensure!(
+            global.len() == air_data.num_global_values,
+            "The checkpoint has {} global values, expected {}",
+            global.len(),
+            air_data.num_global_values
+        );
+        ensure!(
+            next_row <= num_rows,
+            "The checkpoint resumes at row {} of a trace of {} rows",
+            next_row,
+            num_rows
+        );
+
+        let writer = TraceWriter(Arc::new(WriterData {
+            trace: RwLock::new(trace),
+            global: RwLock::new(global),
+            public: RwLock::new(public),
+            challenges: RwLock::new(challenges),
+            memory: RwLock::new(memory),
+            height: num_rows,
+        }));
+        let generator = Self {
+            writer,
+            air_data,
+            num_rows,
+        };
+        Ok((generator, next_row))
+    }
 }
 
 impl<L: AirParameters> TraceGenerator<L::Field, Chip<L>> for ArithmeticGenerator<L> {
@@ -96,34 +191,35 @@ impl<L: AirParameters> TraceGenerator<L::Field, Chip<L>> for ArithmeticGenerator
 
                 let num_rows = self.num_rows;
 
-                // Write the range check table and multiplicitiies
-                if let Some((LookupTable::Element(table), LookupValues::Element(values))) =
-                    &self.air_data.range_data
-                {
-                    assert_eq!(table.table.len(), 1);
-                    let table_column = table.table[0];
-                    for i in 0..num_rows {
-                        self.writer
-                            .write(&table_column, &L::Field::from_canonical_usize(i), i);
-                    }
+                // Write the range check tables and multiplicitiies
+                for range_data in self.air_data.range_checks() {
+                    if let (LookupTable::Element(table), LookupValues::Element(values)) = range_data
+                    {
+                        assert_eq!(table.table.len(), 1);
+                        let table_column = table.table[0];
+                        for i in 0..num_rows {
+                            self.writer
+                                .write(&table_column, &L::Field::from_canonical_usize(i), i);
+                        }
 
-                    self.writer.write_multiplicities_from_fn(
-                        num_rows,
-                        table,
-                        Self::range_fn,
-                        &values
-                            .trace_values
-                            .iter()
-                            .map(LogEntry::value)
-                            .copied()
-                            .collect::<Vec<_>>(),
-                        &values
-                            .public_values
-                            .iter()
-                            .map(LogEntry::value)
-                            .copied()
-                            .collect::<Vec<_>>(),
-                    );
+                        self.writer.write_multiplicities_from_fn(
+                            num_rows,
+                            table,
+                            Self::range_fn,
+                            &values
+                                .trace_values
+                                .iter()
+                                .map(LogEntry::value)
+                                .copied()
+                                .collect::<Vec<_>>(),
+                            &values
+                                .public_values
+                                .iter()
+                                .map(LogEntry::value)
+                                .copied()
+                                .collect::<Vec<_>>(),
+                        );
+                    }
                 }
 
                 let trace = self.trace_clone();
@@ -163,4 +259,267 @@ impl<L: AirParameters> TraceGenerator<L::Field, Chip<L>> for ArithmeticGenerator
             _ => unreachable!("Chip air IOP only has two rounds"),
         }
     }
+
+    fn reset(&self) {
+        ArithmeticGenerator::reset(self)
+    }
+}
+
+/// A function generating the execution trace of a chip from its trace data and number of rows.
+pub type TraceFn<L> =
+    dyn FnMut(&AirTraceData<L>, usize) -> AirTrace<<L as AirParameters>::Field> + Send;
+
+/// A trace generator that builds the execution trace of a chip with a user-supplied closure.
+///
+/// The closure is called once per proof to produce the execution trace, which may either have
+/// the width of the execution trace or the full width of the chip. The extended trace is then
+/// generated from the trace data as for an [`ArithmeticGenerator`].
+///
+/// Since the closure is not serializable, only the trace data and the number of rows are
+/// serialized. A deserialized generator has no closure and fails to generate a trace.
+#[derive(Clone)]
+pub struct ClosureTraceGenerator<L: AirParameters> {
+    generator: ArithmeticGenerator<L>,
+    trace_fn: Option<Arc<Mutex<Box<TraceFn<L>>>>>,
+}
+
+impl<L: AirParameters> ClosureTraceGenerator<L> {
+    pub fn new<T>(air_data: AirTraceData<L>, num_rows: usize, trace_fn: T) -> Self
+    where
+        T: FnMut(&AirTraceData<L>, usize) -> AirTrace<L::Field> + Send + 'static,
+    {
+        Self {
+            generator: ArithmeticGenerator::new(air_data, num_rows),
+            trace_fn: Some(Arc::new(Mutex::new(Box::new(trace_fn)))),
+        }
+    }
+
+    pub fn air_data(&self) -> &AirTraceData<L> {
+        &self.generator.air_data
+    }
+
+    pub fn num_rows(&self) -> usize {
+        self.generator.num_rows
+    }
+
+    /// Calls the closure and loads the resulting trace into the inner generator.
+    fn load_trace(&self) -> Result<()> {
+        let num_rows = self.num_rows();
+        let air_data = self.air_data();
+        let trace_fn = self
+            .trace_fn
+            .as_ref()
+            .ok_or_else(|| anyhow!("a deserialized closure trace generator has no closure"))?;
+        let trace = {
+            let mut trace_fn = trace_fn.lock().unwrap();
+            trace_fn(air_data, num_rows)
+        };
+        ensure!(
+            trace.height() == num_rows,
+            "closure generated a trace of {} rows, expected {}",
+            trace.height(),
+            num_rows
+        );
+        ensure!(
+            trace.width == air_data.execution_trace_length || trace.width == L::num_columns(),
+            "closure generated a trace of width {}, expected {} or {}",
+            trace.width,
+            air_data.execution_trace_length,
+            L::num_columns()
+        );
+
+        let mut full_trace = AirTrace::new_with_value(L::num_columns(), num_rows, L::Field::ZERO);
+        full_trace
+            .rows_par_mut()
+            .zip(trace.rows_par())
+            .for_each(|(row, trace_row)| row[..trace_row.len()].copy_from_slice(trace_row));
+
+        let writer = &self.generator.writer.0;
+        *writer.trace.write().unwrap() = full_trace;
+        *writer.public.write().unwrap() = vec![L::Field::ZERO; air_data.num_public_inputs];
+        *writer.global.write().unwrap() = vec![L::Field::ZERO; air_data.num_global_values];
+        writer.challenges.write().unwrap().clear();
+        Ok(())
+    }
+}
+
+impl<L: AirParameters> TraceGenerator<L::Field, Chip<L>> for ClosureTraceGenerator<L> {
+    type Error = Error;
+
+    fn generate_round(
+        &self,
+        air: &Chip<L>,
+        round: usize,
+        challenges: &[L::Field],
+        global_values: &mut [L::Field],
+        public_inputs: &[L::Field],
+    ) -> Result<AirTrace<L::Field>> {
+        if round == 0 {
+            self.load_trace()?;
+        }
+        self.generator
+            .generate_round(air, round, challenges, global_values, public_inputs)
+    }
+}
+
+impl<L: AirParameters> Debug for ClosureTraceGenerator<L> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("ClosureTraceGenerator")
+            .field("num_rows", &self.num_rows())
+            .finish_non_exhaustive()
+    }
+}
+
+impl<L: AirParameters> Serialize for ClosureTraceGenerator<L> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        (self.air_data(), self.num_rows()).serialize(serializer)
+    }
+}
+
+impl<'de, L: AirParameters> Deserialize<'de> for ClosureTraceGenerator<L> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let (air_data, num_rows) = <(AirTraceData<L>, usize)>::deserialize(deserializer)?;
+        Ok(Self {
+            generator: ArithmeticGenerator::new(air_data, num_rows),
+            trace_fn: None,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::chip::builder::tests::*;
+    use crate::chip::builder::AirBuilder;
+    use crate::chip::register::element::ElementRegister;
+    use crate::chip::register::Register;
+
+    #[derive(Debug, Clone, Serialize, Deserialize)]
+    pub struct ClosureFibonacciParameters;
+
+    impl AirParameters for ClosureFibonacciParameters {
+        type Field = GoldilocksField;
+        type CubicParams = GoldilocksCubicParameters;
+        type Instruction = EmptyInstruction<GoldilocksField>;
+        const NUM_FREE_COLUMNS: usize = 2;
+    }
+
+    #[test]
+    fn test_closure_trace_generator() {
+        type F = GoldilocksField;
+        type L = ClosureFibonacciParameters;
+        type SC = PoseidonGoldilocksStarkConfig;
+
+        let mut builder = AirBuilder::<L>::new();
+        let x_0 = builder.alloc::<ElementRegister>();
+        let x_1 = builder.alloc::<ElementRegister>();
+        builder.set_to_expression_first_row(&x_0, F::ZERO.into());
+        builder.set_to_expression_first_row(&x_1, F::ONE.into());
+        builder.set_to_expression_transition(&x_0.next(), x_1.expr());
+        builder.set_to_expression_transition(&x_1.next(), x_0.expr() + x_1.expr());
+
+        let (air, trace_data) = builder.build();
+
+        let num_rows = 1 << 10;
+        let generator = ClosureTraceGenerator::new(trace_data, num_rows, |air_data, num_rows| {
+            let writer = TraceWriter::new(air_data, num_rows);
+            for i in 0..num_rows {
+                writer.write_row_instructions(air_data, i);
+            }
+            let trace = writer.read_trace().unwrap().clone();
+            trace
+        });
+
+        let stark = Starky::new(air);
+        let config = SC::standard_fast_config(num_rows);
+
+        // Generate proof and verify as a stark
+        test_starky(&stark, &config, &generator, &[]);
+
+        // The closure is not serialized, so a deserialized generator cannot generate a trace.
+        let bytes = bincode::serialize(&generator).unwrap();
+        let deserialized: ClosureTraceGenerator<L> = bincode::deserialize(&bytes).unwrap();
+        assert_eq!(deserialized.num_rows(), num_rows);
+        assert!(deserialized
+            .generate_round(stark.air(), 0, &[], &mut [], &[])
+            .is_err());
+
+        // Test the recursive proof.
+        test_recursive_starky(stark, config, generator, &[]);
+    }
+
+    #[test]
+    fn test_checkpoint_resume() {
+        type F = GoldilocksField;
+        type L = ClosureFibonacciParameters;
+        type SC = PoseidonGoldilocksStarkConfig;
+
+        let mut builder = AirBuilder::<L>::new();
+        let x_0 = builder.alloc::<ElementRegister>();
+        let x_1 = builder.alloc::<ElementRegister>();
+        builder.set_to_expression_first_row(&x_0, F::ZERO.into());
+        builder.set_to_expression_first_row(&x_1, F::ONE.into());
+        builder.set_to_expression_transition(&x_0.next(), x_1.expr());
+        builder.set_to_expression_transition(&x_1.next(), x_0.expr() + x_1.expr());
+
+        let (air, trace_data) = builder.build();
+
+        let num_rows = 1 << 10;
+        let checkpoint_row = 300;
+        let path =
+            std::env::temp_dir().join(format!("curta_checkpoint_test_{}.bin", std::process::id()));
+
+        // Write the first rows and save a checkpoint.
+        let generator = ArithmeticGenerator::<L>::new(trace_data.clone(), num_rows);
+        let writer = generator.new_writer();
+        for i in 0..checkpoint_row {
+            writer.write_row_instructions(&generator.air_data, i);
+        }
+        generator.checkpoint(&path, checkpoint_row).unwrap();
+        drop(writer);
+        drop(generator);
+
+        // Resume from the checkpoint and complete the trace.
+        let (generator, next_row) = ArithmeticGenerator::<L>::resume(&path, trace_data).unwrap();
+        fs::remove_file(&path).unwrap();
+        assert_eq!(next_row, checkpoint_row);
+        let writer = generator.new_writer();
+        for i in next_row..num_rows {
+            writer.write_row_instructions(&generator.air_data, i);
+        }
+        assert_eq!(
+            writer.read(&x_1, num_rows - 1),
+            (0..num_rows)
+                .fold((F::ZERO, F::ONE), |(a, b), _| (b, a + b))
+                .0
+        );
+
+        let stark = Starky::new(air);
+        let config = SC::standard_fast_config(num_rows);
+
+        // Generate proof and verify as a stark
+        test_starky(&stark, &config, &generator, &[]);
+    }
+
+    #[test]
+    fn test_resume_dimension_mismatch() {
+        type L = ClosureFibonacciParameters;
+
+        let mut builder = AirBuilder::<L>::new();
+        builder.alloc::<ElementRegister>();
+        let (_, trace_data) = builder.build();
+
+        let path = std::env::temp_dir().join(format!(
+            "curta_checkpoint_mismatch_test_{}.bin",
+            std::process::id()
+        ));
+        let generator = ArithmeticGenerator::<L>::new(trace_data.clone(), 1 << 4);
+        generator.checkpoint(&path, 0).unwrap();
+
+        let mut public_data = trace_data;
+        public_data.num_public_inputs += 1;
+        let result = ArithmeticGenerator::<L>::resume(&path, public_data);
+        fs::remove_file(&path).unwrap();
+        assert!(result.is_err());
+    }
 }
@@ -3,4 +3,7 @@
 
 pub mod data;
 pub mod generator;
+#[cfg(feature = "profile-trace")]
+pub mod profile;
+pub mod program;
 pub mod writer;
@@ -0,0 +1,108 @@
+//! Timing of the trace generation per instruction type.
+//!
+//! When the `profile-trace` feature is enabled, the time spent writing each instruction to the
+//! trace is accumulated in a thread-local map keyed by the name of the instruction type, see
+//! [`Instruction::name`](crate::chip::instruction::Instruction::name). Since the map is
+//! thread-local, the report of a thread only covers the rows written by that thread.
+
+use core::cell::RefCell;
+use core::time::Duration;
+use std::collections::HashMap;
+
+thread_local! {
+    static TRACE_PROFILE: RefCell<HashMap<&'static str, Duration>> = RefCell::new(HashMap::new());
+}
+
+/// Adds `duration` to the time spent writing instructions named `name`.
+#[inline]
+pub(crate) fn record(name: &'static str, duration: Duration) {
+    TRACE_PROFILE.with(|profile| *profile.borrow_mut().entry(name).or_default() += duration);
+}
+
+/// Returns the time spent writing each instruction type on the current thread, from the slowest
+/// to the fastest.
+pub fn trace_profile_report() -> Vec<(&'static str, Duration)> {
+    let mut report = TRACE_PROFILE.with(|profile| {
+        profile
+            .borrow()
+            .iter()
+            .map(|(name, duration)| (*name, *duration))
+            .collect::<Vec<_>>()
+    });
+    report.sort_by(|a, b| b.1.cmp(&a.1).then(a.0.cmp(b.0)));
+    report
+}
+
+/// Clears the timings recorded on the current thread.
+pub fn reset_trace_profile() {
+    TRACE_PROFILE.with(|profile| profile.borrow_mut().clear());
+}
+
+#[cfg(test)]
+mod tests {
+    use serde::{Deserialize, Serialize};
+
+    use super::*;
+    use crate::chip::builder::tests::*;
+    use crate::chip::builder::AirBuilder;
+    use crate::chip::register::bit::BitRegister;
+    use crate::chip::register::element::ElementRegister;
+    use crate::chip::register::Register;
+    use crate::chip::AirParameters;
+    use crate::math::prelude::*;
+
+    #[derive(Debug, Clone, Serialize, Deserialize)]
+    pub struct ProfileTest;
+
+    impl AirParameters for ProfileTest {
+        type Field = GoldilocksField;
+        type CubicParams = GoldilocksCubicParameters;
+        type Instruction = EmptyInstruction<GoldilocksField>;
+        const NUM_ARITHMETIC_COLUMNS: usize = 0;
+        const NUM_FREE_COLUMNS: usize = 5;
+        const EXTENDED_COLUMNS: usize = 0;
+    }
+
+    #[test]
+    fn test_trace_profile_report() {
+        type F = GoldilocksField;
+        type L = ProfileTest;
+
+        let mut builder = AirBuilder::<L>::new();
+        let bit = builder.alloc::<BitRegister>();
+        let a = builder.alloc::<ElementRegister>();
+        let b = builder.alloc::<ElementRegister>();
+        let selected = builder.select(&bit, &a, &b);
+        let copy = builder.alloc::<ElementRegister>();
+        builder.set_to_expression(&copy, selected.expr());
+
+        let (_, trace_data) = builder.build();
+        let num_rows = 1 << 8;
+        let generator = ArithmeticGenerator::<L>::new(trace_data, num_rows);
+
+        reset_trace_profile();
+        let writer = generator.new_writer();
+        for i in 0..num_rows {
+            writer.write(&bit, &F::from_canonical_usize(i % 2), i);
+            writer.write(&a, &F::from_canonical_usize(i), i);
+            writer.write(&b, &F::from_canonical_usize(2 * i), i);
+            writer.write_row_instructions(&generator.air_data, i);
+        }
+
+        let names = trace_profile_report()
+            .into_iter()
+            .map(|(name, _)| name)
+            .collect::<Vec<_>>();
+        for expected in ["BitConstraint", "Select", "Assign"] {
+            assert!(
+                names.contains(&expected),
+                "{} missing from {:?}",
+                expected,
+                names
+            );
+        }
+
+        reset_trace_profile();
+        assert!(trace_profile_report().is_empty());
+    }
+}
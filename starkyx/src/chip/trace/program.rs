@@ -0,0 +1,187 @@
+//! Generating the trace of a chip from a list of high-level operations.
+//!
+//! A chip whose rows all run the same gadgets can be seen as a machine executing one operation
+//! per row. A [`ProgramTrace`] collects such operations and lays them out across the rows of the
+//! trace, writing the inputs of each operation and filling the witnesses with the instructions of
+//! the chip.
+
+use super::data::AirTraceData;
+use super::generator::ArithmeticGenerator;
+use super::writer::TraceWriter;
+use crate::chip::AirParameters;
+use crate::maybe_rayon::*;
+
+/// An operation executed on one row of a chip.
+pub trait ProgramOperation<F>: Send + Sync {
+    /// The registers of a row holding the inputs of the operation.
+    type Layout: Send + Sync;
+
+    /// Writes the inputs of the operation to the row `row_index` of the trace.
+    fn write_inputs(&self, layout: &Self::Layout, writer: &TraceWriter<F>, row_index: usize);
+}
+
+/// A list of operations, executed one per row of the trace of a chip.
+pub struct ProgramTrace<L: AirParameters, O: ProgramOperation<L::Field>> {
+    air_data: AirTraceData<L>,
+    num_rows: usize,
+    layout: O::Layout,
+    operations: Vec<O>,
+}
+
+impl<L: AirParameters, O: ProgramOperation<L::Field>> ProgramTrace<L, O> {
+    pub fn new(air_data: AirTraceData<L>, num_rows: usize, layout: O::Layout) -> Self {
+        Self {
+            air_data,
+            num_rows,
+            layout,
+            operations: Vec::new(),
+        }
+    }
+
+    pub fn layout(&self) -> &O::Layout {
+        &self.layout
+    }
+
+    pub fn len(&self) -> usize {
+        self.operations.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.operations.is_empty()
+    }
+
+    /// Appends an operation to the program, executed on the next free row of the trace.
+    pub fn push(&mut self, operation: O) {
+        assert!(
+            self.operations.len() < self.num_rows,
+            "the program has more operations than the {} rows of the trace",
+            self.num_rows
+        );
+        self.operations.push(operation);
+    }
+
+    /// Writes the trace of the program, and returns a generator holding it.
+    ///
+    /// Operation `i` is executed on row `i`, and the rows after the last operation repeat it so
+    /// that every row of the trace holds a valid witness.
+    pub fn generate(self) -> ArithmeticGenerator<L> {
+        let last_operation = self
+            .operations
+            .last()
+            .expect("cannot generate the trace of an empty program");
+
+        let generator = ArithmeticGenerator::<L>::new(self.air_data, self.num_rows);
+        let writer = generator.new_writer();
+        writer.write_global_instructions(&generator.air_data);
+        (0..self.num_rows).into_par_iter().for_each(|i| {
+            let operation = self.operations.get(i).unwrap_or(last_operation);
+            operation.write_inputs(&self.layout, &writer, i);
+            writer.write_row_instructions(&generator.air_data, i);
+        });
+
+        generator
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use num::bigint::RandBigInt;
+    use num::BigUint;
+    use rand::thread_rng;
+    use serde::{Deserialize, Serialize};
+
+    use super::*;
+    use crate::chip::builder::tests::*;
+    use crate::chip::builder::AirBuilder;
+    use crate::chip::field::add::FpAddInstruction;
+    use crate::chip::field::parameters::tests::Fp25519;
+    use crate::chip::field::parameters::FieldParameters;
+    use crate::chip::field::register::FieldRegister;
+    use crate::math::prelude::*;
+    use crate::polynomial::Polynomial;
+
+    #[derive(Clone, Debug, Copy, Serialize, Deserialize)]
+    struct ProgramTraceTest;
+
+    impl AirParameters for ProgramTraceTest {
+        type Field = GoldilocksField;
+        type CubicParams = GoldilocksCubicParameters;
+
+        const NUM_ARITHMETIC_COLUMNS: usize = 124;
+        const NUM_FREE_COLUMNS: usize = 2;
+        const EXTENDED_COLUMNS: usize = 195;
+
+        type Instruction = FpAddInstruction<Fp25519>;
+    }
+
+    /// The modular addition `a + b mod p`.
+    struct ModularAddition {
+        a: BigUint,
+        b: BigUint,
+    }
+
+    impl<F: PrimeField64> ProgramOperation<F> for ModularAddition {
+        type Layout = (FieldRegister<Fp25519>, FieldRegister<Fp25519>);
+
+        fn write_inputs(&self, layout: &Self::Layout, writer: &TraceWriter<F>, row_index: usize) {
+            let (a, b) = layout;
+            writer.write(
+                a,
+                &Polynomial::from_biguint_field(&self.a, 16, 16),
+                row_index,
+            );
+            writer.write(
+                b,
+                &Polynomial::from_biguint_field(&self.b, 16, 16),
+                row_index,
+            );
+        }
+    }
+
+    #[test]
+    fn test_program_trace() {
+        type F = GoldilocksField;
+        type L = ProgramTraceTest;
+        type SC = PoseidonGoldilocksStarkConfig;
+
+        let p = Fp25519::modulus();
+
+        let mut builder = AirBuilder::<L>::new();
+        let a = builder.alloc::<FieldRegister<Fp25519>>();
+        let b = builder.alloc::<FieldRegister<Fp25519>>();
+        let sum = builder.fp_add(&a, &b);
+
+        let (air, trace_data) = builder.build();
+        let num_rows = 1 << 16;
+
+        let num_operations = 1000;
+        let mut rng = thread_rng();
+        let mut program = ProgramTrace::<L, ModularAddition>::new(trace_data, num_rows, (a, b));
+        let mut expected = Vec::with_capacity(num_operations);
+        for _ in 0..num_operations {
+            let a_int = rng.gen_biguint(256) % &p;
+            let b_int = rng.gen_biguint(256) % &p;
+            expected.push((&a_int + &b_int) % &p);
+            program.push(ModularAddition { a: a_int, b: b_int });
+        }
+        assert_eq!(program.len(), num_operations);
+
+        let generator = program.generate();
+        let writer = generator.new_writer();
+        for (i, expected) in expected.iter().enumerate() {
+            assert_eq!(
+                writer.read(&sum, i),
+                Polynomial::<F>::from_biguint_field(expected, 16, 16)
+            );
+        }
+
+        let stark = Starky::new(air);
+        let config = SC::standard_fast_config(num_rows);
+
+        // Generate proof and verify as a stark
+        test_starky(&stark, &config, &generator, &[]);
+
+        // Test the recursive proof.
+        test_recursive_starky(stark, config, generator, &[]);
+    }
+}
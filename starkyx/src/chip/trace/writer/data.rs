@@ -60,6 +60,15 @@ impl<T: PartialEq + Eq + Hash> AirWriterData<T> {
         PublicWriter::new(&mut self.public, &mut self.memory, self.trace.height())
     }
 
+    /// Splits the trace into chunks of `chunk_size` consecutive rows, in increasing row order.
+    ///
+    /// Each chunk gets its own copy of the memory map and a read-only view of the public values,
+    /// so the global and public values must be written with [`Self::public_writer`] before the
+    /// chunks are filled.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `chunk_size` does not divide the height of the trace.
     #[inline]
     pub fn chunks(
         &mut self,
@@ -83,6 +92,15 @@ impl<T: PartialEq + Eq + Hash> AirWriterData<T> {
             })
     }
 
+    /// A parallel version of [`Self::chunks`], where the chunks are filled concurrently.
+    ///
+    /// The chunks are disjoint and only read the public values, so filling them with a closure
+    /// that depends only on the row index gives the same trace as [`Self::chunks`]. A closure
+    /// that carries a value from one chunk to the next must use [`Self::chunks`] instead.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `chunk_size` does not divide the height of the trace.
     #[inline]
     pub fn chunks_par(
         &mut self,
@@ -134,3 +152,67 @@ impl<'a, T: PartialEq + Eq + Hash> AirWriterChunkMut<'a, T> {
         )
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use serde::{Deserialize, Serialize};
+
+    use super::*;
+    use crate::chip::builder::tests::*;
+    use crate::chip::builder::AirBuilder;
+    use crate::chip::register::element::ElementRegister;
+    use crate::chip::register::Register;
+    use crate::chip::trace::writer::AirWriter;
+
+    #[derive(Debug, Clone, Serialize, Deserialize)]
+    struct ChunksTest;
+
+    impl AirParameters for ChunksTest {
+        type Field = GoldilocksField;
+        type CubicParams = GoldilocksCubicParameters;
+        type Instruction = EmptyInstruction<GoldilocksField>;
+
+        const NUM_FREE_COLUMNS: usize = 2;
+    }
+
+    #[test]
+    fn test_chunks_par_matches_chunks() {
+        type L = ChunksTest;
+        type F = GoldilocksField;
+
+        let mut builder = AirBuilder::<L>::new();
+        let offset = builder.alloc_public::<ElementRegister>();
+        let x = builder.alloc::<ElementRegister>();
+        let y = builder.alloc::<ElementRegister>();
+        builder.set_to_expression(&y, x.expr() * x.expr() + offset.expr());
+
+        let (_, trace_data) = builder.build();
+
+        let num_rows = 1 << 10;
+        let chunk_size = 1 << 4;
+
+        let fill_public = |writer_data: &mut AirWriterData<F>| {
+            let mut writer = writer_data.public_writer();
+            writer.write(&offset, &F::from_canonical_u32(7));
+        };
+        let fill_chunk = |mut chunk: AirWriterChunkMut<'_, F>| {
+            for i in 0..chunk_size {
+                let mut writer = chunk.row_writer(i);
+                let row_index = writer.row_index().unwrap();
+                writer.write(&x, &F::from_canonical_usize(row_index));
+                trace_data.write_trace_instructions(&mut writer);
+            }
+        };
+
+        let mut sequential = AirWriterData::new(&trace_data, num_rows);
+        fill_public(&mut sequential);
+        sequential.chunks(chunk_size).for_each(fill_chunk);
+
+        let mut parallel = AirWriterData::new(&trace_data, num_rows);
+        fill_public(&mut parallel);
+        parallel.chunks_par(chunk_size).for_each(fill_chunk);
+
+        assert_eq!(parallel.trace.values, sequential.trace.values);
+        assert_eq!(parallel.public, sequential.public);
+    }
+}
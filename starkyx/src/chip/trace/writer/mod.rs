@@ -429,7 +429,11 @@ impl<F: Field> TraceWriter<F> {
         row_index: usize,
     ) {
         for instruction in air_data.instructions.iter() {
+            #[cfg(feature = "profile-trace")]
+            let start = std::time::Instant::now();
             self.write_instruction(instruction, row_index);
+            #[cfg(feature = "profile-trace")]
+            crate::chip::trace::profile::record(instruction.name(), start.elapsed());
         }
     }
 
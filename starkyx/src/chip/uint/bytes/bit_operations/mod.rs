@@ -3,6 +3,7 @@ pub use crate::math::prelude::*;
 pub mod and;
 pub mod not;
 pub mod rotate;
+pub mod rotl;
 pub mod shift;
 pub mod xor;
 
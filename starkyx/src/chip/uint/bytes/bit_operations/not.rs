@@ -5,6 +5,8 @@ use crate::air::AirConstraint;
 use crate::chip::instruction::Instruction;
 use crate::chip::register::array::ArrayRegister;
 use crate::chip::register::bit::BitRegister;
+use crate::chip::register::memory::MemorySlice;
+use crate::chip::register::RegisterSerializable;
 use crate::chip::trace::writer::{AirWriter, TraceWriter};
 pub use crate::math::prelude::*;
 
@@ -44,6 +46,10 @@ impl<F: Field, const NUM_BITS: usize> Instruction<F> for Not<NUM_BITS> {
 
         writer.write_array(&self.result, result);
     }
+
+    fn trace_layout(&self) -> Vec<MemorySlice> {
+        vec![*self.result.register()]
+    }
 }
 
 #[cfg(test)]
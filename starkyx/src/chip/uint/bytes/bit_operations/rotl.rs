@@ -0,0 +1,106 @@
+//! Rotate left by a constant
+//!
+//! Since the rotation amount is known at compile time, the rotation is just a permutation of the
+//! bits: `result[i] = a[(i + n - r) % n]`.
+
+use crate::chip::builder::AirBuilder;
+use crate::chip::register::array::ArrayRegister;
+use crate::chip::register::bit::BitRegister;
+use crate::chip::register::Register;
+use crate::chip::AirParameters;
+pub use crate::math::prelude::*;
+
+impl<L: AirParameters> AirBuilder<L> {
+    /// Rotates the little-endian bits of `a` to the left by `R`.
+    pub fn rotl<const R: usize>(
+        &mut self,
+        a: &ArrayRegister<BitRegister>,
+    ) -> ArrayRegister<BitRegister> {
+        let result = self.alloc_array::<BitRegister>(a.len());
+        self.set_rotl::<R>(a, &result);
+        result
+    }
+
+    pub fn set_rotl<const R: usize>(
+        &mut self,
+        a: &ArrayRegister<BitRegister>,
+        result: &ArrayRegister<BitRegister>,
+    ) {
+        let n = a.len();
+        assert_eq!(result.len(), n, "a and result must have the same length");
+        let r = R % n;
+
+        for i in 0..n {
+            self.set_to_expression(&result.get(i), a.get((i + n - r) % n).expr());
+        }
+    }
+}
+
+#[cfg(test)]
+pub mod tests {
+    use rand::{thread_rng, Rng};
+    use serde::{Deserialize, Serialize};
+
+    use super::*;
+    pub use crate::chip::builder::tests::*;
+
+    #[derive(Debug, Clone, Serialize, Deserialize)]
+    pub struct RotlTest;
+
+    impl AirParameters for RotlTest {
+        type Field = GoldilocksField;
+        type CubicParams = GoldilocksCubicParameters;
+
+        type Instruction = EmptyInstruction<GoldilocksField>;
+
+        const NUM_FREE_COLUMNS: usize = 4 * 32;
+    }
+
+    #[test]
+    fn test_rotl() {
+        type F = GoldilocksField;
+        type L = RotlTest;
+        type SC = PoseidonGoldilocksStarkConfig;
+
+        let mut builder = AirBuilder::<L>::new();
+
+        let a = builder.alloc_array::<BitRegister>(32);
+        let rotl_7 = builder.rotl::<7>(&a);
+        let rotl_16 = builder.rotl::<16>(&a);
+        let rotl_31 = builder.rotl::<31>(&a);
+
+        let (air, trace_data) = builder.build();
+
+        let num_rows = 1 << 9;
+        let generator = ArithmeticGenerator::<L>::new(trace_data, num_rows);
+        let writer = generator.new_writer();
+
+        let to_bits_le =
+            |x: u32| core::array::from_fn::<_, 32, _>(|i| F::from_canonical_u32((x >> i) & 1));
+
+        let mut rng = thread_rng();
+        let a_vals = (0..num_rows).map(|_| rng.gen::<u32>()).collect::<Vec<_>>();
+        for (i, a_val) in a_vals.iter().enumerate() {
+            writer.write_array(&a, to_bits_le(*a_val), i);
+            writer.write_row_instructions(&generator.air_data, i);
+        }
+
+        for (i, a_val) in a_vals.iter().enumerate() {
+            for (result, r) in [(rotl_7, 7), (rotl_16, 16), (rotl_31, 31)] {
+                assert_eq!(
+                    writer.read_vec(&result, i),
+                    to_bits_le(a_val.rotate_left(r)).to_vec()
+                );
+            }
+        }
+
+        let stark = Starky::new(air);
+        let config = SC::standard_fast_config(num_rows);
+
+        // Generate proof and verify as a stark
+        test_starky(&stark, &config, &generator, &[]);
+
+        // Test the recursive proof.
+        test_recursive_starky(stark, config, generator, &[]);
+    }
+}
@@ -13,6 +13,7 @@ use crate::air::parser::AirParser;
 use crate::air::AirConstraint;
 use crate::chip::builder::AirBuilder;
 use crate::chip::instruction::Instruction;
+use crate::chip::register::memory::MemorySlice;
 use crate::chip::trace::writer::{AirWriter, TraceWriter};
 use crate::chip::AirParameters;
 
@@ -50,6 +51,11 @@ impl<L: AirParameters> AirBuilder<L> {
         ByteLookupOperations::new()
     }
 
+    /// Registers the lookups of `operations` into `table`.
+    ///
+    /// A table can be shared by several gadgets, each registering its own operations, as every
+    /// entry of the table is keyed by its opcode. The returned multiplicity data of each
+    /// registration can then be combined with [`ByteMultiplicityData::merge`].
     pub fn register_byte_lookup(
         &mut self,
         table: &mut ByteLogLookupTable<L::Field, L::CubicParams>,
@@ -121,6 +127,28 @@ impl<F: PrimeField64> Instruction<F> for ByteInstructionSet {
             Self::Digest(instruction) => Instruction::<F>::write_to_air(instruction, writer),
         }
     }
+
+    fn trace_layout(&self) -> Vec<MemorySlice> {
+        match self {
+            Self::Op(op) => Instruction::<F>::trace_layout(op),
+            Self::BitAnd(op) => Instruction::<F>::trace_layout(op),
+            Self::BitXor(op) => Instruction::<F>::trace_layout(op),
+            Self::BitNot(op) => Instruction::<F>::trace_layout(op),
+            Self::Decode(instruction) => Instruction::<F>::trace_layout(instruction),
+            Self::Digest(instruction) => Instruction::<F>::trace_layout(instruction),
+        }
+    }
+
+    fn name(&self) -> &'static str {
+        match self {
+            Self::Op(op) => Instruction::<F>::name(op),
+            Self::BitAnd(op) => Instruction::<F>::name(op),
+            Self::BitXor(op) => Instruction::<F>::name(op),
+            Self::BitNot(op) => Instruction::<F>::name(op),
+            Self::Decode(instruction) => Instruction::<F>::name(instruction),
+            Self::Digest(instruction) => Instruction::<F>::name(instruction),
+        }
+    }
 }
 
 impl From<ByteOperationInstruction> for ByteInstructionSet {
@@ -167,8 +195,115 @@ mod tests {
     pub use crate::chip::builder::tests::*;
     use crate::chip::register::Register;
     use crate::chip::uint::bytes::operations::value::ByteOperation;
+    use crate::chip::uint::bytes::operations::{OPCODE_INDICES, OPCODE_RANGE, OPCODE_XOR};
     use crate::chip::uint::bytes::register::ByteRegister;
 
+    #[derive(Debug, Clone, Serialize, Deserialize)]
+    struct SharedByteTableTest;
+
+    impl AirParameters for SharedByteTableTest {
+        type Field = GoldilocksField;
+        type CubicParams = GoldilocksCubicParameters;
+
+        type Instruction = ByteInstructionSet;
+
+        const NUM_FREE_COLUMNS: usize = 31;
+        const EXTENDED_COLUMNS: usize = 87;
+        const NUM_ARITHMETIC_COLUMNS: usize = 0;
+    }
+
+    #[test]
+    fn test_shared_byte_lookup_table() {
+        type F = GoldilocksField;
+        type L = SharedByteTableTest;
+        type SC = PoseidonGoldilocksStarkConfig;
+        const NUM_VALS: usize = 4;
+
+        let mut builder = AirBuilder::<L>::new();
+        let mut byte_table = builder.new_byte_lookup_table();
+
+        // Two gadgets with their own operations, one with xor and one with range checks.
+        let mut xor_operations = builder.byte_operations();
+        let xor_registers = (0..NUM_VALS)
+            .map(|_| {
+                let a = builder.alloc::<ByteRegister>();
+                let b = builder.alloc::<ByteRegister>();
+                let a_xor_b = builder.alloc::<ByteRegister>();
+                let xor = ByteOperation::Xor(a, b, a_xor_b);
+                builder.set_byte_operation(&xor, &mut xor_operations);
+                (a, b)
+            })
+            .collect::<Vec<_>>();
+
+        let mut range_operations = builder.byte_operations();
+        let range_registers = (0..NUM_VALS)
+            .map(|_| {
+                let a = builder.alloc::<ByteRegister>();
+                builder.set_byte_operation(&ByteOperation::Range(a), &mut range_operations);
+                a
+            })
+            .collect::<Vec<_>>();
+
+        let mut byte_mult_data = builder.register_byte_lookup(&mut byte_table, xor_operations);
+        let range_mult_data = builder.register_byte_lookup(&mut byte_table, range_operations);
+        byte_mult_data.merge(range_mult_data);
+        builder.constraint_byte_lookup_table(&byte_table);
+
+        let (air, trace_data) = builder.build();
+
+        let num_rows = 1 << 16;
+        let generator = ArithmeticGenerator::<L>::new(trace_data, num_rows);
+        let writer = generator.new_writer();
+
+        byte_table.write_table_entries(&writer);
+        let xor_value = |k: usize| (k as u8, 2 * k as u8 + 1);
+        let range_value = |k: usize| k as u8 + 10;
+        for i in 0..num_rows {
+            for k in 0..NUM_VALS {
+                let (a, b) = xor_registers[k];
+                let (a_v, b_v) = xor_value(k);
+                writer.write(&a, &F::from_canonical_u8(a_v), i);
+                writer.write(&b, &F::from_canonical_u8(b_v), i);
+                writer.write(
+                    &range_registers[k],
+                    &F::from_canonical_u8(range_value(k)),
+                    i,
+                );
+            }
+            writer.write_row_instructions(&generator.air_data, i);
+        }
+        let multiplicities = byte_mult_data.get_multiplicities(&writer);
+
+        // Each operation is counted once per row in the column of its opcode.
+        let column = |opcode| OPCODE_INDICES.iter().position(|&op| op == opcode).unwrap();
+        let (xor_column, range_column) = (column(OPCODE_XOR), column(OPCODE_RANGE));
+        let count = F::from_canonical_usize(num_rows);
+        for k in 0..NUM_VALS {
+            let (a_v, b_v) = xor_value(k);
+            let xor_row = ((a_v as usize) << 8) + b_v as usize;
+            assert_eq!(multiplicities.row(xor_row)[xor_column], count);
+            // The range entry of `a` is the last entry of the table with `a` as first byte.
+            let range_row = ((range_value(k) as usize) << 8) + 255;
+            assert_eq!(multiplicities.row(range_row)[range_column], count);
+        }
+        let total = multiplicities
+            .rows()
+            .flat_map(|row| row.iter())
+            .fold(F::ZERO, |acc, x| acc + *x);
+        assert_eq!(total, F::from_canonical_usize(2 * NUM_VALS * num_rows));
+
+        writer.write_lookup_multiplicities(byte_table.multiplicities(), &[multiplicities]);
+
+        let stark = Starky::new(air);
+        let config = SC::standard_fast_config(num_rows);
+
+        // Generate proof and verify as a stark
+        test_starky(&stark, &config, &generator, &[]);
+
+        // Test the recursive proof.
+        test_recursive_starky(stark, config, generator, &[]);
+    }
+
     #[derive(Debug, Clone, Serialize, Deserialize)]
     struct ByteOpTest<const N: usize>;
 
@@ -5,6 +5,7 @@ use serde::{Deserialize, Serialize};
 
 use crate::chip::register::array::ArrayRegister;
 use crate::chip::register::element::ElementRegister;
+use crate::chip::register::RegisterSerializable;
 use crate::chip::trace::writer::TraceWriter;
 use crate::chip::uint::bytes::operations::value::ByteOperation;
 use crate::chip::uint::bytes::operations::{
@@ -82,6 +83,18 @@ impl ByteMultiplicityData {
         }
     }
 
+    /// Merges the operations of `other`, registered to the same table, so that the multiplicities
+    /// of both sets of operations are counted together.
+    pub fn merge(&mut self, other: ByteMultiplicityData) {
+        assert_eq!(
+            self.data.multiplicities.register(),
+            other.data.multiplicities.register(),
+            "cannot merge the multiplicities of different byte tables"
+        );
+        self.trace_operations.extend(other.trace_operations);
+        self.public_operations.extend(other.public_operations);
+    }
+
     pub fn get_multiplicities<F: PrimeField64>(&self, writer: &TraceWriter<F>) -> AirTrace<F> {
         let mut multiplicities_trace = AirTrace::new_with_value(NUM_BIT_OPPS + 1, 1 << 16, 0u32);
 
@@ -4,6 +4,7 @@ use super::value::ByteOperation;
 use crate::air::parser::AirParser;
 use crate::air::AirConstraint;
 use crate::chip::instruction::Instruction;
+use crate::chip::register::memory::MemorySlice;
 use crate::chip::trace::writer::{AirWriter, TraceWriter};
 use crate::chip::uint::bytes::register::ByteRegister;
 use crate::math::prelude::*;
@@ -40,4 +41,8 @@ impl<F: PrimeField64> Instruction<F> for ByteOperationInstruction {
         }
         self.inner.write_to_air(writer);
     }
+
+    fn trace_layout(&self) -> Vec<MemorySlice> {
+        self.inner.result_registers()
+    }
 }
@@ -9,7 +9,8 @@ use crate::chip::arithmetic::expression::ArithmeticExpression;
 use crate::chip::builder::AirBuilder;
 use crate::chip::instruction::ConstraintInstruction;
 use crate::chip::register::element::ElementRegister;
-use crate::chip::register::Register;
+use crate::chip::register::memory::MemorySlice;
+use crate::chip::register::{Register, RegisterSerializable};
 use crate::chip::trace::writer::{AirWriter, TraceWriter};
 use crate::chip::uint::bytes::bit_operations::util::u8_to_bits_le;
 use crate::chip::uint::bytes::register::ByteRegister;
@@ -25,6 +26,13 @@ pub enum ByteOperation<T> {
     ShrConst(T, u8, T),
     ShrCarry(T, u8, T, T),
     RotConst(T, u8, T),
+    /// A byte `a` of a 64-bit word rotated right by `n` bits, shifted right by `n % 8` bits into a
+    /// result and a carry as in `ShrCarry`.
+    ///
+    /// The operation is looked up in the shift-with-carry entries of the byte table with the
+    /// rotation `n < 64` itself as the shift, so that it also applies a lookup to rotations by a
+    /// multiple of eight bits.
+    Rotate64(T, u8, T, T),
     Rot(T, T, T),
     Not(T, T),
     Range(T),
@@ -50,6 +58,25 @@ impl<AP: AirParser> AirConstraint<AP> for ByteOperationDigestConstraint {
 impl ConstraintInstruction for ByteOperationDigestConstraint {}
 
 impl ByteOperation<ByteRegister> {
+    /// The registers written by the operation.
+    pub fn result_registers(&self) -> Vec<MemorySlice> {
+        match self {
+            ByteOperation::And(_, _, res)
+            | ByteOperation::Xor(_, _, res)
+            | ByteOperation::Shr(_, _, res)
+            | ByteOperation::ShrConst(_, _, res)
+            | ByteOperation::Rot(_, _, res)
+            | ByteOperation::RotConst(_, _, res)
+            | ByteOperation::Not(_, res) => vec![*res.register()],
+            ByteOperation::ShrFull(_, _, res, carry)
+            | ByteOperation::ShrCarry(_, _, res, carry)
+            | ByteOperation::Rotate64(_, _, res, carry) => {
+                vec![*res.register(), *carry.register()]
+            }
+            ByteOperation::Range(_) => Vec::new(),
+        }
+    }
+
     pub fn expressions<F: Field>(&self) -> [ArithmeticExpression<F>; 5] {
         let opcode: F = self.field_opcode();
 
@@ -92,6 +119,13 @@ impl ByteOperation<ByteRegister> {
             ByteOperation::ShrFull(a, b, res, carry) => {
                 [opcode.into(), a.expr(), b.expr(), res.expr(), carry.expr()]
             }
+            ByteOperation::Rotate64(a, rotation, res, carry) => [
+                opcode.into(),
+                a.expr(),
+                F::from_canonical_u8(*rotation).into(),
+                res.expr(),
+                carry.expr(),
+            ],
             ByteOperation::Rot(a, b, res) => [
                 opcode.into(),
                 a.expr(),
@@ -275,6 +309,15 @@ impl ByteOperation<ByteRegister> {
 
                 ByteOperation::ShrFull(a_val, *b, res_val, carry_val)
             }
+            ByteOperation::Rotate64(a, rotation, result, carry) => {
+                let a_val = from_field(writer.read(a, row_index));
+                let value = ByteOperation::shr_full(a_val, *rotation);
+                if let ByteOperation::ShrFull(_, _, res_val, carry_val) = value {
+                    writer.write(result, &as_field(res_val), row_index);
+                    writer.write(carry, &as_field(carry_val), row_index);
+                }
+                value
+            }
             ByteOperation::Rot(a, b, c) => {
                 let a_val = from_field(writer.read(a, row_index));
                 let b_val = from_field(writer.read(b, row_index));
@@ -375,6 +418,15 @@ impl ByteOperation<ByteRegister> {
 
                 ByteOperation::ShrFull(a_val, *b, res_val, carry_val)
             }
+            ByteOperation::Rotate64(a, rotation, result, carry) => {
+                let a_val = from_field(writer.read(a));
+                let value = ByteOperation::shr_full(a_val, *rotation);
+                if let ByteOperation::ShrFull(_, _, res_val, carry_val) = value {
+                    writer.write(result, &as_field(res_val));
+                    writer.write(carry, &as_field(carry_val));
+                }
+                value
+            }
             ByteOperation::Rot(a, b, c) => {
                 let a_val = from_field(writer.read(a));
                 let b_val = from_field(writer.read(b));
@@ -438,7 +490,8 @@ impl ByteOperation<ByteRegister> {
                 let carry_val = from_field(writer.read(carry, row_index));
                 ByteOperation::ShrFull(a_val, b_val, res_val, carry_val)
             }
-            ByteOperation::ShrCarry(a, b, result, carry) => {
+            ByteOperation::ShrCarry(a, b, result, carry)
+            | ByteOperation::Rotate64(a, b, result, carry) => {
                 let a_val = from_field(writer.read(a, row_index));
                 let res_val = from_field(writer.read(result, row_index));
                 let carry_val = from_field(writer.read(carry, row_index));
@@ -500,7 +553,8 @@ impl ByteOperation<ByteRegister> {
                 let carry_val = from_field(carry.read_from_slice(slice));
                 ByteOperation::ShrFull(a_val, b_val, res_val, carry_val)
             }
-            ByteOperation::ShrCarry(a, b, result, carry) => {
+            ByteOperation::ShrCarry(a, b, result, carry)
+            | ByteOperation::Rotate64(a, b, result, carry) => {
                 let a_val = from_field(a.read_from_slice(slice));
                 let res_val = from_field(result.read_from_slice(slice));
                 let carry_val = from_field(carry.read_from_slice(slice));
@@ -539,6 +593,7 @@ impl<T> ByteOperation<T> {
             ByteOperation::ShrConst(_, _, _) => OPCODE_SHR,
             ByteOperation::ShrCarry(_, _, _, _) => OPCODE_SHR_CARRY,
             ByteOperation::ShrFull(_, _, _, _) => OPCODE_SHR_CARRY,
+            ByteOperation::Rotate64(_, _, _, _) => OPCODE_SHR_CARRY,
             ByteOperation::Rot(_, _, _) => OPCODE_ROT,
             ByteOperation::RotConst(_, _, _) => OPCODE_ROT,
             ByteOperation::Not(_, _) => OPCODE_NOT,
@@ -627,6 +682,9 @@ impl ByteOperation<u8> {
             ByteOperation::ShrCarry(a, b, c, d) => {
                 ByteOperation::ShrCarry(as_field(a), *b, as_field(c), as_field(d))
             }
+            ByteOperation::Rotate64(a, b, c, d) => {
+                ByteOperation::Rotate64(as_field(a), *b, as_field(c), as_field(d))
+            }
             ByteOperation::ShrFull(a, b, c, d) => {
                 ByteOperation::ShrFull(as_field(a), as_field(b), as_field(c), as_field(d))
             }
@@ -697,6 +755,12 @@ impl<L: AirParameters> AirBuilder<L> {
                 let result = self.alloc_public::<ByteRegister>();
                 ByteOperation::ShrCarry(a, *b, result, self.alloc_public::<ByteRegister>())
             }
+            ByteOperation::Rotate64(_, rotation, _, _) => {
+                let a = self.alloc_public::<ByteRegister>();
+                let result = self.alloc_public::<ByteRegister>();
+                let carry = self.alloc_public::<ByteRegister>();
+                ByteOperation::Rotate64(a, *rotation, result, carry)
+            }
             ByteOperation::ShrFull(_, _, _, _) => {
                 let a = self.alloc_public::<ByteRegister>();
                 let b = self.alloc_public::<ByteRegister>();
@@ -0,0 +1,144 @@
+use serde::{Deserialize, Serialize};
+
+use super::bytes::register::ByteRegister;
+use super::register::U32Register;
+use super::util::{u32_from_le_field_bytes, u32_to_le_field_bytes};
+use crate::chip::builder::AirBuilder;
+use crate::chip::register::array::ArrayRegister;
+use crate::chip::trace::writer::AirWriter;
+use crate::chip::AirParameters;
+use crate::math::prelude::*;
+
+/// A digest made of 32-bit words whose byte encoding is big-endian, as in SHA-256.
+///
+/// Each word is stored as a `U32Register`, i.e. as little-endian bytes. The register keeps track
+/// of the word boundaries so that the digest bytes can be read and written in the big-endian
+/// order of the hash specification.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub struct DigestRegister {
+    words: ArrayRegister<U32Register>,
+}
+
+impl DigestRegister {
+    pub fn words(&self) -> ArrayRegister<U32Register> {
+        self.words
+    }
+
+    pub fn num_words(&self) -> usize {
+        self.words.len()
+    }
+
+    /// Returns the byte registers of the digest in big-endian order.
+    pub fn to_be_bytes(&self) -> Vec<ByteRegister> {
+        self.words
+            .iter()
+            .flat_map(|word| {
+                let bytes = word.to_le_bytes();
+                (0..4).rev().map(move |i| bytes.get(i))
+            })
+            .collect()
+    }
+}
+
+impl<L: AirParameters> AirBuilder<L> {
+    /// Allocates a public digest of `num_words` 32-bit words encoded as big-endian bytes.
+    pub fn alloc_public_digest_be(&mut self, num_words: usize) -> DigestRegister {
+        DigestRegister {
+            words: self.alloc_array_public::<U32Register>(num_words),
+        }
+    }
+}
+
+pub trait DigestAirWriter: AirWriter {
+    /// Writes the big-endian encoded `bytes` to the digest register.
+    fn write_digest_be(&mut self, digest: &DigestRegister, bytes: &[u8]) {
+        assert_eq!(
+            bytes.len(),
+            4 * digest.num_words(),
+            "digest length does not match the number of words"
+        );
+        for (word, chunk) in digest.words.iter().zip(bytes.chunks_exact(4)) {
+            let value = u32::from_be_bytes(chunk.try_into().unwrap());
+            self.write(&word, &u32_to_le_field_bytes(value));
+        }
+    }
+
+    /// Reads the digest register as big-endian encoded bytes.
+    fn read_digest_be(&self, digest: &DigestRegister) -> Vec<u8>
+    where
+        Self::Field: PrimeField64,
+    {
+        digest
+            .words
+            .iter()
+            .flat_map(|word| u32_from_le_field_bytes(&self.read(&word)).to_be_bytes())
+            .collect()
+    }
+}
+
+impl<W: AirWriter> DigestAirWriter for W {}
+
+#[cfg(test)]
+mod tests {
+    use plonky2::field::goldilocks_field::GoldilocksField;
+
+    use super::*;
+    use crate::chip::trace::writer::data::AirWriterData;
+    use crate::chip::uint::operations::instruction::UintInstruction;
+    use crate::math::goldilocks::cubic::GoldilocksCubicParameters;
+
+    #[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+    pub struct DigestTest;
+
+    impl AirParameters for DigestTest {
+        type Field = GoldilocksField;
+        type CubicParams = GoldilocksCubicParameters;
+
+        type Instruction = UintInstruction;
+
+        const NUM_FREE_COLUMNS: usize = 0;
+        const EXTENDED_COLUMNS: usize = 0;
+        const NUM_ARITHMETIC_COLUMNS: usize = 0;
+    }
+
+    #[test]
+    fn test_digest_be_bytes() {
+        type F = GoldilocksField;
+        type L = DigestTest;
+
+        let mut builder = AirBuilder::<L>::new();
+        let digest = builder.alloc_public_digest_be(8);
+        let (_, trace_data) = builder.build();
+
+        // The SHA-256 digest of "abc", as state words and as big-endian bytes.
+        let words: [u32; 8] = [
+            0xba7816bf, 0x8f01cfea, 0x414140de, 0x5dae2223, 0xb00361a3, 0x96177a9c, 0xb410ff61,
+            0xf20015ad,
+        ];
+        let bytes = hex::decode("ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad")
+            .unwrap();
+
+        let mut writer_data = AirWriterData::new(&trace_data, 1);
+        let mut writer = writer_data.public_writer();
+
+        // Words written as little-endian `U32Register` values read back as big-endian bytes.
+        for (word, value) in digest.words().iter().zip(words) {
+            writer.write(&word, &u32_to_le_field_bytes::<F>(value));
+        }
+        assert_eq!(writer.read_digest_be(&digest), bytes);
+
+        let be_bytes = digest
+            .to_be_bytes()
+            .iter()
+            .map(|byte| writer.read(byte).as_canonical_u64() as u8)
+            .collect::<Vec<_>>();
+        assert_eq!(be_bytes, bytes);
+
+        // Bytes written in big-endian order are stored as the expected words.
+        writer.write_digest_be(&digest, &[0u8; 32]);
+        writer.write_digest_be(&digest, &bytes);
+        for (word, value) in digest.words().iter().zip(words) {
+            assert_eq!(u32_from_le_field_bytes(&writer.read(&word)), value);
+        }
+    }
+}
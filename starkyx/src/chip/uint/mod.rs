@@ -1,4 +1,6 @@
 pub mod bytes;
+pub mod digest;
 pub mod operations;
 pub mod register;
 pub mod util;
+pub mod width;
@@ -5,11 +5,11 @@ use crate::air::AirConstraint;
 use crate::chip::builder::AirBuilder;
 use crate::chip::instruction::Instruction;
 use crate::chip::register::bit::BitRegister;
-use crate::chip::register::Register;
+use crate::chip::register::memory::MemorySlice;
+use crate::chip::register::{Register, RegisterSerializable};
 use crate::chip::trace::writer::{AirWriter, TraceWriter};
 use crate::chip::uint::bytes::lookup_table::builder_operations::ByteLookupOperations;
 use crate::chip::uint::bytes::operations::instruction::ByteOperationInstruction;
-use crate::chip::uint::bytes::operations::value::ByteOperation;
 use crate::chip::uint::register::{ByteArrayRegister, U32Register, U64Register};
 use crate::chip::AirParameters;
 use crate::math::prelude::*;
@@ -75,6 +75,25 @@ impl<L: AirParameters> AirBuilder<L> {
         result
     }
 
+    /// Computes `a + b mod 2^32`.
+    ///
+    /// The result is constrained by the single instruction `a + b = result + carry * 2^32` with a
+    /// boolean carry, and the result bytes are range checked using the byte lookup `operations`.
+    pub fn add_mod32(
+        &mut self,
+        a: &U32Register,
+        b: &U32Register,
+        operations: &mut ByteLookupOperations,
+    ) -> U32Register
+    where
+        L::Instruction: From<ByteArrayAdd<4>> + From<ByteOperationInstruction>,
+    {
+        let result = self.alloc::<U32Register>();
+        let carry = self.alloc::<BitRegister>();
+        self.set_add_u32(a, b, &None, &result, &carry, operations);
+        result
+    }
+
     pub fn set_add_u32(
         &mut self,
         a: &U32Register,
@@ -88,11 +107,7 @@ impl<L: AirParameters> AirBuilder<L> {
     {
         let add = ByteArrayAdd::<4>::new(*a, *b, *in_carry, *result, *out_carry);
         self.register_instruction(add);
-
-        for byte in result.to_le_bytes() {
-            let result_range = ByteOperation::Range(byte);
-            self.set_byte_operation(&result_range, operations);
-        }
+        self.set_byte_array_range_check(result, operations);
     }
 
     pub fn set_add_u64(
@@ -243,4 +258,96 @@ impl<F: PrimeField64> Instruction<F> for ByteArrayAdd<4> {
             &F::from_canonical_u8(result_carry as u8),
         );
     }
+
+    fn trace_layout(&self) -> Vec<MemorySlice> {
+        vec![*self.result.register(), *self.result_carry.register()]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::{thread_rng, Rng};
+    use serde::{Deserialize, Serialize};
+
+    use super::*;
+    use crate::chip::builder::tests::*;
+    use crate::chip::uint::operations::instruction::UintInstruction;
+    use crate::chip::uint::util::u32_to_le_field_bytes;
+
+    #[derive(Debug, Clone, Serialize, Deserialize)]
+    struct AddMod32Test;
+
+    impl AirParameters for AddMod32Test {
+        type Field = GoldilocksField;
+        type CubicParams = GoldilocksCubicParameters;
+
+        type Instruction = UintInstruction;
+
+        const NUM_FREE_COLUMNS: usize = 40;
+        const EXTENDED_COLUMNS: usize = 60;
+        const NUM_ARITHMETIC_COLUMNS: usize = 0;
+    }
+
+    #[test]
+    fn test_add_mod32() {
+        type F = GoldilocksField;
+        type L = AddMod32Test;
+        type SC = PoseidonGoldilocksStarkConfig;
+
+        let mut builder = AirBuilder::<L>::new();
+        let mut operations = builder.byte_operations();
+
+        let a = builder.alloc::<U32Register>();
+        let b = builder.alloc::<U32Register>();
+        let result = builder.add_mod32(&a, &b, &mut operations);
+        let expected = builder.alloc::<U32Register>();
+        builder.assert_equal(&result, &expected);
+
+        let mut byte_table = builder.new_byte_lookup_table();
+        let byte_data = builder.register_byte_lookup(&mut byte_table, operations);
+        builder.constraint_byte_lookup_table(&byte_table);
+
+        let (air, trace_data) = builder.build();
+
+        let num_rows = 1 << 16;
+        let generator = ArithmeticGenerator::<L>::new(trace_data, num_rows);
+        let writer = generator.new_writer();
+
+        byte_table.write_table_entries(&writer);
+
+        // Make sure the wraparound cases are covered.
+        let edge_cases = [
+            (0, 0),
+            (u32::MAX, 1),
+            (u32::MAX, u32::MAX),
+            (1 << 31, 1 << 31),
+            (u32::MAX - 5, 7),
+        ];
+        let mut rng = thread_rng();
+        for i in 0..num_rows {
+            let (a_val, b_val) = edge_cases
+                .get(i)
+                .copied()
+                .unwrap_or_else(|| (rng.gen::<u32>(), rng.gen::<u32>()));
+            writer.write(&a, &u32_to_le_field_bytes::<F>(a_val), i);
+            writer.write(&b, &u32_to_le_field_bytes::<F>(b_val), i);
+            writer.write(
+                &expected,
+                &u32_to_le_field_bytes::<F>(a_val.wrapping_add(b_val)),
+                i,
+            );
+            writer.write_row_instructions(&generator.air_data, i);
+        }
+        let multiplicities = byte_data.get_multiplicities(&writer);
+        writer.write_lookup_multiplicities(byte_table.multiplicities(), &[multiplicities]);
+
+        let stark = Starky::new(air);
+        let config = SC::standard_fast_config(num_rows);
+
+        // Generate proof and verify as a stark
+        test_starky(&stark, &config, &generator, &[]);
+
+        // Test the recursive proof.
+        test_recursive_starky(stark, config, generator, &[]);
+    }
 }
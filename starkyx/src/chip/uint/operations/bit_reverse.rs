@@ -0,0 +1,105 @@
+use crate::chip::builder::AirBuilder;
+use crate::chip::register::array::ArrayRegister;
+use crate::chip::register::bit::BitRegister;
+use crate::chip::register::{Register, RegisterSerializable};
+use crate::chip::AirParameters;
+
+impl<L: AirParameters> AirBuilder<L> {
+    /// Returns the bits of `a` in reverse order.
+    ///
+    /// An array register is a contiguous slice of memory, so the reversed bits are copies of the
+    /// bits of `a`, each constrained by `result[i] = a[n-1-i]`.
+    pub fn bit_reverse(&mut self, a: &ArrayRegister<BitRegister>) -> ArrayRegister<BitRegister> {
+        let result = if a.is_trace() {
+            self.alloc_array::<BitRegister>(a.len())
+        } else {
+            self.alloc_array_public::<BitRegister>(a.len())
+        };
+        self.set_bit_reverse(a, &result);
+        result
+    }
+
+    pub fn set_bit_reverse(
+        &mut self,
+        a: &ArrayRegister<BitRegister>,
+        result: &ArrayRegister<BitRegister>,
+    ) {
+        assert_eq!(
+            a.len(),
+            result.len(),
+            "bit arrays must have the same length"
+        );
+        for (bit, result_bit) in a.iter().rev().zip(result.iter()) {
+            if result_bit.is_trace() {
+                self.set_to_expression(&result_bit, bit.expr());
+            } else {
+                self.set_to_expression_public(&result_bit, bit.expr());
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::{thread_rng, Rng};
+    use serde::{Deserialize, Serialize};
+
+    use super::*;
+    use crate::chip::builder::tests::*;
+    use crate::chip::uint::bytes::bit_operations::util::u8_to_bits_le;
+    use crate::math::prelude::*;
+
+    #[derive(Debug, Clone, Serialize, Deserialize)]
+    pub struct BitReverseTest;
+
+    impl AirParameters for BitReverseTest {
+        type Field = GoldilocksField;
+        type CubicParams = GoldilocksCubicParameters;
+
+        type Instruction = EmptyInstruction<GoldilocksField>;
+
+        const NUM_FREE_COLUMNS: usize = 16;
+    }
+
+    #[test]
+    fn test_bit_reverse() {
+        type F = GoldilocksField;
+        type L = BitReverseTest;
+        type SC = PoseidonGoldilocksStarkConfig;
+
+        let mut builder = AirBuilder::<L>::new();
+
+        let a = builder.alloc_array::<BitRegister>(8);
+        let result = builder.bit_reverse(&a);
+
+        let (air, trace_data) = builder.build();
+
+        let num_rows = 1 << 9;
+        let generator = ArithmeticGenerator::<L>::new(trace_data, num_rows);
+        let writer = generator.new_writer();
+
+        let mut rng = thread_rng();
+        for i in 0..num_rows {
+            let byte: u8 = rng.gen();
+            let bits = u8_to_bits_le(byte).map(F::from_canonical_u8);
+
+            writer.write_array(&a, bits, i);
+            writer.write_row_instructions(&generator.air_data, i);
+
+            let mut reversed_bits = u8_to_bits_le(byte);
+            reversed_bits.reverse();
+            assert_eq!(reversed_bits, u8_to_bits_le(byte.reverse_bits()));
+            let expected = reversed_bits.map(F::from_canonical_u8);
+            assert_eq!(writer.read_vec(&result, i), expected);
+        }
+
+        let stark = Starky::new(air);
+        let config = SC::standard_fast_config(num_rows);
+
+        // Generate proof and verify as a stark
+        test_starky(&stark, &config, &generator, &[]);
+
+        // Test the recursive proof.
+        test_recursive_starky(stark, config, generator, &[]);
+    }
+}
@@ -4,6 +4,7 @@ use super::add::ByteArrayAdd;
 use crate::air::parser::AirParser;
 use crate::air::AirConstraint;
 use crate::chip::instruction::Instruction;
+use crate::chip::register::memory::MemorySlice;
 use crate::chip::trace::writer::{AirWriter, TraceWriter};
 use crate::chip::uint::bytes::decode::ByteDecodeInstruction;
 use crate::chip::uint::bytes::lookup_table::{ByteInstructionSet, ByteInstructions};
@@ -49,6 +50,20 @@ impl<F: PrimeField64> Instruction<F> for UintInstruction {
             Self::Add(op) => Instruction::<F>::write_to_air(op, writer),
         }
     }
+
+    fn trace_layout(&self) -> Vec<MemorySlice> {
+        match self {
+            Self::Bit(op) => Instruction::<F>::trace_layout(op),
+            Self::Add(op) => Instruction::<F>::trace_layout(op),
+        }
+    }
+
+    fn name(&self) -> &'static str {
+        match self {
+            Self::Bit(op) => Instruction::<F>::name(op),
+            Self::Add(op) => Instruction::<F>::name(op),
+        }
+    }
 }
 
 impl From<ByteInstructionSet> for UintInstruction {
@@ -1,7 +1,10 @@
 pub mod add;
 pub mod and;
+pub mod bit_reverse;
 pub mod instruction;
 pub mod not;
+pub mod popcount;
 pub mod rotate;
+pub mod select;
 pub mod shr;
 pub mod xor;
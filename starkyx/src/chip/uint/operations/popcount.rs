@@ -0,0 +1,96 @@
+use crate::chip::arithmetic::expression::ArithmeticExpression;
+use crate::chip::builder::AirBuilder;
+use crate::chip::register::array::ArrayRegister;
+use crate::chip::register::bit::BitRegister;
+use crate::chip::register::element::ElementRegister;
+use crate::chip::register::{Register, RegisterSerializable};
+use crate::chip::AirParameters;
+
+impl<L: AirParameters> AirBuilder<L> {
+    /// Computes the number of set bits in `a`.
+    ///
+    /// The bits of `a` are already constrained to be boolean, so the result is constrained by the
+    /// single linear constraint `result = a[0] + ... + a[n-1]`.
+    pub fn popcount(&mut self, a: &ArrayRegister<BitRegister>) -> ElementRegister {
+        let result = if a.is_trace() {
+            self.alloc::<ElementRegister>()
+        } else {
+            self.alloc_public::<ElementRegister>()
+        };
+        self.set_popcount(a, &result);
+        result
+    }
+
+    pub fn set_popcount(&mut self, a: &ArrayRegister<BitRegister>, result: &ElementRegister) {
+        let sum = a
+            .iter()
+            .fold(ArithmeticExpression::zero(), |acc, bit| acc + bit.expr());
+        if result.is_trace() {
+            self.set_to_expression(result, sum);
+        } else {
+            self.set_to_expression_public(result, sum);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::{thread_rng, Rng};
+    use serde::{Deserialize, Serialize};
+
+    use super::*;
+    use crate::chip::builder::tests::*;
+    use crate::chip::uint::bytes::bit_operations::util::u8_to_bits_le;
+    use crate::math::prelude::*;
+
+    #[derive(Debug, Clone, Serialize, Deserialize)]
+    pub struct PopcountTest;
+
+    impl AirParameters for PopcountTest {
+        type Field = GoldilocksField;
+        type CubicParams = GoldilocksCubicParameters;
+
+        type Instruction = EmptyInstruction<GoldilocksField>;
+
+        const NUM_FREE_COLUMNS: usize = 10;
+    }
+
+    #[test]
+    fn test_popcount() {
+        type F = GoldilocksField;
+        type L = PopcountTest;
+        type SC = PoseidonGoldilocksStarkConfig;
+
+        let mut builder = AirBuilder::<L>::new();
+
+        let a = builder.alloc_array::<BitRegister>(8);
+        let result = builder.popcount(&a);
+        let expected = builder.alloc::<ElementRegister>();
+        builder.assert_equal(&result, &expected);
+
+        let (air, trace_data) = builder.build();
+
+        let num_rows = 1 << 9;
+        let generator = ArithmeticGenerator::<L>::new(trace_data, num_rows);
+        let writer = generator.new_writer();
+
+        let mut rng = thread_rng();
+        for i in 0..num_rows {
+            let byte: u8 = rng.gen();
+            let bits = u8_to_bits_le(byte).map(F::from_canonical_u8);
+
+            writer.write_array(&a, bits, i);
+            writer.write(&expected, &F::from_canonical_u32(byte.count_ones()), i);
+            writer.write_row_instructions(&generator.air_data, i);
+        }
+
+        let stark = Starky::new(air);
+        let config = SC::standard_fast_config(num_rows);
+
+        // Generate proof and verify as a stark
+        test_starky(&stark, &config, &generator, &[]);
+
+        // Test the recursive proof.
+        test_recursive_starky(stark, config, generator, &[]);
+    }
+}
@@ -6,7 +6,7 @@ use crate::chip::uint::bytes::lookup_table::builder_operations::ByteLookupOperat
 use crate::chip::uint::bytes::operations::instruction::ByteOperationInstruction;
 use crate::chip::uint::bytes::operations::value::ByteOperation;
 use crate::chip::uint::bytes::register::ByteRegister;
-use crate::chip::uint::register::ByteArrayRegister;
+use crate::chip::uint::register::{ByteArrayRegister, U64Register};
 use crate::chip::AirParameters;
 use crate::math::prelude::*;
 
@@ -19,6 +19,62 @@ impl<L: AirParameters> AirBuilder<L> {
         operations: &mut ByteLookupOperations,
     ) where
         L::Instruction: From<ByteOperationInstruction>,
+    {
+        let bit_rotation = (rotation % (N * 8) % 8) as u8;
+        self.set_rotate_right_with(a, rotation, result, operations, |byte, res, carry| {
+            ByteOperation::ShrCarry(byte, bit_rotation, res, carry)
+        })
+    }
+
+    /// Sets `result` to the right rotation of the 64-bit word `a` by `rotation` bits, where each
+    /// byte is shifted with a `Rotate64` byte operation.
+    ///
+    /// All the rotations, including those by a multiple of eight bits like the rotations by 32, 24
+    /// and 16 bits of the BLAKE2b G-function, use one lookup per byte.
+    pub fn set_rotate64(
+        &mut self,
+        a: &U64Register,
+        rotation: usize,
+        result: &U64Register,
+        operations: &mut ByteLookupOperations,
+    ) where
+        L::Instruction: From<ByteOperationInstruction>,
+    {
+        let rotation = (rotation % 64) as u8;
+        self.set_rotate_right_with(
+            a,
+            rotation as usize,
+            result,
+            operations,
+            |byte, res, carry| ByteOperation::Rotate64(byte, rotation, res, carry),
+        )
+    }
+
+    pub fn rotate64(
+        &mut self,
+        a: &U64Register,
+        rotation: usize,
+        operations: &mut ByteLookupOperations,
+    ) -> U64Register
+    where
+        L::Instruction: From<ByteOperationInstruction>,
+    {
+        let result = self.alloc::<U64Register>();
+        self.set_rotate64(a, rotation, &result, operations);
+        result
+    }
+
+    /// Sets `result` to the right rotation of `a` by `rotation` bits, where `shift_carry` gives
+    /// the byte operation shifting a byte right by `rotation % 8` bits into a result and a carry.
+    fn set_rotate_right_with<const N: usize>(
+        &mut self,
+        a: &ByteArrayRegister<N>,
+        rotation: usize,
+        result: &ByteArrayRegister<N>,
+        operations: &mut ByteLookupOperations,
+        shift_carry: impl Fn(ByteRegister, ByteRegister, ByteRegister) -> ByteOperation<ByteRegister>,
+    ) where
+        L::Instruction: From<ByteOperationInstruction>,
     {
         let result_bytes = result.to_le_bytes();
 
@@ -32,24 +88,14 @@ impl<L: AirParameters> AirBuilder<L> {
         let a_bytes_rotated: [_; N] = from_fn(|i| a_bytes.get((i + byte_rotation) % N));
 
         let (last_rot, last_carry) = (self.alloc::<ByteRegister>(), self.alloc::<ByteRegister>());
-        let shr_carry = ByteOperation::ShrCarry(
-            a_bytes_rotated[N - 1],
-            bit_rotation as u8,
-            last_rot,
-            last_carry,
-        );
+        let shr_carry = shift_carry(a_bytes_rotated[N - 1], last_rot, last_carry);
         self.set_byte_operation(&shr_carry, operations);
 
         let mut carry = last_carry.expr();
         for i in (0..N - 1).rev() {
             let (shift_res, next_carry) =
                 (self.alloc::<ByteRegister>(), self.alloc::<ByteRegister>());
-            let shr_carry = ByteOperation::ShrCarry(
-                a_bytes_rotated[i],
-                bit_rotation as u8,
-                shift_res,
-                next_carry,
-            );
+            let shr_carry = shift_carry(a_bytes_rotated[i], shift_res, next_carry);
             self.set_byte_operation(&shr_carry, operations);
             let expected_res = shift_res.expr() + carry.clone() * mult;
             self.set_to_expression(&result_bytes.get(i), expected_res);
@@ -74,4 +120,112 @@ impl<L: AirParameters> AirBuilder<L> {
         self.set_bit_rotate_right(a, rotation, &result, operations);
         result
     }
+
+    /// Sets `result` to the left rotation of `a` by `rotation` bits, as the right rotation by
+    /// `N * 8 - rotation` bits.
+    pub fn set_bit_rotate_left<const N: usize>(
+        &mut self,
+        a: &ByteArrayRegister<N>,
+        rotation: usize,
+        result: &ByteArrayRegister<N>,
+        operations: &mut ByteLookupOperations,
+    ) where
+        L::Instruction: From<ByteOperationInstruction>,
+    {
+        let num_bits = N * 8;
+        self.set_bit_rotate_right(a, num_bits - rotation % num_bits, result, operations)
+    }
+
+    pub fn bit_rotate_left<const N: usize>(
+        &mut self,
+        a: &ByteArrayRegister<N>,
+        rotation: usize,
+        operations: &mut ByteLookupOperations,
+    ) -> ByteArrayRegister<N>
+    where
+        L::Instruction: From<ByteOperationInstruction>,
+    {
+        let result = self.alloc::<ByteArrayRegister<N>>();
+        self.set_bit_rotate_left(a, rotation, &result, operations);
+        result
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::{thread_rng, Rng};
+    use serde::{Deserialize, Serialize};
+
+    use super::*;
+    use crate::chip::builder::tests::*;
+    use crate::chip::uint::operations::instruction::UintInstruction;
+
+    #[derive(Debug, Clone, Serialize, Deserialize)]
+    struct RotateTest;
+
+    impl AirParameters for RotateTest {
+        type Field = GoldilocksField;
+        type CubicParams = GoldilocksCubicParameters;
+
+        type Instruction = UintInstruction;
+
+        const NUM_FREE_COLUMNS: usize = 200;
+        const EXTENDED_COLUMNS: usize = 400;
+        const NUM_ARITHMETIC_COLUMNS: usize = 0;
+    }
+
+    #[test]
+    fn test_rotate64() {
+        type F = GoldilocksField;
+        type L = RotateTest;
+        type SC = PoseidonGoldilocksStarkConfig;
+
+        // The rotations of the G function of BLAKE2b.
+        let rotations = [32, 24, 16, 63];
+
+        let mut builder = AirBuilder::<L>::new();
+        let mut operations = builder.byte_operations();
+
+        let a = builder.alloc::<U64Register>();
+        let expected = rotations.map(|rotation| {
+            let a_rot = builder.rotate64(&a, rotation, &mut operations);
+            let expected = builder.alloc::<U64Register>();
+            builder.assert_equal(&a_rot, &expected);
+            expected
+        });
+
+        // Every rotation uses one lookup per byte.
+        assert_eq!(operations.trace_operations.len(), 8 * rotations.len());
+
+        let mut byte_table = builder.new_byte_lookup_table();
+        let byte_data = builder.register_byte_lookup(&mut byte_table, operations);
+        builder.constraint_byte_lookup_table(&byte_table);
+
+        let (air, trace_data) = builder.build();
+
+        let num_rows = 1 << 16;
+        let generator = ArithmeticGenerator::<L>::new(trace_data, num_rows);
+        let writer = generator.new_writer();
+
+        let to_field = |a: u64| a.to_le_bytes().map(F::from_canonical_u8);
+
+        byte_table.write_table_entries(&writer);
+        let mut rng = thread_rng();
+        for i in 0..num_rows {
+            let a_val = rng.gen::<u64>();
+            writer.write(&a, &to_field(a_val), i);
+            for (expected, rotation) in expected.iter().zip(rotations) {
+                writer.write(expected, &to_field(a_val.rotate_right(rotation as u32)), i);
+            }
+            writer.write_row_instructions(&generator.air_data, i);
+        }
+        let multiplicities = byte_data.get_multiplicities(&writer);
+        writer.write_lookup_multiplicities(byte_table.multiplicities(), &[multiplicities]);
+
+        let stark = Starky::new(air);
+        let config = SC::standard_fast_config(num_rows);
+
+        // Generate proof and verify as a stark
+        test_starky(&stark, &config, &generator, &[]);
+    }
 }
@@ -1,5 +1,8 @@
 use serde::{Deserialize, Serialize};
 
+use super::bytes::lookup_table::builder_operations::ByteLookupOperations;
+use super::bytes::operations::instruction::ByteOperationInstruction;
+use super::bytes::operations::value::ByteOperation;
 use super::bytes::register::ByteRegister;
 use crate::chip::arithmetic::expression::ArithmeticExpression;
 use crate::chip::builder::AirBuilder;
@@ -11,6 +14,7 @@ use crate::chip::register::cell::CellType;
 use crate::chip::register::cubic::CubicRegister;
 use crate::chip::register::memory::MemorySlice;
 use crate::chip::register::{Register, RegisterSerializable, RegisterSized};
+use crate::chip::AirParameters;
 use crate::math::prelude::cubic::element::CubicElement;
 use crate::math::prelude::*;
 
@@ -67,12 +71,38 @@ impl<const N: usize> Register for ByteArrayRegister<N> {
     }
 }
 
+impl<L: AirParameters> AirBuilder<L> {
+    /// Range checks every byte of `register` using the byte lookup `operations`.
+    pub fn set_byte_array_range_check<const N: usize>(
+        &mut self,
+        register: &ByteArrayRegister<N>,
+        operations: &mut ByteLookupOperations,
+    ) where
+        L::Instruction: From<ByteOperationInstruction>,
+    {
+        for byte in register.to_le_bytes() {
+            self.set_byte_operation(&ByteOperation::Range(byte), operations);
+        }
+    }
+
+    /// Allocates a `U64Register` whose eight bytes are range checked using the byte lookup
+    /// `operations`.
+    pub fn alloc_checked_u64(&mut self, operations: &mut ByteLookupOperations) -> U64Register
+    where
+        L::Instruction: From<ByteOperationInstruction>,
+    {
+        let register = self.alloc::<U64Register>();
+        self.set_byte_array_range_check(&register, operations);
+        register
+    }
+}
+
 impl MemoryValue for U32Register {
     fn num_challenges() -> usize {
         0
     }
 
-    fn compress<L: crate::chip::AirParameters>(
+    fn compress<L: AirParameters>(
         &self,
         builder: &mut AirBuilder<L>,
         ptr: RawPointer,
@@ -99,7 +129,7 @@ impl MemoryValue for U64Register {
         0
     }
 
-    fn compress<L: crate::chip::AirParameters>(
+    fn compress<L: AirParameters>(
         &self,
         builder: &mut AirBuilder<L>,
         ptr: RawPointer,
@@ -126,10 +156,12 @@ impl MemoryValue for U64Register {
 #[cfg(test)]
 mod tests {
     use plonky2::field::goldilocks_field::GoldilocksField;
+    use rand::{thread_rng, Rng};
 
     use super::*;
+    use crate::chip::builder::tests::*;
     use crate::chip::uint::operations::instruction::UintInstruction;
-    use crate::chip::AirParameters;
+    use crate::chip::uint::util::{u64_from_le_field_bytes, u64_to_le_field_bytes};
     use crate::math::goldilocks::cubic::GoldilocksCubicParameters;
 
     #[derive(Debug, Clone, Copy, Serialize, Deserialize)]
@@ -163,4 +195,62 @@ mod tests {
 
         builder.assert_equal(&a, &b);
     }
+
+    #[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+    pub struct U64RegisterTest;
+
+    impl AirParameters for U64RegisterTest {
+        type Field = GoldilocksField;
+        type CubicParams = GoldilocksCubicParameters;
+
+        type Instruction = UintInstruction;
+
+        const NUM_FREE_COLUMNS: usize = 100;
+        const EXTENDED_COLUMNS: usize = 150;
+        const NUM_ARITHMETIC_COLUMNS: usize = 0;
+    }
+
+    #[test]
+    fn test_u64_register_round_trip() {
+        type F = GoldilocksField;
+        type L = U64RegisterTest;
+        type SC = PoseidonGoldilocksStarkConfig;
+        const NUM_VALS: usize = 4;
+
+        let mut builder = AirBuilder::<L>::new();
+        let mut operations = builder.byte_operations();
+        let registers = (0..NUM_VALS)
+            .map(|_| builder.alloc_checked_u64(&mut operations))
+            .collect::<Vec<_>>();
+        assert_eq!(operations.trace_operations.len(), 8 * NUM_VALS);
+
+        let mut byte_table = builder.new_byte_lookup_table();
+        let byte_data = builder.register_byte_lookup(&mut byte_table, operations);
+        builder.constraint_byte_lookup_table(&byte_table);
+
+        let (air, trace_data) = builder.build();
+
+        let num_rows = 1 << 16;
+        let generator = ArithmeticGenerator::<L>::new(trace_data, num_rows);
+        let writer = generator.new_writer();
+
+        byte_table.write_table_entries(&writer);
+        let mut rng = thread_rng();
+        for i in 0..num_rows {
+            for register in registers.iter() {
+                let value = rng.gen::<u64>();
+                writer.write(register, &u64_to_le_field_bytes::<F>(value), i);
+                assert_eq!(u64_from_le_field_bytes(&writer.read(register, i)), value);
+            }
+            writer.write_row_instructions(&generator.air_data, i);
+        }
+        let multiplicities = byte_data.get_multiplicities(&writer);
+        writer.write_lookup_multiplicities(byte_table.multiplicities(), &[multiplicities]);
+
+        let stark = Starky::new(air);
+        let config = SC::standard_fast_config(num_rows);
+
+        // Generate proof and verify as a stark
+        test_starky(&stark, &config, &generator, &[]);
+    }
 }
@@ -0,0 +1,213 @@
+use serde::{Deserialize, Serialize};
+
+use super::bytes::lookup_table::builder_operations::ByteLookupOperations;
+use super::bytes::operations::instruction::ByteOperationInstruction;
+use super::bytes::operations::value::ByteOperation;
+use super::bytes::register::ByteRegister;
+use crate::chip::arithmetic::expression::ArithmeticExpression;
+use crate::chip::builder::AirBuilder;
+use crate::chip::register::array::ArrayRegister;
+use crate::chip::register::Register;
+use crate::chip::trace::writer::TraceWriter;
+use crate::chip::AirParameters;
+use crate::math::prelude::*;
+
+/// The bit-width of an unsigned integer register, chosen at runtime.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Width {
+    W8,
+    W16,
+    W32,
+    W64,
+}
+
+impl Width {
+    pub const fn num_bits(&self) -> usize {
+        8 * self.num_bytes()
+    }
+
+    pub const fn num_bytes(&self) -> usize {
+        match self {
+            Width::W8 => 1,
+            Width::W16 => 2,
+            Width::W32 => 4,
+            Width::W64 => 8,
+        }
+    }
+
+    /// Returns whether `value` fits in this width.
+    pub const fn contains(&self, value: u64) -> bool {
+        match self {
+            Width::W64 => true,
+            _ => value >> self.num_bits() == 0,
+        }
+    }
+}
+
+/// An unsigned integer register whose width is only known at runtime, stored as its little-endian
+/// bytes.
+///
+/// This allows code handling variable widths to use a single register type instead of one of
+/// `ByteRegister`, `ByteArrayRegister<2>`, `U32Register` or `U64Register`.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub struct WidthRegister {
+    width: Width,
+    bytes: ArrayRegister<ByteRegister>,
+}
+
+impl WidthRegister {
+    pub fn width(&self) -> Width {
+        self.width
+    }
+
+    /// The byte decomposition of the register, in little-endian order.
+    pub fn to_le_bytes(&self) -> ArrayRegister<ByteRegister> {
+        self.bytes
+    }
+
+    /// The expression `sum_i bytes[i] * 2^(8 * i)` of the value of the register.
+    ///
+    /// For `Width::W64`, the value is reduced modulo the field characteristic.
+    pub fn expr<F: Field>(&self) -> ArithmeticExpression<F> {
+        self.bytes
+            .iter()
+            .enumerate()
+            .fold(ArithmeticExpression::zero(), |acc, (i, byte)| {
+                let two_i = ArithmeticExpression::from(F::from_canonical_u64(1 << (8 * i)));
+                acc + two_i * byte.expr()
+            })
+    }
+}
+
+impl<L: AirParameters> AirBuilder<L> {
+    /// Allocates a register of the given `width` whose bytes are range checked using the byte
+    /// lookup `operations`.
+    pub fn alloc_width(
+        &mut self,
+        width: Width,
+        operations: &mut ByteLookupOperations,
+    ) -> WidthRegister
+    where
+        L::Instruction: From<ByteOperationInstruction>,
+    {
+        let bytes = self.alloc_array::<ByteRegister>(width.num_bytes());
+        for byte in bytes {
+            self.set_byte_operation(&ByteOperation::Range(byte), operations);
+        }
+        WidthRegister { width, bytes }
+    }
+}
+
+impl<F: PrimeField64> TraceWriter<F> {
+    /// Writes the little-endian bytes of `value` to `register` at `row_index`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `value` does not fit in the width of the register.
+    pub fn write_width(&self, register: &WidthRegister, value: u64, row_index: usize) {
+        assert!(
+            register.width.contains(value),
+            "Value {} does not fit in {} bits",
+            value,
+            register.width.num_bits()
+        );
+        let bytes = value.to_le_bytes().map(F::from_canonical_u8);
+        self.write_array(
+            &register.bytes,
+            &bytes[..register.width.num_bytes()],
+            row_index,
+        );
+    }
+
+    /// Reads the value of `register` at `row_index`.
+    pub fn read_width(&self, register: &WidthRegister, row_index: usize) -> u64 {
+        self.read_vec(&register.bytes, row_index)
+            .iter()
+            .rev()
+            .fold(0, |acc, byte| (acc << 8) | byte.as_canonical_u64())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::{thread_rng, Rng};
+
+    use super::*;
+    use crate::chip::builder::tests::*;
+    use crate::chip::uint::operations::instruction::UintInstruction;
+
+    #[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+    pub struct WidthRegisterTest;
+
+    impl AirParameters for WidthRegisterTest {
+        type Field = GoldilocksField;
+        type CubicParams = GoldilocksCubicParameters;
+
+        type Instruction = UintInstruction;
+
+        const NUM_FREE_COLUMNS: usize = 20;
+        const EXTENDED_COLUMNS: usize = 40;
+        const NUM_ARITHMETIC_COLUMNS: usize = 0;
+    }
+
+    fn alloc_widths<L: AirParameters>(
+        builder: &mut AirBuilder<L>,
+        widths: &[Width],
+        operations: &mut ByteLookupOperations,
+    ) -> Vec<WidthRegister>
+    where
+        L::Instruction: From<ByteOperationInstruction>,
+    {
+        widths
+            .iter()
+            .map(|width| builder.alloc_width(*width, operations))
+            .collect()
+    }
+
+    #[test]
+    fn test_width_register() {
+        type L = WidthRegisterTest;
+        type SC = PoseidonGoldilocksStarkConfig;
+
+        let mut builder = AirBuilder::<L>::new();
+        let mut operations = builder.byte_operations();
+        let widths = [Width::W8, Width::W16, Width::W8, Width::W16];
+        let registers = alloc_widths(&mut builder, &widths, &mut operations);
+        assert_eq!(registers[0].to_le_bytes().len(), 1);
+        assert_eq!(registers[1].to_le_bytes().len(), 2);
+        assert_eq!(operations.trace_operations.len(), 6);
+
+        let mut byte_table = builder.new_byte_lookup_table();
+        let byte_data = builder.register_byte_lookup(&mut byte_table, operations);
+        builder.constraint_byte_lookup_table(&byte_table);
+
+        let (air, trace_data) = builder.build();
+
+        let num_rows = 1 << 16;
+        let generator = ArithmeticGenerator::<L>::new(trace_data, num_rows);
+        let writer = generator.new_writer();
+
+        byte_table.write_table_entries(&writer);
+        let mut rng = thread_rng();
+        for i in 0..num_rows {
+            for register in registers.iter() {
+                let value = rng.gen::<u64>() >> (64 - register.width().num_bits());
+                writer.write_width(register, value, i);
+                assert_eq!(writer.read_width(register, i), value);
+                assert_eq!(
+                    writer.read_expression(&register.expr(), i),
+                    vec![GoldilocksField::from_canonical_u64(value)]
+                );
+            }
+            writer.write_row_instructions(&generator.air_data, i);
+        }
+        let multiplicities = byte_data.get_multiplicities(&writer);
+        writer.write_lookup_multiplicities(byte_table.multiplicities(), &[multiplicities]);
+
+        let stark = Starky::new(air);
+        let config = SC::standard_fast_config(num_rows);
+
+        // Generate proof and verify as a stark
+        test_starky(&stark, &config, &generator, &[]);
+    }
+}
@@ -288,6 +288,13 @@ pub trait Builder: Sized {
         self.api().watch(data, name);
     }
 
+    /// Prints out a log message (using the log::debug! macro) with the value of the expression.
+    ///
+    /// The message will be presented with `RUST_LOG=debug` or `RUST_LOG=trace`.
+    fn watch_expression(&mut self, expression: ArithmeticExpression<Self::Field>, name: &str) {
+        self.api().watch_expression(expression, name);
+    }
+
     /// Computes the expression `expression` and returns the result as a public register of type `T`.
     fn public_expression<T: Register>(
         &mut self,
@@ -298,6 +305,7 @@ pub trait Builder: Sized {
         register
     }
 
+    /// Computes `lhs + rhs` using the operation defined for the type of `lhs`.
     fn add<Lhs, Rhs>(&mut self, lhs: Lhs, rhs: Rhs) -> <Lhs as ops::Add<Self, Rhs>>::Output
     where
         Lhs: Add<Self, Rhs>,
@@ -309,6 +317,7 @@ pub trait Builder: Sized {
         value.double(self)
     }
 
+    /// Computes `lhs - rhs` using the operation defined for the type of `lhs`.
     fn sub<Lhs, Rhs>(&mut self, lhs: Lhs, rhs: Rhs) -> <Lhs as ops::Sub<Self, Rhs>>::Output
     where
         Lhs: Sub<Self, Rhs>,
@@ -316,6 +325,7 @@ pub trait Builder: Sized {
         lhs.sub(rhs, self)
     }
 
+    /// Computes `lhs * rhs` using the operation defined for the type of `lhs`.
     fn mul<Lhs, Rhs>(&mut self, lhs: Lhs, rhs: Rhs) -> <Lhs as ops::Mul<Self, Rhs>>::Output
     where
         Lhs: Mul<Self, Rhs>,
@@ -323,6 +333,7 @@ pub trait Builder: Sized {
         lhs.mul(rhs, self)
     }
 
+    /// Computes `lhs / rhs` using the operation defined for the type of `lhs`.
     fn div<Lhs, Rhs>(&mut self, lhs: Lhs, rhs: Rhs) -> <Lhs as ops::Div<Self, Rhs>>::Output
     where
         Lhs: Div<Self, Rhs>,
@@ -8,6 +8,7 @@ use crate::chip::register::element::ElementRegister;
 use crate::chip::trace::writer::TraceWriter;
 use crate::chip::uint::bytes::lookup_table::builder_operations::ByteLookupOperations;
 use crate::chip::uint::operations::instruction::UintInstructions;
+use crate::chip::uint::register::U64Register;
 use crate::chip::AirParameters;
 use crate::machine::builder::Builder;
 use crate::plonky2::stark::config::{CurtaConfig, StarkyConfig};
@@ -51,6 +52,14 @@ where
         }
     }
 
+    /// Returns the right rotation of the 64-bit word `a` by `rotation` bits, constrained by one
+    /// `Rotate64` byte lookup per byte.
+    pub fn rotate64(&mut self, a: &U64Register, rotation: usize) -> U64Register {
+        self.api.rotate64(a, rotation, &mut self.operations)
+    }
+
+    /// Builds the main stark of `num_rows` rows together with the lookup stark of the byte
+    /// operations it uses.
     pub fn build<C: CurtaConfig<D, F = L::Field>, const D: usize>(
         self,
         num_rows: usize,
@@ -32,6 +32,13 @@ use crate::plonky2::stark::Starky;
 use crate::plonky2::Plonky2Air;
 use crate::trace::AirTrace;
 
+/// A stark whose byte operations are looked up in a companion byte-operation stark.
+///
+/// The lookup stark holds the table of all byte operations (and, xor, shifts, rotations, range
+/// checks, ...), whose preprocessed trace is committed once in `byte_trace_cap`, and the
+/// multiplicities of the operations used by the main stark. The two starks share their
+/// challenges, and the proof consists of a proof of each of them. A `ByteStark` is built by
+/// [`BytesBuilder::build`](super::builder::BytesBuilder::build).
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(bound = "")]
 pub struct ByteStark<L: AirParameters, C, const D: usize>
@@ -205,9 +212,14 @@ where
         );
 
         // Absorve the trace commitments into the challenger.
-        challenger.observe_cap(&lookup_preprocessed_commitment.merkle_tree.cap);
-        challenger.observe_cap(&main_execution_commitment.merkle_tree.cap);
-        challenger.observe_cap(&lookup_multiplicity_commitment.merkle_tree.cap);
+        self.config.transcript_order.observe_rounds(
+            challenger,
+            &[
+                (&[], &lookup_preprocessed_commitment.merkle_tree.cap),
+                (&[], &main_execution_commitment.merkle_tree.cap),
+                (&[], &lookup_multiplicity_commitment.merkle_tree.cap),
+            ],
+        );
 
         // Get random AIR challenges.
         let challenges = challenger.get_n_challenges(self.stark.air.num_challenges);
@@ -271,11 +283,14 @@ where
             self.lookup_config.commit(&lookup_extended_trace, timing)
         );
 
-        // Obsderve global values.
-        challenger.observe_elements(&main_global);
-        // Observe extended trace commitments.
-        challenger.observe_cap(&main_extended_commitment.merkle_tree.cap);
-        challenger.observe_cap(&lookup_extended_commitment.merkle_tree.cap);
+        // Observe global values and extended trace commitments.
+        self.config.transcript_order.observe_rounds(
+            challenger,
+            &[
+                (&main_global, &main_extended_commitment.merkle_tree.cap),
+                (&[], &lookup_extended_commitment.merkle_tree.cap),
+            ],
+        );
 
         // Return the air commitments.
         (
@@ -358,21 +373,27 @@ where
         // Observe public values.
         challenger.observe_elements(public_values);
 
-        // Observe preprocessesd trace commitment.
-        challenger.observe_cap(&proof.lookup_proof.trace_caps[1]);
-
-        // Observe execution trace commitments.
-        challenger.observe_cap(&proof.main_proof.trace_caps[0]);
-        challenger.observe_cap(&proof.lookup_proof.trace_caps[0]);
+        // Observe the preprocessed and execution trace commitments.
+        self.config.transcript_order.observe_rounds(
+            &mut challenger,
+            &[
+                (&[], &proof.lookup_proof.trace_caps[1]),
+                (&[], &proof.main_proof.trace_caps[0]),
+                (&[], &proof.lookup_proof.trace_caps[0]),
+            ],
+        );
 
         // Get challenges.
         let challenges = challenger.get_n_challenges(self.stark.air.num_challenges);
 
-        // Observe global values.
-        challenger.observe_elements(&proof.global_values);
-        // Observe extended trace commitments.
-        challenger.observe_cap(&proof.main_proof.trace_caps[1]);
-        challenger.observe_cap(&proof.lookup_proof.trace_caps[2]);
+        // Observe global values and extended trace commitments.
+        self.config.transcript_order.observe_rounds(
+            &mut challenger,
+            &[
+                (&proof.global_values, &proof.main_proof.trace_caps[1]),
+                (&[], &proof.lookup_proof.trace_caps[2]),
+            ],
+        );
 
         // Get all challenges.
         let main_challenges = proof.main_proof.get_iop_challenges(
@@ -399,6 +420,8 @@ where
         proof: ByteStarkProof<L::Field, C, D>,
         public_values: &[L::Field],
     ) -> Result<()> {
+        StarkyVerifier::validate_num_rounds(&self.stark, &proof.main_proof)?;
+        StarkyVerifier::validate_num_rounds(&self.lookup_stark, &proof.lookup_proof)?;
         let ByteStarkChallenges {
             main_challenges,
             lookup_challenges,
@@ -466,21 +489,27 @@ where
         // Observe public values.
         challenger.observe_elements(public_values);
 
-        // Observe preprocessesd trace commitment.
-        challenger.observe_cap(&proof.lookup_proof.trace_caps[1]);
-
-        // Observe execution trace commitments.
-        challenger.observe_cap(&proof.main_proof.trace_caps[0]);
-        challenger.observe_cap(&proof.lookup_proof.trace_caps[0]);
+        // Observe the preprocessed and execution trace commitments.
+        self.config.transcript_order.observe_rounds_target(
+            &mut challenger,
+            &[
+                (&[], &proof.lookup_proof.trace_caps[1]),
+                (&[], &proof.main_proof.trace_caps[0]),
+                (&[], &proof.lookup_proof.trace_caps[0]),
+            ],
+        );
 
         // Get challenges.
         let challenges = challenger.get_n_challenges(builder, self.stark.air.num_challenges);
 
-        // Observe global values.
-        challenger.observe_elements(&proof.global_values);
-        // Observe extended trace commitments.
-        challenger.observe_cap(&proof.main_proof.trace_caps[1]);
-        challenger.observe_cap(&proof.lookup_proof.trace_caps[2]);
+        // Observe global values and extended trace commitments.
+        self.config.transcript_order.observe_rounds_target(
+            &mut challenger,
+            &[
+                (&proof.global_values, &proof.main_proof.trace_caps[1]),
+                (&[], &proof.lookup_proof.trace_caps[2]),
+            ],
+        );
 
         // Get all challenges.
         let main_challenges = proof.main_proof.get_iop_challenges_target(
@@ -1,10 +1,7 @@
-use super::stark::EmulatedStark;
+use super::stark::{EmulatedStark, RangeLookupStark};
 use super::RangeParameters;
 use crate::chip::builder::AirBuilder;
-use crate::chip::register::array::ArrayRegister;
 use crate::chip::register::element::ElementRegister;
-use crate::chip::register::memory::MemorySlice;
-use crate::chip::register::RegisterSerializable;
 use crate::chip::AirParameters;
 use crate::machine::builder::Builder;
 use crate::plonky2::stark::config::{CurtaConfig, StarkyConfig};
@@ -12,6 +9,15 @@ use crate::plonky2::stark::Starky;
 
 pub(crate) const NUM_LOOKUP_ROWS: usize = 1 << 16;
 
+/// A builder for an AIR whose arithmetic columns are range-checked by a separate lookup stark.
+///
+/// Registers allocated through the [`Builder`] interface behave as in [`AirBuilder`], except that
+/// the `u16` range checks of all arithmetic columns (and of the global arithmetic registers) are
+/// proven in a companion lookup stark instead of in extended columns of the main AIR. Field
+/// arithmetic such as `add`, `sub` and `mul` on [`FieldRegister`]s is available through the
+/// [`Builder`] trait.
+///
+/// [`FieldRegister`]: crate::chip::field::register::FieldRegister
 pub struct EmulatedBuilder<L: AirParameters> {
     pub api: AirBuilder<L>,
     pub clk: ElementRegister,
@@ -33,6 +39,7 @@ impl<L: AirParameters> Builder for EmulatedBuilder<L> {
 }
 
 impl<L: AirParameters> EmulatedBuilder<L> {
+    /// Creates a new builder with a clock register and local memory initialized.
     pub fn new() -> Self {
         let mut api = AirBuilder::<L>::new();
         let clk = api.clock();
@@ -41,11 +48,31 @@ impl<L: AirParameters> EmulatedBuilder<L> {
         EmulatedBuilder { api, clk }
     }
 
+    /// Builds the main stark together with the lookup stark that range-checks its arithmetic
+    /// columns.
+    ///
+    /// If there are no values to range-check, no lookup stark is built and the main stark is
+    /// proven on its own.
     pub fn build<C: CurtaConfig<D, F = L::Field>, const D: usize>(
         self,
         num_rows: usize,
     ) -> EmulatedStark<L, C, D> {
         let EmulatedBuilder { mut api, .. } = self;
+
+        let values = api.range_checked_arithmetic();
+        if values.is_empty() {
+            let config = StarkyConfig::<C, D>::standard_fast_config(num_rows);
+            let (air, trace_data) = api.build();
+            let stark = Starky::new(air);
+
+            return EmulatedStark {
+                config,
+                stark,
+                air_data: trace_data,
+                lookup: None,
+            };
+        }
+
         let shared_memory = api.shared_memory.clone();
         let mut lookup_builder =
             AirBuilder::<RangeParameters<L::Field, L::CubicParams>>::init(shared_memory);
@@ -55,14 +82,6 @@ impl<L: AirParameters> EmulatedBuilder<L> {
         // Allocate multiplicities.
         let multiplicity = lookup_builder.alloc_array::<ElementRegister>(1);
 
-        let values = ArrayRegister::<ElementRegister>::from_register_unsafe(MemorySlice::Local(
-            0,
-            L::NUM_ARITHMETIC_COLUMNS,
-        ))
-        .into_iter()
-        .chain(api.global_arithmetic.iter().copied())
-        .collect::<Vec<_>>();
-
         let mut table_data = lookup_builder.new_lookup(&[lookup_table], &multiplicity);
         let lookup_values = table_data.register_lookup_values(&mut api, &values);
         lookup_builder.constrain_element_lookup_table(table_data);
@@ -79,12 +98,14 @@ impl<L: AirParameters> EmulatedBuilder<L> {
             config,
             stark,
             air_data: trace_data,
-            lookup_config,
-            lookup_stark,
-            lookup_air_data: lookup_trace_data,
-            lookup_values,
-            lookup_table,
-            multiplicity,
+            lookup: Some(RangeLookupStark {
+                config: lookup_config,
+                stark: lookup_stark,
+                air_data: lookup_trace_data,
+                values: lookup_values,
+                table: lookup_table,
+                multiplicity,
+            }),
         }
     }
 }
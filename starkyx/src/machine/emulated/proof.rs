@@ -1,36 +1,122 @@
+use std::collections::HashMap;
+
+use anyhow::{anyhow, Result};
 use plonky2::field::extension::Extendable;
 use plonky2::hash::hash_types::RichField;
 use plonky2::iop::target::Target;
+use serde::{Deserialize, Serialize};
 
 use crate::plonky2::stark::config::CurtaConfig;
 use crate::plonky2::stark::proof::{
     AirProof, AirProofTarget, StarkProofChallenges, StarkProofChallengesTarget,
 };
 
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(bound = "")]
 pub struct EmulatedStarkProof<
     F: RichField + Extendable<D>,
     C: CurtaConfig<D, F = F>,
     const D: usize,
 > {
     pub main_proof: AirProof<F, C, D>,
-    pub lookup_proof: AirProof<F, C, D>,
+    /// The proof of the lookup stark, or `None` if the main stark has no range-checked values.
+    ///
+    /// The lookup table is a clock register of the lookup stark, pinned to `0, 1, 2, ...` by its
+    /// constraints, so the proof carries no commitment to it.
+    pub lookup_proof: Option<AirProof<F, C, D>>,
     pub global_values: Vec<F>,
 }
 
+/// A compact encoding of an [`EmulatedStarkProof`].
+///
+/// The global values are shared by the main and lookup proofs and often contain repeated entries,
+/// such as constants. They are stored as a table of the distinct values, in order of first
+/// appearance, together with the index into the table of each global value.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(bound = "")]
+pub struct CompressedEmulatedStarkProof<
+    F: RichField + Extendable<D>,
+    C: CurtaConfig<D, F = F>,
+    const D: usize,
+> {
+    pub main_proof: AirProof<F, C, D>,
+    pub lookup_proof: Option<AirProof<F, C, D>>,
+    pub distinct_global_values: Vec<F>,
+    pub global_value_indices: Vec<u32>,
+}
+
+impl<F: RichField + Extendable<D>, C: CurtaConfig<D, F = F>, const D: usize>
+    EmulatedStarkProof<F, C, D>
+{
+    pub fn compress(self) -> CompressedEmulatedStarkProof<F, C, D> {
+        let mut distinct_global_values = Vec::new();
+        let mut positions = HashMap::new();
+        let global_value_indices = self
+            .global_values
+            .iter()
+            .map(|value| {
+                *positions.entry(*value).or_insert_with(|| {
+                    distinct_global_values.push(*value);
+                    (distinct_global_values.len() - 1) as u32
+                })
+            })
+            .collect();
+
+        CompressedEmulatedStarkProof {
+            main_proof: self.main_proof,
+            lookup_proof: self.lookup_proof,
+            distinct_global_values,
+            global_value_indices,
+        }
+    }
+}
+
+impl<F: RichField + Extendable<D>, C: CurtaConfig<D, F = F>, const D: usize>
+    CompressedEmulatedStarkProof<F, C, D>
+{
+    /// Recovers the proof from its compact encoding.
+    ///
+    /// Returns an error if an index of `global_value_indices` is out of the range of the table of
+    /// distinct values.
+    pub fn decompress(self) -> Result<EmulatedStarkProof<F, C, D>> {
+        let global_values = self
+            .global_value_indices
+            .iter()
+            .map(|&index| {
+                self.distinct_global_values
+                    .get(index as usize)
+                    .copied()
+                    .ok_or_else(|| {
+                        anyhow!(
+                            "global value index {} out of range for {} distinct values",
+                            index,
+                            self.distinct_global_values.len()
+                        )
+                    })
+            })
+            .collect::<Result<_>>()?;
+
+        Ok(EmulatedStarkProof {
+            main_proof: self.main_proof,
+            lookup_proof: self.lookup_proof,
+            global_values,
+        })
+    }
+}
+
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct EmulatedStarkProofTarget<const D: usize> {
     pub main_proof: AirProofTarget<D>,
-    pub lookup_proof: AirProofTarget<D>,
+    pub lookup_proof: Option<AirProofTarget<D>>,
     pub global_values: Vec<Target>,
 }
 
 pub struct EmulatedStarkChallenges<F: RichField + Extendable<D>, const D: usize> {
     pub(crate) main_challenges: StarkProofChallenges<F, D>,
-    pub(crate) lookup_challenges: StarkProofChallenges<F, D>,
+    pub(crate) lookup_challenges: Option<StarkProofChallenges<F, D>>,
 }
 
 pub struct EmulatedStarkChallengesTarget<const D: usize> {
     pub(crate) main_challenges: StarkProofChallengesTarget<D>,
-    pub(crate) lookup_challenges: StarkProofChallengesTarget<D>,
+    pub(crate) lookup_challenges: Option<StarkProofChallengesTarget<D>>,
 }
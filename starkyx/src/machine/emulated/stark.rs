@@ -1,4 +1,4 @@
-use anyhow::Result;
+use anyhow::{ensure, Result};
 use plonky2::field::extension::Extendable;
 use plonky2::hash::hash_types::RichField;
 use plonky2::iop::challenger::{Challenger, RecursiveChallenger};
@@ -15,10 +15,12 @@ use super::proof::{
     EmulatedStarkProofTarget,
 };
 use super::RangeParameters;
+use crate::air::RAirData;
 use crate::chip::register::array::ArrayRegister;
 use crate::chip::register::element::ElementRegister;
 use crate::chip::table::lookup::values::LogLookupValues;
 use crate::chip::trace::data::AirTraceData;
+use crate::chip::trace::generator::ArithmeticGenerator;
 use crate::chip::trace::writer::{InnerWriterData, TraceWriter};
 use crate::chip::{AirParameters, Chip};
 use crate::math::prelude::*;
@@ -32,17 +34,33 @@ use crate::plonky2::stark::Starky;
 use crate::plonky2::Plonky2Air;
 use crate::trace::AirTrace;
 
+/// A stark whose arithmetic columns are range-checked by a companion lookup stark.
+///
+/// If the main stark has no range-checked values, there is no lookup stark and the proof consists
+/// of the main stark proof alone.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(bound = "")]
 pub struct EmulatedStark<L: AirParameters, C, const D: usize> {
     pub config: StarkyConfig<C, D>,
     pub stark: Starky<Chip<L>>,
     pub air_data: AirTraceData<L>,
-    pub(crate) lookup_config: StarkyConfig<C, D>,
-    pub(crate) lookup_stark: Starky<Chip<RangeParameters<L::Field, L::CubicParams>>>,
-    pub(crate) lookup_air_data: AirTraceData<RangeParameters<L::Field, L::CubicParams>>,
-    pub(crate) lookup_values: LogLookupValues<ElementRegister, L::Field, L::CubicParams>,
-    pub(crate) lookup_table: ElementRegister,
+    pub(crate) lookup: Option<RangeLookupStark<L, C, D>>,
+}
+
+/// The lookup stark range-checking the values of the main stark of an [`EmulatedStark`].
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(bound = "")]
+pub(crate) struct RangeLookupStark<L: AirParameters, C, const D: usize> {
+    pub(crate) config: StarkyConfig<C, D>,
+    pub(crate) stark: Starky<Chip<RangeParameters<L::Field, L::CubicParams>>>,
+    pub(crate) air_data: AirTraceData<RangeParameters<L::Field, L::CubicParams>>,
+    pub(crate) values: LogLookupValues<ElementRegister, L::Field, L::CubicParams>,
+    /// The lookup table, holding `0, 1, ..., NUM_LOOKUP_ROWS - 1`.
+    ///
+    /// The table is a clock register, constrained to be `0` in the first row and to increase by
+    /// one at every transition, so the prover cannot substitute another table and the verifier
+    /// needs no separate commitment to it.
+    pub(crate) table: ElementRegister,
     pub(crate) multiplicity: ArrayRegister<ElementRegister>,
 }
 
@@ -60,12 +78,14 @@ where
         &self.config
     }
 
-    pub const fn lookup_stark(&self) -> &Starky<Chip<RangeParameters<L::Field, L::CubicParams>>> {
-        &self.lookup_stark
+    /// The lookup stark, or `None` if the main stark has no range-checked values.
+    pub fn lookup_stark(&self) -> Option<&Starky<Chip<RangeParameters<L::Field, L::CubicParams>>>> {
+        self.lookup.as_ref().map(|lookup| &lookup.stark)
     }
 
-    pub const fn lookup_config(&self) -> &StarkyConfig<C, D> {
-        &self.lookup_config
+    /// The config of the lookup stark, or `None` if the main stark has no range-checked values.
+    pub fn lookup_config(&self) -> Option<&StarkyConfig<C, D>> {
+        self.lookup.as_ref().map(|lookup| &lookup.config)
     }
 
     #[inline]
@@ -75,12 +95,14 @@ where
 
     fn generate_execution_traces(
         &self,
+        lookup: &RangeLookupStark<L, C, D>,
         execution_trace: &AirTrace<L::Field>,
         public_values: &[L::Field],
+        table_value: impl Fn(usize) -> L::Field,
     ) -> (TraceWriter<L::Field>, TraceWriter<L::Field>) {
         // Initialize writers.
         let main_writer = TraceWriter::new(&self.air_data, execution_trace.height());
-        let lookup_writer = TraceWriter::new(&self.lookup_air_data, NUM_LOOKUP_ROWS);
+        let lookup_writer = TraceWriter::new(&lookup.air_data, NUM_LOOKUP_ROWS);
 
         // Insert execution trace and into main writer.
         let execution_trace_length = self.stark.air.execution_trace_length;
@@ -105,27 +127,28 @@ where
 
         // Write lookup table values
         for i in 0..NUM_LOOKUP_ROWS {
-            lookup_writer.write(&self.lookup_table, &L::Field::from_canonical_usize(i), i);
+            lookup_writer.write(&lookup.table, &table_value(i), i);
         }
         for i in 0..NUM_LOOKUP_ROWS {
-            lookup_writer.write_row_instructions(&self.lookup_air_data, i);
+            lookup_writer.write_row_instructions(&lookup.air_data, i);
         }
         // Write multiplicities
         let multiplicities = main_writer.get_multiplicities_from_fn(
             1,
             NUM_LOOKUP_ROWS,
-            &self.lookup_values.trace_values,
-            &self.lookup_values.public_values,
+            &lookup.values.trace_values,
+            &lookup.values.public_values,
             Self::range_fn,
         );
 
-        lookup_writer.write_lookup_multiplicities(self.multiplicity, &[multiplicities]);
+        lookup_writer.write_lookup_multiplicities(lookup.multiplicity, &[multiplicities]);
 
         (main_writer, lookup_writer)
     }
 
     fn generate_extended_traces(
         &self,
+        lookup: &RangeLookupStark<L, C, D>,
         main_writer: &TraceWriter<L::Field>,
         lookup_writer: &TraceWriter<L::Field>,
     ) {
@@ -139,7 +162,7 @@ where
             .copy_from_slice(&main_writer.global.read().unwrap());
 
         // Write the extended trace values
-        self.lookup_air_data.write_extended_trace(lookup_writer);
+        lookup.air_data.write_extended_trace(lookup_writer);
 
         // Update global values
         main_writer
@@ -151,8 +174,10 @@ where
 
     fn generate_trace(
         &self,
+        lookup: &RangeLookupStark<L, C, D>,
         execution_trace: &AirTrace<L::Field>,
         public_values: &[L::Field],
+        table_value: impl Fn(usize) -> L::Field,
         challenger: &mut Challenger<L::Field, C::Hasher>,
         timing: &mut TimingTree,
     ) -> (AirCommitment<L::Field, C, D>, AirCommitment<L::Field, C, D>) {
@@ -161,7 +186,7 @@ where
 
         // Generate execution traces.
         let (main_writer, lookup_writer) =
-            self.generate_execution_traces(execution_trace, public_values);
+            self.generate_execution_traces(lookup, execution_trace, public_values, table_value);
 
         let main_execution_trace_values = main_writer
             .read_trace()
@@ -178,12 +203,12 @@ where
             .read_trace()
             .unwrap()
             .rows_par()
-            .flat_map(|row| row[0..self.lookup_stark.air.execution_trace_length].to_vec())
+            .flat_map(|row| row[0..lookup.stark.air.execution_trace_length].to_vec())
             .collect::<Vec<_>>();
 
         let lookup_execution_trace = AirTrace {
             values: lookup_execution_trace_values,
-            width: self.lookup_stark.air.execution_trace_length,
+            width: lookup.stark.air.execution_trace_length,
         };
 
         // Commit to execution traces
@@ -196,12 +221,17 @@ where
         let lookup_execution_commitment = timed!(
             timing,
             "Commit to lookup execution trace",
-            self.lookup_config.commit(&lookup_execution_trace, timing)
+            lookup.config.commit(&lookup_execution_trace, timing)
         );
 
         // Absorve the trace commitments into the challenger.
-        challenger.observe_cap(&main_execution_commitment.merkle_tree.cap);
-        challenger.observe_cap(&lookup_execution_commitment.merkle_tree.cap);
+        self.config.transcript_order.observe_rounds(
+            challenger,
+            &[
+                (&[], &main_execution_commitment.merkle_tree.cap),
+                (&[], &lookup_execution_commitment.merkle_tree.cap),
+            ],
+        );
 
         // Get random AIR challenges.
         let challenges = challenger.get_n_challenges(self.stark.air.num_challenges);
@@ -218,7 +248,7 @@ where
             .extend_from_slice(&challenges);
 
         // Generate extended traces.
-        self.generate_extended_traces(&main_writer, &lookup_writer);
+        self.generate_extended_traces(lookup, &main_writer, &lookup_writer);
 
         let InnerWriterData {
             trace: main_trace,
@@ -252,24 +282,27 @@ where
 
         let lookup_extended_trace_values = lookup_trace
             .rows_par()
-            .flat_map(|row| row[self.lookup_stark.air.execution_trace_length..].to_vec())
+            .flat_map(|row| row[lookup.stark.air.execution_trace_length..].to_vec())
             .collect::<Vec<_>>();
         let lookup_extended_trace = AirTrace {
             values: lookup_extended_trace_values,
             width: RangeParameters::<L::Field, L::CubicParams>::num_columns()
-                - self.lookup_stark.air.execution_trace_length,
+                - lookup.stark.air.execution_trace_length,
         };
         let lookup_extended_commitment = timed!(
             timing,
             "Commit to lookup extended trace",
-            self.lookup_config.commit(&lookup_extended_trace, timing)
+            lookup.config.commit(&lookup_extended_trace, timing)
         );
 
-        // Obsderve global values.
-        challenger.observe_elements(&main_global);
-        // Observe extended trace commitments.
-        challenger.observe_cap(&main_extended_commitment.merkle_tree.cap);
-        challenger.observe_cap(&lookup_extended_commitment.merkle_tree.cap);
+        // Observe global values and extended trace commitments.
+        self.config.transcript_order.observe_rounds(
+            challenger,
+            &[
+                (&main_global, &main_extended_commitment.merkle_tree.cap),
+                (&[], &lookup_extended_commitment.merkle_tree.cap),
+            ],
+        );
 
         // Return the air commitments.
         (
@@ -288,12 +321,89 @@ where
         )
     }
 
+    /// Proves the main stark on its own, with the same transcript as a [`Starky`] proof.
+    fn prove_without_lookup(
+        &self,
+        execution_trace: &AirTrace<L::Field>,
+        public_values: &[L::Field],
+        timing: &mut TimingTree,
+    ) -> Result<EmulatedStarkProof<L::Field, C, D>> {
+        let generator =
+            ArithmeticGenerator::<L>::new(self.air_data.clone(), execution_trace.height());
+
+        // Insert the execution trace into the generator.
+        let execution_trace_length = self.stark.air.execution_trace_length;
+        generator
+            .writer
+            .write_trace()
+            .unwrap()
+            .rows_par_mut()
+            .zip(execution_trace.rows_par())
+            .for_each(|(row, execution_row)| {
+                row[0..execution_trace_length]
+                    .copy_from_slice(&execution_row[0..execution_trace_length]);
+            });
+
+        let mut challenger = Challenger::new();
+        let air_commitment = timed!(
+            timing,
+            "Generate stark trace",
+            StarkyProver::generate_trace(
+                &self.config,
+                &self.stark,
+                public_values,
+                &generator,
+                &mut challenger,
+                timing,
+            )?
+        );
+
+        let main_proof = timed!(
+            timing,
+            "Generate main proof",
+            StarkyProver::prove_with_trace(
+                &self.config,
+                &self.stark,
+                air_commitment,
+                &mut challenger,
+                &mut TimingTree::default(),
+            )?
+        );
+
+        Ok(EmulatedStarkProof {
+            main_proof: main_proof.air_proof,
+            lookup_proof: None,
+            global_values: main_proof.global_values,
+        })
+    }
+
     pub fn prove(
         &self,
         execution_trace: &AirTrace<L::Field>,
         public_values: &[L::Field],
         timing: &mut TimingTree,
     ) -> Result<EmulatedStarkProof<L::Field, C, D>> {
+        self.prove_with_table(
+            execution_trace,
+            public_values,
+            L::Field::from_canonical_usize,
+            timing,
+        )
+    }
+
+    /// Proves the stark with the lookup table entries given by `table_value`, which must be the
+    /// identity for the lookup stark constraints to hold.
+    fn prove_with_table(
+        &self,
+        execution_trace: &AirTrace<L::Field>,
+        public_values: &[L::Field],
+        table_value: impl Fn(usize) -> L::Field,
+        timing: &mut TimingTree,
+    ) -> Result<EmulatedStarkProof<L::Field, C, D>> {
+        let Some(lookup) = &self.lookup else {
+            return self.prove_without_lookup(execution_trace, public_values, timing);
+        };
+
         // Initialize challenger.
         let mut challenger = Challenger::new();
 
@@ -301,7 +411,14 @@ where
         let (main_air_commitment, lookup_air_commitment) = timed!(
             timing,
             "Generate stark trace",
-            self.generate_trace(execution_trace, public_values, &mut challenger, timing)
+            self.generate_trace(
+                lookup,
+                execution_trace,
+                public_values,
+                table_value,
+                &mut challenger,
+                timing
+            )
         );
 
         // Generate individual stark proofs.
@@ -321,8 +438,8 @@ where
             timing,
             "Generate lookup proof",
             StarkyProver::prove_with_trace(
-                &self.lookup_config,
-                &self.lookup_stark,
+                &lookup.config,
+                &lookup.stark,
                 lookup_air_commitment,
                 &mut challenger,
                 &mut TimingTree::default(),
@@ -332,7 +449,7 @@ where
         // Return the proof.
         Ok(EmulatedStarkProof {
             main_proof: main_proof.air_proof,
-            lookup_proof: lookup_proof.air_proof,
+            lookup_proof: Some(lookup_proof.air_proof),
             global_values: lookup_proof.global_values,
         })
     }
@@ -348,18 +465,47 @@ where
         // Observe public values.
         challenger.observe_elements(public_values);
 
+        let (Some(lookup), Some(lookup_proof)) = (&self.lookup, &proof.lookup_proof) else {
+            // Without a lookup stark, the rounds of the main stark are observed as in a
+            // `Starky` proof.
+            let challenges = self.config.transcript_order.round_challenges(
+                &mut challenger,
+                &self.stark.air.round_data(),
+                &proof.global_values,
+                &proof.main_proof.trace_caps,
+            );
+            let main_challenges = proof.main_proof.get_iop_challenges(
+                &self.config,
+                self.config.degree_bits,
+                challenges,
+                &mut challenger,
+            );
+            return EmulatedStarkChallenges {
+                main_challenges,
+                lookup_challenges: None,
+            };
+        };
+
         // Observe execution trace commitments.
-        challenger.observe_cap(&proof.main_proof.trace_caps[0]);
-        challenger.observe_cap(&proof.lookup_proof.trace_caps[0]);
+        self.config.transcript_order.observe_rounds(
+            &mut challenger,
+            &[
+                (&[], &proof.main_proof.trace_caps[0]),
+                (&[], &lookup_proof.trace_caps[0]),
+            ],
+        );
 
         // Get challenges.
         let challenges = challenger.get_n_challenges(self.stark.air.num_challenges);
 
-        // Observe global values.
-        challenger.observe_elements(&proof.global_values);
-        // Observe extended trace commitments.
-        challenger.observe_cap(&proof.main_proof.trace_caps[1]);
-        challenger.observe_cap(&proof.lookup_proof.trace_caps[1]);
+        // Observe global values and extended trace commitments.
+        self.config.transcript_order.observe_rounds(
+            &mut challenger,
+            &[
+                (&proof.global_values, &proof.main_proof.trace_caps[1]),
+                (&[], &lookup_proof.trace_caps[1]),
+            ],
+        );
 
         // Get all challenges.
         let main_challenges = proof.main_proof.get_iop_challenges(
@@ -368,16 +514,16 @@ where
             challenges.clone(),
             &mut challenger,
         );
-        let lookup_challenges = proof.lookup_proof.get_iop_challenges(
-            &self.lookup_config,
-            self.lookup_config.degree_bits,
+        let lookup_challenges = lookup_proof.get_iop_challenges(
+            &lookup.config,
+            lookup.config.degree_bits,
             challenges,
             &mut challenger,
         );
 
         EmulatedStarkChallenges {
             main_challenges,
-            lookup_challenges,
+            lookup_challenges: Some(lookup_challenges),
         }
     }
 
@@ -386,6 +532,14 @@ where
         proof: EmulatedStarkProof<L::Field, C, D>,
         public_values: &[L::Field],
     ) -> Result<()> {
+        ensure!(
+            self.lookup.is_some() == proof.lookup_proof.is_some(),
+            "The proof does not match the lookup stark of the emulated stark"
+        );
+        StarkyVerifier::validate_num_rounds(&self.stark, &proof.main_proof)?;
+        if let (Some(lookup), Some(lookup_proof)) = (&self.lookup, &proof.lookup_proof) {
+            StarkyVerifier::validate_num_rounds(&lookup.stark, lookup_proof)?;
+        }
         let EmulatedStarkChallenges {
             main_challenges,
             lookup_challenges,
@@ -405,9 +559,15 @@ where
             &global_values,
             main_challenges,
         )?;
+
+        let (Some(lookup), Some(lookup_proof), Some(lookup_challenges)) =
+            (&self.lookup, lookup_proof, lookup_challenges)
+        else {
+            return Ok(());
+        };
         StarkyVerifier::verify_with_challenges(
-            &self.lookup_config,
-            &self.lookup_stark,
+            &lookup.config,
+            &lookup.stark,
             lookup_proof,
             public_values,
             &global_values,
@@ -420,7 +580,10 @@ where
         builder: &mut CircuitBuilder<L::Field, D>,
     ) -> (EmulatedStarkProofTarget<D>, Vec<Target>) {
         let main_proof = add_virtual_air_proof(builder, &self.stark, &self.config);
-        let lookup_proof = add_virtual_air_proof(builder, &self.lookup_stark, &self.lookup_config);
+        let lookup_proof = self
+            .lookup
+            .as_ref()
+            .map(|lookup| add_virtual_air_proof(builder, &lookup.stark, &lookup.config));
 
         let num_global_values = self.stark.air.num_global_values;
         let global_values = builder.add_virtual_targets(num_global_values);
@@ -448,18 +611,48 @@ where
         // Observe public values.
         challenger.observe_elements(public_values);
 
+        let (Some(lookup), Some(lookup_proof)) = (&self.lookup, &proof.lookup_proof) else {
+            // Without a lookup stark, the rounds of the main stark are observed as in a
+            // `Starky` proof.
+            let challenges = self.config.transcript_order.round_challenges_target(
+                builder,
+                &mut challenger,
+                &self.stark.air.round_data(),
+                &proof.global_values,
+                &proof.main_proof.trace_caps,
+            );
+            let main_challenges = proof.main_proof.get_iop_challenges_target(
+                builder,
+                &self.config,
+                challenges,
+                &mut challenger,
+            );
+            return EmulatedStarkChallengesTarget {
+                main_challenges,
+                lookup_challenges: None,
+            };
+        };
+
         // Observe execution trace commitments.
-        challenger.observe_cap(&proof.main_proof.trace_caps[0]);
-        challenger.observe_cap(&proof.lookup_proof.trace_caps[0]);
+        self.config.transcript_order.observe_rounds_target(
+            &mut challenger,
+            &[
+                (&[], &proof.main_proof.trace_caps[0]),
+                (&[], &lookup_proof.trace_caps[0]),
+            ],
+        );
 
         // Get challenges.
         let challenges = challenger.get_n_challenges(builder, self.stark.air.num_challenges);
 
-        // Observe global values.
-        challenger.observe_elements(&proof.global_values);
-        // Observe extended trace commitments.
-        challenger.observe_cap(&proof.main_proof.trace_caps[1]);
-        challenger.observe_cap(&proof.lookup_proof.trace_caps[1]);
+        // Observe global values and extended trace commitments.
+        self.config.transcript_order.observe_rounds_target(
+            &mut challenger,
+            &[
+                (&proof.global_values, &proof.main_proof.trace_caps[1]),
+                (&[], &lookup_proof.trace_caps[1]),
+            ],
+        );
 
         // Get all challenges.
         let main_challenges = proof.main_proof.get_iop_challenges_target(
@@ -468,16 +661,16 @@ where
             challenges.clone(),
             &mut challenger,
         );
-        let lookup_challenges = proof.lookup_proof.get_iop_challenges_target(
+        let lookup_challenges = lookup_proof.get_iop_challenges_target(
             builder,
-            &self.lookup_config,
+            &lookup.config,
             challenges,
             &mut challenger,
         );
 
         EmulatedStarkChallengesTarget {
             main_challenges,
-            lookup_challenges,
+            lookup_challenges: Some(lookup_challenges),
         }
     }
 
@@ -504,15 +697,19 @@ where
             challenges.main_challenges,
         );
 
-        StarkyVerifier::verify_with_challenges_circuit(
-            builder,
-            &self.lookup_config,
-            &self.lookup_stark,
-            lookup_proof,
-            public_values,
-            global_values,
-            challenges.lookup_challenges,
-        )
+        if let (Some(lookup), Some(lookup_proof), Some(lookup_challenges)) =
+            (&self.lookup, lookup_proof, challenges.lookup_challenges)
+        {
+            StarkyVerifier::verify_with_challenges_circuit(
+                builder,
+                &lookup.config,
+                &lookup.stark,
+                lookup_proof,
+                public_values,
+                global_values,
+                lookup_challenges,
+            )
+        }
     }
 
     pub fn set_proof_target<W: WitnessWrite<L::Field>>(
@@ -528,7 +725,13 @@ where
         } = proof_tagret;
 
         set_air_proof_target(witness, main_proof, &proof.main_proof);
-        set_air_proof_target(witness, lookup_proof, &proof.lookup_proof);
+        match (lookup_proof, &proof.lookup_proof) {
+            (Some(lookup_proof_target), Some(lookup_proof)) => {
+                set_air_proof_target(witness, lookup_proof_target, lookup_proof)
+            }
+            (None, None) => {}
+            _ => panic!("The proof does not match the lookup stark of the proof target"),
+        }
 
         witness.set_target_arr(global_values, &proof.global_values);
     }
@@ -538,6 +741,7 @@ where
 mod tests {
     use num::bigint::RandBigInt;
     use plonky2::field::goldilocks_field::GoldilocksField;
+    use plonky2::field::types::Sample;
     use plonky2::iop::witness::PartialWitness;
     use plonky2::plonk::circuit_data::CircuitConfig;
     use serde::{Deserialize, Serialize};
@@ -635,4 +839,292 @@ mod tests {
 
         timing.print();
     }
+
+    #[test]
+    #[should_panic(expected = "the vanishing polynomial is not divisible by Z_H")]
+    fn test_emulated_stark_tampered_table() {
+        type L = RangeTest;
+        type F = GoldilocksField;
+        type C = CurtaPoseidonGoldilocksConfig;
+
+        let mut timing = TimingTree::new("test_emulated_stark_tampered_table", log::Level::Debug);
+
+        let mut builder = EmulatedBuilder::<L>::new();
+
+        let a = builder.alloc::<FieldRegister<Fp25519>>();
+        let b = builder.alloc::<FieldRegister<Fp25519>>();
+        let _ = builder.add(a, b);
+
+        let num_rows = 1 << 5;
+        let stark = builder.build::<C, 2>(num_rows);
+
+        let mut writer_data = AirWriterData::new(&stark.air_data, num_rows);
+
+        let p = Fp25519::modulus();
+        let air_data = &stark.air_data;
+        air_data.write_global_instructions(&mut writer_data.public_writer());
+
+        writer_data.chunks(num_rows).for_each(|mut chunk| {
+            let mut rng = rand::thread_rng();
+            for i in 0..num_rows {
+                let mut writer = chunk.row_writer(i);
+                let a_int = rng.gen_biguint(256) % &p;
+                let b_int = rng.gen_biguint(256) % &p;
+                let p_a = Polynomial::<F>::from_biguint_field(&a_int, 16, 16);
+                let p_b = Polynomial::<F>::from_biguint_field(&b_int, 16, 16);
+                writer.write(&a, &p_a);
+                writer.write(&b, &p_b);
+                air_data.write_trace_instructions(&mut writer);
+            }
+        });
+
+        let (trace, public) = (writer_data.trace, writer_data.public);
+
+        // Swap two table entries so that the table is no longer `0..NUM_LOOKUP_ROWS`, while
+        // still containing every value the range checks look up.
+        let tampered = |i: usize| match i {
+            1 => F::from_canonical_usize(2),
+            2 => F::from_canonical_usize(1),
+            _ => F::from_canonical_usize(i),
+        };
+        let proof = stark
+            .prove_with_table(&trace, &public, tampered, &mut timing)
+            .unwrap();
+
+        // Only reachable if the prover does not catch the tampered table.
+        stark.verify(proof, &public).unwrap();
+    }
+
+    #[derive(Debug, Clone, Serialize, Deserialize)]
+    pub struct FpMulSubTest;
+
+    impl AirParameters for FpMulSubTest {
+        type Field = GoldilocksField;
+        type CubicParams = GoldilocksCubicParameters;
+
+        type Instruction = FpInstruction<Fp25519>;
+
+        const NUM_ARITHMETIC_COLUMNS: usize = 232;
+        const NUM_FREE_COLUMNS: usize = 1;
+        const EXTENDED_COLUMNS: usize = 354;
+    }
+
+    #[test]
+    fn test_fp_mul_sub_emulated_stark() {
+        type L = FpMulSubTest;
+        type F = GoldilocksField;
+        type C = CurtaPoseidonGoldilocksConfig;
+        type Config = <C as CurtaConfig<2>>::GenericConfig;
+
+        let _ = env_logger::builder().is_test(true).try_init();
+
+        let mut timing = TimingTree::new("test_fp_mul_sub_emulated_stark", log::Level::Debug);
+
+        let mut builder = EmulatedBuilder::<L>::new();
+
+        let a = builder.alloc::<FieldRegister<Fp25519>>();
+        let b = builder.alloc::<FieldRegister<Fp25519>>();
+        let c = builder.alloc::<FieldRegister<Fp25519>>();
+        let ab = builder.mul(a, b);
+        let result = builder.sub(ab, c);
+
+        let num_rows = 1 << 5;
+        let stark = builder.build::<C, 2>(num_rows);
+
+        let mut writer_data = AirWriterData::new(&stark.air_data, num_rows);
+
+        let p = Fp25519::modulus();
+        let air_data = &stark.air_data;
+        air_data.write_global_instructions(&mut writer_data.public_writer());
+
+        let k = 1 << 0;
+        writer_data.chunks(k).for_each(|mut chunk| {
+            let mut rng = rand::thread_rng();
+            for i in 0..k {
+                let mut writer = chunk.row_writer(i);
+                let a_int = rng.gen_biguint(256) % &p;
+                let b_int = rng.gen_biguint(256) % &p;
+                let c_int = rng.gen_biguint(256) % &p;
+                let p_a = Polynomial::<F>::from_biguint_field(&a_int, 16, 16);
+                let p_b = Polynomial::<F>::from_biguint_field(&b_int, 16, 16);
+                let p_c = Polynomial::<F>::from_biguint_field(&c_int, 16, 16);
+                writer.write(&a, &p_a);
+                writer.write(&b, &p_b);
+                writer.write(&c, &p_c);
+                air_data.write_trace_instructions(&mut writer);
+
+                let expected = (&p + &a_int * &b_int % &p - &c_int) % &p;
+                let p_expected = Polynomial::<F>::from_biguint_field(&expected, 16, 16);
+                assert_eq!(writer.read(&result), p_expected);
+            }
+        });
+
+        let (trace, public) = (writer_data.trace, writer_data.public);
+
+        let proof = stark.prove(&trace, &public, &mut timing).unwrap();
+
+        stark.verify(proof.clone(), &public).unwrap();
+
+        let config_rec = CircuitConfig::standard_recursion_config();
+        let mut recursive_builder = CircuitBuilder::<GoldilocksField, 2>::new(config_rec);
+
+        let (proof_target, public_input) =
+            stark.add_virtual_proof_with_pis_target(&mut recursive_builder);
+        stark.verify_circuit(&mut recursive_builder, &proof_target, &public_input);
+
+        let data = recursive_builder.build::<Config>();
+
+        let mut pw = PartialWitness::new();
+
+        pw.set_target_arr(&public_input, &public);
+        stark.set_proof_target(&mut pw, &proof_target, proof);
+
+        let rec_proof = data.prove(pw).unwrap();
+        data.verify(rec_proof).unwrap();
+
+        timing.print();
+    }
+
+    #[derive(Debug, Clone, Serialize, Deserialize)]
+    pub struct NoRangeTest;
+
+    impl AirParameters for NoRangeTest {
+        type Field = GoldilocksField;
+        type CubicParams = GoldilocksCubicParameters;
+
+        type Instruction = FpInstruction<Fp25519>;
+
+        const NUM_ARITHMETIC_COLUMNS: usize = 0;
+        const NUM_FREE_COLUMNS: usize = 5;
+        const EXTENDED_COLUMNS: usize = 3;
+    }
+
+    #[test]
+    fn test_emulated_stark_without_lookup() {
+        type L = NoRangeTest;
+        type F = GoldilocksField;
+        type C = CurtaPoseidonGoldilocksConfig;
+        type Config = <C as CurtaConfig<2>>::GenericConfig;
+
+        let mut timing = TimingTree::new("test_emulated_stark_without_lookup", log::Level::Debug);
+
+        let mut builder = EmulatedBuilder::<L>::new();
+
+        let a = builder.alloc::<ElementRegister>();
+        let b = builder.alloc::<ElementRegister>();
+        let sum = builder.add(a, b);
+        let product = builder.mul(a, sum);
+
+        let num_rows = 1 << 5;
+        let stark = builder.build::<C, 2>(num_rows);
+        assert!(stark.lookup_stark().is_none());
+
+        let mut writer_data = AirWriterData::new(&stark.air_data, num_rows);
+
+        let air_data = &stark.air_data;
+        air_data.write_global_instructions(&mut writer_data.public_writer());
+
+        writer_data.chunks(1).for_each(|mut chunk| {
+            let mut writer = chunk.row_writer(0);
+            let a_value = F::rand();
+            let b_value = F::rand();
+            writer.write(&a, &a_value);
+            writer.write(&b, &b_value);
+            air_data.write_trace_instructions(&mut writer);
+            assert_eq!(writer.read(&product), a_value * (a_value + b_value));
+        });
+
+        let (trace, public) = (writer_data.trace, writer_data.public);
+
+        let proof = stark.prove(&trace, &public, &mut timing).unwrap();
+        assert!(proof.lookup_proof.is_none());
+
+        stark.verify(proof.clone(), &public).unwrap();
+
+        let config_rec = CircuitConfig::standard_recursion_config();
+        let mut recursive_builder = CircuitBuilder::<GoldilocksField, 2>::new(config_rec);
+
+        let (proof_target, public_input) =
+            stark.add_virtual_proof_with_pis_target(&mut recursive_builder);
+        stark.verify_circuit(&mut recursive_builder, &proof_target, &public_input);
+
+        let data = recursive_builder.build::<Config>();
+
+        let mut pw = PartialWitness::new();
+
+        pw.set_target_arr(&public_input, &public);
+        stark.set_proof_target(&mut pw, &proof_target, proof);
+
+        let rec_proof = data.prove(pw).unwrap();
+        data.verify(rec_proof).unwrap();
+
+        timing.print();
+    }
+
+    #[test]
+    fn test_emulated_proof_compression() {
+        type L = RangeTest;
+        type F = GoldilocksField;
+        type C = CurtaPoseidonGoldilocksConfig;
+
+        let mut timing = TimingTree::new("test_emulated_proof_compression", log::Level::Debug);
+
+        let mut builder = EmulatedBuilder::<L>::new();
+
+        let a = builder.alloc::<FieldRegister<Fp25519>>();
+        let b = builder.alloc::<FieldRegister<Fp25519>>();
+        let _ = builder.add(a, b);
+
+        let num_rows = 1 << 5;
+        let stark = builder.build::<C, 2>(num_rows);
+
+        let mut writer_data = AirWriterData::new(&stark.air_data, num_rows);
+
+        let p = Fp25519::modulus();
+        let air_data = &stark.air_data;
+        air_data.write_global_instructions(&mut writer_data.public_writer());
+
+        writer_data.chunks(1).for_each(|mut chunk| {
+            let mut rng = rand::thread_rng();
+            let mut writer = chunk.row_writer(0);
+            let a_int = rng.gen_biguint(256) % &p;
+            let b_int = rng.gen_biguint(256) % &p;
+            writer.write(&a, &Polynomial::<F>::from_biguint_field(&a_int, 16, 16));
+            writer.write(&b, &Polynomial::<F>::from_biguint_field(&b_int, 16, 16));
+            air_data.write_trace_instructions(&mut writer);
+        });
+
+        let (trace, public) = (writer_data.trace, writer_data.public);
+
+        let proof = stark.prove(&trace, &public, &mut timing).unwrap();
+
+        // A decompressed proof is identical to the original and verifies.
+        let decompressed = proof.clone().compress().decompress().unwrap();
+        assert_eq!(decompressed, proof);
+        stark.verify(decompressed, &public).unwrap();
+
+        // The encoding of a proof with many repeated global values is smaller.
+        let mut large_proof = proof;
+        large_proof.global_values = (0..1 << 12)
+            .map(|i| F::from_canonical_usize(i % 16))
+            .collect();
+        let compressed = large_proof.clone().compress();
+        assert_eq!(compressed.distinct_global_values.len(), 16);
+
+        let proof_size = bincode::serialize(&large_proof).unwrap().len();
+        let compressed_size = bincode::serialize(&compressed).unwrap().len();
+        assert!(compressed_size < proof_size);
+        log::debug!(
+            "proof size: {} bytes, compressed size: {} bytes",
+            proof_size,
+            compressed_size
+        );
+
+        assert_eq!(compressed.clone().decompress().unwrap(), large_proof);
+
+        // An index out of the table of distinct values is rejected.
+        let mut malformed = compressed;
+        malformed.global_value_indices[0] = 16;
+        assert!(malformed.decompress().is_err());
+    }
 }
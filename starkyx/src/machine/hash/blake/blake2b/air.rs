@@ -1474,23 +1474,23 @@ where
         v_a_inter = builder.add(v_a_inter, *x);
 
         let mut v_d_inter = builder.xor(*v_d, v_a_inter);
-        v_d_inter = builder.rotate_right(v_d_inter, 32);
+        v_d_inter = builder.rotate64(&v_d_inter, 32);
 
         let mut v_c_inter = builder.add(*v_c, v_d_inter);
 
         let mut v_b_inter = builder.xor(*v_b, v_c_inter);
-        v_b_inter = builder.rotate_right(v_b_inter, 24);
+        v_b_inter = builder.rotate64(&v_b_inter, 24);
 
         v_a_inter = builder.add(v_a_inter, v_b_inter);
         v_a_inter = builder.add(v_a_inter, *y);
 
         v_d_inter = builder.xor(v_d_inter, v_a_inter);
-        v_d_inter = builder.rotate_right(v_d_inter, 16);
+        v_d_inter = builder.rotate64(&v_d_inter, 16);
 
         v_c_inter = builder.add(v_c_inter, v_d_inter);
 
         v_b_inter = builder.xor(v_b_inter, v_c_inter);
-        v_b_inter = builder.rotate_right(v_b_inter, 63);
+        v_b_inter = builder.rotate64(&v_b_inter, 63);
 
         (v_a_inter, v_b_inter, v_c_inter, v_d_inter)
     }
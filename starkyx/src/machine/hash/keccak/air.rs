@@ -0,0 +1,306 @@
+use log::debug;
+use plonky2::util::log2_ceil;
+
+use super::pure::KeccakPure;
+use super::{
+    pi_index, DIGEST_LANES, KECCAK256, NUM_ROUNDS, RATE_LANES, ROTATION_OFFSETS, ROUND_CONSTANTS,
+    STATE_LANES,
+};
+use crate::chip::memory::pointer::slice::Slice;
+use crate::chip::memory::time::Time;
+use crate::chip::memory::value::MemoryValue;
+use crate::chip::register::array::ArrayRegister;
+use crate::chip::register::bit::BitRegister;
+use crate::chip::register::element::ElementRegister;
+use crate::chip::register::Register;
+use crate::chip::uint::operations::instruction::UintInstructions;
+use crate::chip::uint::register::U64Register;
+use crate::chip::uint::util::{u64_from_le_field_bytes, u64_to_le_field_bytes};
+use crate::chip::AirParameters;
+use crate::machine::builder::Builder;
+use crate::machine::bytes::builder::BytesBuilder;
+use crate::machine::hash::{HashIntConversion, HashInteger};
+use crate::math::prelude::*;
+
+impl<B: Builder> HashInteger<B> for KECCAK256 {
+    type IntRegister = U64Register;
+    type Value = <U64Register as Register>::Value<B::Field>;
+}
+
+impl<B: Builder> HashIntConversion<B> for KECCAK256 {
+    fn int_to_field_value(int: Self::Integer) -> Self::Value {
+        u64_to_le_field_bytes(int)
+    }
+
+    fn field_value_to_int(value: &Self::Value) -> Self::Integer {
+        u64_from_le_field_bytes(value)
+    }
+}
+
+/// Keccak-256 AIR implementation.
+///
+/// Each row of the trace applies one round of Keccak-f[1600] to the 25 lanes of the state, so that
+/// a block is absorbed every `NUM_ROUNDS` rows. The block is xored into the state in the first row
+/// of its cycle, and the state is reset to zero after the last block of each message.
+pub trait KeccakAir<B: Builder>: KeccakPure + HashIntConversion<B> {
+    /// Computes the digests of the messages given by their padded blocks of `RATE_LANES` lanes.
+    ///
+    /// The `end_bits` flag the last block of each message, and the `digest_bits` flag the blocks
+    /// whose digest is exposed, at the positions given by `digest_indices`.
+    fn keccak256(
+        builder: &mut B,
+        padded_chunks: &[ArrayRegister<Self::IntRegister>],
+        end_bits: &ArrayRegister<BitRegister>,
+        digest_bits: &ArrayRegister<BitRegister>,
+        digest_indices: ArrayRegister<ElementRegister>,
+    ) -> Vec<ArrayRegister<Self::IntRegister>>;
+
+    /// Applies one round of Keccak-f[1600] to `state`.
+    fn keccak_round(
+        builder: &mut B,
+        state: &[Self::IntRegister],
+        round_constant: Self::IntRegister,
+    ) -> Vec<Self::IntRegister>;
+}
+
+/// Stores one value per block in a slice, so that it can be loaded in every row of the block's
+/// cycle. The blocks past `values` are padding blocks whose value is `dummy`, the last of which
+/// only has `length_last_block` rows.
+fn store_per_block<B: Builder, V: MemoryValue>(
+    builder: &mut B,
+    values: impl Iterator<Item = V>,
+    dummy: V,
+    num_blocks: usize,
+    length_last_block: usize,
+) -> Slice<V> {
+    let cycle_length = builder.constant(&B::Field::from_canonical_usize(NUM_ROUNDS));
+    let last_length = builder.constant(&B::Field::from_canonical_usize(length_last_block));
+
+    let slice = builder.uninit_slice();
+    let mut num_real_blocks = 0;
+    for (i, value) in values.enumerate() {
+        builder.store(
+            &slice.get(i),
+            value,
+            &Time::zero(),
+            Some(cycle_length),
+            None,
+            None,
+        );
+        num_real_blocks += 1;
+    }
+    for i in num_real_blocks..num_blocks - 1 {
+        builder.store(
+            &slice.get(i),
+            dummy,
+            &Time::zero(),
+            Some(cycle_length),
+            None,
+            None,
+        );
+    }
+    builder.store(
+        &slice.get(num_blocks - 1),
+        dummy,
+        &Time::zero(),
+        Some(last_length),
+        None,
+        None,
+    );
+    slice
+}
+
+impl<L: AirParameters> KeccakAir<BytesBuilder<L>> for KECCAK256
+where
+    L::Instruction: UintInstructions,
+{
+    fn keccak256(
+        builder: &mut BytesBuilder<L>,
+        padded_chunks: &[ArrayRegister<U64Register>],
+        end_bits: &ArrayRegister<BitRegister>,
+        digest_bits: &ArrayRegister<BitRegister>,
+        digest_indices: ArrayRegister<ElementRegister>,
+    ) -> Vec<ArrayRegister<U64Register>> {
+        assert_eq!(padded_chunks.len(), end_bits.len());
+        assert_eq!(padded_chunks.len(), digest_bits.len());
+        let num_real_blocks = padded_chunks.len();
+        let degree_log = log2_ceil(num_real_blocks * NUM_ROUNDS);
+        assert!(degree_log < 31, "AIR degree is too large");
+        debug!("AIR degree after padding: {}", 1 << degree_log);
+        // The degree is a power of two, so the last block is always a partial padding block.
+        let num_blocks = (1 << degree_log) / NUM_ROUNDS + 1;
+        let length_last_block = (1 << degree_log) % NUM_ROUNDS;
+
+        // Store the round constants, which are read once per block except for the rounds past
+        // the end of the last partial block.
+        let num_blocks_element = builder.constant(&L::Field::from_canonical_usize(num_blocks));
+        let num_blocks_minus_one =
+            builder.constant(&L::Field::from_canonical_usize(num_blocks - 1));
+        let round_constant_values = builder
+            .constant_array::<U64Register>(&ROUND_CONSTANTS.map(u64_to_le_field_bytes::<L::Field>));
+        let round_constants = builder.uninit_slice();
+        for (i, value) in round_constant_values.iter().enumerate() {
+            let multiplicity = if i < length_last_block {
+                num_blocks_element
+            } else {
+                num_blocks_minus_one
+            };
+            builder.store(
+                &round_constants.get(i),
+                value,
+                &Time::zero(),
+                Some(multiplicity),
+                None,
+                None,
+            );
+        }
+
+        // Store the blocks and their flags, with zero padding blocks.
+        let zero = builder.constant::<BitRegister>(&L::Field::ZERO);
+        let zero_lane = builder.constant::<U64Register>(&u64_to_le_field_bytes(0));
+        let end_bit_slice = store_per_block(
+            builder,
+            end_bits.iter(),
+            zero,
+            num_blocks,
+            length_last_block,
+        );
+        let digest_bit_slice = store_per_block(
+            builder,
+            digest_bits.iter(),
+            zero,
+            num_blocks,
+            length_last_block,
+        );
+        let lane_slices = (0..RATE_LANES)
+            .map(|j| {
+                store_per_block(
+                    builder,
+                    padded_chunks.iter().map(|chunk| chunk.get(j)),
+                    zero_lane,
+                    num_blocks,
+                    length_last_block,
+                )
+            })
+            .collect::<Vec<_>>();
+
+        // The cycle of `NUM_ROUNDS = 3 * 8` rows ends when both a 3-loop and an 8-cycle end.
+        let loop_3 = builder.api().loop_instr(3);
+        let cycle_8 = builder.cycle(3);
+        let cycle_end_bit = builder.mul(loop_3.get_iteration_reg(2), cycle_8.end_bit);
+
+        let block_id = builder.process_id(NUM_ROUNDS, cycle_end_bit);
+        let clk = builder.clk();
+        let round_index = builder
+            .expression(clk.expr() - block_id.expr() * L::Field::from_canonical_usize(NUM_ROUNDS));
+        let is_first_round = builder.alloc::<BitRegister>();
+        builder.set_to_expression_first_row(&is_first_round, L::Field::ONE.into());
+        builder.set_to_expression_transition(&is_first_round.next(), cycle_end_bit.expr());
+
+        let time = Time::zero();
+        let end_bit = builder.load(&end_bit_slice.get_at(block_id), &time, None, None);
+        let digest_bit = builder.load(&digest_bit_slice.get_at(block_id), &time, None, None);
+        let round_constant = builder.load(&round_constants.get_at(round_index), &time, None, None);
+
+        // The state before the round, which starts at zero.
+        let state = builder.alloc_array::<U64Register>(STATE_LANES);
+        for lane in state.iter() {
+            builder.set_to_expression_first_row(&lane, zero_lane.expr());
+        }
+
+        // Absorb the block in the first round.
+        let mut round_input = Vec::with_capacity(STATE_LANES);
+        for (j, lane) in state.iter().enumerate() {
+            if j < RATE_LANES {
+                let word = builder.load(&lane_slices[j].get_at(block_id), &time, None, None);
+                let absorbed = builder.xor(&lane, &word);
+                round_input.push(builder.select(is_first_round, &absorbed, &lane));
+            } else {
+                round_input.push(lane);
+            }
+        }
+
+        let round_output = Self::keccak_round(builder, &round_input, round_constant);
+
+        // Carry the state to the next round, and reset it after the last block of a message.
+        let reset = builder.expression::<BitRegister>(cycle_end_bit.expr() * end_bit.expr());
+        for (lane, output) in state.iter().zip(round_output.iter()) {
+            builder.set_to_expression_transition(&lane.next(), output.expr() * reset.not_expr());
+        }
+
+        // Expose the digests of the flagged blocks.
+        let num_digests = digest_indices.len();
+        let digests = (0..num_digests)
+            .map(|_| builder.alloc_array_public::<U64Register>(DIGEST_LANES))
+            .collect::<Vec<_>>();
+        let digest_ptr = builder.uninit_slice();
+        for (index, digest) in digest_indices.iter().zip(digests.iter()) {
+            for (j, lane) in digest.iter().enumerate() {
+                builder.free(&digest_ptr.get(j), lane, &Time::from_element(index));
+            }
+        }
+        let flag = builder.expression(cycle_end_bit.expr() * digest_bit.expr());
+        for (j, lane) in round_output.iter().take(DIGEST_LANES).enumerate() {
+            builder.store(
+                &digest_ptr.get(j),
+                *lane,
+                &Time::from_element(block_id),
+                Some(flag),
+                None,
+                None,
+            );
+        }
+
+        digests
+    }
+
+    fn keccak_round(
+        builder: &mut BytesBuilder<L>,
+        state: &[U64Register],
+        round_constant: U64Register,
+    ) -> Vec<U64Register> {
+        assert_eq!(state.len(), STATE_LANES);
+
+        // Theta: c[x] = a[x, 0] ^ ... ^ a[x, 4] and d[x] = c[x - 1] ^ rotl(c[x + 1], 1).
+        let mut c = Vec::with_capacity(5);
+        for x in 0..5 {
+            let mut c_x = state[x];
+            for y in 1..5 {
+                c_x = builder.xor(&c_x, &state[x + 5 * y]);
+            }
+            c.push(c_x);
+        }
+        let mut d = Vec::with_capacity(5);
+        for x in 0..5 {
+            let c_rot = builder.rotate_left(&c[(x + 1) % 5], 1);
+            d.push(builder.xor(&c[(x + 4) % 5], &c_rot));
+        }
+        let mut a = Vec::with_capacity(STATE_LANES);
+        for (i, lane) in state.iter().enumerate() {
+            a.push(builder.xor(lane, &d[i % 5]));
+        }
+
+        // Rho and pi: b[y, 2x + 3y] = rotl(a[x, y], r[x, y]).
+        let mut b = vec![a[0]; STATE_LANES];
+        for (i, lane) in a.iter().enumerate() {
+            b[pi_index(i)] = match ROTATION_OFFSETS[i] {
+                0 => *lane,
+                rotation => builder.rotate_left(lane, rotation),
+            };
+        }
+
+        // Chi: a[x, y] = b[x, y] ^ (!b[x + 1, y] & b[x + 2, y]).
+        let mut result = Vec::with_capacity(STATE_LANES);
+        for i in 0..STATE_LANES {
+            let (x, y) = (i % 5, i / 5);
+            let not_b = builder.not(&b[(x + 1) % 5 + 5 * y]);
+            let and_b = builder.and(&not_b, &b[(x + 2) % 5 + 5 * y]);
+            result.push(builder.xor(&b[i], &and_b));
+        }
+
+        // Iota.
+        result[0] = builder.xor(&result[0], &round_constant);
+
+        result
+    }
+}
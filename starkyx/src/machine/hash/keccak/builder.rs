@@ -0,0 +1,178 @@
+use super::air::KeccakAir;
+use crate::chip::register::array::ArrayRegister;
+use crate::chip::register::bit::BitRegister;
+use crate::chip::register::element::ElementRegister;
+use crate::machine::builder::Builder;
+
+pub trait KeccakBuilder: Builder {
+    fn keccak256<K: KeccakAir<Self>>(
+        &mut self,
+        padded_chunks: &[ArrayRegister<K::IntRegister>],
+        end_bits: &ArrayRegister<BitRegister>,
+        digest_bits: &ArrayRegister<BitRegister>,
+        digest_indices: ArrayRegister<ElementRegister>,
+    ) -> Vec<ArrayRegister<K::IntRegister>> {
+        K::keccak256(self, padded_chunks, end_bits, digest_bits, digest_indices)
+    }
+}
+
+impl<B: Builder> KeccakBuilder for B {}
+
+#[cfg(test)]
+mod tests {
+    use itertools::Itertools;
+    use plonky2::field::goldilocks_field::GoldilocksField;
+    use plonky2::iop::witness::{PartialWitness, WitnessWrite};
+    use plonky2::plonk::circuit_builder::CircuitBuilder;
+    use plonky2::plonk::circuit_data::CircuitConfig;
+    use plonky2::timed;
+    use plonky2::util::log2_ceil;
+    use plonky2::util::timing::TimingTree;
+    use serde::{Deserialize, Serialize};
+
+    use super::*;
+    use crate::chip::trace::writer::data::AirWriterData;
+    use crate::chip::trace::writer::AirWriter;
+    use crate::chip::uint::operations::instruction::UintInstruction;
+    use crate::chip::uint::util::{u64_from_le_field_bytes, u64_to_le_field_bytes};
+    use crate::chip::AirParameters;
+    use crate::machine::bytes::builder::BytesBuilder;
+    use crate::machine::hash::keccak::pure::KeccakPure;
+    use crate::machine::hash::keccak::{KECCAK256, NUM_ROUNDS, RATE_LANES};
+    use crate::math::goldilocks::cubic::GoldilocksCubicParameters;
+    use crate::math::prelude::*;
+    use crate::plonky2::stark::config::{CurtaConfig, CurtaPoseidonGoldilocksConfig};
+
+    #[derive(Clone, Debug, Serialize, Deserialize)]
+    pub struct KeccakTest;
+
+    impl AirParameters for KeccakTest {
+        type Field = GoldilocksField;
+        type CubicParams = GoldilocksCubicParameters;
+        type Instruction = UintInstruction;
+
+        const NUM_FREE_COLUMNS: usize = 2600;
+        const EXTENDED_COLUMNS: usize = 4200;
+    }
+
+    #[test]
+    fn test_keccak256() {
+        type L = KeccakTest;
+        type C = CurtaPoseidonGoldilocksConfig;
+        type Config = <C as CurtaConfig<2>>::GenericConfig;
+
+        let _ = env_logger::builder().is_test(true).try_init();
+        let mut timing = TimingTree::new("test_keccak256", log::Level::Debug);
+
+        let messages: [&[u8]; 2] = [b"", b"abc"];
+        let expected_digests = [
+            "c5d2460186f7233c927e7db2dcc703c0e500b653ca82273b7bfad8045d85a470",
+            "4e03657aea45a94fc7d47ba826c8d667c0d1e6e33a64a036ec44f58fa12d6c45",
+        ];
+
+        let mut end_bits_values = Vec::new();
+        let padded_chunks_values = messages
+            .iter()
+            .flat_map(|msg| {
+                let padded_msg = KECCAK256::pad(msg);
+                let num_chunks = padded_msg.len() / RATE_LANES;
+                end_bits_values.extend_from_slice(&vec![GoldilocksField::ZERO; num_chunks - 1]);
+                end_bits_values.push(GoldilocksField::ONE);
+                padded_msg
+            })
+            .collect::<Vec<_>>();
+        let num_blocks = end_bits_values.len();
+
+        // Build the stark.
+        let mut builder = BytesBuilder::<L>::new();
+        let padded_chunks = (0..num_blocks)
+            .map(|_| builder.alloc_array_public(RATE_LANES))
+            .collect::<Vec<_>>();
+        let end_bits = builder.alloc_array_public::<BitRegister>(num_blocks);
+        let digest_indices = builder.alloc_array_public(messages.len());
+        let digests =
+            builder.keccak256::<KECCAK256>(&padded_chunks, &end_bits, &end_bits, digest_indices);
+
+        let num_rows = 1 << log2_ceil(NUM_ROUNDS * num_blocks);
+        let stark = builder.build::<C, 2>(num_rows);
+
+        // Build the recursive circuit.
+        let config_rec = CircuitConfig::standard_recursion_config();
+        let mut recursive_builder = CircuitBuilder::<GoldilocksField, 2>::new(config_rec);
+        let (proof_target, public_input) =
+            stark.add_virtual_proof_with_pis_target(&mut recursive_builder);
+        stark.verify_circuit(&mut recursive_builder, &proof_target, &public_input);
+        let rec_data = recursive_builder.build::<Config>();
+
+        // Write the public inputs.
+        let mut writer_data = AirWriterData::new(&stark.air_data, num_rows);
+        let mut writer = writer_data.public_writer();
+
+        let mut state = [0u64; 25];
+        let mut digests_iter = digests.iter();
+        let mut digest_indices_iter = digest_indices.iter();
+        for (i, ((block, register), end_bit_value)) in padded_chunks_values
+            .chunks_exact(RATE_LANES)
+            .zip_eq(padded_chunks.iter())
+            .zip_eq(end_bits_values.iter())
+            .enumerate()
+        {
+            writer.write_array(register, block.iter().map(|x| u64_to_le_field_bytes(*x)));
+            writer.write(&end_bits.get(i), end_bit_value);
+
+            KECCAK256::absorb(&mut state, block);
+            if *end_bit_value == GoldilocksField::ONE {
+                writer.write(
+                    &digest_indices_iter.next().unwrap(),
+                    &GoldilocksField::from_canonical_usize(i),
+                );
+                let digest = digests_iter.next().unwrap();
+                writer.write_array(digest, state.iter().map(|x| u64_to_le_field_bytes(*x)));
+                state = [0u64; 25];
+            }
+        }
+
+        timed!(timing, "write input", {
+            stark.air_data.write_global_instructions(&mut writer);
+
+            for mut chunk in writer_data.chunks(num_rows) {
+                for i in 0..num_rows {
+                    let mut writer = chunk.window_writer(i);
+                    stark.air_data.write_trace_instructions(&mut writer);
+                }
+            }
+        });
+
+        // Compare the digests with the test vectors.
+        let writer = writer_data.public_writer();
+        for (digest, expected) in digests.iter().zip_eq(expected_digests) {
+            let digest = digest
+                .iter()
+                .map(|lane| u64_from_le_field_bytes(&writer.read(&lane)))
+                .collect::<Vec<_>>();
+            assert_eq!(digest, KECCAK256::decode(expected));
+        }
+
+        let (trace, public) = (writer_data.trace, writer_data.public);
+
+        let proof = timed!(
+            timing,
+            "generate stark proof",
+            stark.prove(&trace, &public, &mut timing).unwrap()
+        );
+        stark.verify(proof.clone(), &public).unwrap();
+
+        let mut pw = PartialWitness::new();
+        pw.set_target_arr(&public_input, &public);
+        stark.set_proof_target(&mut pw, &proof_target, proof);
+
+        let rec_proof = timed!(
+            timing,
+            "generate recursive proof",
+            rec_data.prove(pw).unwrap()
+        );
+        rec_data.verify(rec_proof).unwrap();
+
+        timing.print();
+    }
+}
@@ -0,0 +1,56 @@
+use serde::{Deserialize, Serialize};
+
+pub mod air;
+pub mod builder;
+pub mod pure;
+
+/// The Keccak-256 hash function, as used by Ethereum, whose permutation is Keccak-f[1600].
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub struct KECCAK256;
+
+/// The number of rounds of the Keccak-f[1600] permutation.
+const NUM_ROUNDS: usize = 24;
+/// The number of 64-bit lanes of the state.
+const STATE_LANES: usize = 25;
+/// The number of lanes absorbed per block, for a rate of 1088 bits.
+const RATE_LANES: usize = 17;
+/// The number of lanes of the state that form the 256-bit digest.
+const DIGEST_LANES: usize = 4;
+
+const ROUND_CONSTANTS: [u64; NUM_ROUNDS] = [
+    0x0000000000000001,
+    0x0000000000008082,
+    0x800000000000808a,
+    0x8000000080008000,
+    0x000000000000808b,
+    0x0000000080000001,
+    0x8000000080008081,
+    0x8000000000008009,
+    0x000000000000008a,
+    0x0000000000000088,
+    0x0000000080008009,
+    0x000000008000000a,
+    0x000000008000808b,
+    0x800000000000008b,
+    0x8000000000008089,
+    0x8000000000008003,
+    0x8000000000008002,
+    0x8000000000000080,
+    0x000000000000800a,
+    0x800000008000000a,
+    0x8000000080008081,
+    0x8000000000008080,
+    0x0000000080000001,
+    0x8000000080008008,
+];
+
+/// The rotation offsets of the rho step, indexed by the lane `x + 5 * y`.
+const ROTATION_OFFSETS: [usize; STATE_LANES] = [
+    0, 1, 62, 28, 27, 36, 44, 6, 55, 20, 3, 10, 43, 25, 39, 41, 45, 15, 21, 8, 18, 2, 61, 56, 14,
+];
+
+/// The lane `x + 5 * y` to which the pi step moves the lane at `index`, i.e. `(y, 2x + 3y)`.
+const fn pi_index(index: usize) -> usize {
+    let (x, y) = (index % 5, index / 5);
+    y + 5 * ((2 * x + 3 * y) % 5)
+}
@@ -0,0 +1,147 @@
+use super::{
+    pi_index, DIGEST_LANES, KECCAK256, NUM_ROUNDS, RATE_LANES, ROTATION_OFFSETS, ROUND_CONSTANTS,
+    STATE_LANES,
+};
+use crate::machine::hash::stream::BlockHash;
+use crate::machine::hash::HashPureInteger;
+
+impl HashPureInteger for KECCAK256 {
+    type Integer = u64;
+}
+
+pub trait KeccakPure: HashPureInteger {
+    /// Pad a byte message to a multiple of `RATE_LANES` little-endian lanes, with the `0x01 ...
+    /// 0x80` padding of the original Keccak submission.
+    fn pad(msg: &[u8]) -> Vec<Self::Integer>;
+
+    /// Applies one round of Keccak-f[1600] to `state`.
+    fn round(state: &mut [Self::Integer; STATE_LANES], round_constant: Self::Integer);
+
+    /// Applies the Keccak-f[1600] permutation to `state`.
+    fn permute(state: &mut [Self::Integer; STATE_LANES]);
+
+    /// Absorbs a padded block into `state` and permutes it.
+    fn absorb(state: &mut [Self::Integer; STATE_LANES], block: &[Self::Integer]);
+
+    fn hash(msg: &[u8]) -> [Self::Integer; DIGEST_LANES];
+
+    /// Decode a digest encoded as a hex string to its little-endian lanes.
+    fn decode(digest: &str) -> [Self::Integer; DIGEST_LANES];
+}
+
+impl KeccakPure for KECCAK256 {
+    fn pad(msg: &[u8]) -> Vec<u64> {
+        let rate_bytes = RATE_LANES * 8;
+        let mut padded_msg = msg.to_vec();
+        padded_msg.push(0x01);
+        padded_msg.resize(padded_msg.len().next_multiple_of(rate_bytes), 0);
+        *padded_msg.last_mut().unwrap() |= 0x80;
+
+        padded_msg
+            .chunks_exact(8)
+            .map(|lane| u64::from_le_bytes(lane.try_into().unwrap()))
+            .collect()
+    }
+
+    fn round(state: &mut [u64; STATE_LANES], round_constant: u64) {
+        // Theta.
+        let c: [u64; 5] = core::array::from_fn(|x| (0..5).fold(0, |acc, y| acc ^ state[x + 5 * y]));
+        for (i, lane) in state.iter_mut().enumerate() {
+            let x = i % 5;
+            *lane ^= c[(x + 4) % 5] ^ c[(x + 1) % 5].rotate_left(1);
+        }
+
+        // Rho and pi.
+        let mut b = [0u64; STATE_LANES];
+        for (i, lane) in state.iter().enumerate() {
+            b[pi_index(i)] = lane.rotate_left(ROTATION_OFFSETS[i] as u32);
+        }
+
+        // Chi.
+        for (i, lane) in state.iter_mut().enumerate() {
+            let (x, y) = (i % 5, i / 5);
+            *lane = b[i] ^ (!b[(x + 1) % 5 + 5 * y] & b[(x + 2) % 5 + 5 * y]);
+        }
+
+        // Iota.
+        state[0] ^= round_constant;
+    }
+
+    fn permute(state: &mut [u64; STATE_LANES]) {
+        for round_constant in ROUND_CONSTANTS.iter().take(NUM_ROUNDS) {
+            Self::round(state, *round_constant);
+        }
+    }
+
+    fn absorb(state: &mut [u64; STATE_LANES], block: &[u64]) {
+        assert_eq!(block.len(), RATE_LANES);
+        for (lane, word) in state.iter_mut().zip(block) {
+            *lane ^= word;
+        }
+        Self::permute(state);
+    }
+
+    fn hash(msg: &[u8]) -> [u64; DIGEST_LANES] {
+        let mut state = [0u64; STATE_LANES];
+        for block in Self::pad(msg).chunks_exact(RATE_LANES) {
+            Self::absorb(&mut state, block);
+        }
+        core::array::from_fn(|i| state[i])
+    }
+
+    fn decode(digest: &str) -> [u64; DIGEST_LANES] {
+        let bytes = hex::decode(digest).unwrap();
+        assert_eq!(bytes.len(), DIGEST_LANES * 8);
+        core::array::from_fn(|i| u64::from_le_bytes(bytes[8 * i..8 * i + 8].try_into().unwrap()))
+    }
+}
+
+impl BlockHash for KECCAK256 {
+    type State = [u64; STATE_LANES];
+
+    const BLOCK_BYTES: usize = RATE_LANES * 8;
+
+    fn initial_state() -> Self::State {
+        [0; STATE_LANES]
+    }
+
+    fn block_from_bytes(block: &[u8]) -> Vec<u64> {
+        block
+            .chunks_exact(8)
+            .map(|lane| u64::from_le_bytes(lane.try_into().unwrap()))
+            .collect()
+    }
+
+    fn pad_tail(tail: &[u8], _length: usize) -> Vec<u64> {
+        // The padding does not depend on the length of the message.
+        Self::pad(tail)
+    }
+
+    fn process_block(state: &mut Self::State, block: &[u64]) {
+        Self::absorb(state, block);
+    }
+
+    fn digest(state: &Self::State) -> Vec<u64> {
+        state[..DIGEST_LANES].to_vec()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_keccak256_pure() {
+        let msgs: [&[u8]; 2] = [b"", b"abc"];
+        let digests = [
+            "c5d2460186f7233c927e7db2dcc703c0e500b653ca82273b7bfad8045d85a470",
+            "4e03657aea45a94fc7d47ba826c8d667c0d1e6e33a64a036ec44f58fa12d6c45",
+        ];
+        for (msg, digest) in msgs.iter().zip(digests) {
+            assert_eq!(KECCAK256::hash(msg), KECCAK256::decode(digest));
+        }
+
+        // A message longer than the rate is padded to two blocks.
+        assert_eq!(KECCAK256::pad(&[0xab; 200]).len(), 2 * RATE_LANES);
+    }
+}
@@ -8,7 +8,9 @@ use crate::chip::register::array::ArrayRegister;
 use crate::chip::register::Register;
 
 pub mod blake;
+pub mod keccak;
 pub mod sha;
+pub mod stream;
 
 pub trait HashPureInteger {
     type Integer: Num + Copy + Debug;
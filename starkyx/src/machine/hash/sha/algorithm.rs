@@ -28,6 +28,11 @@ pub trait SHAPure<const CYCLE_LENGTH: usize>:
     const INITIAL_HASH: [Self::Integer; 8];
     const ROUND_CONSTANTS: [Self::Integer; CYCLE_LENGTH];
 
+    /// The number of words of the final hash state that form the digest.
+    ///
+    /// Truncated variants, such as SHA-224 and SHA-384, output fewer than the eight state words.
+    const DIGEST_WORDS: usize = 8;
+
     /// Pad a byte message to a vector of `Self::Integer` values.
     fn pad(msg: &[u8]) -> Vec<Self::Integer>;
 
@@ -38,6 +43,8 @@ pub trait SHAPure<const CYCLE_LENGTH: usize>:
     fn process(hash: [Self::Integer; 8], w: &[Self::Integer; CYCLE_LENGTH]) -> [Self::Integer; 8];
 
     /// Decode a digest encoded as a string to a vector of `Self::Integer` values.
+    ///
+    /// For truncated variants, the words past `DIGEST_WORDS` are set to zero.
     fn decode(digest: &str) -> [Self::Integer; 8];
 }
 
@@ -96,6 +103,12 @@ pub trait SHAir<B: Builder, const CYCLE_LENGTH: usize>:
         vars_next: &[Self::IntRegister],
     ) -> Self::StateVariable;
 
+    /// The digest of a final hash state, given by its first `DIGEST_WORDS` words.
+    fn digest(state: Self::StateVariable) -> ArrayRegister<Self::IntRegister> {
+        let state: ArrayRegister<Self::IntRegister> = state.into();
+        state.get_subarray(0..Self::DIGEST_WORDS)
+    }
+
     fn sha(
         builder: &mut B,
         padded_chunks: &[ArrayRegister<Self::IntRegister>],
@@ -152,12 +152,12 @@ pub mod test_utils {
         // Compare expected digests with the trace values.
         let writer = writer_data.public_writer();
         for (digest, expected) in hash_state.iter().zip_eq(expected_digests) {
-            let array: ArrayRegister<S::IntRegister> = (*digest).into();
-            let digest = writer
-                .read_array::<_, 8>(&array)
-                .map(|x| S::field_value_to_int(&x));
+            let digest = S::digest(*digest)
+                .iter()
+                .map(|word| S::field_value_to_int(&writer.read(&word)))
+                .collect::<Vec<_>>();
             let expected_digest = S::decode(expected);
-            assert_eq!(digest, expected_digest);
+            assert_eq!(digest, expected_digest[..S::DIGEST_WORDS]);
         }
 
         let (trace, public) = (writer_data.trace, writer_data.public);
@@ -0,0 +1,164 @@
+use super::SHA224;
+use crate::chip::memory::pointer::slice::Slice;
+use crate::chip::memory::time::Time;
+use crate::chip::register::array::ArrayRegister;
+use crate::chip::register::bit::BitRegister;
+use crate::chip::register::element::ElementRegister;
+use crate::chip::register::Register;
+use crate::chip::uint::operations::instruction::UintInstructions;
+use crate::chip::uint::register::{U32Register, U64Register};
+use crate::chip::uint::util::{u32_from_le_field_bytes, u32_to_le_field_bytes};
+use crate::chip::AirParameters;
+use crate::machine::builder::Builder;
+use crate::machine::bytes::builder::BytesBuilder;
+use crate::machine::hash::sha::algorithm::SHAir;
+use crate::machine::hash::sha::sha256::register::SHA256DigestRegister;
+use crate::machine::hash::sha::sha256::SHA256;
+use crate::machine::hash::{HashDigest, HashIntConversion, HashInteger};
+
+impl<B: Builder> HashInteger<B> for SHA224 {
+    type IntRegister = U32Register;
+    type Value = <U32Register as Register>::Value<B::Field>;
+}
+
+impl<B: Builder> HashIntConversion<B> for SHA224 {
+    fn int_to_field_value(int: Self::Integer) -> Self::Value {
+        u32_to_le_field_bytes(int)
+    }
+
+    fn field_value_to_int(value: &Self::Value) -> Self::Integer {
+        u32_from_le_field_bytes(value)
+    }
+}
+
+impl<B: Builder> HashDigest<B> for SHA224 {
+    type DigestRegister = SHA256DigestRegister;
+}
+
+/// The steps of SHA-224 are the ones of SHA-256, only the initial hash and the digest length
+/// differ.
+impl<L: AirParameters> SHAir<BytesBuilder<L>, 64> for SHA224
+where
+    L::Instruction: UintInstructions,
+{
+    type StateVariable = SHA256DigestRegister;
+    type StatePointer = Slice<U64Register>;
+
+    fn clk(builder: &mut BytesBuilder<L>) -> ElementRegister {
+        SHA256::clk(builder)
+    }
+
+    fn cycles_end_bits(builder: &mut BytesBuilder<L>) -> (BitRegister, BitRegister) {
+        SHA256::cycles_end_bits(builder)
+    }
+
+    fn load_state(
+        builder: &mut BytesBuilder<L>,
+        hash_state_public: &[Self::StateVariable],
+        digest_indices: ArrayRegister<ElementRegister>,
+    ) -> Self::StatePointer {
+        SHA256::load_state(builder, hash_state_public, digest_indices)
+    }
+
+    fn store_state(
+        builder: &mut BytesBuilder<L>,
+        state_ptr: &Self::StatePointer,
+        state_next: Self::StateVariable,
+        time: &Time<L::Field>,
+        flag: Option<ElementRegister>,
+    ) {
+        SHA256::store_state(builder, state_ptr, state_next, time, flag)
+    }
+
+    fn preprocessing_step(
+        builder: &mut BytesBuilder<L>,
+        w_i_minus_15: Self::IntRegister,
+        w_i_minus_2: Self::IntRegister,
+        w_i_mimus_16: Self::IntRegister,
+        w_i_mimus_7: Self::IntRegister,
+    ) -> Self::IntRegister {
+        SHA256::preprocessing_step(
+            builder,
+            w_i_minus_15,
+            w_i_minus_2,
+            w_i_mimus_16,
+            w_i_mimus_7,
+        )
+    }
+
+    fn processing_step(
+        builder: &mut BytesBuilder<L>,
+        vars: ArrayRegister<Self::IntRegister>,
+        w_i: Self::IntRegister,
+        round_constant: Self::IntRegister,
+    ) -> Vec<Self::IntRegister> {
+        SHA256::processing_step(builder, vars, w_i, round_constant)
+    }
+
+    fn absorb(
+        builder: &mut BytesBuilder<L>,
+        state: ArrayRegister<Self::IntRegister>,
+        vars_next: &[Self::IntRegister],
+    ) -> Self::StateVariable {
+        SHA256::absorb(builder, state, vars_next)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use core::iter;
+
+    use plonky2::field::goldilocks_field::GoldilocksField;
+    use serde::{Deserialize, Serialize};
+
+    use super::*;
+    use crate::chip::uint::operations::instruction::UintInstruction;
+    use crate::machine::hash::sha::builder::test_utils::test_sha;
+    use crate::math::goldilocks::cubic::GoldilocksCubicParameters;
+
+    #[derive(Clone, Debug, Serialize, Deserialize)]
+    pub struct SHA224Test;
+
+    impl AirParameters for SHA224Test {
+        type Field = GoldilocksField;
+        type CubicParams = GoldilocksCubicParameters;
+
+        type Instruction = UintInstruction;
+
+        const NUM_FREE_COLUMNS: usize = 418;
+        const EXTENDED_COLUMNS: usize = 912;
+    }
+
+    fn test_sha224<'a, I: IntoIterator<Item = &'a [u8]>, J: IntoIterator<Item = &'a str>>(
+        messages: I,
+        expected_digests: J,
+    ) {
+        test_sha::<SHA224Test, SHA224, _, _, 64>(messages, expected_digests)
+    }
+
+    #[test]
+    fn test_sha224_short_message() {
+        let msg = b"abc";
+        let expected_digest = "23097d223405d8228642a477bda255b32aadbce4bda0b3f7e36c9da7";
+        let num_messages = 2;
+        test_sha224(
+            iter::repeat(msg).take(num_messages).map(|x| x.as_slice()),
+            iter::repeat(expected_digest).take(num_messages),
+        )
+    }
+
+    #[test]
+    fn test_sha224_changing_length_message() {
+        let empty_expected_digest = "d14a028c2a3a2bc9476102bb288234c415a2b01f828ea62ac5b3e42f";
+        let short_msg = b"abc";
+        let short_expected_digest = "23097d223405d8228642a477bda255b32aadbce4bda0b3f7e36c9da7";
+        test_sha224(
+            [b"".as_slice(), short_msg.as_slice(), b"".as_slice()],
+            [
+                empty_expected_digest,
+                short_expected_digest,
+                empty_expected_digest,
+            ],
+        );
+    }
+}
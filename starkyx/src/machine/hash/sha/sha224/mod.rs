@@ -0,0 +1,14 @@
+//! SHA-224, which uses the SHA-256 compression function with a different initial hash and
+//! truncates the digest to its first seven words.
+
+use serde::{Deserialize, Serialize};
+
+pub mod air;
+pub mod pure;
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub struct SHA224;
+
+pub(crate) const INITIAL_HASH: [u32; 8] = [
+    0xc1059ed8, 0x367cd507, 0x3070dd17, 0xf70e5939, 0xffc00b31, 0x68581511, 0x64f98fa7, 0xbefa4fa4,
+];
@@ -0,0 +1,37 @@
+use super::{INITIAL_HASH, SHA224};
+use crate::machine::hash::sha::algorithm::SHAPure;
+use crate::machine::hash::sha::sha256::{ROUND_CONSTANTS, SHA256};
+use crate::machine::hash::HashPureInteger;
+
+impl HashPureInteger for SHA224 {
+    type Integer = u32;
+}
+
+impl SHAPure<64> for SHA224 {
+    const INITIAL_HASH: [Self::Integer; 8] = INITIAL_HASH;
+    const ROUND_CONSTANTS: [Self::Integer; 64] = ROUND_CONSTANTS;
+    const DIGEST_WORDS: usize = 7;
+
+    fn pad(msg: &[u8]) -> Vec<Self::Integer> {
+        SHA256::pad(msg)
+    }
+
+    fn pre_process(chunk: &[Self::Integer]) -> [Self::Integer; 64] {
+        SHA256::pre_process(chunk)
+    }
+
+    fn process(hash: [Self::Integer; 8], w: &[Self::Integer; 64]) -> [Self::Integer; 8] {
+        SHA256::process(hash, w)
+    }
+
+    fn decode(digest: &str) -> [Self::Integer; 8] {
+        let mut words = [0u32; 8];
+        for (word, bytes) in words
+            .iter_mut()
+            .zip(hex::decode(digest).unwrap().chunks_exact(4))
+        {
+            *word = u32::from_be_bytes(bytes.try_into().unwrap());
+        }
+        words
+    }
+}
@@ -1,5 +1,6 @@
 use super::{INITIAL_HASH, ROUND_CONSTANTS, SHA256};
 use crate::machine::hash::sha::algorithm::SHAPure;
+use crate::machine::hash::stream::BlockHash;
 use crate::machine::hash::HashPureInteger;
 
 impl HashPureInteger for SHA256 {
@@ -77,6 +78,41 @@ impl SHAPure<64> for SHA256 {
     }
 }
 
+impl BlockHash for SHA256 {
+    type State = [u32; 8];
+
+    const BLOCK_BYTES: usize = 64;
+
+    fn initial_state() -> Self::State {
+        INITIAL_HASH
+    }
+
+    fn block_from_bytes(block: &[u8]) -> Vec<u32> {
+        block
+            .chunks_exact(4)
+            .map(|slice| u32::from_be_bytes(slice.try_into().unwrap()))
+            .collect()
+    }
+
+    fn pad_tail(tail: &[u8], length: usize) -> Vec<u32> {
+        // The padding of the tail only differs from that of the message by its length.
+        let mut padded_tail = Self::pad(tail);
+        let num_words = padded_tail.len();
+        let len = (length * 8) as u64;
+        padded_tail[num_words - 2] = (len >> 32) as u32;
+        padded_tail[num_words - 1] = len as u32;
+        padded_tail
+    }
+
+    fn process_block(state: &mut Self::State, block: &[u32]) {
+        *state = Self::process(*state, &Self::pre_process(block));
+    }
+
+    fn digest(state: &Self::State) -> Vec<u32> {
+        state.to_vec()
+    }
+}
+
 pub fn step(msg: [u32; 8], w_i: u32, round_constant: u32) -> [u32; 8] {
     let mut a = msg[0];
     let mut b = msg[1];
@@ -0,0 +1,164 @@
+use super::SHA384;
+use crate::chip::memory::pointer::slice::Slice;
+use crate::chip::memory::time::Time;
+use crate::chip::register::array::ArrayRegister;
+use crate::chip::register::bit::BitRegister;
+use crate::chip::register::element::ElementRegister;
+use crate::chip::register::Register;
+use crate::chip::uint::operations::instruction::UintInstructions;
+use crate::chip::uint::register::U64Register;
+use crate::chip::uint::util::{u64_from_le_field_bytes, u64_to_le_field_bytes};
+use crate::chip::AirParameters;
+use crate::machine::builder::Builder;
+use crate::machine::bytes::builder::BytesBuilder;
+use crate::machine::hash::sha::algorithm::SHAir;
+use crate::machine::hash::sha::sha512::register::SHA512DigestRegister;
+use crate::machine::hash::sha::sha512::SHA512;
+use crate::machine::hash::{HashDigest, HashIntConversion, HashInteger};
+
+impl<B: Builder> HashInteger<B> for SHA384 {
+    type IntRegister = U64Register;
+    type Value = <U64Register as Register>::Value<B::Field>;
+}
+
+impl<B: Builder> HashIntConversion<B> for SHA384 {
+    fn int_to_field_value(int: Self::Integer) -> Self::Value {
+        u64_to_le_field_bytes(int)
+    }
+
+    fn field_value_to_int(value: &Self::Value) -> Self::Integer {
+        u64_from_le_field_bytes(value)
+    }
+}
+
+impl<B: Builder> HashDigest<B> for SHA384 {
+    type DigestRegister = SHA512DigestRegister;
+}
+
+/// The steps of SHA-384 are the ones of SHA-512, only the initial hash and the digest length
+/// differ.
+impl<L: AirParameters> SHAir<BytesBuilder<L>, 80> for SHA384
+where
+    L::Instruction: UintInstructions,
+{
+    type StateVariable = SHA512DigestRegister;
+    type StatePointer = Slice<U64Register>;
+
+    fn clk(builder: &mut BytesBuilder<L>) -> ElementRegister {
+        SHA512::clk(builder)
+    }
+
+    fn cycles_end_bits(builder: &mut BytesBuilder<L>) -> (BitRegister, BitRegister) {
+        SHA512::cycles_end_bits(builder)
+    }
+
+    fn load_state(
+        builder: &mut BytesBuilder<L>,
+        hash_state_public: &[Self::StateVariable],
+        digest_indices: ArrayRegister<ElementRegister>,
+    ) -> Self::StatePointer {
+        SHA512::load_state(builder, hash_state_public, digest_indices)
+    }
+
+    fn store_state(
+        builder: &mut BytesBuilder<L>,
+        state_ptr: &Self::StatePointer,
+        state_next: Self::StateVariable,
+        time: &Time<L::Field>,
+        flag: Option<ElementRegister>,
+    ) {
+        SHA512::store_state(builder, state_ptr, state_next, time, flag)
+    }
+
+    fn preprocessing_step(
+        builder: &mut BytesBuilder<L>,
+        w_i_minus_15: Self::IntRegister,
+        w_i_minus_2: Self::IntRegister,
+        w_i_mimus_16: Self::IntRegister,
+        w_i_mimus_7: Self::IntRegister,
+    ) -> Self::IntRegister {
+        SHA512::preprocessing_step(
+            builder,
+            w_i_minus_15,
+            w_i_minus_2,
+            w_i_mimus_16,
+            w_i_mimus_7,
+        )
+    }
+
+    fn processing_step(
+        builder: &mut BytesBuilder<L>,
+        vars: ArrayRegister<Self::IntRegister>,
+        w_i: Self::IntRegister,
+        round_constant: Self::IntRegister,
+    ) -> Vec<Self::IntRegister> {
+        SHA512::processing_step(builder, vars, w_i, round_constant)
+    }
+
+    fn absorb(
+        builder: &mut BytesBuilder<L>,
+        state: ArrayRegister<Self::IntRegister>,
+        vars_next: &[Self::IntRegister],
+    ) -> Self::StateVariable {
+        SHA512::absorb(builder, state, vars_next)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use core::iter;
+
+    use plonky2::field::goldilocks_field::GoldilocksField;
+    use serde::{Deserialize, Serialize};
+
+    use super::*;
+    use crate::chip::uint::operations::instruction::UintInstruction;
+    use crate::machine::hash::sha::builder::test_utils::test_sha;
+    use crate::math::goldilocks::cubic::GoldilocksCubicParameters;
+
+    #[derive(Clone, Debug, Serialize, Deserialize)]
+    pub struct SHA384Test;
+
+    impl AirParameters for SHA384Test {
+        type Field = GoldilocksField;
+        type CubicParams = GoldilocksCubicParameters;
+
+        type Instruction = UintInstruction;
+
+        const NUM_FREE_COLUMNS: usize = 815;
+        const EXTENDED_COLUMNS: usize = 1782;
+    }
+
+    fn test_sha384<'a, I: IntoIterator<Item = &'a [u8]>, J: IntoIterator<Item = &'a str>>(
+        messages: I,
+        expected_digests: J,
+    ) {
+        test_sha::<SHA384Test, SHA384, _, _, 80>(messages, expected_digests)
+    }
+
+    #[test]
+    fn test_sha384_short_message() {
+        let msg = b"abc";
+        let expected_digest = "cb00753f45a35e8bb5a03d699ac65007272c32ab0eded1631a8b605a43ff5bed8086072ba1e7cc2358baeca134c825a7";
+        let num_messages = 2;
+        test_sha384(
+            iter::repeat(msg).take(num_messages).map(|x| x.as_slice()),
+            iter::repeat(expected_digest).take(num_messages),
+        )
+    }
+
+    #[test]
+    fn test_sha384_changing_length_message() {
+        let empty_expected_digest = "38b060a751ac96384cd9327eb1b1e36a21fdb71114be07434c0cc7bf63f6e1da274edebfe76f65fbd51ad2f14898b95b";
+        let short_msg = b"abc";
+        let short_expected_digest = "cb00753f45a35e8bb5a03d699ac65007272c32ab0eded1631a8b605a43ff5bed8086072ba1e7cc2358baeca134c825a7";
+        test_sha384(
+            [b"".as_slice(), short_msg.as_slice(), b"".as_slice()],
+            [
+                empty_expected_digest,
+                short_expected_digest,
+                empty_expected_digest,
+            ],
+        );
+    }
+}
@@ -0,0 +1,37 @@
+use super::{INITIAL_HASH, SHA384};
+use crate::machine::hash::sha::algorithm::SHAPure;
+use crate::machine::hash::sha::sha512::{ROUND_CONSTANTS, SHA512};
+use crate::machine::hash::HashPureInteger;
+
+impl HashPureInteger for SHA384 {
+    type Integer = u64;
+}
+
+impl SHAPure<80> for SHA384 {
+    const INITIAL_HASH: [Self::Integer; 8] = INITIAL_HASH;
+    const ROUND_CONSTANTS: [Self::Integer; 80] = ROUND_CONSTANTS;
+    const DIGEST_WORDS: usize = 6;
+
+    fn pad(msg: &[u8]) -> Vec<Self::Integer> {
+        SHA512::pad(msg)
+    }
+
+    fn pre_process(chunk: &[Self::Integer]) -> [Self::Integer; 80] {
+        SHA512::pre_process(chunk)
+    }
+
+    fn process(hash: [Self::Integer; 8], w: &[Self::Integer; 80]) -> [Self::Integer; 8] {
+        SHA512::process(hash, w)
+    }
+
+    fn decode(digest: &str) -> [Self::Integer; 8] {
+        let mut words = [0u64; 8];
+        for (word, bytes) in words
+            .iter_mut()
+            .zip(hex::decode(digest).unwrap().chunks_exact(8))
+        {
+            *word = u64::from_be_bytes(bytes.try_into().unwrap());
+        }
+        words
+    }
+}
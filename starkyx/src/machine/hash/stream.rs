@@ -0,0 +1,406 @@
+use core::marker::PhantomData;
+
+use super::sha::algorithm::SHAir;
+use super::HashPureInteger;
+use crate::chip::register::array::ArrayRegister;
+use crate::chip::register::bit::BitRegister;
+use crate::chip::register::element::ElementRegister;
+use crate::chip::register::memory::MemorySlice;
+use crate::chip::register::{Register, RegisterSerializable};
+use crate::chip::trace::writer::AirWriter;
+use crate::chip::uint::bytes::register::ByteRegister;
+use crate::machine::builder::Builder;
+use crate::math::prelude::*;
+
+/// A hash function whose padded message is processed one block at a time, so that its digest can
+/// be computed from a message given in several parts.
+pub trait BlockHash: HashPureInteger {
+    type State: Copy;
+
+    /// The number of message bytes in a block.
+    const BLOCK_BYTES: usize;
+
+    fn initial_state() -> Self::State;
+
+    /// Converts a full block of message bytes to `Self::Integer` values.
+    fn block_from_bytes(block: &[u8]) -> Vec<Self::Integer>;
+
+    /// Pads the remaining bytes `tail` of a message of `length` bytes to full blocks.
+    fn pad_tail(tail: &[u8], length: usize) -> Vec<Self::Integer>;
+
+    /// Processes a padded block.
+    fn process_block(state: &mut Self::State, block: &[Self::Integer]);
+
+    /// The digest of the final state.
+    fn digest(state: &Self::State) -> Vec<Self::Integer>;
+}
+
+/// The padded message and digest of a message given to a [`HashBuilderPure`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HashOutput<H: HashPureInteger> {
+    /// The padded message, as expected for the padded chunks of the hash AIR.
+    pub padded_message: Vec<H::Integer>,
+    pub digest: Vec<H::Integer>,
+}
+
+/// Computes a hash from a message given in several parts with [`Self::update`].
+///
+/// The full blocks are processed as soon as they are available, and the padding is only applied
+/// to the remaining bytes in [`Self::finalize`]. This is the pure counterpart of [`HashBuilder`].
+#[derive(Debug, Clone)]
+pub struct HashBuilderPure<H: BlockHash> {
+    state: H::State,
+    buffer: Vec<u8>,
+    length: usize,
+    padded_message: Vec<H::Integer>,
+    _marker: PhantomData<H>,
+}
+
+impl<H: BlockHash> HashBuilderPure<H> {
+    pub fn new() -> Self {
+        Self {
+            state: H::initial_state(),
+            buffer: Vec::with_capacity(H::BLOCK_BYTES),
+            length: 0,
+            padded_message: Vec::new(),
+            _marker: PhantomData,
+        }
+    }
+
+    /// Appends `data` to the message.
+    pub fn update(&mut self, data: &[u8]) -> &mut Self {
+        self.length += data.len();
+        self.buffer.extend_from_slice(data);
+
+        let num_full_bytes = self.buffer.len() - self.buffer.len() % H::BLOCK_BYTES;
+        for block in self.buffer[..num_full_bytes].chunks_exact(H::BLOCK_BYTES) {
+            let block = H::block_from_bytes(block);
+            H::process_block(&mut self.state, &block);
+            self.padded_message.extend(block);
+        }
+        self.buffer.drain(..num_full_bytes);
+        self
+    }
+
+    /// Pads the message and returns it along with its digest.
+    pub fn finalize(mut self) -> HashOutput<H> {
+        let tail = H::pad_tail(&self.buffer, self.length);
+        let block_length = H::BLOCK_BYTES / core::mem::size_of::<H::Integer>();
+        for block in tail.chunks_exact(block_length) {
+            H::process_block(&mut self.state, block);
+        }
+        self.padded_message.extend(tail);
+
+        HashOutput {
+            padded_message: self.padded_message,
+            digest: H::digest(&self.state),
+        }
+    }
+}
+
+impl<H: BlockHash> Default for HashBuilderPure<H> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Builds the SHA hash of a message of public byte registers given in several parts with
+/// [`Self::update`].
+///
+/// The padding is applied in [`Self::finalize`], which allocates the padded chunks of the message
+/// as public registers and constrains them to hold the message bytes followed by the padding
+/// bytes. Since the length of the message is known when building the AIR, the padding bytes are
+/// constants.
+#[derive(Debug, Clone)]
+pub struct HashBuilder<B: Builder, S, const CYCLE_LENGTH: usize> {
+    message: Vec<ByteRegister>,
+    _marker: PhantomData<(B, S)>,
+}
+
+/// The registers of a message hashed with a [`HashBuilder`].
+#[derive(Debug, Clone)]
+pub struct HashRegisters<B: Builder, S: SHAir<B, CYCLE_LENGTH>, const CYCLE_LENGTH: usize> {
+    pub padded_chunks: Vec<ArrayRegister<S::IntRegister>>,
+    pub end_bits: ArrayRegister<BitRegister>,
+    pub digest_index: ElementRegister,
+    pub state: S::StateVariable,
+}
+
+impl<B: Builder, S: SHAir<B, CYCLE_LENGTH>, const CYCLE_LENGTH: usize>
+    HashBuilder<B, S, CYCLE_LENGTH>
+{
+    pub fn new() -> Self {
+        Self {
+            message: Vec::new(),
+            _marker: PhantomData,
+        }
+    }
+
+    /// Appends the public byte registers `data` to the message.
+    pub fn update(&mut self, data: &[ByteRegister]) -> &mut Self {
+        for byte in data {
+            assert!(
+                matches!(byte.register(), MemorySlice::Public(_, _)),
+                "The message of a hash builder must consist of public registers"
+            );
+        }
+        self.message.extend_from_slice(data);
+        self
+    }
+
+    /// Pads the message and hashes it in the SHA AIR.
+    ///
+    /// The AIR must not contain another SHA computation.
+    pub fn finalize(self, builder: &mut B) -> HashRegisters<B, S, CYCLE_LENGTH> {
+        let length = self.message.len();
+        // The padding only depends on the length of the message.
+        let padded_zeros = S::pad(&vec![0u8; length]);
+        assert_eq!(padded_zeros.len() % 16, 0);
+        let num_chunks = padded_zeros.len() / 16;
+
+        let padded_chunks = (0..num_chunks)
+            .map(|_| builder.alloc_array_public::<S::IntRegister>(16))
+            .collect::<Vec<_>>();
+        let words = padded_chunks.iter().flat_map(|chunk| chunk.iter());
+        for (i, (word, padded_word)) in words.zip(padded_zeros).enumerate() {
+            let padded_word = S::int_to_field_value(padded_word);
+            let padded_bytes = S::IntRegister::align(&padded_word);
+            // The words are big-endian while their registers hold little-endian bytes.
+            let bytes = ArrayRegister::<ByteRegister>::from_register_unsafe(*word.register());
+            let num_bytes = bytes.len();
+            for (j, (byte, padded_byte)) in bytes.iter().zip(padded_bytes.iter()).enumerate() {
+                let position = i * num_bytes + num_bytes - 1 - j;
+                if position < length {
+                    builder.assert_equal(&byte, &self.message[position]);
+                } else {
+                    builder.assert_expression_zero(byte.expr() - *padded_byte);
+                }
+            }
+        }
+
+        // The message is hashed on its own, so its digest is the state after the last chunk.
+        let end_bits = builder.alloc_array_public::<BitRegister>(num_chunks);
+        for (i, end_bit) in end_bits.iter().enumerate() {
+            let value = if i == num_chunks - 1 {
+                B::Field::ONE
+            } else {
+                B::Field::ZERO
+            };
+            builder.assert_expression_zero(end_bit.expr() - value);
+        }
+        let digest_indices = builder.alloc_array_public::<ElementRegister>(1);
+        let digest_index = digest_indices.get(0);
+        builder.assert_expression_zero(
+            digest_index.expr() - B::Field::from_canonical_usize(num_chunks - 1),
+        );
+
+        let state = S::sha(
+            builder,
+            &padded_chunks,
+            &end_bits,
+            &end_bits,
+            digest_indices,
+        )[0];
+
+        HashRegisters {
+            padded_chunks,
+            end_bits,
+            digest_index,
+            state,
+        }
+    }
+}
+
+impl<B: Builder, S: SHAir<B, CYCLE_LENGTH>, const CYCLE_LENGTH: usize> Default
+    for HashBuilder<B, S, CYCLE_LENGTH>
+{
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<B: Builder, S: SHAir<B, CYCLE_LENGTH>, const CYCLE_LENGTH: usize>
+    HashRegisters<B, S, CYCLE_LENGTH>
+{
+    /// The digest registers.
+    pub fn digest(&self) -> ArrayRegister<S::IntRegister> {
+        S::digest(self.state)
+    }
+
+    /// Writes the padded chunks, end bits, digest index and final state for the message `msg`.
+    ///
+    /// The message registers given to the [`HashBuilder`] are written separately.
+    pub fn write(&self, writer: &mut impl AirWriter<Field = B::Field>, msg: &[u8]) {
+        let padded_msg = S::pad(msg);
+        assert_eq!(padded_msg.len(), self.padded_chunks.len() * 16);
+
+        let mut state = S::INITIAL_HASH;
+        for (register, chunk) in self.padded_chunks.iter().zip(padded_msg.chunks_exact(16)) {
+            writer.write_array(register, chunk.iter().map(|x| S::int_to_field_value(*x)));
+            state = S::process(state, &S::pre_process(chunk));
+        }
+
+        let num_chunks = self.padded_chunks.len();
+        for (i, end_bit) in self.end_bits.iter().enumerate() {
+            let value = if i == num_chunks - 1 {
+                B::Field::ONE
+            } else {
+                B::Field::ZERO
+            };
+            writer.write(&end_bit, &value);
+        }
+        writer.write(
+            &self.digest_index,
+            &B::Field::from_canonical_usize(num_chunks - 1),
+        );
+        let state_array: ArrayRegister<S::IntRegister> = self.state.into();
+        writer.write_array(&state_array, state.map(S::int_to_field_value));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use plonky2::field::goldilocks_field::GoldilocksField;
+    use plonky2::iop::witness::{PartialWitness, WitnessWrite};
+    use plonky2::plonk::circuit_builder::CircuitBuilder;
+    use plonky2::plonk::circuit_data::CircuitConfig;
+    use plonky2::util::log2_ceil;
+    use plonky2::util::timing::TimingTree;
+    use serde::{Deserialize, Serialize};
+
+    use super::*;
+    use crate::chip::trace::writer::data::AirWriterData;
+    use crate::chip::uint::operations::instruction::UintInstruction;
+    use crate::chip::uint::util::u32_from_le_field_bytes;
+    use crate::chip::AirParameters;
+    use crate::machine::bytes::builder::BytesBuilder;
+    use crate::machine::hash::keccak::pure::KeccakPure;
+    use crate::machine::hash::keccak::KECCAK256;
+    use crate::machine::hash::sha::algorithm::SHAPure;
+    use crate::machine::hash::sha::sha256::SHA256;
+    use crate::math::goldilocks::cubic::GoldilocksCubicParameters;
+    use crate::plonky2::stark::config::{CurtaConfig, CurtaPoseidonGoldilocksConfig};
+
+    #[derive(Clone, Debug, Serialize, Deserialize)]
+    pub struct HashBuilderTest;
+
+    impl AirParameters for HashBuilderTest {
+        type Field = GoldilocksField;
+        type CubicParams = GoldilocksCubicParameters;
+
+        type Instruction = UintInstruction;
+
+        const NUM_FREE_COLUMNS: usize = 418;
+        const EXTENDED_COLUMNS: usize = 912;
+    }
+
+    /// Proves the SHA-256 hash of `msg` given to a [`HashBuilder`] in parts of `part_length`
+    /// bytes, verifies the proof in a circuit and returns the digest.
+    fn prove_sha256_in_parts(msg: &[u8], part_length: usize) -> Vec<u32> {
+        type F = GoldilocksField;
+        type L = HashBuilderTest;
+        type C = CurtaPoseidonGoldilocksConfig;
+        type Config = <C as CurtaConfig<2>>::GenericConfig;
+
+        let mut builder = BytesBuilder::<L>::new();
+        let message = builder.alloc_array_public::<ByteRegister>(msg.len());
+        let message_bytes = message.iter().collect::<Vec<_>>();
+        let mut hasher = HashBuilder::<_, SHA256, 64>::new();
+        for part in message_bytes.chunks(part_length) {
+            hasher.update(part);
+        }
+        let hash = hasher.finalize(&mut builder);
+
+        let num_rows = 1 << log2_ceil(64 * hash.padded_chunks.len());
+        let stark = builder.build::<C, 2>(num_rows);
+
+        let config_rec = CircuitConfig::standard_recursion_config();
+        let mut recursive_builder = CircuitBuilder::<F, 2>::new(config_rec);
+        let (proof_target, public_input) =
+            stark.add_virtual_proof_with_pis_target(&mut recursive_builder);
+        stark.verify_circuit(&mut recursive_builder, &proof_target, &public_input);
+        let rec_data = recursive_builder.build::<Config>();
+
+        let mut writer_data = AirWriterData::new(&stark.air_data, num_rows);
+        let mut writer = writer_data.public_writer();
+        writer.write_array(&message, msg.iter().map(|b| F::from_canonical_u8(*b)));
+        hash.write(&mut writer, msg);
+        stark.air_data.write_global_instructions(&mut writer);
+        for mut chunk in writer_data.chunks(num_rows) {
+            for i in 0..num_rows {
+                let mut writer = chunk.window_writer(i);
+                stark.air_data.write_trace_instructions(&mut writer);
+            }
+        }
+
+        let writer = writer_data.public_writer();
+        let digest = hash
+            .digest()
+            .iter()
+            .map(|word| u32_from_le_field_bytes(&writer.read(&word)))
+            .collect::<Vec<_>>();
+
+        let (trace, public) = (writer_data.trace, writer_data.public);
+        let mut timing = TimingTree::default();
+        let proof = stark.prove(&trace, &public, &mut timing).unwrap();
+        stark.verify(proof.clone(), &public).unwrap();
+
+        let mut pw = PartialWitness::new();
+        pw.set_target_arr(&public_input, &public);
+        stark.set_proof_target(&mut pw, &proof_target, proof);
+        let rec_proof = rec_data.prove(pw).unwrap();
+        rec_data.verify(rec_proof).unwrap();
+
+        digest
+    }
+
+    #[test]
+    fn test_hash_builder_sha256_air() {
+        let msg = (0..100u8).collect::<Vec<_>>();
+        let expected_digest = hash_in_parts::<SHA256>(&msg, msg.len()).digest;
+
+        let one_shot_digest = prove_sha256_in_parts(&msg, msg.len());
+        assert_eq!(one_shot_digest, expected_digest);
+        let chunked_digest = prove_sha256_in_parts(&msg, 7);
+        assert_eq!(chunked_digest, one_shot_digest);
+    }
+
+    fn hash_in_parts<H: BlockHash>(msg: &[u8], part_length: usize) -> HashOutput<H> {
+        let mut hasher = HashBuilderPure::<H>::new();
+        for part in msg.chunks(part_length) {
+            hasher.update(part);
+        }
+        hasher.finalize()
+    }
+
+    #[test]
+    fn test_hash_builder() {
+        let msgs = [vec![], b"abc".to_vec(), vec![0xab; 200], vec![0x5a; 1000]];
+
+        for msg in msgs.iter() {
+            let padded_msg = SHA256::pad(msg);
+            let mut state = SHA256::INITIAL_HASH;
+            for chunk in padded_msg.chunks_exact(16) {
+                state = SHA256::process(state, &SHA256::pre_process(chunk));
+            }
+            let keccak_digest = KECCAK256::hash(msg);
+
+            for part_length in [1, 7, 64, 135, 136, 300] {
+                let output = hash_in_parts::<SHA256>(msg, part_length);
+                assert_eq!(output.padded_message, padded_msg);
+                assert_eq!(output.digest, state);
+
+                let output = hash_in_parts::<KECCAK256>(msg, part_length);
+                assert_eq!(output.padded_message, KECCAK256::pad(msg));
+                assert_eq!(output.digest, keccak_digest);
+            }
+        }
+
+        let mut hasher = HashBuilderPure::<SHA256>::new();
+        hasher.update(b"a").update(b"bc");
+        assert_eq!(
+            hasher.finalize().digest,
+            SHA256::decode("ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad")
+        );
+    }
+}
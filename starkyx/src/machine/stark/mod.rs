@@ -78,33 +78,33 @@ where
         // Insert public inputs into writer.
         writer.public_mut().unwrap().copy_from_slice(public_values);
 
-        // Write the range check table and multiplicitiies
-        if let Some((LookupTable::Element(table), LookupValues::Element(values))) =
-            &self.air_data.range_data
-        {
-            assert_eq!(table.table.len(), 1);
-            let table_column = table.table[0];
-            for i in 0..num_rows {
-                writer.write(&table_column, &L::Field::from_canonical_usize(i), i);
+        // Write the range check tables and multiplicitiies
+        for range_data in self.air_data.range_checks() {
+            if let (LookupTable::Element(table), LookupValues::Element(values)) = range_data {
+                assert_eq!(table.table.len(), 1);
+                let table_column = table.table[0];
+                for i in 0..num_rows {
+                    writer.write(&table_column, &L::Field::from_canonical_usize(i), i);
+                }
+
+                writer.write_multiplicities_from_fn(
+                    num_rows,
+                    table,
+                    Self::range_fn,
+                    &values
+                        .trace_values
+                        .iter()
+                        .map(LogEntry::value)
+                        .copied()
+                        .collect::<Vec<_>>(),
+                    &values
+                        .public_values
+                        .iter()
+                        .map(LogEntry::value)
+                        .copied()
+                        .collect::<Vec<_>>(),
+                );
             }
-
-            writer.write_multiplicities_from_fn(
-                num_rows,
-                table,
-                Self::range_fn,
-                &values
-                    .trace_values
-                    .iter()
-                    .map(LogEntry::value)
-                    .copied()
-                    .collect::<Vec<_>>(),
-                &values
-                    .public_values
-                    .iter()
-                    .map(LogEntry::value)
-                    .copied()
-                    .collect::<Vec<_>>(),
-            );
         }
 
         writer
@@ -146,7 +146,9 @@ where
         );
 
         // Absorve the trace commitments into the challenger.
-        challenger.observe_cap(&execution_commitment.merkle_tree.cap);
+        self.config
+            .transcript_order
+            .observe_rounds(challenger, &[(&[], &execution_commitment.merkle_tree.cap)]);
 
         // Get random AIR challenges.
         let challenges = challenger.get_n_challenges(self.stark.air.num_challenges);
@@ -183,10 +185,11 @@ where
             self.config.commit(&extended_trace, timing)
         );
 
-        // Obsderve global values.
-        challenger.observe_elements(&global);
-        // Observe extended trace commitments.
-        challenger.observe_cap(&extended_commitment.merkle_tree.cap);
+        // Observe global values and extended trace commitments.
+        self.config.transcript_order.observe_rounds(
+            challenger,
+            &[(&global, &extended_commitment.merkle_tree.cap)],
+        );
 
         // Return the air commitment.
         AirCommitment {
@@ -242,15 +245,18 @@ where
         challenger.observe_elements(public_values);
 
         // Observe execution trace commitments.
-        challenger.observe_cap(&proof.air_proof.trace_caps[0]);
+        self.config
+            .transcript_order
+            .observe_rounds(&mut challenger, &[(&[], &proof.air_proof.trace_caps[0])]);
 
         // Get challenges.
         let challenges = challenger.get_n_challenges(self.stark.air.num_challenges);
 
-        // Observe global values.
-        challenger.observe_elements(&proof.global_values);
-        // Observe extended trace commitments.
-        challenger.observe_cap(&proof.air_proof.trace_caps[1]);
+        // Observe global values and extended trace commitments.
+        self.config.transcript_order.observe_rounds(
+            &mut challenger,
+            &[(&proof.global_values, &proof.air_proof.trace_caps[1])],
+        );
 
         // Get all challenges.
         proof.air_proof.get_iop_challenges(
@@ -266,6 +272,7 @@ where
         proof: StarkProof<L::Field, C, D>,
         public_values: &[L::Field],
     ) -> Result<()> {
+        StarkyVerifier::validate_num_rounds(&self.stark, &proof.air_proof)?;
         let challenges = self.get_challenges(&proof, public_values);
 
         let StarkProof {
@@ -315,15 +322,18 @@ where
         challenger.observe_elements(public_values);
 
         // Observe execution trace commitments.
-        challenger.observe_cap(&proof.air_proof.trace_caps[0]);
+        self.config
+            .transcript_order
+            .observe_rounds_target(&mut challenger, &[(&[], &proof.air_proof.trace_caps[0])]);
 
         // Get challenges.
         let challenges = challenger.get_n_challenges(builder, self.stark.air.num_challenges);
 
-        // Observe global values.
-        challenger.observe_elements(&proof.global_values);
-        // Observe extended trace commitments.
-        challenger.observe_cap(&proof.air_proof.trace_caps[1]);
+        // Observe global values and extended trace commitments.
+        self.config.transcript_order.observe_rounds_target(
+            &mut challenger,
+            &[(&proof.global_values, &proof.air_proof.trace_caps[1])],
+        );
 
         // Get all challenges.
         proof.air_proof.get_iop_challenges_target(
@@ -383,16 +393,20 @@ mod tests {
     use serde::{Deserialize, Serialize};
 
     use super::*;
+    use crate::chip::arithmetic::expression::ArithmeticExpression;
     use crate::chip::field::instruction::FpInstruction;
     use crate::chip::field::parameters::tests::Fp25519;
     use crate::chip::field::parameters::FieldParameters;
     use crate::chip::field::register::FieldRegister;
+    use crate::chip::instruction::empty::EmptyInstruction;
+    use crate::chip::register::element::ElementRegister;
+    use crate::chip::register::Register;
     use crate::chip::trace::writer::data::AirWriterData;
     use crate::chip::trace::writer::AirWriter;
     use crate::machine::builder::Builder;
     use crate::machine::stark::builder::StarkBuilder;
     use crate::math::goldilocks::cubic::GoldilocksCubicParameters;
-    use crate::plonky2::stark::config::CurtaPoseidonGoldilocksConfig;
+    use crate::plonky2::stark::config::{CurtaPoseidonGoldilocksConfig, TranscriptOrder};
     use crate::polynomial::Polynomial;
 
     #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -474,4 +488,70 @@ mod tests {
 
         timing.print();
     }
+
+    #[derive(Debug, Clone, Serialize, Deserialize)]
+    pub struct TranscriptOrderTest;
+
+    impl AirParameters for TranscriptOrderTest {
+        type Field = GoldilocksField;
+        type CubicParams = GoldilocksCubicParameters;
+
+        type Instruction = EmptyInstruction<GoldilocksField>;
+
+        const NUM_FREE_COLUMNS: usize = 2;
+        const EXTENDED_COLUMNS: usize = 1;
+    }
+
+    #[test]
+    fn test_transcript_order_stark() {
+        type L = TranscriptOrderTest;
+        type F = GoldilocksField;
+        type C = CurtaPoseidonGoldilocksConfig;
+
+        // A Fibonacci stark with a global value, observed with the extended trace.
+        let mut builder = StarkBuilder::<L>::new();
+        let x_0 = builder.alloc::<ElementRegister>();
+        let x_1 = builder.alloc::<ElementRegister>();
+        builder.set_to_expression_transition(&x_0.next(), x_1.expr());
+        builder.set_to_expression_transition(&x_1.next(), x_0.expr() + x_1.expr());
+        let global = builder.api().alloc_global::<ElementRegister>();
+        builder.api().set_to_expression_public(
+            &global,
+            ArithmeticExpression::from_constant(F::from_canonical_u64(7)),
+        );
+
+        let num_rows = 1 << 5;
+        let mut stark = builder.build::<C, 2>(num_rows);
+
+        let mut writer_data = AirWriterData::new(&stark.air_data, num_rows);
+        stark
+            .air_data
+            .write_global_instructions(&mut writer_data.public_writer());
+        writer_data.chunks(num_rows).for_each(|mut chunk| {
+            for i in 0..num_rows {
+                let mut writer = chunk.window_writer(i);
+                if i == 0 {
+                    writer.write(&x_0, &F::ZERO);
+                    writer.write(&x_1, &F::ONE);
+                }
+                stark.air_data.write_trace_instructions(&mut writer);
+            }
+        });
+        let (trace, public) = (writer_data.trace, writer_data.public);
+
+        let orders = [
+            TranscriptOrder::PerRound,
+            TranscriptOrder::CapsBeforeGlobals,
+        ];
+        for (i, order) in orders.iter().enumerate() {
+            stark.config.transcript_order = *order;
+            let proof = stark
+                .prove(&trace, &public, &mut TimingTree::default())
+                .unwrap();
+            stark.verify(proof.clone(), &public).unwrap();
+
+            stark.config.transcript_order = orders[1 - i];
+            assert!(stark.verify(proof, &public).is_err());
+        }
+    }
 }
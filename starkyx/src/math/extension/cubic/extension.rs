@@ -3,6 +3,7 @@ use core::iter::{Product, Sum};
 use core::marker::PhantomData;
 use core::ops::{Add, AddAssign, Div, DivAssign, Mul, MulAssign, Neg, Sub, SubAssign};
 
+use num::{BigUint, ToPrimitive};
 use rand::Rng;
 use serde::{Deserialize, Serialize};
 
@@ -69,6 +70,101 @@ impl<F: Field, P: CubicParameters<F>> CubicExtension<F, P> {
     pub fn inverse(&self) -> Self {
         self.try_inverse().expect("Cannot invert zero")
     }
+
+    /// Inverts a batch of elements using Montgomery's trick.
+    ///
+    /// Only a single field inversion is performed, at the cost of `3(n-1)` multiplications. Panics
+    /// if any of the elements is zero.
+    pub fn batch_inverse(elements: &[Self]) -> Vec<Self> {
+        let n = elements.len();
+        if n == 0 {
+            return Vec::new();
+        }
+
+        // prefix_products[i] = elements[0] * ... * elements[i-1]
+        let mut prefix_products = Vec::with_capacity(n);
+        let mut acc = Self::ONE;
+        for element in elements.iter() {
+            prefix_products.push(acc);
+            acc *= *element;
+        }
+
+        let mut acc_inv = acc.inverse();
+        let mut inverses = vec![Self::ZERO; n];
+        for ((inverse, prefix), element) in inverses
+            .iter_mut()
+            .zip(prefix_products)
+            .zip(elements.iter())
+            .rev()
+        {
+            *inverse = acc_inv * prefix;
+            acc_inv *= *element;
+        }
+        inverses
+    }
+}
+
+impl<F: PrimeField64, P: CubicParameters<F>> CubicExtension<F, P> {
+    /// Embeds the integer `n`, reduced modulo the order of the base field, in the extension.
+    pub fn from_base_biguint(n: &BigUint) -> Self {
+        let reduced = (n % F::order()).to_u64().unwrap();
+        Self::from_base_field(F::from_canonical_u64(reduced))
+    }
+
+    /// Returns the canonical integer value of `self`, or `None` if `self` is not in the base
+    /// field.
+    pub fn to_base_biguint(&self) -> Option<BigUint> {
+        self.in_base_field()
+            .then(|| BigUint::from(self.0.as_array()[0].as_canonical_u64()))
+    }
+
+    /// Returns a square root of `self`, or `None` if `self` is not a square.
+    ///
+    /// The extension has odd degree over the base field, so `p^3 - 1 = (p - 1)(p^2 + p + 1)` has
+    /// the same two-adicity as `p - 1` and a non-residue of the base field is still a non-residue
+    /// in the extension. The root is computed with the Tonelli-Shanks algorithm using such a
+    /// non-residue.
+    pub fn sqrt(&self) -> Option<Self> {
+        if *self == Self::ZERO {
+            return Some(Self::ZERO);
+        }
+
+        let p = F::order();
+        let order_minus_one = BigUint::from(p).pow(3) - 1u32;
+        // Euler's criterion.
+        if self.pow_biguint(&(&order_minus_one >> 1)) != Self::ONE {
+            return None;
+        }
+
+        // order - 1 = 2^s * t with t odd.
+        let s = (p - 1).trailing_zeros() as usize;
+        let t = &order_minus_one >> s;
+
+        let non_residue = (2..)
+            .map(F::from_canonical_u64)
+            .find(|z| z.pow((p - 1) / 2) == -F::ONE)
+            .unwrap();
+
+        let mut m = s;
+        let mut c = Self::from_base_field(non_residue).pow_biguint(&t);
+        let mut b = self.pow_biguint(&t);
+        let mut root = self.pow_biguint(&((&t + 1u32) >> 1));
+        while b != Self::ONE {
+            // The least `i` such that `b^(2^i) = 1`, which is smaller than `m`.
+            let mut i = 0;
+            let mut b_pow = b;
+            while b_pow != Self::ONE {
+                b_pow = b_pow.square();
+                i += 1;
+            }
+            let factor = c.two_pow(m - i - 1);
+            m = i;
+            c = factor.square();
+            b *= c;
+            root *= factor;
+        }
+        Some(root)
+    }
 }
 
 impl<F: Field, P: CubicParameters<F>> From<[F; 3]> for CubicExtension<F, P> {
@@ -0,0 +1,101 @@
+use num::{BigUint, One};
+
+use crate::chip::ec::point::AffinePoint;
+use crate::chip::ec::EllipticCurve;
+use crate::chip::utils::biguint_to_bits_le;
+
+/// A host-side cache of the powers `2^i * G` of a fixed base point `G`, for `i` up to the number
+/// of scalar bits of the curve.
+///
+/// A scalar multiplication of the base is then computed with one addition per set bit of the
+/// scalar and no doubling, which speeds up the trace generation of fixed-base operations, such as
+/// the scalar multiplications of the generator in signatures.
+#[derive(Debug, Clone)]
+pub struct FixedBaseTable<E> {
+    powers: Vec<AffinePoint<E>>,
+}
+
+impl<E: EllipticCurve> FixedBaseTable<E> {
+    /// Precomputes the powers of `base`.
+    pub fn new(base: &AffinePoint<E>) -> Self {
+        let mut powers = Vec::with_capacity(E::nb_scalar_bits());
+        let mut power = base.clone();
+        for _ in 1..E::nb_scalar_bits() {
+            let next = E::ec_double(&power);
+            powers.push(power);
+            power = next;
+        }
+        powers.push(power);
+        Self { powers }
+    }
+
+    /// Precomputes the powers of the generator of the curve.
+    pub fn generator() -> Self {
+        Self::new(&E::ec_generator())
+    }
+
+    /// The base point `G`.
+    pub fn base(&self) -> &AffinePoint<E> {
+        &self.powers[0]
+    }
+
+    /// The point `2^i * G`.
+    pub fn power(&self, i: usize) -> &AffinePoint<E> {
+        &self.powers[i]
+    }
+
+    /// Computes `scalar * G` from the cached powers.
+    ///
+    /// As for [`AffinePoint::scalar_mul`], the scalar is reduced modulo `2^nb_scalar_bits`, and
+    /// the multiplication fails for a zero scalar on curves without an affine neutral element.
+    pub fn scalar_mul(&self, scalar: &BigUint) -> AffinePoint<E> {
+        let power_two_modulus = BigUint::one() << E::nb_scalar_bits();
+        let scalar = scalar % &power_two_modulus;
+        biguint_to_bits_le(&scalar, E::nb_scalar_bits())
+            .into_iter()
+            .zip(self.powers.iter())
+            .filter(|(bit, _)| *bit)
+            .fold(E::ec_neutral(), |acc, (_, power)| {
+                acc.map_or_else(|| Some(power.clone()), |r| Some(&r + power))
+            })
+            .expect("Scalar multiplication failed")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use num::bigint::RandBigInt;
+    use num::Zero;
+    use rand::thread_rng;
+
+    use super::*;
+    use crate::chip::ec::edwards::ed25519::params::Ed25519;
+    use crate::chip::ec::weierstrass::bn254::Bn254;
+
+    #[test]
+    fn test_fixed_base_table() {
+        let mut rng = thread_rng();
+
+        let table = FixedBaseTable::<Ed25519>::generator();
+        assert_eq!(table.base(), &Ed25519::ec_generator());
+        for _ in 0..10 {
+            let scalar = rng.gen_biguint(256);
+            assert_eq!(
+                table.scalar_mul(&scalar),
+                Ed25519::ec_generator().scalar_mul(&scalar)
+            );
+        }
+        assert_eq!(
+            table.scalar_mul(&BigUint::zero()),
+            Ed25519::ec_neutral().unwrap()
+        );
+
+        let base = Bn254::ec_generator().sw_scalar_mul(&rng.gen_biguint(256));
+        let table = FixedBaseTable::<Bn254>::new(&base);
+        for i in 1..Bn254::nb_scalar_bits() {
+            assert_eq!(table.power(i), &table.power(i - 1).sw_double());
+        }
+        let scalar = rng.gen_biguint(256);
+        assert_eq!(table.scalar_mul(&scalar), base.sw_scalar_mul(&scalar));
+    }
+}
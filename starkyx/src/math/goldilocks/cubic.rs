@@ -28,6 +28,8 @@ impl CubicParameters<GoldilocksField> for GoldilocksCubicParameters {
 
 #[cfg(test)]
 mod tests {
+    use num::BigUint;
+
     use super::*;
     use crate::math::prelude::*;
 
@@ -87,4 +89,69 @@ mod tests {
             assert_eq!(a * a_inv, GF3::ONE);
         }
     }
+
+    #[test]
+    fn test_gf3_batch_inverse() {
+        let num_tests = 100;
+
+        let elements = (0..num_tests).map(|_| GF3::rand()).collect::<Vec<_>>();
+        let batch_inverses = GF3::batch_inverse(&elements);
+
+        assert_eq!(batch_inverses.len(), elements.len());
+        for (a, a_inv) in elements.iter().zip(batch_inverses.iter()) {
+            assert_eq!(*a_inv, a.inverse());
+        }
+
+        assert!(GF3::batch_inverse(&[]).is_empty());
+    }
+
+    #[test]
+    fn test_gf3_sqrt() {
+        let num_tests = 100;
+
+        assert_eq!(GF3::ZERO.sqrt(), Some(GF3::ZERO));
+
+        // The multiplicative generator of the Goldilocks field is a non-residue, which stays a
+        // non-residue in the extension of odd degree.
+        let non_residue = GF3::from_base_field(GoldilocksField(7));
+
+        for _ in 0..num_tests {
+            let a = GF3::rand();
+            let square = a * a;
+            assert_eq!(square.sqrt().map(|r| r * r), Some(square));
+
+            if a != GF3::ZERO {
+                assert_eq!((square * non_residue).sqrt(), None);
+            }
+        }
+    }
+
+    #[test]
+    fn test_gf3_biguint_round_trip() {
+        let num_tests = 100;
+        let order = BigUint::from((-GoldilocksField::ONE).as_canonical_u64()) + 1u32;
+
+        for _ in 0..num_tests {
+            let a = GoldilocksField::rand();
+            let a_int = BigUint::from(a.as_canonical_u64());
+
+            let element = GF3::from_base_biguint(&a_int);
+            assert_eq!(element, GF3::from_base_field(a));
+            assert_eq!(element.to_base_biguint(), Some(a_int.clone()));
+
+            // Integers are reduced modulo the order of the base field.
+            assert_eq!(GF3::from_base_biguint(&(&a_int + &order)), element);
+        }
+
+        assert_eq!(GF3::ZERO.to_base_biguint(), Some(BigUint::from(0u32)));
+        assert_eq!(
+            GF3::new(
+                GoldilocksField::ONE,
+                GoldilocksField::ONE,
+                GoldilocksField::ZERO
+            )
+            .to_base_biguint(),
+            None
+        );
+    }
 }
@@ -1,6 +1,7 @@
 pub mod algebra;
 pub mod extension;
 pub mod field;
+pub mod fixed_base;
 pub mod goldilocks;
 
 pub mod prelude {
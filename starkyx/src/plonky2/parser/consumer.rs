@@ -1,13 +1,50 @@
+use alloc::sync::Arc;
 use alloc::vec;
+use core::fmt::Debug;
 use core::marker::PhantomData;
 
 use plonky2::field::extension::Extendable;
 use plonky2::field::packed::PackedField;
+use plonky2::field::types::Field;
 use plonky2::hash::hash_types::RichField;
 use plonky2::iop::ext_target::ExtensionTarget;
 use plonky2::iop::target::Target;
 use plonky2::plonk::circuit_builder::CircuitBuilder;
 
+/// Chooses the coefficients with which the constraints of an AIR are combined into a single
+/// constraint for each challenge `alpha`.
+///
+/// By default, the constraints `c_0, ..., c_{n-1}` are combined as `sum_i alpha^(n-1-i) c_i`,
+/// the evaluation at `alpha` of the polynomial with coefficients given by the constraints. A
+/// combiner replaces this by `sum_i coefficient(alpha, i) c_i`, for example to match the
+/// combination of an external verifier. The combination is only sound if the coefficients are
+/// unpredictable to the prover, and the prover and verifier must use the same combiner. The
+/// recursive verifier always uses the default combination.
+pub trait ConstraintCombiner<F: Field>: Debug + Send + Sync {
+    /// The coefficient of the constraint of index `index` in the combination for `alpha`.
+    fn coefficient(&self, alpha: F, index: usize) -> F;
+}
+
+/// The coefficients of the constraints, indexed by the challenge and then by the constraint.
+pub type ConstraintCoefficients<T> = Arc<Vec<Vec<T>>>;
+
+/// Computes the coefficients given by `combiner` to the first `num_constraints` constraints, for
+/// each challenge in `alphas`.
+pub fn combiner_coefficients<F: Field>(
+    combiner: &dyn ConstraintCombiner<F>,
+    alphas: &[F],
+    num_constraints: usize,
+) -> Vec<Vec<F>> {
+    alphas
+        .iter()
+        .map(|&alpha| {
+            (0..num_constraints)
+                .map(|i| combiner.coefficient(alpha, i))
+                .collect()
+        })
+        .collect()
+}
+
 pub struct ConstraintConsumer<P: PackedField> {
     /// Random values used to combine multiple constraints into one.
     alphas: Vec<P::Scalar>,
@@ -17,6 +54,12 @@ pub struct ConstraintConsumer<P: PackedField> {
     // result, it should be made private.
     pub constraint_accs: Vec<P>,
 
+    /// The coefficients of the constraints, if they are not combined with powers of alpha.
+    coefficients: Option<ConstraintCoefficients<P::Scalar>>,
+
+    /// The number of constraints that have been emitted so far.
+    num_constraints: usize,
+
     /// The evaluation of `X - g^(n-1)`.
     z_last: P,
 
@@ -39,16 +82,30 @@ impl<P: PackedField> ConstraintConsumer<P> {
         Self {
             constraint_accs: vec![P::ZEROS; alphas.len()],
             alphas,
+            coefficients: None,
+            num_constraints: 0,
             z_last,
             lagrange_basis_first,
             lagrange_basis_last,
         }
     }
 
+    /// Combines the constraints with the given coefficients instead of powers of alpha, see
+    /// [`ConstraintCombiner`].
+    pub fn with_coefficients(mut self, coefficients: ConstraintCoefficients<P::Scalar>) -> Self {
+        self.coefficients = Some(coefficients);
+        self
+    }
+
     pub fn accumulators(self) -> Vec<P> {
         self.constraint_accs
     }
 
+    /// The number of constraints that have been emitted so far.
+    pub fn num_constraints(&self) -> usize {
+        self.num_constraints
+    }
+
     /// Add one constraint valid on all rows except the last.
     pub fn constraint_transition(&mut self, constraint: P) {
         self.constraint(constraint * self.z_last);
@@ -56,10 +113,21 @@ impl<P: PackedField> ConstraintConsumer<P> {
 
     /// Add one constraint on all rows.
     pub fn constraint(&mut self, constraint: P) {
-        for (&alpha, acc) in self.alphas.iter().zip(&mut self.constraint_accs) {
-            *acc *= alpha;
-            *acc += constraint;
+        match &self.coefficients {
+            Some(coefficients) => {
+                for (acc, coefficients) in self.constraint_accs.iter_mut().zip(coefficients.iter())
+                {
+                    *acc += constraint * coefficients[self.num_constraints];
+                }
+            }
+            None => {
+                for (&alpha, acc) in self.alphas.iter().zip(&mut self.constraint_accs) {
+                    *acc *= alpha;
+                    *acc += constraint;
+                }
+            }
         }
+        self.num_constraints += 1;
     }
 
     /// Add one constraint, but first multiply it by a filter such that it will only apply to the
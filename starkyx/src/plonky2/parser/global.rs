@@ -69,19 +69,35 @@ where
     }
 
     fn constraint(&mut self, constraint: Self::Var) {
-        assert_eq!(constraint.as_slice(), P::ZEROS.as_slice());
+        assert_eq!(
+            constraint.as_slice(),
+            P::ZEROS.as_slice(),
+            "global constraint is not satisfied"
+        );
     }
 
     fn constraint_transition(&mut self, constraint: Self::Var) {
-        assert_eq!(constraint.as_slice(), P::ZEROS.as_slice());
+        assert_eq!(
+            constraint.as_slice(),
+            P::ZEROS.as_slice(),
+            "global constraint is not satisfied"
+        );
     }
 
     fn constraint_first_row(&mut self, constraint: Self::Var) {
-        assert_eq!(constraint.as_slice(), P::ZEROS.as_slice());
+        assert_eq!(
+            constraint.as_slice(),
+            P::ZEROS.as_slice(),
+            "global constraint is not satisfied"
+        );
     }
 
     fn constraint_last_row(&mut self, constraint: Self::Var) {
-        assert_eq!(constraint.as_slice(), P::ZEROS.as_slice());
+        assert_eq!(
+            constraint.as_slice(),
+            P::ZEROS.as_slice(),
+            "global constraint is not satisfied"
+        );
     }
 
     fn add(&mut self, a: Self::Var, b: Self::Var) -> Self::Var {
@@ -11,7 +11,9 @@ use self::consumer::{ConstraintConsumer, RecursiveConstraintConsumer};
 use crate::air::extension::cubic::CubicParser;
 use crate::air::parser::AirParser;
 use crate::math::extension::cubic::parameters::CubicParameters;
+use crate::polynomial::ops::PolynomialOps;
 use crate::polynomial::parser::PolynomialParser;
+use crate::polynomial::Polynomial;
 
 pub struct StarkParser<'a, F, FE, P, const D: usize, const D2: usize>
 where
@@ -21,6 +23,9 @@ where
 {
     pub(crate) local_vars: &'a [P],
     pub(crate) next_vars: &'a [P],
+    /// The values of the rows at the shifts `opening_shifts`, see [`AirParser::shifted_slice`].
+    pub(crate) shifted_vars: &'a [Vec<P>],
+    pub(crate) opening_shifts: &'a [usize],
     pub(crate) global_vars: &'a [P],
     pub(crate) public_vars: &'a [P],
     pub(crate) challenges: &'a [P],
@@ -31,12 +36,57 @@ pub struct RecursiveStarkParser<'a, F: RichField + Extendable<D>, const D: usize
     pub(crate) builder: &'a mut CircuitBuilder<F, D>,
     pub(crate) local_vars: &'a [ExtensionTarget<D>],
     pub(crate) next_vars: &'a [ExtensionTarget<D>],
+    pub(crate) shifted_vars: &'a [Vec<ExtensionTarget<D>>],
+    pub(crate) opening_shifts: &'a [usize],
     pub(crate) global_vars: &'a [ExtensionTarget<D>],
     pub(crate) public_vars: &'a [ExtensionTarget<D>],
     pub(crate) challenges: &'a [ExtensionTarget<D>],
     pub(crate) consumer: &'a mut RecursiveConstraintConsumer<F, D>,
 }
 
+impl<'a, F, FE, P, const D: usize, const D2: usize> StarkParser<'a, F, FE, P, D, D2>
+where
+    F: RichField + Extendable<D>,
+    FE: FieldExtension<D2, BaseField = F>,
+    P: PackedField<Scalar = FE>,
+{
+    /// A parser evaluating the constraints on the `P::WIDTH` consecutive rows packed in
+    /// `local_vars`, whose next rows are packed in `next_vars`. The trace is not opened at any
+    /// other shift.
+    pub fn new(
+        local_vars: &'a [P],
+        next_vars: &'a [P],
+        global_vars: &'a [P],
+        public_vars: &'a [P],
+        challenges: &'a [P],
+        consumer: &'a mut ConstraintConsumer<P>,
+    ) -> Self {
+        Self {
+            local_vars,
+            next_vars,
+            shifted_vars: &[],
+            opening_shifts: &[],
+            global_vars,
+            public_vars,
+            challenges,
+            consumer,
+        }
+    }
+}
+
+/// The values of the row at `shift` among the rows `shifted_vars` opened at `opening_shifts`.
+fn shifted_vars<'a, V>(
+    opening_shifts: &[usize],
+    shifted_vars: &'a [Vec<V>],
+    shift: usize,
+) -> &'a [V] {
+    let index = opening_shifts
+        .iter()
+        .position(|&s| s == shift)
+        .unwrap_or_else(|| panic!("The trace is not opened at shift {}", shift));
+    &shifted_vars[index]
+}
+
 impl<'a, F, FE, P, const D: usize, const D2: usize> AirParser for StarkParser<'a, F, FE, P, D, D2>
 where
     F: RichField + Extendable<D>,
@@ -66,6 +116,14 @@ where
         self.public_vars
     }
 
+    fn shifted_slice(&self, shift: usize) -> &[Self::Var] {
+        match shift {
+            0 => self.local_vars,
+            1 => self.next_vars,
+            _ => shifted_vars(self.opening_shifts, self.shifted_vars, shift),
+        }
+    }
+
     fn constant(&mut self, value: Self::Field) -> Self::Var {
         P::from(FE::from_basefield(value))
     }
@@ -118,6 +176,8 @@ where
     }
 }
 
+/// The polynomial operations work directly on the packed coefficients, each holding the values
+/// of `P::WIDTH` rows, instead of going through the parser var by var.
 impl<'a, F, FE, P, const D: usize, const D2: usize> PolynomialParser
     for StarkParser<'a, F, FE, P, D, D2>
 where
@@ -125,6 +185,33 @@ where
     FE: FieldExtension<D2, BaseField = F>,
     P: PackedField<Scalar = FE>,
 {
+    fn poly_add(&mut self, a: &Polynomial<P>, b: &Polynomial<P>) -> Polynomial<P> {
+        Polynomial::from_coefficients(PolynomialOps::add(a.coefficients(), b.coefficients()))
+    }
+
+    fn poly_sub(&mut self, a: &Polynomial<P>, b: &Polynomial<P>) -> Polynomial<P> {
+        Polynomial::from_coefficients(PolynomialOps::sub(a.coefficients(), b.coefficients()))
+    }
+
+    fn poly_mul(&mut self, a: &Polynomial<P>, b: &Polynomial<P>) -> Polynomial<P> {
+        Polynomial::from_coefficients(PolynomialOps::scalar_poly_mul(
+            a.coefficients(),
+            b.coefficients(),
+        ))
+    }
+
+    fn poly_scalar_mul(&mut self, a: &Polynomial<P>, b: &P) -> Polynomial<P> {
+        Polynomial::from_coefficients(PolynomialOps::scalar_mul(a.coefficients(), b))
+    }
+
+    fn poly_mul_poly_const(&mut self, a: &Polynomial<P>, b: &Polynomial<F>) -> Polynomial<P> {
+        let b = b
+            .coefficients()
+            .iter()
+            .map(|x| FE::from_basefield(*x))
+            .collect::<Vec<_>>();
+        Polynomial::from_coefficients(PolynomialOps::scalar_poly_mul(a.coefficients(), &b))
+    }
 }
 
 impl<'a, F, FE, E: CubicParameters<F>, P, const D: usize, const D2: usize> CubicParser<E>
@@ -166,6 +253,14 @@ impl<'a, F: RichField + Extendable<D>, const D: usize> AirParser
         self.public_vars
     }
 
+    fn shifted_slice(&self, shift: usize) -> &[Self::Var] {
+        match shift {
+            0 => self.local_vars,
+            1 => self.next_vars,
+            _ => shifted_vars(self.opening_shifts, self.shifted_vars, shift),
+        }
+    }
+
     fn constraint(&mut self, constraint: Self::Var) {
         self.consumer.constraint(self.builder, constraint);
     }
@@ -223,3 +318,158 @@ impl<'a, F: RichField + Extendable<D>, E: CubicParameters<F>, const D: usize> Cu
     for RecursiveStarkParser<'a, F, D>
 {
 }
+
+#[cfg(test)]
+mod tests {
+    use plonky2::field::packable::Packable;
+    use plonky2::field::types::{Field, Sample};
+    use serde::{Deserialize, Serialize};
+
+    use super::*;
+    use crate::chip::builder::tests::*;
+    use crate::chip::builder::AirBuilder;
+    use crate::chip::field::mul::FpMulInstruction;
+    use crate::chip::field::parameters::tests::Fp25519;
+    use crate::chip::field::register::FieldRegister;
+    use crate::chip::AirParameters;
+    use crate::trace::window::TraceWindow;
+
+    type F = GoldilocksField;
+    type P = <F as Packable>::Packing;
+
+    #[derive(Clone, Debug, Copy, Serialize, Deserialize)]
+    struct PackedMulTest;
+
+    impl AirParameters for PackedMulTest {
+        type Field = GoldilocksField;
+        type CubicParams = GoldilocksCubicParameters;
+
+        const NUM_ARITHMETIC_COLUMNS: usize = 124;
+        const NUM_FREE_COLUMNS: usize = 2;
+        const EXTENDED_COLUMNS: usize = 195;
+
+        type Instruction = FpMulInstruction<Fp25519>;
+    }
+
+    #[test]
+    fn test_packed_polynomial_ops() {
+        let a = Polynomial::from_coefficients(F::rand_vec(16));
+        let b = Polynomial::from_coefficients(F::rand_vec(11));
+        let c = F::rand();
+
+        let mut scalar_parser = TraceWindowParser::<F>::new(TraceWindow::empty(), &[], &[], &[]);
+        let mut consumer = ConstraintConsumer::<F>::new(vec![], F::ZERO, F::ZERO, F::ZERO);
+        let mut packed_parser =
+            StarkParser::<F, F, F, 2, 1>::new(&[], &[], &[], &[], &[], &mut consumer);
+
+        assert_eq!(
+            packed_parser.poly_add(&a, &b),
+            scalar_parser.poly_add(&a, &b)
+        );
+        assert_eq!(
+            packed_parser.poly_add(&b, &a),
+            scalar_parser.poly_add(&b, &a)
+        );
+        assert_eq!(
+            packed_parser.poly_sub(&a, &b),
+            scalar_parser.poly_sub(&a, &b)
+        );
+        assert_eq!(
+            packed_parser.poly_sub(&b, &a),
+            scalar_parser.poly_sub(&b, &a)
+        );
+        assert_eq!(
+            packed_parser.poly_mul(&a, &b),
+            scalar_parser.poly_mul(&a, &b)
+        );
+        assert_eq!(
+            packed_parser.poly_scalar_mul(&a, &c),
+            scalar_parser.poly_scalar_mul(&a, &c)
+        );
+        assert_eq!(
+            packed_parser.poly_mul_poly_const(&a, &b),
+            scalar_parser.poly_mul_poly_const(&a, &b)
+        );
+    }
+
+    #[test]
+    fn test_packed_parser_matches_scalar() {
+        type L = PackedMulTest;
+
+        let mut builder = AirBuilder::<L>::new();
+        let a = builder.alloc::<FieldRegister<Fp25519>>();
+        let b = builder.alloc::<FieldRegister<Fp25519>>();
+        builder.fp_mul(&a, &b);
+        let (air, _) = builder.build();
+
+        // Random values do not satisfy the constraints, so the accumulators to compare are not
+        // zero.
+        let width = L::num_columns();
+        let num_rows = 2 * P::WIDTH;
+        let trace = (0..num_rows)
+            .map(|_| F::rand_vec(width))
+            .collect::<Vec<_>>();
+        let challenges = F::rand_vec(air.num_challenges);
+        let global_values = F::rand_vec(air.num_global_values);
+        let public_values = F::rand_vec(air.num_public_values);
+        let alphas = F::rand_vec(2);
+        let z_last = F::rand_vec(P::WIDTH);
+        let lagrange_first = F::rand_vec(P::WIDTH);
+        let lagrange_last = F::rand_vec(P::WIDTH);
+
+        // Evaluate the constraints on the first `P::WIDTH` rows at once.
+        let pack_rows = |start: usize| {
+            (0..width)
+                .map(|column| {
+                    let values = (0..P::WIDTH)
+                        .map(|row| trace[start + row][column])
+                        .collect::<Vec<_>>();
+                    *P::from_slice(&values)
+                })
+                .collect::<Vec<_>>()
+        };
+        let broadcast = |values: &[F]| values.iter().map(|x| P::from(*x)).collect::<Vec<_>>();
+        let (local, next) = (pack_rows(0), pack_rows(1));
+        let packed_challenges = broadcast(&challenges);
+        let packed_global_values = broadcast(&global_values);
+        let packed_public_values = broadcast(&public_values);
+        let mut consumer = ConstraintConsumer::new(
+            alphas.clone(),
+            *P::from_slice(&z_last),
+            *P::from_slice(&lagrange_first),
+            *P::from_slice(&lagrange_last),
+        );
+        let mut parser = StarkParser::<F, F, P, 2, 1>::new(
+            &local,
+            &next,
+            &packed_global_values,
+            &packed_public_values,
+            &packed_challenges,
+            &mut consumer,
+        );
+        air.eval(&mut parser);
+        let packed_accumulators = consumer.accumulators();
+
+        // Evaluate the constraints row by row.
+        for row in 0..P::WIDTH {
+            let mut consumer = ConstraintConsumer::<F>::new(
+                alphas.clone(),
+                z_last[row],
+                lagrange_first[row],
+                lagrange_last[row],
+            );
+            let mut parser = StarkParser::<F, F, F, 2, 1>::new(
+                &trace[row],
+                &trace[row + 1],
+                &global_values,
+                &public_values,
+                &challenges,
+                &mut consumer,
+            );
+            air.eval(&mut parser);
+            for (packed, scalar) in packed_accumulators.iter().zip(consumer.accumulators()) {
+                assert_eq!(packed.as_slice()[row], scalar);
+            }
+        }
+    }
+}
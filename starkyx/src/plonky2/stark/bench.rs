@@ -0,0 +1,119 @@
+//! Statistics on the verification time of Stark proofs.
+
+use std::time::{Duration, Instant};
+
+use anyhow::{ensure, Result};
+use plonky2::field::extension::Extendable;
+use plonky2::hash::hash_types::RichField;
+
+use super::config::{CurtaConfig, StarkyConfig};
+use super::proof::StarkProof;
+use super::verifier::StarkyVerifier;
+use super::Starky;
+use crate::plonky2::StarkyAir;
+
+/// Summary statistics of the verification times measured by [`bench_verify`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct VerifyStats {
+    pub iters: usize,
+    pub mean: Duration,
+    pub median: Duration,
+    pub std_dev: Duration,
+    pub min: Duration,
+    pub max: Duration,
+}
+
+impl VerifyStats {
+    fn from_samples(mut samples: Vec<Duration>) -> Self {
+        samples.sort();
+        let iters = samples.len();
+        let nanos = samples
+            .iter()
+            .map(|s| s.as_nanos() as f64)
+            .collect::<Vec<_>>();
+
+        let mean = nanos.iter().sum::<f64>() / iters as f64;
+        let median = if iters % 2 == 0 {
+            (nanos[iters / 2 - 1] + nanos[iters / 2]) / 2.0
+        } else {
+            nanos[iters / 2]
+        };
+        let variance = nanos.iter().map(|x| (x - mean) * (x - mean)).sum::<f64>() / iters as f64;
+
+        Self {
+            iters,
+            mean: Duration::from_nanos(mean as u64),
+            median: Duration::from_nanos(median as u64),
+            std_dev: Duration::from_nanos(variance.sqrt() as u64),
+            min: samples[0],
+            max: samples[iters - 1],
+        }
+    }
+}
+
+/// Verifies `proof` `iters` times and returns statistics on the verification time.
+///
+/// Each iteration verifies a fresh copy of the proof, and copying the proof is not included in
+/// the measured time. Returns an error if `iters` is zero or if the proof fails to verify.
+pub fn bench_verify<A, F, C, const D: usize>(
+    stark: &Starky<A>,
+    config: &StarkyConfig<C, D>,
+    proof: &StarkProof<F, C, D>,
+    public_inputs: &[F],
+    iters: usize,
+) -> Result<VerifyStats>
+where
+    F: RichField + Extendable<D>,
+    C: CurtaConfig<D, F = F, FE = F::Extension>,
+    A: StarkyAir<F, D>,
+{
+    ensure!(iters > 0, "The number of iterations must be positive");
+
+    let mut samples = Vec::with_capacity(iters);
+    for _ in 0..iters {
+        let proof = proof.clone();
+        let start = Instant::now();
+        StarkyVerifier::verify(config, stark, proof, public_inputs)?;
+        samples.push(start.elapsed());
+    }
+
+    Ok(VerifyStats::from_samples(samples))
+}
+
+#[cfg(test)]
+mod tests {
+    use plonky2::field::goldilocks_field::GoldilocksField;
+
+    use super::*;
+    use crate::math::prelude::*;
+    use crate::plonky2::stark::tests::fibonacci_proof;
+
+    #[test]
+    fn test_verify_stats() {
+        let samples = [4, 1, 3, 2].map(Duration::from_millis).to_vec();
+        let stats = VerifyStats::from_samples(samples);
+        assert_eq!(stats.iters, 4);
+        assert_eq!(stats.mean, Duration::from_micros(2500));
+        assert_eq!(stats.median, Duration::from_micros(2500));
+        assert_eq!(stats.min, Duration::from_millis(1));
+        assert_eq!(stats.max, Duration::from_millis(4));
+        // The standard deviation of 1, 2, 3, 4 is sqrt(1.25).
+        assert_eq!(stats.std_dev.as_micros(), 1118);
+    }
+
+    #[test]
+    fn test_bench_verify() {
+        type F = GoldilocksField;
+
+        let (stark, config, public_inputs, proof) = fibonacci_proof(1 << 5);
+
+        let stats = bench_verify(&stark, &config, &proof, &public_inputs, 5).unwrap();
+        assert_eq!(stats.iters, 5);
+        assert!(stats.min <= stats.median && stats.median <= stats.max);
+        assert!(stats.min <= stats.mean && stats.mean <= stats.max);
+
+        assert!(bench_verify(&stark, &config, &proof, &public_inputs, 0).is_err());
+        let wrong_inputs = [F::ZERO, F::ONE, F::ZERO];
+        assert!(bench_verify(&stark, &config, &proof, &wrong_inputs, 1).is_err());
+    }
+}
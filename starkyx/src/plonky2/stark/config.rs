@@ -1,17 +1,24 @@
 use core::fmt::Debug;
 
+use anyhow::{anyhow, ensure, Result};
+use itertools::Itertools;
 use plonky2::field::extension::{Extendable, FieldExtension};
 use plonky2::field::polynomial::PolynomialValues;
 use plonky2::fri::oracle::PolynomialBatch;
 use plonky2::fri::reduction_strategies::FriReductionStrategy;
 use plonky2::fri::{FriConfig, FriParams};
-use plonky2::hash::hash_types::RichField;
-use plonky2::plonk::config::{AlgebraicHasher, GenericConfig, PoseidonGoldilocksConfig};
+use plonky2::hash::hash_types::{MerkleCapTarget, RichField};
+use plonky2::hash::merkle_tree::MerkleCap;
+use plonky2::iop::challenger::{Challenger, RecursiveChallenger};
+use plonky2::iop::target::Target;
+use plonky2::plonk::circuit_builder::CircuitBuilder;
+use plonky2::plonk::config::{AlgebraicHasher, GenericConfig, Hasher, PoseidonGoldilocksConfig};
 use plonky2::util::log2_strict;
 use plonky2::util::timing::TimingTree;
 use serde::de::DeserializeOwned;
 use serde::{Deserialize, Serialize};
 
+use crate::air::RoundDatum;
 use crate::maybe_rayon::*;
 use crate::trace::AirTrace;
 use crate::utils::serde::{deserialize_fri_config, serialize_fri_config};
@@ -49,6 +56,33 @@ pub struct StarkyConfig<C, const D: usize> {
     #[serde(deserialize_with = "deserialize_fri_config")]
     pub fri_config: FriConfig,
 
+    /// Whether the prover blinds the unused columns of the trace with random values, see
+    /// [`RAirData::unused_columns`](crate::air::RAirData::unused_columns).
+    ///
+    /// Only the unused columns are blinded, the columns holding the trace values are committed as
+    /// they are, so this does not make the proof zero-knowledge.
+    #[serde(default)]
+    pub blind_unused_columns: bool,
+
+    /// The order in which the data of each round is observed by the challenger.
+    #[serde(default)]
+    pub transcript_order: TranscriptOrder,
+
+    /// The maximal number of cells, rows times columns, of a trace the prover accepts to prove.
+    ///
+    /// A larger trace is rejected with an error before it is generated, instead of possibly
+    /// exhausting the memory of the prover.
+    #[serde(default)]
+    pub max_trace_cells: Option<usize>,
+
+    /// The shifts `k` of the additional points `zeta * g^k` at which the trace is opened, besides
+    /// the points `zeta` and `zeta * g` of the current and next rows.
+    ///
+    /// The openings at these points are checked against the trace commitments by the verifier,
+    /// and are reported in [`StarkOpeningSet::shifted_values`](super::proof::StarkOpeningSet).
+    #[serde(default)]
+    pub opening_shifts: Vec<usize>,
+
     _marker: core::marker::PhantomData<C>,
 }
 
@@ -68,14 +102,152 @@ impl<C: CurtaConfig<D>, const D: usize> StarkyConfig<C, D> {
                 reduction_strategy: FriReductionStrategy::ConstantArityBits(4, 5),
                 num_query_rounds: 84,
             },
+            blind_unused_columns: false,
+            transcript_order: TranscriptOrder::PerRound,
+            max_trace_cells: None,
+            opening_shifts: Vec::new(),
             _marker: core::marker::PhantomData,
         }
     }
 
+    /// Returns the configuration with the blinding of unused trace columns enabled or disabled.
+    pub fn with_blind_unused_columns(mut self, blind_unused_columns: bool) -> Self {
+        self.blind_unused_columns = blind_unused_columns;
+        self
+    }
+
+    /// Returns the configuration with the given transcript order.
+    ///
+    /// The prover and the verifier must use the same order, otherwise they derive different
+    /// challenges and the proof is rejected.
+    pub fn with_transcript_order(mut self, transcript_order: TranscriptOrder) -> Self {
+        self.transcript_order = transcript_order;
+        self
+    }
+
+    /// Returns the configuration with the given budget of trace cells, see
+    /// [`Self::max_trace_cells`].
+    pub fn with_max_trace_cells(mut self, max_trace_cells: usize) -> Self {
+        self.max_trace_cells = Some(max_trace_cells);
+        self
+    }
+
+    /// Returns the configuration with the given additional opening shifts, see
+    /// [`Self::opening_shifts`].
+    ///
+    /// # Panics
+    ///
+    /// Panics if a shift is `0` or `1`, which are always opened, if a shift is not smaller than
+    /// the number of rows, or if a shift is repeated.
+    pub fn with_opening_shifts(mut self, opening_shifts: Vec<usize>) -> Self {
+        let num_rows = 1usize << self.degree_bits;
+        for (i, shift) in opening_shifts.iter().enumerate() {
+            assert!(*shift > 1, "The rows at shifts 0 and 1 are always opened");
+            assert!(
+                *shift < num_rows,
+                "Opening shift {} is not smaller than the number of rows {}",
+                shift,
+                num_rows
+            );
+            assert!(
+                !opening_shifts[..i].contains(shift),
+                "Repeated opening shift {}",
+                shift
+            );
+        }
+        self.opening_shifts = opening_shifts;
+        self
+    }
+
+    /// Checks that a trace of `num_columns` columns fits in the budget of trace cells.
+    pub fn check_trace_cells(&self, num_columns: usize) -> Result<()> {
+        let Some(max_trace_cells) = self.max_trace_cells else {
+            return Ok(());
+        };
+        let num_rows = 1usize << self.degree_bits;
+        let num_cells = num_rows.checked_mul(num_columns).ok_or_else(|| {
+            anyhow!(
+                "Trace of {} rows and {} columns exceeds the budget of {} cells",
+                num_rows,
+                num_columns,
+                max_trace_cells
+            )
+        })?;
+        ensure!(
+            num_cells <= max_trace_cells,
+            "Trace of {} rows and {} columns has {} cells, exceeding the budget of {} cells",
+            num_rows,
+            num_columns,
+            num_cells,
+            max_trace_cells
+        );
+        Ok(())
+    }
+
     pub fn fri_params(&self) -> FriParams {
         self.fri_config.fri_params(self.degree_bits, false)
     }
 
+    /// Estimates the size in bytes of a serialized proof of a trace of `num_rows` rows and
+    /// `num_columns` columns with this configuration.
+    ///
+    /// The estimate follows the encoding of `bincode`, where vectors are prefixed by their length
+    /// as a `u64`, and assumes a single trace commitment, no global values, and a quotient of
+    /// `2^rate_bits` chunks per challenge, the largest one allowed by the rate. This is the case of
+    /// a chip, whose constraints have degree 3, with the standard rate of 2.
+    pub fn estimate_proof_size(&self, num_rows: usize, num_columns: usize) -> usize {
+        const LEN_PREFIX: usize = core::mem::size_of::<u64>();
+
+        let fri_params = self.fri_config.fri_params(log2_strict(num_rows), false);
+        let rate_bits = self.fri_config.rate_bits;
+        let cap_height = self.fri_config.cap_height;
+
+        let field_size = core::mem::size_of::<C::F>();
+        let extension_size = D * field_size;
+        let hash_size = core::mem::size_of::<<C::Hasher as Hasher<C::F>>::Hash>();
+        let cap_size = LEN_PREFIX + (1 << cap_height) * hash_size;
+        let merkle_proof_size =
+            |tree_bits: usize| LEN_PREFIX + (tree_bits - cap_height) * hash_size;
+
+        let num_quotient_polys = self.num_challenges << rate_bits;
+        let lde_bits = fri_params.lde_bits();
+
+        // The trace caps, the quotient cap and the openings at `zeta`, `g * zeta` and the
+        // shifted points.
+        let caps_size = LEN_PREFIX + 2 * cap_size;
+        let num_shifts = self.opening_shifts.len();
+        let openings_size = 4 * LEN_PREFIX
+            + num_shifts * LEN_PREFIX
+            + ((2 + num_shifts) * num_columns + num_quotient_polys) * extension_size;
+
+        // The initial trees of a query round open the trace and the quotient at the same index,
+        // and each reduction step opens a coset of the folded polynomial.
+        let initial_trees_size = LEN_PREFIX
+            + [num_columns, num_quotient_polys]
+                .iter()
+                .map(|num_polys| LEN_PREFIX + num_polys * field_size + merkle_proof_size(lde_bits))
+                .sum::<usize>();
+        let mut tree_bits = lde_bits;
+        let mut steps_size = LEN_PREFIX;
+        for &arity_bits in fri_params.reduction_arity_bits.iter() {
+            tree_bits -= arity_bits;
+            steps_size += LEN_PREFIX + (1 << arity_bits) * extension_size;
+            steps_size += merkle_proof_size(tree_bits);
+        }
+        let query_round_size = initial_trees_size + steps_size;
+
+        let num_reductions = fri_params.reduction_arity_bits.len();
+        let fri_size = LEN_PREFIX
+            + num_reductions * cap_size
+            + LEN_PREFIX
+            + self.fri_config.num_query_rounds * query_round_size
+            + LEN_PREFIX
+            + fri_params.final_poly_len() * extension_size
+            + field_size;
+
+        caps_size + openings_size + fri_size
+    }
+
     pub fn commit(
         &self,
         trace: &AirTrace<C::F>,
@@ -95,6 +267,140 @@ impl<C: CurtaConfig<D>, const D: usize> StarkyConfig<C, D> {
     }
 }
 
+/// The order in which the challenger observes the data of the rounds of a proof.
+///
+/// The rounds committed since the last challenges were drawn are pending. The pending rounds are
+/// observed before drawing the challenges of a round and after the last round. The challenges are
+/// always drawn round by round, as the trace of the next round may depend on them, so only the
+/// arrangement of the observations of the pending rounds differs.
+#[derive(Debug, Copy, Clone, Default, Eq, PartialEq, Serialize, Deserialize)]
+pub enum TranscriptOrder {
+    /// Each pending round is observed in turn, its global values followed by its trace cap.
+    #[default]
+    PerRound,
+    /// The trace caps of all the pending rounds are observed first, followed by all of their
+    /// global values.
+    CapsBeforeGlobals,
+}
+
+impl TranscriptOrder {
+    /// Whether the pending rounds are observed after the round `round` of `round_data`.
+    pub(crate) fn observes_after(round_data: &[RoundDatum], round: usize) -> bool {
+        round_data[round].num_challenges > 0 || round == round_data.len() - 1
+    }
+
+    /// Observes the pending rounds, given by their global values and trace caps.
+    pub(crate) fn observe_rounds<F, H, OH>(
+        &self,
+        challenger: &mut Challenger<F, H>,
+        rounds: &[(&[F], &MerkleCap<F, OH>)],
+    ) where
+        F: RichField,
+        H: Hasher<F>,
+        OH: Hasher<F, Hash = H::Hash>,
+    {
+        match self {
+            TranscriptOrder::PerRound => {
+                for (global_values, cap) in rounds {
+                    challenger.observe_elements(global_values);
+                    challenger.observe_cap(cap);
+                }
+            }
+            TranscriptOrder::CapsBeforeGlobals => {
+                for (_, cap) in rounds {
+                    challenger.observe_cap(cap);
+                }
+                for (global_values, _) in rounds {
+                    challenger.observe_elements(global_values);
+                }
+            }
+        }
+    }
+
+    /// Observes the pending rounds in a circuit.
+    pub(crate) fn observe_rounds_target<
+        F: RichField + Extendable<D>,
+        H: AlgebraicHasher<F>,
+        const D: usize,
+    >(
+        &self,
+        challenger: &mut RecursiveChallenger<F, H, D>,
+        rounds: &[(&[Target], &MerkleCapTarget)],
+    ) {
+        match self {
+            TranscriptOrder::PerRound => {
+                for (global_values, cap) in rounds {
+                    challenger.observe_elements(global_values);
+                    challenger.observe_cap(cap);
+                }
+            }
+            TranscriptOrder::CapsBeforeGlobals => {
+                for (_, cap) in rounds {
+                    challenger.observe_cap(cap);
+                }
+                for (global_values, _) in rounds {
+                    challenger.observe_elements(global_values);
+                }
+            }
+        }
+    }
+
+    /// Observes the rounds of a proof with trace caps `caps` and returns their challenges.
+    pub(crate) fn round_challenges<F, H, OH>(
+        &self,
+        challenger: &mut Challenger<F, H>,
+        round_data: &[RoundDatum],
+        global_values: &[F],
+        caps: &[MerkleCap<F, OH>],
+    ) -> Vec<F>
+    where
+        F: RichField,
+        H: Hasher<F>,
+        OH: Hasher<F, Hash = H::Hash>,
+    {
+        let mut challenges = Vec::new();
+        let mut pending = Vec::new();
+        for (r, (round, cap)) in round_data.iter().zip_eq(caps.iter()).enumerate() {
+            let (id_0, id_1) = round.global_values_range;
+            pending.push((&global_values[id_0..id_1], cap));
+            if Self::observes_after(round_data, r) {
+                self.observe_rounds(challenger, &pending);
+                pending.clear();
+                challenges.extend(challenger.get_n_challenges(round.num_challenges));
+            }
+        }
+        challenges
+    }
+
+    /// Observes the rounds of a proof with trace caps `caps` in a circuit and returns their
+    /// challenges.
+    pub(crate) fn round_challenges_target<
+        F: RichField + Extendable<D>,
+        H: AlgebraicHasher<F>,
+        const D: usize,
+    >(
+        &self,
+        builder: &mut CircuitBuilder<F, D>,
+        challenger: &mut RecursiveChallenger<F, H, D>,
+        round_data: &[RoundDatum],
+        global_values: &[Target],
+        caps: &[MerkleCapTarget],
+    ) -> Vec<Target> {
+        let mut challenges = Vec::new();
+        let mut pending = Vec::new();
+        for (r, (round, cap)) in round_data.iter().zip_eq(caps.iter()).enumerate() {
+            let (id_0, id_1) = round.global_values_range;
+            pending.push((&global_values[id_0..id_1], cap));
+            if Self::observes_after(round_data, r) {
+                self.observe_rounds_target(challenger, &pending);
+                pending.clear();
+                challenges.extend(challenger.get_n_challenges(builder, round.num_challenges));
+            }
+        }
+        challenges
+    }
+}
+
 #[derive(Debug, Copy, Clone, Eq, PartialEq, Serialize, Deserialize)]
 pub struct CurtaPoseidonGoldilocksConfig;
 
@@ -0,0 +1,108 @@
+//! A harness to test that the verifier rejects tampered proofs.
+
+use plonky2::field::extension::Extendable;
+use plonky2::hash::hash_types::RichField;
+
+use super::config::{CurtaConfig, StarkyConfig};
+use super::proof::StarkProof;
+use super::prover::StarkyProver;
+use super::verifier::StarkyVerifier;
+use super::Starky;
+use crate::plonky2::StarkyAir;
+use crate::trace::generator::TraceGenerator;
+
+/// Generates a valid proof, applies `mutate` to it and asserts that the verifier rejects it.
+///
+/// A mutation that leaves the proof unchanged is skipped, so `mutate` can be drawn at random by a
+/// fuzz target.
+///
+/// # Panics
+///
+/// Panics if the valid proof is rejected or if the mutated proof is accepted.
+pub fn fuzz_verify<A, T, F, C, const D: usize>(
+    stark: &Starky<A>,
+    config: &StarkyConfig<C, D>,
+    trace_generator: &T,
+    public_inputs: &[F],
+    mutate: impl Fn(&mut StarkProof<F, C, D>),
+) where
+    F: RichField + Extendable<D>,
+    C: CurtaConfig<D, F = F, FE = F::Extension>,
+    A: StarkyAir<F, D>,
+    T: TraceGenerator<F, A>,
+    T::Error: Into<anyhow::Error>,
+{
+    let proof =
+        StarkyProver::<F, C, D>::prove(config, stark, trace_generator, public_inputs).unwrap();
+
+    let mut mutated_proof = proof.clone();
+    mutate(&mut mutated_proof);
+
+    StarkyVerifier::verify(config, stark, proof.clone(), public_inputs)
+        .expect("the valid proof was rejected");
+    if mutated_proof == proof {
+        return;
+    }
+    assert!(
+        StarkyVerifier::verify(config, stark, mutated_proof, public_inputs).is_err(),
+        "the mutated proof was accepted"
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use plonky2::field::goldilocks_field::GoldilocksField;
+    use plonky2::field::types::Field;
+
+    use super::*;
+    use crate::air::fibonacci::FibonacciAir;
+    use crate::plonky2::stark::config::{
+        CurtaPoseidonGoldilocksConfig, PoseidonGoldilocksStarkConfig,
+    };
+    use crate::plonky2::stark::tests::fibonacci_statement;
+
+    type F = GoldilocksField;
+    type E = <F as Extendable<2>>::Extension;
+    type C = CurtaPoseidonGoldilocksConfig;
+    type SC = PoseidonGoldilocksStarkConfig;
+
+    /// Runs `fuzz_verify` on a Fibonacci stark large enough to have FRI reduction steps.
+    fn fuzz_fibonacci(mutate: impl Fn(&mut StarkProof<F, C, 2>)) {
+        let num_rows = 1 << 10usize;
+        let stark = Starky::new(FibonacciAir::new());
+        let (public_inputs, trace_generator) = fibonacci_statement(num_rows);
+        let config = SC::standard_fast_config(num_rows);
+
+        fuzz_verify(&stark, &config, &trace_generator, &public_inputs, mutate);
+    }
+
+    #[test]
+    fn test_fuzz_verify_openings() {
+        fuzz_fibonacci(|proof| proof.air_proof.openings.local_values[0] += E::ONE);
+        fuzz_fibonacci(|proof| proof.air_proof.openings.next_values[1] += E::ONE);
+        fuzz_fibonacci(|proof| proof.air_proof.openings.quotient_polys[0] += E::ONE);
+    }
+
+    #[test]
+    fn test_fuzz_verify_caps() {
+        fuzz_fibonacci(|proof| proof.air_proof.trace_caps[0].0[0].elements[0] += F::ONE);
+        fuzz_fibonacci(|proof| proof.air_proof.quotient_polys_cap.0[3].elements[2] += F::ONE);
+    }
+
+    #[test]
+    fn test_fuzz_verify_fri() {
+        fuzz_fibonacci(|proof| {
+            proof.air_proof.opening_proof.commit_phase_merkle_caps[0].0[0].elements[0] += F::ONE
+        });
+        fuzz_fibonacci(|proof| {
+            let query_round = &mut proof.air_proof.opening_proof.query_round_proofs[0];
+            query_round.initial_trees_proof.evals_proofs[0].0[0] += F::ONE;
+        });
+        fuzz_fibonacci(|proof| {
+            let query_round = &mut proof.air_proof.opening_proof.query_round_proofs[1];
+            query_round.steps[0].evals[0] += E::ONE;
+        });
+        fuzz_fibonacci(|proof| proof.air_proof.opening_proof.final_poly.coeffs[0] += E::ONE);
+        fuzz_fibonacci(|proof| proof.air_proof.opening_proof.pow_witness += F::ONE);
+    }
+}
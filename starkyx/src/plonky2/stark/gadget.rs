@@ -7,8 +7,16 @@ use super::config::{CurtaConfig, StarkyConfig};
 use super::proof::StarkProofTarget;
 use super::verifier::{add_virtual_stark_proof, StarkyVerifier};
 use super::Starky;
+use crate::air::RAirData;
 use crate::plonky2::Plonky2Air;
 
+/// The number of field elements absorbed by a Poseidon permutation.
+const POSEIDON_RATE: usize = 8;
+
+/// The number of extension multiply-add operations of an `ArithmeticExtensionGate` with the
+/// standard recursion config.
+const EXTENSION_OPS_PER_GATE: usize = 10;
+
 pub trait StarkGadget<
     F: RichField + Extendable<D>,
     C: CurtaConfig<D, F = F, FE = F::Extension>,
@@ -59,3 +67,76 @@ impl<F: RichField + Extendable<D>, C: CurtaConfig<D, F = F, FE = F::Extension>,
         StarkyVerifier::verify_circuit(self, config, stark, proof, public_inputs)
     }
 }
+
+/// Estimates the number of gates of the circuit verifying a proof of `stark` with `config`, as
+/// built by [`StarkGadget::verify_stark_proof`] with the standard recursion config of plonky2.
+///
+/// The estimate counts a gate per Poseidon permutation of the Merkle proofs and the challenger, and
+/// the extension arithmetic of each FRI query round. The evaluation of the constraints is not
+/// counted, so the estimate is only meaningful for proofs dominated by FRI, and is expected to be
+/// within a factor of two of the actual number of gates.
+pub fn estimate_recursive_gates<
+    F: RichField + Extendable<D>,
+    C: CurtaConfig<D, F = F>,
+    A: RAirData,
+    const D: usize,
+>(
+    stark: &Starky<A>,
+    config: &StarkyConfig<C, D>,
+) -> usize {
+    let fri_params = config.fri_params();
+    let cap_height = config.fri_config.cap_height;
+    let num_permutations = |num_elements: usize| num_elements.div_ceil(POSEIDON_RATE);
+    // Leaves of at most four elements are not hashed.
+    let leaf_hash_gates = |num_elements: usize| {
+        if num_elements <= 4 {
+            0
+        } else {
+            num_permutations(num_elements)
+        }
+    };
+
+    let num_columns = stark.air().num_columns();
+    let num_quotient_polys = stark.num_quotient_polys(config);
+    let oracle_sizes = stark
+        .air()
+        .round_data()
+        .iter()
+        .map(|round| round.num_columns)
+        .chain(core::iter::once(num_quotient_polys))
+        .collect::<Vec<_>>();
+
+    // The Merkle proofs of the initial trees, each followed by the selection of a cap element.
+    let lde_bits = fri_params.lde_bits();
+    let initial_trees_gates = oracle_sizes
+        .iter()
+        .map(|&size| leaf_hash_gates(size) + lde_bits - cap_height + 1)
+        .sum::<usize>();
+
+    // The Merkle proof of each reduction step, the selection of the evaluation in the coset and
+    // the interpolation of the folded value.
+    let mut tree_bits = lde_bits;
+    let mut steps_gates = 0;
+    for &arity_bits in fri_params.reduction_arity_bits.iter() {
+        tree_bits -= arity_bits;
+        steps_gates += leaf_hash_gates((1 << arity_bits) * D) + tree_bits - cap_height + 3;
+    }
+
+    // The combination of the openings at the query point and the evaluation of the final
+    // polynomial, plus the decomposition of the query index.
+    let num_steps = fri_params.reduction_arity_bits.len();
+    let extension_ops =
+        2 * num_columns + num_quotient_polys + fri_params.final_poly_len() + 4 * num_steps;
+    let arithmetic_gates = extension_ops.div_ceil(EXTENSION_OPS_PER_GATE) + 2;
+
+    let query_round_gates = initial_trees_gates + steps_gates + arithmetic_gates;
+
+    // The challenger observes the caps, the openings and the final polynomial.
+    let cap_len = (1 << cap_height) * 4;
+    let num_observed = (oracle_sizes.len() + num_steps) * cap_len
+        + (2 * num_columns + num_quotient_polys + fri_params.final_poly_len()) * D
+        + stark.air().num_public_inputs();
+    let challenger_gates = num_permutations(num_observed);
+
+    config.fri_config.num_query_rounds * query_round_gates + challenger_gates
+}
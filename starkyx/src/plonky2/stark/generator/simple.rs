@@ -7,6 +7,7 @@ use plonky2::iop::target::Target;
 use plonky2::iop::witness::{PartitionWitness, Witness};
 use plonky2::plonk::circuit_data::CommonCircuitData;
 use plonky2::util::serialization::{Buffer, IoResult};
+use serde::de::DeserializeOwned;
 use serde::{Deserialize, Serialize};
 
 use super::super::config::StarkyConfig;
@@ -18,25 +19,31 @@ use crate::chip::{AirParameters, Chip};
 use crate::plonky2::stark::config::CurtaConfig;
 use crate::plonky2::stark::Starky;
 use crate::plonky2::Plonky2Air;
+use crate::trace::generator::TraceGenerator;
 use crate::utils::serde::{BufferRead, BufferWrite};
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
-#[serde(bound = "")]
-pub struct SimpleStarkWitnessGenerator<L: AirParameters, C, const D: usize> {
+#[serde(bound(serialize = "T: Serialize", deserialize = "T: DeserializeOwned"))]
+pub struct SimpleStarkWitnessGenerator<
+    L: AirParameters,
+    C,
+    const D: usize,
+    T = ArithmeticGenerator<L>,
+> {
     pub config: StarkyConfig<C, D>,
     pub stark: Starky<Chip<L>>,
     pub proof_target: StarkProofTarget<D>,
     pub public_input_targets: Vec<Target>,
-    pub trace_generator: ArithmeticGenerator<L>,
+    pub trace_generator: T,
 }
 
-impl<L: AirParameters, C, const D: usize> SimpleStarkWitnessGenerator<L, C, D> {
+impl<L: AirParameters, C, const D: usize, T> SimpleStarkWitnessGenerator<L, C, D, T> {
     pub fn new(
         config: StarkyConfig<C, D>,
         stark: Starky<Chip<L>>,
         proof_target: StarkProofTarget<D>,
         public_input_targets: Vec<Target>,
-        trace_generator: ArithmeticGenerator<L>,
+        trace_generator: T,
     ) -> Self {
         Self {
             config,
@@ -49,20 +56,23 @@ impl<L: AirParameters, C, const D: usize> SimpleStarkWitnessGenerator<L, C, D> {
 
     pub fn id() -> String {
         format!(
-            "SimpleStarkWitnessGenerator, air parameters: {}, D = {}",
+            "SimpleStarkWitnessGenerator, air parameters: {}, trace generator: {}, D = {}",
             L::id(),
+            core::any::type_name::<T>(),
             D
         )
         .to_string()
     }
 }
 
-impl<L: AirParameters, C, const D: usize> SimpleGenerator<L::Field, D>
-    for SimpleStarkWitnessGenerator<L, C, D>
+impl<L: AirParameters, C, const D: usize, T> SimpleGenerator<L::Field, D>
+    for SimpleStarkWitnessGenerator<L, C, D, T>
 where
     L::Field: RichField + Extendable<D>,
     Chip<L>: Plonky2Air<L::Field, D>,
     C: CurtaConfig<D, F = L::Field>,
+    T: TraceGenerator<L::Field, Chip<L>>,
+    T::Error: Into<anyhow::Error>,
 {
     fn id(&self) -> String {
         Self::id()
@@ -15,7 +15,10 @@ use serde::{Deserialize, Serialize};
 use self::config::{CurtaConfig, StarkyConfig};
 use crate::air::RAirData;
 
+#[cfg(feature = "bench")]
+pub mod bench;
 pub mod config;
+pub mod fuzz;
 pub mod gadget;
 pub mod generator;
 pub mod proof;
@@ -88,10 +91,17 @@ impl<A> Starky<A> {
         };
         let zeta_next_batch = FriBatchInfo {
             point: zeta.scalar_mul(g),
-            polynomials: trace_info,
+            polynomials: trace_info.clone(),
         };
+        let shifted_batches = config.opening_shifts.iter().map(|&shift| FriBatchInfo {
+            point: zeta.scalar_mul(g.exp_u64(shift as u64)),
+            polynomials: trace_info.clone(),
+        });
 
-        let batches = vec![zeta_batch, zeta_next_batch];
+        let batches = [zeta_batch, zeta_next_batch]
+            .into_iter()
+            .chain(shifted_batches)
+            .collect();
         FriInstanceInfo { oracles, batches }
     }
 
@@ -138,16 +148,28 @@ impl<A> Starky<A> {
         let zeta_next = builder.mul_const_extension(g, zeta);
         let zeta_next_batch = FriBatchInfoTarget {
             point: zeta_next,
-            polynomials: trace_info,
+            polynomials: trace_info.clone(),
         };
-
-        let batches = vec![zeta_batch, zeta_next_batch];
+        let shifted_batches = config
+            .opening_shifts
+            .iter()
+            .map(|&shift| FriBatchInfoTarget {
+                point: builder.mul_const_extension(g.exp_u64(shift as u64), zeta),
+                polynomials: trace_info.clone(),
+            })
+            .collect::<Vec<_>>();
+
+        let batches = [zeta_batch, zeta_next_batch]
+            .into_iter()
+            .chain(shifted_batches)
+            .collect();
         FriInstanceInfoTarget { oracles, batches }
     }
 }
 
 #[cfg(test)]
 pub(crate) mod tests {
+    use alloc::sync::Arc;
     use core::fmt::Debug;
 
     use plonky2::field::goldilocks_field::GoldilocksField;
@@ -160,15 +182,28 @@ pub(crate) mod tests {
     use super::generator::simple::SimpleStarkWitnessGenerator;
     use super::*;
     use crate::air::fibonacci::FibonacciAir;
-    use crate::chip::builder::tests::ArithmeticGenerator;
+    use crate::air::parser::AirParser;
+    use crate::air::{RAir, RoundDatum};
+    use crate::chip::arithmetic::expression::ArithmeticExpression;
+    use crate::chip::builder::AirBuilder;
+    use crate::chip::instruction::empty::EmptyInstruction;
+    use crate::chip::register::element::ElementRegister;
+    use crate::chip::register::Register;
+    use crate::chip::trace::generator::ArithmeticGenerator;
     use crate::chip::{AirParameters, Chip};
+    use crate::math::goldilocks::cubic::GoldilocksCubicParameters;
     use crate::math::prelude::*;
-    use crate::plonky2::stark::config::PoseidonGoldilocksStarkConfig;
-    use crate::plonky2::stark::gadget::StarkGadget;
+    use crate::plonky2::parser::consumer::ConstraintCombiner;
+    use crate::plonky2::stark::config::{
+        CurtaPoseidonGoldilocksConfig, PoseidonGoldilocksStarkConfig, TranscriptOrder,
+    };
+    use crate::plonky2::stark::gadget::{estimate_recursive_gates, StarkGadget};
+    use crate::plonky2::stark::proof::{StarkProof, TranscriptState, PROOF_FORMAT_VERSION};
     use crate::plonky2::stark::prover::StarkyProver;
-    use crate::plonky2::stark::verifier::StarkyVerifier;
+    use crate::plonky2::stark::verifier::{set_stark_proof_target, StarkyVerifier};
     use crate::plonky2::{Plonky2Air, StarkyAir};
     use crate::trace::generator::{ConstantGenerator, TraceGenerator};
+    use crate::trace::AirTrace;
 
     /// Generate the proof and verify as a stark
     pub(crate) fn test_starky<
@@ -198,17 +233,20 @@ pub(crate) mod tests {
     /// Generate a Stark proof and a recursive proof using the witness generator
     pub(crate) fn test_recursive_starky<
         L: AirParameters<Field = F>,
+        T,
         F: RichField + Extendable<D>,
         C: CurtaConfig<D, F = F, FE = F::Extension> + 'static + Serialize + DeserializeOwned,
         const D: usize,
     >(
         stark: Starky<Chip<L>>,
         config: StarkyConfig<C, D>,
-        trace_generator: ArithmeticGenerator<L>,
+        trace_generator: T,
         public_inputs: &[F],
     ) where
         C::Hasher: AlgebraicHasher<F>,
         Chip<L>: Plonky2Air<F, D>,
+        T: TraceGenerator<F, Chip<L>>,
+        T::Error: Into<anyhow::Error>,
     {
         let config_rec = CircuitConfig::standard_recursion_config();
         let mut builder = CircuitBuilder::<F, D>::new(config_rec);
@@ -241,27 +279,719 @@ pub(crate) mod tests {
         data.verify(recursive_proof).unwrap();
     }
 
-    #[test]
-    fn test_plonky2_fibonacci_stark() {
+    /// The public inputs and the trace generator of the Fibonacci stark on `num_rows` rows,
+    /// starting from `0, 1`.
+    pub(crate) fn fibonacci_statement(
+        num_rows: usize,
+    ) -> ([GoldilocksField; 3], ConstantGenerator<GoldilocksField>) {
         type F = GoldilocksField;
-        type SC = PoseidonGoldilocksStarkConfig;
-
-        let num_rows = 1 << 5usize;
-        let air = FibonacciAir::new();
-        let stark = Starky::<FibonacciAir>::new(air);
 
         let public_inputs = [
             F::ZERO,
             F::ONE,
             FibonacciAir::fibonacci(num_rows - 1, F::ZERO, F::ONE),
         ];
-
         let trace = FibonacciAir::generate_trace(F::ZERO, F::ONE, num_rows);
-        let trace_generator = ConstantGenerator::new(trace);
+
+        (public_inputs, ConstantGenerator::new(trace))
+    }
+
+    /// Proves the Fibonacci stark on `num_rows` rows with the standard fast config.
+    pub(crate) fn fibonacci_proof(
+        num_rows: usize,
+    ) -> (
+        Starky<FibonacciAir>,
+        PoseidonGoldilocksStarkConfig,
+        [GoldilocksField; 3],
+        StarkProof<GoldilocksField, CurtaPoseidonGoldilocksConfig, 2>,
+    ) {
+        let stark = Starky::new(FibonacciAir::new());
+        let config = PoseidonGoldilocksStarkConfig::standard_fast_config(num_rows);
+        let (public_inputs, trace_generator) = fibonacci_statement(num_rows);
+
+        let proof = StarkyProver::prove(&config, &stark, &trace_generator, &public_inputs).unwrap();
+
+        (stark, config, public_inputs, proof)
+    }
+
+    #[test]
+    fn test_plonky2_fibonacci_stark() {
+        type SC = PoseidonGoldilocksStarkConfig;
+
+        let num_rows = 1 << 5usize;
+        let air = FibonacciAir::new();
+        let stark = Starky::<FibonacciAir>::new(air);
+        let (public_inputs, trace_generator) = fibonacci_statement(num_rows);
 
         let config = SC::standard_fast_config(num_rows);
 
         // Generate proof and verify as a stark
         test_starky(&stark, &config, &trace_generator, &public_inputs);
     }
+
+    #[test]
+    fn test_verify_with_public_inputs_hash() {
+        type F = GoldilocksField;
+        type C = CurtaPoseidonGoldilocksConfig;
+        const D: usize = 2;
+
+        let (stark, config, public_inputs, proof) = fibonacci_proof(1 << 5);
+        let public_inputs_hash = StarkyVerifier::<F, C, D>::hash_public_inputs(&public_inputs);
+
+        StarkyVerifier::verify_with_public_inputs_hash(
+            &config,
+            &stark,
+            proof.clone(),
+            &public_inputs,
+            public_inputs_hash,
+        )
+        .unwrap();
+
+        // Public inputs that do not open the claimed hash are rejected.
+        let mut other_inputs = public_inputs;
+        other_inputs[2] += F::ONE;
+        assert!(StarkyVerifier::verify_with_public_inputs_hash(
+            &config,
+            &stark,
+            proof.clone(),
+            &other_inputs,
+            public_inputs_hash,
+        )
+        .is_err());
+
+        // So is a hash of other public inputs.
+        let other_hash = StarkyVerifier::<F, C, D>::hash_public_inputs(&other_inputs);
+        assert!(StarkyVerifier::verify_with_public_inputs_hash(
+            &config,
+            &stark,
+            proof,
+            &public_inputs,
+            other_hash,
+        )
+        .is_err());
+    }
+
+    #[derive(Debug, Clone, Serialize, Deserialize)]
+    struct BlindFibonacciParameters;
+
+    impl AirParameters for BlindFibonacciParameters {
+        type Field = GoldilocksField;
+        type CubicParams = GoldilocksCubicParameters;
+        type Instruction = EmptyInstruction<GoldilocksField>;
+        const NUM_ARITHMETIC_COLUMNS: usize = 0;
+        const NUM_FREE_COLUMNS: usize = 4;
+        const EXTENDED_COLUMNS: usize = 0;
+    }
+
+    #[test]
+    fn test_estimate_recursive_gates() {
+        type F = GoldilocksField;
+        type C = CurtaPoseidonGoldilocksConfig;
+        type SC = PoseidonGoldilocksStarkConfig;
+        const D: usize = 2;
+
+        let stark = Starky::new(FibonacciAir::new());
+        for log_num_rows in [5, 10] {
+            let config = SC::standard_fast_config(1 << log_num_rows);
+
+            let mut builder =
+                CircuitBuilder::<F, D>::new(CircuitConfig::standard_recursion_config());
+            let virtual_proof = builder.add_virtual_stark_proof(&stark, &config);
+            let public_input_targets = builder.add_virtual_targets(stark.air().num_public_inputs());
+            builder.verify_stark_proof(&config, &stark, &virtual_proof, &public_input_targets);
+            let num_gates = builder.num_gates();
+
+            let estimate = estimate_recursive_gates::<F, C, _, D>(&stark, &config);
+            assert!(
+                estimate <= 2 * num_gates && num_gates <= 2 * estimate,
+                "estimate: {} gates, circuit: {} gates",
+                estimate,
+                num_gates
+            );
+        }
+    }
+
+    #[test]
+    fn test_blind_unused_columns_stark() {
+        type F = GoldilocksField;
+        type L = BlindFibonacciParameters;
+        type C = CurtaPoseidonGoldilocksConfig;
+        type SC = PoseidonGoldilocksStarkConfig;
+        const D: usize = 2;
+
+        // Only two of the four free columns are used, the other two are blinded.
+        let mut builder = AirBuilder::<L>::new();
+        let x_0 = builder.alloc::<ElementRegister>();
+        let x_1 = builder.alloc::<ElementRegister>();
+        builder.set_to_expression_transition(&x_0.next(), x_1.expr());
+        builder.set_to_expression_transition(&x_1.next(), x_0.expr() + x_1.expr());
+
+        let (air, trace_data) = builder.build();
+        assert_eq!(air.unused_columns(), vec![2, 3]);
+
+        let num_rows = 1 << 5usize;
+        let generator = ArithmeticGenerator::<L>::new(trace_data, num_rows);
+        let writer = generator.new_writer();
+        writer.write(&x_0, &F::ZERO, 0);
+        writer.write(&x_1, &F::ONE, 0);
+        for i in 0..num_rows {
+            writer.write_row_instructions(&generator.air_data, i);
+        }
+
+        let stark = Starky::new(air);
+        let config = SC::standard_fast_config(num_rows).with_blind_unused_columns(true);
+
+        let proof = StarkyProver::<F, C, D>::prove(&config, &stark, &generator, &[]).unwrap();
+        let other_proof = StarkyProver::<F, C, D>::prove(&config, &stark, &generator, &[]).unwrap();
+
+        // Two proofs of the same statement differ in the blinded columns.
+        assert_ne!(proof, other_proof);
+
+        StarkyVerifier::verify(&config, &stark, proof, &[]).unwrap();
+        StarkyVerifier::verify(&config, &stark, other_proof, &[]).unwrap();
+    }
+
+    #[test]
+    fn test_verify_rejects_mismatched_trace_caps() {
+        let (stark, config, public_inputs, proof) = fibonacci_proof(1 << 5);
+
+        // A proof with one trace cap per round of another stark is rejected, not mis-derived.
+        let mut bad_proof = proof.clone();
+        let extra_cap = bad_proof.air_proof.trace_caps[0].clone();
+        bad_proof.air_proof.trace_caps.push(extra_cap);
+        let err = StarkyVerifier::verify(&config, &stark, bad_proof, &public_inputs).unwrap_err();
+        assert!(err.to_string().contains("Invalid number of trace caps"));
+
+        let mut bad_proof = proof.clone();
+        bad_proof.air_proof.trace_caps.clear();
+        assert!(StarkyVerifier::verify(&config, &stark, bad_proof, &public_inputs).is_err());
+
+        StarkyVerifier::verify(&config, &stark, proof, &public_inputs).unwrap();
+    }
+
+    #[test]
+    fn test_verify_rejects_truncated_openings() {
+        let (stark, config, public_inputs, proof) = fibonacci_proof(1 << 5);
+
+        let mut bad_proof = proof.clone();
+        bad_proof.air_proof.openings.local_values.pop();
+        let err = StarkyVerifier::verify(&config, &stark, bad_proof, &public_inputs).unwrap_err();
+        assert!(err
+            .to_string()
+            .contains("Invalid number of opened local values"));
+
+        let mut bad_proof = proof.clone();
+        bad_proof.air_proof.openings.next_values.truncate(1);
+        let err = StarkyVerifier::verify(&config, &stark, bad_proof, &public_inputs).unwrap_err();
+        assert!(err
+            .to_string()
+            .contains("Invalid number of opened next values"));
+
+        StarkyVerifier::verify(&config, &stark, proof, &public_inputs).unwrap();
+    }
+
+    #[test]
+    fn test_proof_bytes() {
+        type F = GoldilocksField;
+        type C = CurtaPoseidonGoldilocksConfig;
+        const D: usize = 2;
+
+        let (stark, config, public_inputs, proof) = fibonacci_proof(1 << 5);
+
+        let bytes = proof.to_bytes().unwrap();
+        assert_eq!(bytes[0], PROOF_FORMAT_VERSION);
+        let decoded = StarkProof::<F, C, D>::from_bytes(&bytes).unwrap();
+        assert_eq!(decoded, proof);
+        StarkyVerifier::verify(&config, &stark, decoded, &public_inputs).unwrap();
+
+        // An encoding with an unknown version is rejected.
+        let mut bad_bytes = bytes.clone();
+        bad_bytes[0] = PROOF_FORMAT_VERSION + 1;
+        let err = StarkProof::<F, C, D>::from_bytes(&bad_bytes).unwrap_err();
+        assert!(err.to_string().contains("Unsupported proof format version"));
+
+        assert!(StarkProof::<F, C, D>::from_bytes(&[]).is_err());
+        assert!(StarkProof::<F, C, D>::from_bytes(&bytes[..bytes.len() / 2]).is_err());
+    }
+
+    #[test]
+    fn test_derive_challenges() {
+        type F = GoldilocksField;
+
+        let num_rows = 1 << 5usize;
+        let (stark, config, public_inputs, proof) = fibonacci_proof(num_rows);
+
+        let challenges = proof.derive_challenges(&config, &stark, &public_inputs);
+
+        assert_eq!(challenges.alphas.len(), config.num_challenges);
+        assert_eq!(
+            challenges.fri_query_indices.len(),
+            config.fri_config.num_query_rounds
+        );
+        let lde_size = num_rows << config.fri_config.rate_bits;
+        assert!(challenges.fri_query_indices.iter().all(|&i| i < lde_size));
+
+        // The challenges only depend on the proof and the public inputs.
+        assert_eq!(
+            proof.derive_challenges(&config, &stark, &public_inputs),
+            challenges
+        );
+        let other_inputs = [F::ONE, F::ONE, public_inputs[2]];
+        assert_ne!(
+            proof.derive_challenges(&config, &stark, &other_inputs),
+            challenges
+        );
+    }
+
+    /// Combines the constraints with coefficients that are not the powers of alpha.
+    #[derive(Debug)]
+    struct ShiftedPowers;
+
+    impl ConstraintCombiner<GoldilocksField> for ShiftedPowers {
+        fn coefficient(&self, alpha: GoldilocksField, index: usize) -> GoldilocksField {
+            alpha.exp_u64(index as u64 + 1) + GoldilocksField::from_canonical_usize(index)
+        }
+    }
+
+    #[test]
+    fn test_custom_constraint_combiner() {
+        type F = GoldilocksField;
+        type C = CurtaPoseidonGoldilocksConfig;
+        type SC = PoseidonGoldilocksStarkConfig;
+        const D: usize = 2;
+
+        let num_rows = 1 << 5usize;
+        let stark = Starky::new(FibonacciAir::new());
+        let (public_inputs, trace_generator) = fibonacci_statement(num_rows);
+        let config = SC::standard_fast_config(num_rows);
+
+        let combiner: Arc<dyn ConstraintCombiner<F>> = Arc::new(ShiftedPowers);
+        let proof = StarkyProver::<F, C, D>::prove_with_combiner(
+            &config,
+            &stark,
+            &trace_generator,
+            &public_inputs,
+            combiner.clone(),
+        )
+        .unwrap();
+
+        StarkyVerifier::verify_with_combiner(
+            &config,
+            &stark,
+            proof.clone(),
+            &public_inputs,
+            combiner,
+        )
+        .unwrap();
+
+        // The default combination does not match the quotient committed by the prover.
+        assert!(StarkyVerifier::verify(&config, &stark, proof, &public_inputs).is_err());
+    }
+
+    /// A trace generator that must not be called.
+    #[derive(Debug, Serialize, Deserialize)]
+    struct UnreachableGenerator;
+
+    impl<F, A> TraceGenerator<F, A> for UnreachableGenerator {
+        type Error = anyhow::Error;
+
+        fn generate_round(
+            &self,
+            _air: &A,
+            _round: usize,
+            _challenges: &[F],
+            _global_values: &mut [F],
+            _public_inputs: &[F],
+        ) -> Result<AirTrace<F>, Self::Error> {
+            panic!("the trace must not be generated")
+        }
+    }
+
+    #[test]
+    fn test_max_trace_cells() {
+        type F = GoldilocksField;
+        type C = CurtaPoseidonGoldilocksConfig;
+        type SC = PoseidonGoldilocksStarkConfig;
+        const D: usize = 2;
+
+        let num_rows = 1 << 5usize;
+        let stark = Starky::new(FibonacciAir::new());
+        let num_cells = num_rows * stark.air().num_columns();
+        let (public_inputs, trace_generator) = fibonacci_statement(num_rows);
+
+        // An over-budget trace is rejected before it is generated.
+        let config = SC::standard_fast_config(num_rows).with_max_trace_cells(num_cells - 1);
+        let err =
+            StarkyProver::<F, C, D>::prove(&config, &stark, &UnreachableGenerator, &public_inputs)
+                .unwrap_err();
+        assert!(err.to_string().contains("exceeding the budget"));
+
+        // A trace within the budget is proven as usual.
+        let config = SC::standard_fast_config(num_rows).with_max_trace_cells(num_cells);
+        test_starky(&stark, &config, &trace_generator, &public_inputs);
+    }
+
+    #[test]
+    fn test_estimate_proof_size() {
+        type F = GoldilocksField;
+        type L = BlindFibonacciParameters;
+        type C = CurtaPoseidonGoldilocksConfig;
+        type SC = PoseidonGoldilocksStarkConfig;
+        const D: usize = 2;
+
+        let mut builder = AirBuilder::<L>::new();
+        let x_0 = builder.alloc::<ElementRegister>();
+        let x_1 = builder.alloc::<ElementRegister>();
+        builder.set_to_expression_transition(&x_0.next(), x_1.expr());
+        builder.set_to_expression_transition(&x_1.next(), x_0.expr() + x_1.expr());
+
+        let (air, trace_data) = builder.build();
+
+        for log_num_rows in [5, 10] {
+            let num_rows = 1 << log_num_rows;
+            let generator = ArithmeticGenerator::<L>::new(trace_data.clone(), num_rows);
+            let writer = generator.new_writer();
+            writer.write(&x_0, &F::ZERO, 0);
+            writer.write(&x_1, &F::ONE, 0);
+            for i in 0..num_rows {
+                writer.write_row_instructions(&generator.air_data, i);
+            }
+
+            let stark = Starky::new(air.clone());
+            let config = SC::standard_fast_config(num_rows);
+            let num_columns = stark.air().num_columns();
+
+            let proof = StarkyProver::<F, C, D>::prove(&config, &stark, &generator, &[]).unwrap();
+            let proof_size = bincode::serialize(&proof).unwrap().len();
+            let estimate = config.estimate_proof_size(num_rows, num_columns);
+
+            let error = proof_size.abs_diff(estimate);
+            assert!(
+                error * 20 <= proof_size,
+                "estimate: {} bytes, proof: {} bytes",
+                estimate,
+                proof_size
+            );
+        }
+    }
+
+    #[derive(Debug, Clone, Serialize, Deserialize)]
+    struct TranscriptOrderParameters;
+
+    impl AirParameters for TranscriptOrderParameters {
+        type Field = GoldilocksField;
+        type CubicParams = GoldilocksCubicParameters;
+        type Instruction = EmptyInstruction<GoldilocksField>;
+        const NUM_ARITHMETIC_COLUMNS: usize = 0;
+        const NUM_FREE_COLUMNS: usize = 2;
+        const EXTENDED_COLUMNS: usize = 1;
+    }
+
+    #[test]
+    fn test_transcript_order() {
+        type F = GoldilocksField;
+        type L = TranscriptOrderParameters;
+        type C = CurtaPoseidonGoldilocksConfig;
+        type SC = PoseidonGoldilocksStarkConfig;
+        const D: usize = 2;
+
+        // A Fibonacci chip with a global value, observed in the second round, and an unused
+        // extended column to have two rounds.
+        let mut builder = AirBuilder::<L>::new();
+        let x_0 = builder.alloc::<ElementRegister>();
+        let x_1 = builder.alloc::<ElementRegister>();
+        builder.set_to_expression_transition(&x_0.next(), x_1.expr());
+        builder.set_to_expression_transition(&x_1.next(), x_0.expr() + x_1.expr());
+        let global = builder.alloc_global::<ElementRegister>();
+        builder.set_to_expression_public(
+            &global,
+            ArithmeticExpression::from_constant(F::from_canonical_u64(7)),
+        );
+
+        let (air, trace_data) = builder.build();
+
+        let num_rows = 1 << 5usize;
+        let generator = ArithmeticGenerator::<L>::new(trace_data, num_rows);
+        let writer = generator.new_writer();
+        writer.write_global_instructions(&generator.air_data);
+        writer.write(&x_0, &F::ZERO, 0);
+        writer.write(&x_1, &F::ONE, 0);
+        for i in 0..num_rows {
+            writer.write_row_instructions(&generator.air_data, i);
+        }
+
+        let stark = Starky::new(air);
+        let orders = [
+            TranscriptOrder::PerRound,
+            TranscriptOrder::CapsBeforeGlobals,
+        ];
+        for (i, order) in orders.iter().enumerate() {
+            let config = SC::standard_fast_config(num_rows).with_transcript_order(*order);
+            let other_config =
+                SC::standard_fast_config(num_rows).with_transcript_order(orders[1 - i]);
+
+            let proof = StarkyProver::<F, C, D>::prove(&config, &stark, &generator, &[]).unwrap();
+            assert!(StarkyVerifier::verify(&other_config, &stark, proof.clone(), &[]).is_err());
+            StarkyVerifier::verify(&config, &stark, proof, &[]).unwrap();
+
+            test_recursive_starky(stark.clone(), config, generator.clone(), &[]);
+        }
+    }
+
+    #[test]
+    fn test_transcript_chain() {
+        type F = GoldilocksField;
+        type C = CurtaPoseidonGoldilocksConfig;
+        type SC = PoseidonGoldilocksStarkConfig;
+        const D: usize = 2;
+
+        let num_rows = 1 << 5usize;
+        let stark = Starky::new(FibonacciAir::new());
+        let config = SC::standard_fast_config(num_rows);
+
+        // Two Fibonacci proofs with different initial values.
+        let statement = |x_0: F, x_1: F| {
+            let public_inputs = vec![x_0, x_1, FibonacciAir::fibonacci(num_rows - 1, x_0, x_1)];
+            let trace = FibonacciAir::generate_trace(x_0, x_1, num_rows);
+            (public_inputs, ConstantGenerator::new(trace))
+        };
+        let (public_inputs_0, generator_0) = statement(F::ZERO, F::ONE);
+        let (public_inputs_1, generator_1) = statement(F::ONE, F::from_canonical_u64(2));
+
+        let mut prover_transcript = TranscriptState::new();
+        let proof_0 = StarkyProver::<F, C, D>::prove_with_transcript(
+            &config,
+            &stark,
+            &generator_0,
+            &public_inputs_0,
+            &mut prover_transcript,
+        )
+        .unwrap();
+        let proof_1 = StarkyProver::<F, C, D>::prove_with_transcript(
+            &config,
+            &stark,
+            &generator_1,
+            &public_inputs_1,
+            &mut prover_transcript,
+        )
+        .unwrap();
+
+        // The chain verifies in order.
+        let mut verifier_transcript = TranscriptState::new();
+        StarkyVerifier::verify_with_transcript(
+            &config,
+            &stark,
+            proof_0.clone(),
+            &public_inputs_0,
+            &mut verifier_transcript,
+        )
+        .unwrap();
+        StarkyVerifier::verify_with_transcript(
+            &config,
+            &stark,
+            proof_1.clone(),
+            &public_inputs_1,
+            &mut verifier_transcript,
+        )
+        .unwrap();
+        assert_eq!(verifier_transcript, prover_transcript);
+
+        // The second proof depends on the first one, so the reordered chain is rejected.
+        let mut reordered_transcript = TranscriptState::new();
+        assert!(StarkyVerifier::verify_with_transcript(
+            &config,
+            &stark,
+            proof_1,
+            &public_inputs_1,
+            &mut reordered_transcript,
+        )
+        .is_err());
+        assert_eq!(reordered_transcript, TranscriptState::new());
+        assert!(StarkyVerifier::verify_with_transcript(
+            &config,
+            &stark,
+            proof_0.clone(),
+            &public_inputs_0,
+            &mut proof_0.combine(&TranscriptState::new(), &public_inputs_0),
+        )
+        .is_err());
+    }
+
+    #[derive(Debug, Clone, Serialize, Deserialize)]
+    struct OpeningShiftsParameters;
+
+    impl AirParameters for OpeningShiftsParameters {
+        type Field = GoldilocksField;
+        type CubicParams = GoldilocksCubicParameters;
+        type Instruction = EmptyInstruction<GoldilocksField>;
+        const NUM_ARITHMETIC_COLUMNS: usize = 0;
+        const NUM_FREE_COLUMNS: usize = 2;
+        const EXTENDED_COLUMNS: usize = 0;
+    }
+
+    #[test]
+    fn test_opening_shifts() {
+        type F = GoldilocksField;
+        type L = OpeningShiftsParameters;
+        type C = CurtaPoseidonGoldilocksConfig;
+        type SC = PoseidonGoldilocksStarkConfig;
+        const D: usize = 2;
+
+        let mut builder = AirBuilder::<L>::new();
+        let x_0 = builder.alloc::<ElementRegister>();
+        let x_1 = builder.alloc::<ElementRegister>();
+        builder.set_to_expression_transition(&x_0.next(), x_1.expr());
+        builder.set_to_expression_transition(&x_1.next(), x_0.expr() + x_1.expr());
+
+        let (air, trace_data) = builder.build();
+
+        let num_rows = 1 << 5usize;
+        let generator = ArithmeticGenerator::<L>::new(trace_data, num_rows);
+        let writer = generator.new_writer();
+        writer.write(&x_0, &F::ZERO, 0);
+        writer.write(&x_1, &F::ONE, 0);
+        for i in 0..num_rows {
+            writer.write_row_instructions(&generator.air_data, i);
+        }
+
+        let stark = Starky::new(air);
+        // Open the trace on a window of three rows, at `zeta`, `g * zeta` and `g^2 * zeta`.
+        let config = SC::standard_fast_config(num_rows).with_opening_shifts(vec![2]);
+        let num_columns = stark.air().num_columns();
+
+        let proof = StarkyProver::<F, C, D>::prove(&config, &stark, &generator, &[]).unwrap();
+        let shifted_values = &proof.air_proof.openings.shifted_values;
+        assert_eq!(shifted_values.len(), 1);
+        assert!(shifted_values.iter().all(|v| v.len() == num_columns));
+
+        // The shifted openings are checked against the trace commitment.
+        let mut bad_proof = proof.clone();
+        bad_proof.air_proof.openings.shifted_values[0][0] += <F as Extendable<D>>::Extension::ONE;
+        assert!(StarkyVerifier::verify(&config, &stark, bad_proof, &[]).is_err());
+
+        // The number of shifted openings must match the config.
+        let default_config = SC::standard_fast_config(num_rows);
+        let err = StarkyVerifier::verify(&default_config, &stark, proof.clone(), &[]).unwrap_err();
+        assert!(err
+            .to_string()
+            .contains("Invalid number of shifted openings"));
+
+        StarkyVerifier::verify(&config, &stark, proof, &[]).unwrap();
+
+        test_recursive_starky(stark, config, generator, &[]);
+    }
+
+    /// The recurrence `x_{i+2} = t * x_{i+1} - x_i` with `t = w + 1/w`, where `w` is a root of
+    /// unity of order the number of rows.
+    ///
+    /// Its solutions are combinations of `w^i` and `w^{-i}`, so the recurrence also holds when
+    /// the rows wrap around the trace.
+    #[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+    struct RecurrenceAir {
+        degree_bits: usize,
+    }
+
+    impl RecurrenceAir {
+        fn coefficient<F: RichField>(&self) -> F {
+            let w = F::primitive_root_of_unity(self.degree_bits);
+            w + w.inverse()
+        }
+
+        fn generate_trace<F: RichField>(&self, x_0: F, x_1: F) -> AirTrace<F> {
+            let t = self.coefficient::<F>();
+            let mut values = vec![x_0, x_1];
+            for i in 2..1 << self.degree_bits {
+                values.push(t * values[i - 1] - values[i - 2]);
+            }
+            AirTrace::from_rows(values, 1)
+        }
+    }
+
+    impl RAirData for RecurrenceAir {
+        fn width(&self) -> usize {
+            1
+        }
+
+        fn constraint_degree(&self) -> usize {
+            1
+        }
+
+        fn round_data(&self) -> Vec<RoundDatum> {
+            vec![RoundDatum::new(self.width(), (0, 0), 0)]
+        }
+
+        fn num_public_inputs(&self) -> usize {
+            2
+        }
+    }
+
+    impl<AP: AirParser<Field = GoldilocksField>> RAir<AP> for RecurrenceAir {
+        fn eval(&self, parser: &mut AP) {
+            let x = parser.local_slice()[0];
+            let x_next = parser.next_slice()[0];
+            let x_next_next = parser.shifted_slice(2)[0];
+
+            let first = parser.sub(x, parser.public_slice()[0]);
+            parser.constraint_first_row(first);
+            let second = parser.sub(x_next, parser.public_slice()[1]);
+            parser.constraint_first_row(second);
+
+            // x_{i+2} - t * x_{i+1} + x_i = 0 on every row.
+            let t_x_next = parser.mul_const(x_next, self.coefficient());
+            let constraint = parser.sub(x_next_next, t_x_next);
+            let constraint = parser.add(constraint, x);
+            parser.constraint(constraint);
+        }
+
+        fn eval_global(&self, _parser: &mut AP) {}
+    }
+
+    #[test]
+    fn test_opening_shifts_recurrence() {
+        type F = GoldilocksField;
+        type C = CurtaPoseidonGoldilocksConfig;
+        type SC = PoseidonGoldilocksStarkConfig;
+        const D: usize = 2;
+
+        let num_rows = 1 << 5usize;
+        let air = RecurrenceAir { degree_bits: 5 };
+        let stark = Starky::new(air);
+        let public_inputs = [F::ONE, F::from_canonical_u64(7)];
+        let trace = air.generate_trace(public_inputs[0], public_inputs[1]);
+        let generator = ConstantGenerator::new(trace);
+
+        // The window of three rows is opened at `zeta`, `g * zeta` and `g^2 * zeta`.
+        let config = SC::standard_fast_config(num_rows).with_opening_shifts(vec![2]);
+        let proof =
+            StarkyProver::<F, C, D>::prove(&config, &stark, &generator, &public_inputs).unwrap();
+        StarkyVerifier::verify(&config, &stark, proof.clone(), &public_inputs).unwrap();
+
+        // The constraint on the row at shift 2 is checked against its opening.
+        let mut bad_proof = proof.clone();
+        bad_proof.air_proof.openings.shifted_values[0][0] += <F as Extendable<D>>::Extension::ONE;
+        assert!(StarkyVerifier::verify(&config, &stark, bad_proof, &public_inputs).is_err());
+
+        // Verify the proof recursively.
+        let config_rec = CircuitConfig::standard_recursion_config();
+        let mut builder = CircuitBuilder::<F, D>::new(config_rec);
+        let virtual_proof = builder.add_virtual_stark_proof(&stark, &config);
+        let public_input_targets = builder.add_virtual_targets(public_inputs.len());
+        builder.verify_stark_proof(&config, &stark, &virtual_proof, &public_input_targets);
+        let data = builder.build::<<C as CurtaConfig<D>>::GenericConfig>();
+
+        let mut pw = PartialWitness::new();
+        pw.set_target_arr(&public_input_targets, &public_inputs);
+        set_stark_proof_target(&mut pw, &virtual_proof, &proof);
+        let recursive_proof = data.prove(pw).unwrap();
+        data.verify(recursive_proof).unwrap();
+    }
+
+    #[test]
+    #[should_panic(expected = "is not smaller than the number of rows")]
+    fn test_opening_shift_out_of_range() {
+        type SC = PoseidonGoldilocksStarkConfig;
+
+        let num_rows = 1 << 5usize;
+        let _ = SC::standard_fast_config(num_rows).with_opening_shifts(vec![num_rows]);
+    }
 }
@@ -1,3 +1,6 @@
+use core::iter::once;
+
+use anyhow::{anyhow, ensure, Result};
 use itertools::Itertools;
 use plonky2::field::extension::{Extendable, FieldExtension};
 use plonky2::fri::oracle::PolynomialBatch;
@@ -5,13 +8,13 @@ use plonky2::fri::proof::{FriChallenges, FriChallengesTarget, FriProof, FriProof
 use plonky2::fri::structure::{
     FriOpeningBatch, FriOpeningBatchTarget, FriOpenings, FriOpeningsTarget,
 };
-use plonky2::hash::hash_types::{MerkleCapTarget, RichField};
+use plonky2::hash::hash_types::{HashOut, MerkleCapTarget, RichField};
 use plonky2::hash::merkle_tree::MerkleCap;
 use plonky2::iop::challenger::{Challenger, RecursiveChallenger};
 use plonky2::iop::ext_target::ExtensionTarget;
 use plonky2::iop::target::Target;
 use plonky2::plonk::circuit_builder::CircuitBuilder;
-use plonky2::plonk::config::GenericConfig;
+use plonky2::plonk::config::{AlgebraicHasher, GenericConfig, Hasher};
 use serde::{Deserialize, Serialize};
 
 use super::config::{CurtaConfig, StarkyConfig};
@@ -20,9 +23,10 @@ use crate::air::{RAir, RAirData};
 use crate::maybe_rayon::*;
 use crate::plonky2::parser::RecursiveStarkParser;
 use crate::utils::serde::{
-    deserialize_extension_targets, deserialize_fri_proof_target, deserialize_merkle_cap_target,
-    deserialize_merkle_cap_targets, serialize_extension_targets, serialize_fri_proof_target,
-    serialize_merkle_cap_target, serialize_merkle_cap_targets,
+    deserialize_extension_target_vecs, deserialize_extension_targets, deserialize_fri_proof_target,
+    deserialize_merkle_cap_target, deserialize_merkle_cap_targets, serialize_extension_target_vecs,
+    serialize_extension_targets, serialize_fri_proof_target, serialize_merkle_cap_target,
+    serialize_merkle_cap_targets,
 };
 
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
@@ -82,6 +86,10 @@ impl<F: RichField + Extendable<D>, C: CurtaConfig<D, F = F>, const D: usize> Air
     }
 }
 
+/// The version of the binary encoding of [`StarkProof`], written as the first byte of
+/// [`StarkProof::to_bytes`].
+pub const PROOF_FORMAT_VERSION: u8 = 2;
+
 /// A proof of a STARK computation.
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 #[serde(bound = "")]
@@ -101,6 +109,176 @@ impl<F: RichField + Extendable<D>, C: CurtaConfig<D, F = F>, const D: usize> Sta
         lde_bits - config.fri_config.rate_bits
     }
 
+    /// Encodes the proof as bytes, prefixed by the format version [`PROOF_FORMAT_VERSION`].
+    pub fn to_bytes(&self) -> Result<Vec<u8>> {
+        let mut bytes = vec![PROOF_FORMAT_VERSION];
+        bincode::serialize_into(&mut bytes, self)
+            .map_err(|e| anyhow!("Failed to encode proof: {}", e))?;
+        Ok(bytes)
+    }
+
+    /// Decodes a proof encoded by [`Self::to_bytes`].
+    ///
+    /// Returns an error if the encoding was made with another format version.
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self> {
+        let (version, proof_bytes) = bytes
+            .split_first()
+            .ok_or_else(|| anyhow!("Empty proof encoding"))?;
+        ensure!(
+            *version == PROOF_FORMAT_VERSION,
+            "Unsupported proof format version {}, expected {}",
+            version,
+            PROOF_FORMAT_VERSION
+        );
+        bincode::deserialize(proof_bytes).map_err(|e| anyhow!("Failed to decode proof: {}", e))
+    }
+
+    /// Checks that the shape of the proof matches the config and the stark.
+    ///
+    /// This verifies cap heights, opening-set lengths and the shape of the FRI proof without
+    /// doing any cryptographic work, so it can be used to cheaply reject malformed proofs before
+    /// running the full verifier.
+    pub fn verify_structure<A: RAirData>(
+        &self,
+        config: &StarkyConfig<C, D>,
+        stark: &Starky<A>,
+    ) -> Result<()> {
+        let fri_params = config.fri_params();
+        let cap_height = fri_params.config.cap_height;
+
+        let StarkProof {
+            air_proof:
+                AirProof {
+                    trace_caps,
+                    quotient_polys_cap,
+                    openings,
+                    opening_proof,
+                },
+            global_values,
+        } = self;
+
+        let StarkOpeningSet {
+            local_values,
+            next_values,
+            shifted_values,
+            quotient_polys,
+        } = openings;
+
+        let round_data = stark.air().round_data();
+        let num_quotient_polys = stark.num_quotient_polys(config);
+
+        // Check the trace and quotient commitments.
+        ensure!(
+            trace_caps.len() == round_data.len(),
+            "Invalid number of trace caps"
+        );
+        for cap in trace_caps.iter() {
+            ensure!(cap.height() == cap_height, "Invalid trace cap height");
+        }
+        ensure!(
+            quotient_polys_cap.height() == cap_height,
+            "Invalid quotient cap height"
+        );
+
+        // Check the openings and global values.
+        ensure!(
+            global_values.len() == stark.air().num_global_values(),
+            "Invalid number of global values"
+        );
+        ensure!(
+            local_values.len() == stark.air().num_columns(),
+            "Invalid number of local values"
+        );
+        ensure!(
+            next_values.len() == stark.air().num_columns(),
+            "Invalid number of next values"
+        );
+        ensure!(
+            shifted_values.len() == config.opening_shifts.len(),
+            "Invalid number of shifted openings"
+        );
+        for values in shifted_values.iter() {
+            ensure!(
+                values.len() == stark.air().num_columns(),
+                "Invalid number of shifted values"
+            );
+        }
+        ensure!(
+            quotient_polys.len() == num_quotient_polys,
+            "Invalid number of quotient polynomial openings"
+        );
+
+        // Check the shape of the FRI proof.
+        let FriProof {
+            commit_phase_merkle_caps,
+            query_round_proofs,
+            final_poly,
+            ..
+        } = opening_proof;
+
+        let oracle_sizes = round_data
+            .iter()
+            .map(|round| round.num_columns)
+            .chain(once(num_quotient_polys))
+            .collect::<Vec<_>>();
+
+        ensure!(
+            commit_phase_merkle_caps.len() == fri_params.reduction_arity_bits.len(),
+            "Invalid number of FRI commit phase caps"
+        );
+        for cap in commit_phase_merkle_caps.iter() {
+            ensure!(
+                cap.height() == cap_height,
+                "Invalid FRI commit phase cap height"
+            );
+        }
+        ensure!(
+            query_round_proofs.len() == fri_params.config.num_query_rounds,
+            "Invalid number of FRI query rounds"
+        );
+        for query_round in query_round_proofs.iter() {
+            let evals_proofs = &query_round.initial_trees_proof.evals_proofs;
+            ensure!(
+                evals_proofs.len() == oracle_sizes.len(),
+                "Invalid number of FRI initial tree proofs"
+            );
+            for ((leaf, merkle_proof), &num_polys) in evals_proofs.iter().zip(oracle_sizes.iter()) {
+                ensure!(leaf.len() == num_polys, "Invalid FRI initial leaf length");
+                ensure!(
+                    merkle_proof.siblings.len() + cap_height == fri_params.lde_bits(),
+                    "Invalid FRI initial Merkle proof length"
+                );
+            }
+
+            ensure!(
+                query_round.steps.len() == fri_params.reduction_arity_bits.len(),
+                "Invalid number of FRI query steps"
+            );
+            let mut codeword_len_bits = fri_params.lde_bits();
+            for (step, &arity_bits) in query_round
+                .steps
+                .iter()
+                .zip(fri_params.reduction_arity_bits.iter())
+            {
+                codeword_len_bits -= arity_bits;
+                ensure!(
+                    step.evals.len() == 1 << arity_bits,
+                    "Invalid FRI query step evaluations length"
+                );
+                ensure!(
+                    step.merkle_proof.siblings.len() + cap_height == codeword_len_bits,
+                    "Invalid FRI query step Merkle proof length"
+                );
+            }
+        }
+        ensure!(
+            final_poly.len() == fri_params.final_poly_len(),
+            "Invalid FRI final polynomial length"
+        );
+
+        Ok(())
+    }
+
     pub fn get_iop_challenges(
         &self,
         config: &StarkyConfig<C, D>,
@@ -118,6 +296,39 @@ impl<F: RichField + Extendable<D>, C: CurtaConfig<D, F = F>, const D: usize> Sta
         stark: &Starky<A>,
         public_inputs: &[F],
         degree_bits: usize,
+    ) -> StarkProofChallenges<F, D> {
+        self.get_challenges_with_transcript(
+            config,
+            stark,
+            public_inputs,
+            degree_bits,
+            &TranscriptState::new(),
+        )
+    }
+
+    /// Re-derives all the Fiat-Shamir challenges of the proof.
+    ///
+    /// The challenges are computed exactly as the verifier does, which makes it possible to
+    /// compare them with the transcript of an external verifier when debugging a mismatch.
+    pub fn derive_challenges<A: RAirData>(
+        &self,
+        config: &StarkyConfig<C, D>,
+        stark: &Starky<A>,
+        public_inputs: &[F],
+    ) -> DerivedChallenges<F, D> {
+        let degree_bits = self.recover_degree_bits(config);
+        self.get_challenges(config, stark, public_inputs, degree_bits)
+            .into()
+    }
+
+    /// Computes the challenges of a proof generated after the proofs absorbed in `transcript`.
+    pub(crate) fn get_challenges_with_transcript<A: RAirData>(
+        &self,
+        config: &StarkyConfig<C, D>,
+        stark: &Starky<A>,
+        public_inputs: &[F],
+        degree_bits: usize,
+        transcript: &TranscriptState<F>,
     ) -> StarkProofChallenges<F, D> {
         let StarkProof {
             air_proof: AirProof { trace_caps, .. },
@@ -126,20 +337,79 @@ impl<F: RichField + Extendable<D>, C: CurtaConfig<D, F = F>, const D: usize> Sta
         } = &self;
 
         let mut challenger = Challenger::<F, C::Hasher>::new();
+        transcript.observe(&mut challenger);
         // Observe public inputs
         challenger.observe_elements(public_inputs);
 
-        let mut challenges = vec![];
-        for (round, cap) in stark.air().round_data().iter().zip_eq(trace_caps.iter()) {
-            let (id_0, id_1) = round.global_values_range;
-            challenger.observe_elements(&global_values[id_0..id_1]);
-            challenger.observe_cap(cap);
-            let round_challenges = challenger.get_n_challenges(round.num_challenges);
-            challenges.extend(round_challenges);
-        }
+        let challenges = config.transcript_order.round_challenges(
+            &mut challenger,
+            &stark.air().round_data(),
+            global_values,
+            trace_caps,
+        );
 
         self.get_iop_challenges(config, degree_bits, challenges, &mut challenger)
     }
+
+    /// Absorbs this proof and its public inputs into `transcript`, returning the state with which
+    /// the next proof of a chain is generated and verified.
+    pub fn combine(
+        &self,
+        transcript: &TranscriptState<F>,
+        public_inputs: &[F],
+    ) -> TranscriptState<F> {
+        let StarkProof {
+            air_proof:
+                AirProof {
+                    trace_caps,
+                    quotient_polys_cap,
+                    ..
+                },
+            global_values,
+        } = &self;
+
+        let elements = transcript
+            .digest
+            .iter()
+            .flat_map(|digest| digest.elements)
+            .chain(public_inputs.iter().copied())
+            .chain(
+                trace_caps
+                    .iter()
+                    .chain(once(quotient_polys_cap))
+                    .flat_map(|cap| cap.flatten()),
+            )
+            .chain(global_values.iter().copied())
+            .collect::<Vec<_>>();
+
+        TranscriptState {
+            digest: Some(C::Hasher::hash_no_pad(&elements)),
+        }
+    }
+}
+
+/// The Fiat-Shamir state carried along a chain of proofs.
+///
+/// Each proof of a chain is generated and verified with a challenger that first observes the
+/// digest of the proofs before it, so reordering or replacing a proof changes the challenges of
+/// all the proofs that follow. The first proof of a chain is an ordinary proof.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TranscriptState<F: RichField> {
+    digest: Option<HashOut<F>>,
+}
+
+impl<F: RichField> TranscriptState<F> {
+    /// The state at the start of a chain.
+    pub fn new() -> Self {
+        Self { digest: None }
+    }
+
+    /// Observes the digest of the previous proofs, if any.
+    pub fn observe<H: AlgebraicHasher<F>>(&self, challenger: &mut Challenger<F, H>) {
+        if let Some(digest) = self.digest {
+            challenger.observe_elements(&digest.elements);
+        }
+    }
 }
 
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
@@ -254,14 +524,13 @@ impl<const D: usize> StarkProofTarget<D> {
         // Observe public inputs
         challenger.observe_elements(public_inputs);
 
-        let mut challenges = vec![];
-        for (round, cap) in stark.air().round_data().iter().zip(trace_caps.iter()) {
-            let (id_0, id_1) = round.global_values_range;
-            challenger.observe_elements(&global_values[id_0..id_1]);
-            challenger.observe_cap(cap);
-            let round_challenges = challenger.get_n_challenges(builder, round.num_challenges);
-            challenges.extend(round_challenges);
-        }
+        let challenges = config.transcript_order.round_challenges_target(
+            builder,
+            &mut challenger,
+            &stark.air().round_data(),
+            global_values,
+            trace_caps,
+        );
 
         self.get_iop_challenges_target(builder, config, challenges, &mut challenger)
     }
@@ -280,6 +549,48 @@ pub struct StarkProofChallenges<F: RichField + Extendable<D>, const D: usize> {
     pub fri_challenges: FriChallenges<F, D>,
 }
 
+/// The Fiat-Shamir challenges of a [`StarkProof`], as returned by
+/// [`StarkProof::derive_challenges`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DerivedChallenges<F: RichField + Extendable<D>, const D: usize> {
+    /// Random values used to combine STARK constraints.
+    pub alphas: Vec<F>,
+    /// The challenges of the AIR, drawn after the trace of each round is committed.
+    pub betas: Vec<F>,
+    /// Point at which the STARK polynomials are opened.
+    pub zeta: F::Extension,
+    /// Scaling factor used to combine the FRI openings.
+    pub fri_alpha: F::Extension,
+    /// Challenges of the FRI commit phase, one for each reduction.
+    pub fri_betas: Vec<F::Extension>,
+    /// Response of the FRI proof of work.
+    pub fri_pow_response: F,
+    /// Indices of the FRI queries.
+    pub fri_query_indices: Vec<usize>,
+}
+
+impl<F: RichField + Extendable<D>, const D: usize> From<StarkProofChallenges<F, D>>
+    for DerivedChallenges<F, D>
+{
+    fn from(challenges: StarkProofChallenges<F, D>) -> Self {
+        let StarkProofChallenges {
+            stark_alphas,
+            stark_betas,
+            stark_zeta,
+            fri_challenges,
+        } = challenges;
+        Self {
+            alphas: stark_alphas,
+            betas: stark_betas,
+            zeta: stark_zeta,
+            fri_alpha: fri_challenges.fri_alpha,
+            fri_betas: fri_challenges.fri_betas,
+            fri_pow_response: fri_challenges.fri_pow_response,
+            fri_query_indices: fri_challenges.fri_query_indices,
+        }
+    }
+}
+
 pub struct StarkProofChallengesTarget<const D: usize> {
     pub stark_alphas: Vec<Target>,
     pub stark_betas: Vec<Target>,
@@ -293,6 +604,8 @@ pub struct StarkProofChallengesTarget<const D: usize> {
 pub struct StarkOpeningSet<F: RichField + Extendable<D>, const D: usize> {
     pub local_values: Vec<F::Extension>,
     pub next_values: Vec<F::Extension>,
+    /// The trace values at `zeta * g^k` for each shift `k` of [`StarkyConfig::opening_shifts`].
+    pub shifted_values: Vec<Vec<F::Extension>>,
     pub quotient_polys: Vec<F::Extension>,
 }
 
@@ -300,6 +613,7 @@ impl<F: RichField + Extendable<D>, const D: usize> StarkOpeningSet<F, D> {
     pub fn new<C: GenericConfig<D, F = F>>(
         zeta: F::Extension,
         g: F,
+        opening_shifts: &[usize],
         trace_commitments: &[PolynomialBatch<F, C, D>],
         quotient_commitment: &PolynomialBatch<F, C, D>,
     ) -> Self {
@@ -319,10 +633,21 @@ impl<F: RichField + Extendable<D>, const D: usize> StarkOpeningSet<F, D> {
             .par_iter()
             .flat_map(|trace| eval_commitment(zeta_next, trace))
             .collect::<Vec<_>>();
+        let shifted_values = opening_shifts
+            .iter()
+            .map(|&shift| {
+                let zeta_shifted = zeta.scalar_mul(g.exp_u64(shift as u64));
+                trace_commitments
+                    .par_iter()
+                    .flat_map(|trace| eval_commitment(zeta_shifted, trace))
+                    .collect::<Vec<_>>()
+            })
+            .collect::<Vec<_>>();
         let quotient_polys = eval_commitment(zeta, quotient_commitment);
         Self {
             local_values,
             next_values,
+            shifted_values,
             quotient_polys,
         }
     }
@@ -339,8 +664,14 @@ impl<F: RichField + Extendable<D>, const D: usize> StarkOpeningSet<F, D> {
         let zeta_next_batch = FriOpeningBatch {
             values: self.next_values.to_vec(),
         };
+        let shifted_batches = self.shifted_values.iter().map(|values| FriOpeningBatch {
+            values: values.to_vec(),
+        });
         FriOpenings {
-            batches: vec![zeta_batch, zeta_next_batch],
+            batches: [zeta_batch, zeta_next_batch]
+                .into_iter()
+                .chain(shifted_batches)
+                .collect(),
         }
     }
 }
@@ -353,6 +684,9 @@ pub struct StarkOpeningSetTarget<const D: usize> {
     #[serde(serialize_with = "serialize_extension_targets")]
     #[serde(deserialize_with = "deserialize_extension_targets")]
     pub next_values: Vec<ExtensionTarget<D>>,
+    #[serde(serialize_with = "serialize_extension_target_vecs")]
+    #[serde(deserialize_with = "deserialize_extension_target_vecs")]
+    pub shifted_values: Vec<Vec<ExtensionTarget<D>>>,
     #[serde(serialize_with = "serialize_extension_targets")]
     #[serde(deserialize_with = "deserialize_extension_targets")]
     pub quotient_polys: Vec<ExtensionTarget<D>>,
@@ -371,8 +705,42 @@ impl<const D: usize> StarkOpeningSetTarget<D> {
         let zeta_next_batch = FriOpeningBatchTarget {
             values: self.next_values.to_vec(),
         };
+        let shifted_batches = self
+            .shifted_values
+            .iter()
+            .map(|values| FriOpeningBatchTarget {
+                values: values.to_vec(),
+            });
         FriOpeningsTarget {
-            batches: vec![zeta_batch, zeta_next_batch],
+            batches: [zeta_batch, zeta_next_batch]
+                .into_iter()
+                .chain(shifted_batches)
+                .collect(),
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use crate::plonky2::stark::tests::fibonacci_proof;
+
+    #[test]
+    fn test_verify_structure() {
+        let (stark, config, _, proof) = fibonacci_proof(1 << 5);
+        proof.verify_structure(&config, &stark).unwrap();
+
+        // A proof with a wrong-length opening set should be rejected.
+        let mut bad_proof = proof.clone();
+        bad_proof.air_proof.openings.local_values.pop();
+        assert!(bad_proof.verify_structure(&config, &stark).is_err());
+
+        let mut bad_proof = proof;
+        let extra_value = bad_proof.air_proof.openings.quotient_polys[0];
+        bad_proof
+            .air_proof
+            .openings
+            .quotient_polys
+            .push(extra_value);
+        assert!(bad_proof.verify_structure(&config, &stark).is_err());
+    }
+}
@@ -1,14 +1,16 @@
 //! Prover
 
+use alloc::sync::Arc;
 use core::fmt::Debug;
 use core::iter::once;
+use core::ops::Range;
 
 use anyhow::{ensure, Result};
 use plonky2::field::extension::Extendable;
 use plonky2::field::packable::Packable;
 use plonky2::field::packed::PackedField;
 use plonky2::field::polynomial::{PolynomialCoeffs, PolynomialValues};
-use plonky2::field::types::Field;
+use plonky2::field::types::{Field, Sample};
 use plonky2::field::zero_poly_coset::ZeroPolyOnCoset;
 use plonky2::fri::oracle::PolynomialBatch;
 use plonky2::hash::hash_types::RichField;
@@ -16,14 +18,19 @@ use plonky2::iop::challenger::Challenger;
 use plonky2::util::timing::TimingTree;
 use plonky2::util::{log2_ceil, transpose};
 
-use super::config::{CurtaConfig, StarkyConfig};
+use super::config::{CurtaConfig, StarkyConfig, TranscriptOrder};
 use super::Starky;
 use crate::maybe_rayon::*;
-use crate::plonky2::parser::consumer::ConstraintConsumer;
+use crate::plonky2::parser::consumer::{
+    combiner_coefficients, ConstraintCoefficients, ConstraintCombiner, ConstraintConsumer,
+};
 use crate::plonky2::parser::StarkParser;
-use crate::plonky2::stark::proof::{AirProof, StarkOpeningSet, StarkProof};
+use crate::plonky2::stark::proof::{AirProof, StarkOpeningSet, StarkProof, TranscriptState};
 use crate::plonky2::StarkyAir;
 use crate::trace::generator::TraceGenerator;
+#[cfg(feature = "mmap")]
+use crate::trace::mmap::{MmapAirTrace, MmapField};
+use crate::trace::view::TraceViewMut;
 
 #[derive(Debug, Clone)]
 pub struct StarkyProver<F, C, const D: usize>(core::marker::PhantomData<(F, C)>);
@@ -60,19 +67,28 @@ where
         T: TraceGenerator<F, A>,
         T::Error: Into<anyhow::Error>,
     {
+        config.check_trace_cells(stark.air().num_columns())?;
+
         let mut challenges = vec![];
         let mut global_values = vec![F::ZERO; stark.air().num_global_values()];
 
         // Oberve public inputs
         challenger.observe_elements(public_inputs);
 
-        let rate_bits = config.fri_config.rate_bits;
-        let cap_height = config.fri_config.cap_height;
+        let unused_columns = if config.blind_unused_columns {
+            stark.air().unused_columns()
+        } else {
+            Vec::new()
+        };
 
-        let mut trace_commitments = Vec::new();
-        for (r, round) in stark.air().round_data().iter().enumerate() {
-            let (id_0, id_1) = round.global_values_range;
-            let round_trace = trace_generator
+        let round_data = stark.air().round_data();
+        let mut trace_commitments: Vec<PolynomialBatch<F, C::GenericConfig, D>> = Vec::new();
+        let mut column_offset = 0;
+        // The first round that has not been observed yet.
+        let mut first_pending = 0;
+        for (r, round) in round_data.iter().enumerate() {
+            let (_, id_1) = round.global_values_range;
+            let mut round_trace = trace_generator
                 .generate_round(
                     stark.air(),
                     r,
@@ -82,23 +98,33 @@ where
                 )
                 .map_err(|e| e.into())?;
 
-            let trace_cols = round_trace
-                .as_columns()
-                .into_par_iter()
-                .map(PolynomialValues::from)
-                .collect::<Vec<_>>();
-
-            let commitment = PolynomialBatch::<F, C::GenericConfig, D>::from_values(
-                trace_cols, rate_bits, false, cap_height, timing, None,
+            let blinding_columns = Self::blinding_columns(
+                &unused_columns,
+                column_offset..column_offset + round.num_columns,
             );
-            challenger.observe_elements(&global_values[id_0..id_1]);
-            let cap = commitment.merkle_tree.cap.clone();
-            challenger.observe_cap(&cap);
+            column_offset += round.num_columns;
+
+            let commitment =
+                Self::commit_round(config, round_trace.view_mut(), &blinding_columns, timing);
             trace_commitments.push(commitment);
 
-            // Get the challenges for next round
-            let round_challenges = challenger.get_n_challenges(round.num_challenges);
-            challenges.extend(round_challenges);
+            if TranscriptOrder::observes_after(&round_data, r) {
+                let pending = (first_pending..=r)
+                    .map(|i| {
+                        let (id_0, id_1) = round_data[i].global_values_range;
+                        (
+                            &global_values[id_0..id_1],
+                            &trace_commitments[i].merkle_tree.cap,
+                        )
+                    })
+                    .collect::<Vec<_>>();
+                config.transcript_order.observe_rounds(challenger, &pending);
+                first_pending = r + 1;
+
+                // Get the challenges for next round
+                let round_challenges = challenger.get_n_challenges(round.num_challenges);
+                challenges.extend(round_challenges);
+            }
         }
 
         Ok(AirCommitment {
@@ -109,12 +135,126 @@ where
         })
     }
 
+    /// The unused columns among `round_columns`, relative to the first column of the round, which
+    /// are blinded as no constraint depends on them.
+    fn blinding_columns(unused_columns: &[usize], round_columns: Range<usize>) -> Vec<usize> {
+        unused_columns
+            .iter()
+            .filter(|column| round_columns.contains(*column))
+            .map(|column| column - round_columns.start)
+            .collect()
+    }
+
+    /// Blinds the columns `blinding_columns` of the round trace `trace` and commits to its columns.
+    fn commit_round(
+        config: &StarkyConfig<C, D>,
+        mut trace: TraceViewMut<'_, F>,
+        blinding_columns: &[usize],
+        timing: &mut TimingTree,
+    ) -> PolynomialBatch<F, C::GenericConfig, D> {
+        let width = trace.width;
+        if !blinding_columns.is_empty() {
+            trace.values.par_chunks_exact_mut(width).for_each(|row| {
+                for &column in blinding_columns.iter() {
+                    row[column] = F::rand();
+                }
+            });
+        }
+
+        let values = &*trace.values;
+        let trace_cols = (0..width)
+            .into_par_iter()
+            .map(|column| {
+                PolynomialValues::new(values.iter().skip(column).step_by(width).copied().collect())
+            })
+            .collect::<Vec<_>>();
+
+        PolynomialBatch::<F, C::GenericConfig, D>::from_values(
+            trace_cols,
+            config.fri_config.rate_bits,
+            false,
+            config.fri_config.cap_height,
+            timing,
+            None,
+        )
+    }
+
+    /// Proves a single-round trace held in a memory-mapped buffer.
+    ///
+    /// Unlike a [`TraceGenerator`], which returns an in-memory [`AirTrace`](crate::trace::AirTrace),
+    /// the trace is committed to directly from the mapping, so that only its polynomials are kept
+    /// in memory.
+    #[cfg(feature = "mmap")]
+    pub fn prove_mmap<A: StarkyAir<F, D>>(
+        config: &StarkyConfig<C, D>,
+        stark: &Starky<A>,
+        trace: &mut MmapAirTrace<F>,
+        public_inputs: &[F],
+    ) -> Result<StarkProof<F, C, D>>
+    where
+        F: MmapField,
+    {
+        config.check_trace_cells(stark.air().num_columns())?;
+        let round_data = stark.air().round_data();
+        ensure!(
+            round_data.len() == 1 && round_data[0].num_challenges == 0,
+            "A memory-mapped trace can only be proven for an air with a single round"
+        );
+        ensure!(
+            stark.air().num_global_values() == 0,
+            "A memory-mapped trace can only be proven for an air with no global values"
+        );
+        ensure!(
+            trace.width() == round_data[0].num_columns,
+            "The trace width {} does not match the {} columns of the air",
+            trace.width(),
+            round_data[0].num_columns
+        );
+
+        let mut challenger = Challenger::<F, C::Hasher>::new();
+        TranscriptState::new().observe(&mut challenger);
+        let mut timing = TimingTree::default();
+        challenger.observe_elements(public_inputs);
+
+        let blinding_columns = if config.blind_unused_columns {
+            Self::blinding_columns(&stark.air().unused_columns(), 0..trace.width())
+        } else {
+            Vec::new()
+        };
+        let commitment =
+            Self::commit_round(config, trace.view_mut(), &blinding_columns, &mut timing);
+        config
+            .transcript_order
+            .observe_rounds(&mut challenger, &[(&[], &commitment.merkle_tree.cap)]);
+
+        let air_commitment = AirCommitment {
+            trace_commitments: vec![commitment],
+            public_inputs: public_inputs.to_vec(),
+            global_values: Vec::new(),
+            challenges: Vec::new(),
+        };
+        Self::prove_with_trace(config, stark, air_commitment, &mut challenger, &mut timing)
+    }
+
     pub fn prove_with_trace<A: StarkyAir<F, D>>(
         config: &StarkyConfig<C, D>,
         stark: &Starky<A>,
         air_commitment: AirCommitment<F, C, D>,
         challenger: &mut Challenger<F, C::Hasher>,
         timing: &mut TimingTree,
+    ) -> Result<StarkProof<F, C, D>> {
+        Self::prove_with_trace_and_combiner(config, stark, air_commitment, challenger, timing, None)
+    }
+
+    /// Proves the committed trace, combining the constraints with `combiner` if given and with
+    /// powers of alpha otherwise.
+    fn prove_with_trace_and_combiner<A: StarkyAir<F, D>>(
+        config: &StarkyConfig<C, D>,
+        stark: &Starky<A>,
+        air_commitment: AirCommitment<F, C, D>,
+        challenger: &mut Challenger<F, C::Hasher>,
+        timing: &mut TimingTree,
+        combiner: Option<&Arc<dyn ConstraintCombiner<F>>>,
     ) -> Result<StarkProof<F, C, D>> {
         let AirCommitment {
             trace_commitments,
@@ -153,6 +293,7 @@ where
             &global_vars,
             &public_vars,
             challenger,
+            combiner,
         );
         let quotient_degree_factor = stark.air().quotient_degree_factor();
         let all_quotient_chunks = quotient_polys
@@ -189,7 +330,13 @@ where
             zeta.exp_power_of_2(degree_bits) != F::Extension::ONE,
             "Opening point is in the subgroup."
         );
-        let openings = StarkOpeningSet::new(zeta, g, &trace_commitments, &quotient_commitment);
+        let openings = StarkOpeningSet::new(
+            zeta,
+            g,
+            &config.opening_shifts,
+            &trace_commitments,
+            &quotient_commitment,
+        );
         challenger.observe_openings(&openings.to_fri_openings());
 
         let initial_merkle_trees = trace_commitments
@@ -230,12 +377,38 @@ where
         trace_generator: &T,
         public_inputs: &[F],
     ) -> Result<StarkProof<F, C, D>>
+    where
+        A: StarkyAir<F, D>,
+        T: TraceGenerator<F, A>,
+        T::Error: Into<anyhow::Error>,
+    {
+        Self::prove_with_transcript(
+            config,
+            stark,
+            trace_generator,
+            public_inputs,
+            &mut TranscriptState::new(),
+        )
+    }
+
+    /// Generates a proof whose constraints are combined with `combiner` instead of powers of
+    /// alpha, see [`ConstraintCombiner`]. The proof must be verified with
+    /// [`StarkyVerifier::verify_with_combiner`](super::verifier::StarkyVerifier::verify_with_combiner)
+    /// and the same combiner.
+    pub fn prove_with_combiner<A, T>(
+        config: &StarkyConfig<C, D>,
+        stark: &Starky<A>,
+        trace_generator: &T,
+        public_inputs: &[F],
+        combiner: Arc<dyn ConstraintCombiner<F>>,
+    ) -> Result<StarkProof<F, C, D>>
     where
         A: StarkyAir<F, D>,
         T: TraceGenerator<F, A>,
         T::Error: Into<anyhow::Error>,
     {
         let mut challenger = Challenger::<F, C::Hasher>::new();
+        TranscriptState::new().observe(&mut challenger);
         let mut timing = TimingTree::default();
         let air_commitment = Self::generate_trace(
             config,
@@ -246,7 +419,46 @@ where
             &mut timing,
         )?;
 
-        Self::prove_with_trace(config, stark, air_commitment, &mut challenger, &mut timing)
+        Self::prove_with_trace_and_combiner(
+            config,
+            stark,
+            air_commitment,
+            &mut challenger,
+            &mut timing,
+            Some(&combiner),
+        )
+    }
+
+    /// Generates the next proof of a chain, whose challenges depend on the proofs absorbed in
+    /// `transcript`, and absorbs the new proof into `transcript`.
+    pub fn prove_with_transcript<A, T>(
+        config: &StarkyConfig<C, D>,
+        stark: &Starky<A>,
+        trace_generator: &T,
+        public_inputs: &[F],
+        transcript: &mut TranscriptState<F>,
+    ) -> Result<StarkProof<F, C, D>>
+    where
+        A: StarkyAir<F, D>,
+        T: TraceGenerator<F, A>,
+        T::Error: Into<anyhow::Error>,
+    {
+        let mut challenger = Challenger::<F, C::Hasher>::new();
+        transcript.observe(&mut challenger);
+        let mut timing = TimingTree::default();
+        let air_commitment = Self::generate_trace(
+            config,
+            stark,
+            public_inputs,
+            trace_generator,
+            &mut challenger,
+            &mut timing,
+        )?;
+
+        let proof =
+            Self::prove_with_trace(config, stark, air_commitment, &mut challenger, &mut timing)?;
+        *transcript = proof.combine(transcript, public_inputs);
+        Ok(proof)
     }
 
     #[allow(clippy::too_many_arguments)]
@@ -259,6 +471,7 @@ where
         global_vars: &[P<F>],
         public_vars: &[P<F>],
         challenger: &mut Challenger<F, C::Hasher>,
+        combiner: Option<&Arc<dyn ConstraintCombiner<F>>>,
     ) -> Vec<PolynomialCoeffs<F>>
     where
         A: StarkyAir<F, D>,
@@ -292,6 +505,8 @@ where
                 .flat_map(|commitment| commitment.get_lde_values_packed(i_start, step))
                 .collect()
         };
+        // The rows opened at the shifts of the config, besides the local and next rows.
+        let opening_shifts = &config.opening_shifts;
         // Last element of the subgroup.
         let last = F::primitive_root_of_unity(degree_bits).inverse();
         let size = degree << quotient_degree_bits;
@@ -301,38 +516,62 @@ where
             size,
         );
 
+        // Evaluates the constraints at the batch of `P::WIDTH` points starting at `i_start`.
+        let eval_constraints = |i_start: usize, coefficients: Option<ConstraintCoefficients<F>>| {
+            let i_next_start = (i_start + next_step) % size;
+            let i_range = i_start..i_start + P::<F>::WIDTH;
+
+            let x = *P::<F>::from_slice(&coset[i_range.clone()]);
+            let z_last = x - last;
+            let lagrange_basis_first = *P::<F>::from_slice(&lagrange_first.values[i_range.clone()]);
+            let lagrange_basis_last = *P::<F>::from_slice(&lagrange_last.values[i_range]);
+
+            let mut consumer = ConstraintConsumer::new(
+                alphas.clone(),
+                z_last,
+                lagrange_basis_first,
+                lagrange_basis_last,
+            );
+            if let Some(coefficients) = coefficients {
+                consumer = consumer.with_coefficients(coefficients);
+            }
+            let shifted_vars = opening_shifts
+                .iter()
+                .map(|shift| get_trace_values_packed((i_start + shift * next_step) % size))
+                .collect::<Vec<_>>();
+            let mut parser = StarkParser {
+                local_vars: &get_trace_values_packed(i_start),
+                next_vars: &get_trace_values_packed(i_next_start),
+                shifted_vars: &shifted_vars,
+                opening_shifts,
+                global_vars,
+                public_vars,
+                challenges: challenges_vars,
+                consumer: &mut consumer,
+            };
+
+            stark.air().eval(&mut parser);
+            consumer
+        };
+
+        // The coefficients of a combiner are computed once for all the points, for the number of
+        // constraints found by evaluating them at the first batch of points.
+        let coefficients = combiner.map(|combiner| {
+            let num_constraints = eval_constraints(0, None).num_constraints();
+            Arc::new(combiner_coefficients(
+                combiner.as_ref(),
+                &alphas,
+                num_constraints,
+            ))
+        });
+
         // We will step by `P::WIDTH`, and in each iteration, evaluate the quotient polynomial at
         // a batch of `P::WIDTH` points.
         let quotient_values = (0..size)
             .into_par_iter()
             .step_by(P::<F>::WIDTH)
             .flat_map_iter(|i_start| {
-                let i_next_start = (i_start + next_step) % size;
-                let i_range = i_start..i_start + P::<F>::WIDTH;
-
-                let x = *P::<F>::from_slice(&coset[i_range.clone()]);
-                let z_last = x - last;
-                let lagrange_basis_first =
-                    *P::<F>::from_slice(&lagrange_first.values[i_range.clone()]);
-                let lagrange_basis_last = *P::<F>::from_slice(&lagrange_last.values[i_range]);
-
-                let mut consumer = ConstraintConsumer::new(
-                    alphas.clone(),
-                    z_last,
-                    lagrange_basis_first,
-                    lagrange_basis_last,
-                );
-                let mut parser = StarkParser {
-                    local_vars: &get_trace_values_packed(i_start),
-                    next_vars: &get_trace_values_packed(i_next_start),
-                    global_vars,
-                    public_vars,
-                    challenges: challenges_vars,
-                    consumer: &mut consumer,
-                };
-
-                stark.air().eval(&mut parser);
-
+                let consumer = eval_constraints(i_start, coefficients.clone());
                 let mut constraints_evals = consumer.accumulators();
                 // We divide the constraints evaluations by `Z_H(x)`.
                 let denominator_inv: P<F> = z_h_on_coset.eval_inverse_packed(i_start);
@@ -1,3 +1,4 @@
+use alloc::sync::Arc;
 use core::iter::once;
 use std::collections::HashMap;
 
@@ -7,23 +8,26 @@ use plonky2::field::extension::{Extendable, FieldExtension};
 use plonky2::field::types::Field as Plonky2Field;
 use plonky2::fri::verifier::verify_fri_proof;
 use plonky2::fri::witness_util::set_fri_proof_target;
-use plonky2::hash::hash_types::RichField;
+use plonky2::hash::hash_types::{HashOut, RichField};
 use plonky2::iop::ext_target::ExtensionTarget;
 use plonky2::iop::target::Target;
 use plonky2::iop::witness::WitnessWrite;
 use plonky2::plonk::circuit_builder::CircuitBuilder;
-use plonky2::plonk::config::AlgebraicHasher;
+use plonky2::plonk::config::{AlgebraicHasher, Hasher};
 use plonky2::plonk::plonk_common::reduce_with_powers;
 use plonky2::util::reducing::ReducingFactorTarget;
 
 use super::config::{CurtaConfig, StarkyConfig};
 use super::proof::{
     AirProofTarget, StarkOpeningSet, StarkOpeningSetTarget, StarkProof, StarkProofChallenges,
-    StarkProofChallengesTarget, StarkProofTarget,
+    StarkProofChallengesTarget, StarkProofTarget, TranscriptState,
 };
 use super::Starky;
 use crate::air::{RAir, RAirData};
-use crate::plonky2::parser::consumer::{ConstraintConsumer, RecursiveConstraintConsumer};
+use crate::plonky2::parser::consumer::{
+    combiner_coefficients, ConstraintCoefficients, ConstraintCombiner, ConstraintConsumer,
+    RecursiveConstraintConsumer,
+};
 use crate::plonky2::parser::global::{GlobalRecursiveStarkParser, GlobalStarkParser};
 use crate::plonky2::parser::{RecursiveStarkParser, StarkParser};
 use crate::plonky2::stark::proof::AirProof;
@@ -45,6 +49,31 @@ where
         global_values: &[F],
         challenges: StarkProofChallenges<F, D>,
     ) -> Result<()>
+    where
+        A: StarkyAir<F, D>,
+    {
+        Self::verify_with_challenges_and_combiner(
+            config,
+            stark,
+            proof,
+            public_inputs,
+            global_values,
+            challenges,
+            None,
+        )
+    }
+
+    /// Verifies the proof, combining the constraints with `combiner` if given and with powers of
+    /// alpha otherwise.
+    fn verify_with_challenges_and_combiner<A>(
+        config: &StarkyConfig<C, D>,
+        stark: &Starky<A>,
+        proof: AirProof<F, C, D>,
+        public_inputs: &[F],
+        global_values: &[F],
+        challenges: StarkProofChallenges<F, D>,
+        combiner: Option<&Arc<dyn ConstraintCombiner<F>>>,
+    ) -> Result<()>
     where
         A: StarkyAir<F, D>,
     {
@@ -52,9 +81,23 @@ where
 
         Self::validate_proof_shape(config, stark, &proof, global_values)?;
 
+        let num_challenges = stark
+            .air()
+            .round_data()
+            .iter()
+            .map(|round| round.num_challenges)
+            .sum::<usize>();
+        ensure!(
+            challenges.stark_betas.len() == num_challenges,
+            "Invalid number of challenges: expected {}, got {}",
+            num_challenges,
+            challenges.stark_betas.len()
+        );
+
         let StarkOpeningSet {
             local_values,
             next_values,
+            shifted_values,
             quotient_polys,
         } = &proof.openings;
 
@@ -83,28 +126,51 @@ where
         let (l_0, l_last) = Self::eval_l_0_and_l_last(degree_bits, challenges.stark_zeta);
         let last = F::primitive_root_of_unity(degree_bits).inverse();
         let z_last = challenges.stark_zeta - last.into();
-        let mut consumer = ConstraintConsumer::<F::Extension>::new(
-            challenges
-                .stark_alphas
-                .iter()
-                .map(|&alpha| F::Extension::from_basefield(alpha))
-                .collect::<Vec<_>>(),
-            z_last,
-            l_0,
-            l_last,
-        );
-
-        let mut parser = StarkParser {
-            local_vars: local_values,
-            next_vars: next_values,
-            global_vars: &global_values_ext,
-            public_vars: &public_inputs_ext,
-            challenges: &challenges_ext,
-            consumer: &mut consumer,
+        let eval_constraints = |coefficients: Option<ConstraintCoefficients<F::Extension>>| {
+            let mut consumer = ConstraintConsumer::<F::Extension>::new(
+                challenges
+                    .stark_alphas
+                    .iter()
+                    .map(|&alpha| F::Extension::from_basefield(alpha))
+                    .collect::<Vec<_>>(),
+                z_last,
+                l_0,
+                l_last,
+            );
+            if let Some(coefficients) = coefficients {
+                consumer = consumer.with_coefficients(coefficients);
+            }
+
+            let mut parser = StarkParser {
+                local_vars: local_values,
+                next_vars: next_values,
+                shifted_vars: shifted_values,
+                opening_shifts: &config.opening_shifts,
+                global_vars: &global_values_ext,
+                public_vars: &public_inputs_ext,
+                challenges: &challenges_ext,
+                consumer: &mut consumer,
+            };
+
+            stark.air().eval(&mut parser);
+            consumer
         };
 
-        stark.air().eval(&mut parser);
-        let vanishing_polys_zeta = consumer.accumulators();
+        // The coefficients of a combiner are computed for the number of constraints of the AIR.
+        let coefficients = combiner.map(|combiner| {
+            let num_constraints = eval_constraints(None).num_constraints();
+            let coefficients =
+                combiner_coefficients(combiner.as_ref(), &challenges.stark_alphas, num_constraints)
+                    .into_iter()
+                    .map(|row| {
+                        row.into_iter()
+                            .map(F::Extension::from_basefield)
+                            .collect::<Vec<_>>()
+                    })
+                    .collect::<Vec<_>>();
+            Arc::new(coefficients)
+        });
+        let vanishing_polys_zeta = eval_constraints(coefficients).accumulators();
 
         // Check each polynomial identity, of the form `vanishing(x) = Z_H(x) quotient(x)`, at zeta.
         let zeta_pow_deg = challenges.stark_zeta.exp_power_of_2(degree_bits);
@@ -154,20 +220,116 @@ where
     where
         A: StarkyAir<F, D>,
     {
+        Self::verify_with_transcript(
+            config,
+            stark,
+            proof,
+            public_inputs,
+            &mut TranscriptState::new(),
+        )
+    }
+
+    /// Verifies a proof generated by
+    /// [`StarkyProver::prove_with_combiner`](super::prover::StarkyProver::prove_with_combiner),
+    /// whose constraints are combined with `combiner` instead of powers of alpha.
+    pub fn verify_with_combiner<A>(
+        config: &StarkyConfig<C, D>,
+        stark: &Starky<A>,
+        proof: StarkProof<F, C, D>,
+        public_inputs: &[F],
+        combiner: Arc<dyn ConstraintCombiner<F>>,
+    ) -> Result<()>
+    where
+        A: StarkyAir<F, D>,
+    {
+        Self::verify_with_transcript_and_combiner(
+            config,
+            stark,
+            proof,
+            public_inputs,
+            &mut TranscriptState::new(),
+            Some(&combiner),
+        )
+    }
+
+    /// Verifies the proof against the full public inputs and checks that they open the claimed
+    /// `public_inputs_hash`, as computed by [`Self::hash_public_inputs`].
+    pub fn verify_with_public_inputs_hash<A>(
+        config: &StarkyConfig<C, D>,
+        stark: &Starky<A>,
+        proof: StarkProof<F, C, D>,
+        public_inputs: &[F],
+        public_inputs_hash: HashOut<F>,
+    ) -> Result<()>
+    where
+        A: StarkyAir<F, D>,
+    {
+        ensure!(
+            Self::hash_public_inputs(public_inputs) == public_inputs_hash,
+            "The public inputs do not match the public inputs hash"
+        );
+        Self::verify(config, stark, proof, public_inputs)
+    }
+
+    /// Verifies the next proof of a chain against the proofs absorbed in `transcript`, and
+    /// absorbs the proof into `transcript` if it is valid.
+    pub fn verify_with_transcript<A>(
+        config: &StarkyConfig<C, D>,
+        stark: &Starky<A>,
+        proof: StarkProof<F, C, D>,
+        public_inputs: &[F],
+        transcript: &mut TranscriptState<F>,
+    ) -> Result<()>
+    where
+        A: StarkyAir<F, D>,
+    {
+        Self::verify_with_transcript_and_combiner(
+            config,
+            stark,
+            proof,
+            public_inputs,
+            transcript,
+            None,
+        )
+    }
+
+    fn verify_with_transcript_and_combiner<A>(
+        config: &StarkyConfig<C, D>,
+        stark: &Starky<A>,
+        proof: StarkProof<F, C, D>,
+        public_inputs: &[F],
+        transcript: &mut TranscriptState<F>,
+        combiner: Option<&Arc<dyn ConstraintCombiner<F>>>,
+    ) -> Result<()>
+    where
+        A: StarkyAir<F, D>,
+    {
+        Self::validate_num_rounds(stark, &proof.air_proof)?;
+        Self::validate_num_openings(stark, &proof.air_proof)?;
         let degree_bits = proof.recover_degree_bits(config);
-        let challenges = proof.get_challenges(config, stark, public_inputs, degree_bits);
+        let challenges = proof.get_challenges_with_transcript(
+            config,
+            stark,
+            public_inputs,
+            degree_bits,
+            transcript,
+        );
+        let next_transcript = proof.combine(transcript, public_inputs);
         let StarkProof {
             air_proof,
             global_values,
         } = proof;
-        Self::verify_with_challenges(
+        Self::verify_with_challenges_and_combiner(
             config,
             stark,
             air_proof,
             public_inputs,
             &global_values,
             challenges,
-        )
+            combiner,
+        )?;
+        *transcript = next_transcript;
+        Ok(())
     }
 
     pub fn validate_proof_shape<A: RAirData>(
@@ -189,23 +351,86 @@ where
         } = proof;
 
         let StarkOpeningSet {
-            local_values,
-            next_values,
+            shifted_values,
             quotient_polys,
+            ..
         } = openings;
 
+        Self::validate_num_rounds(stark, proof)?;
         for cap in trace_caps.iter() {
             ensure!(cap.height() == cap_height);
         }
         ensure!(quotient_polys_cap.height() == cap_height);
         ensure!(global_values.len() == stark.air().num_global_values());
-        ensure!(local_values.len() == stark.air().num_columns());
-        ensure!(next_values.len() == stark.air().num_columns());
+        Self::validate_num_openings(stark, proof)?;
+        ensure!(
+            shifted_values.len() == config.opening_shifts.len(),
+            "Invalid number of shifted openings: expected {}, got {}",
+            config.opening_shifts.len(),
+            shifted_values.len()
+        );
         ensure!(quotient_polys.len() == stark.num_quotient_polys(config));
 
         Ok(())
     }
 
+    /// Checks that the proof has one trace commitment per round of the stark.
+    ///
+    /// The challenges are derived round by round from the trace commitments, so this must be
+    /// checked before deriving them from a proof that may have been produced for another stark.
+    pub fn validate_num_rounds<A: RAirData>(
+        stark: &Starky<A>,
+        proof: &AirProof<F, C, D>,
+    ) -> Result<()> {
+        let num_rounds = stark.air().num_rounds();
+        ensure!(
+            proof.trace_caps.len() == num_rounds,
+            "Invalid number of trace caps: expected {}, got {}",
+            num_rounds,
+            proof.trace_caps.len()
+        );
+        Ok(())
+    }
+
+    /// Checks that the proof opens every column of the trace, in the local and next rows as well
+    /// as in the rows of every opening shift.
+    ///
+    /// The columns are those of the trace commitments of all rounds, which the verifier splits
+    /// the opened values into, so this is checked before the openings are used.
+    pub fn validate_num_openings<A: RAirData>(
+        stark: &Starky<A>,
+        proof: &AirProof<F, C, D>,
+    ) -> Result<()> {
+        let num_columns = stark.air().num_columns();
+        let StarkOpeningSet {
+            local_values,
+            next_values,
+            shifted_values,
+            ..
+        } = &proof.openings;
+        ensure!(
+            local_values.len() == num_columns,
+            "Invalid number of opened local values: expected {}, got {}",
+            num_columns,
+            local_values.len()
+        );
+        ensure!(
+            next_values.len() == num_columns,
+            "Invalid number of opened next values: expected {}, got {}",
+            num_columns,
+            next_values.len()
+        );
+        for values in shifted_values.iter() {
+            ensure!(
+                values.len() == num_columns,
+                "Invalid number of opened shifted values: expected {}, got {}",
+                num_columns,
+                values.len()
+            );
+        }
+        Ok(())
+    }
+
     /// Evaluate the Lagrange polynomials `L_0` and `L_(n-1)` at a point `x`.
     /// `L_0(x) = (x^n - 1)/(n * (x - 1))`
     /// `L_(n-1)(x) = (x^n - 1)/(n * (g * x - 1))`, with `g` the first element of the subgroup.
@@ -232,6 +457,7 @@ where
         let StarkOpeningSetTarget {
             local_values,
             next_values,
+            shifted_values,
             quotient_polys,
         } = &proof.openings;
 
@@ -288,6 +514,8 @@ where
             builder,
             local_vars: local_values,
             next_vars: next_values,
+            shifted_vars: shifted_values,
+            opening_shifts: &config.opening_shifts,
             global_vars: &global_vals_ext,
             public_vars: &public_inputs_ext,
             challenges: &challenges_ext,
@@ -355,6 +583,11 @@ where
         )
     }
 
+    /// Computes the hash of the public inputs checked by [`Self::verify_with_public_inputs_hash`].
+    pub fn hash_public_inputs(public_inputs: &[F]) -> HashOut<F> {
+        C::InnerHasher::hash_no_pad(public_inputs)
+    }
+
     fn eval_l_0_and_l_last_circuit(
         builder: &mut CircuitBuilder<F, D>,
         log_n: usize,
@@ -444,6 +677,11 @@ pub(crate) fn add_stark_opening_set_target<
     StarkOpeningSetTarget {
         local_values: builder.add_virtual_extension_targets(stark.air().num_columns()),
         next_values: builder.add_virtual_extension_targets(stark.air().num_columns()),
+        shifted_values: config
+            .opening_shifts
+            .iter()
+            .map(|_| builder.add_virtual_extension_targets(stark.air().num_columns()))
+            .collect(),
         quotient_polys: builder
             .add_virtual_extension_targets(stark.air().quotient_degree_factor() * num_challenges),
     }
@@ -19,6 +19,9 @@ pub trait TraceGenerator<F, A>:
         global_values: &mut [F],
         public_inputs: &[F],
     ) -> Result<AirTrace<F>, Self::Error>;
+
+    /// Clears the state kept by the generator once a proof has been generated.
+    fn reset(&self) {}
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
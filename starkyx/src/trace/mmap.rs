@@ -0,0 +1,273 @@
+//! A memory-mapped backing for stark traces that do not fit in memory.
+
+use core::marker::PhantomData;
+use core::mem::size_of;
+use core::slice::{ChunksExact, ChunksExactMut};
+use std::fs::OpenOptions;
+use std::io;
+use std::path::Path;
+
+use memmap2::MmapMut;
+use plonky2::field::goldilocks_field::GoldilocksField;
+use plonky2_maybe_rayon::rayon::slice::{
+    ChunksExact as ParChunksExact, ChunksExactMut as ParChunksExactMut,
+};
+use plonky2_maybe_rayon::{MaybeParChunks, MaybeParChunksMut};
+
+use super::view::{TraceView, TraceViewMut};
+use super::AirTrace;
+use crate::math::field::PrimeField64;
+
+/// A field whose elements can be read from any bytes of their size, as a single `u64` with no
+/// invalid bit patterns.
+///
+/// The trait is sealed, since the trace reinterprets the bytes of the mapping as field elements.
+pub trait MmapField: PrimeField64 + sealed::Sealed {}
+
+impl MmapField for GoldilocksField {}
+
+mod sealed {
+    use plonky2::field::goldilocks_field::GoldilocksField;
+
+    pub trait Sealed {}
+
+    impl Sealed for GoldilocksField {}
+}
+
+// `GoldilocksField` is a transparent wrapper of a `u64`, possibly non-canonical, so any 8 bytes
+// aligned to 8 are a valid element.
+const _: () = assert!(size_of::<GoldilocksField>() == size_of::<u64>());
+const _: () = assert!(core::mem::align_of::<GoldilocksField>() == core::mem::align_of::<u64>());
+
+/// A stark trace stored as a matrix in row major order in a memory-mapped buffer.
+///
+/// The trace exposes the same row interface as [`AirTrace`], but the values live in a file (or an
+/// anonymous mapping) and are paged in and out by the operating system as needed.
+#[derive(Debug)]
+pub struct MmapAirTrace<T> {
+    mmap: MmapMut,
+    width: usize,
+    height: usize,
+    _marker: PhantomData<T>,
+}
+
+impl<T: MmapField> MmapAirTrace<T> {
+    /// Creates a zero-initialized trace backed by the file at `path`.
+    ///
+    /// The file is created if it does not exist and truncated otherwise.
+    pub fn new(path: impl AsRef<Path>, width: usize, num_rows: usize) -> io::Result<Self> {
+        let len = Self::byte_len(width, num_rows);
+        let file = OpenOptions::new()
+            .read(true)
+            .write(true)
+            .create(true)
+            .truncate(true)
+            .open(path)?;
+        file.set_len(len as u64)?;
+        // Safety: the file is owned by the trace and is not expected to be modified externally
+        // while it is mapped.
+        let mmap = unsafe { MmapMut::map_mut(&file)? };
+        Ok(Self::from_mmap(mmap, width, num_rows))
+    }
+
+    /// Creates a zero-initialized trace backed by an anonymous memory mapping.
+    pub fn new_anon(width: usize, num_rows: usize) -> io::Result<Self> {
+        let mmap = MmapMut::map_anon(Self::byte_len(width, num_rows))?;
+        Ok(Self::from_mmap(mmap, width, num_rows))
+    }
+
+    fn byte_len(width: usize, num_rows: usize) -> usize {
+        assert!(
+            width > 0 && num_rows > 0,
+            "Cannot map an empty trace of width {} and {} rows",
+            width,
+            num_rows
+        );
+        width * num_rows * size_of::<T>()
+    }
+
+    fn from_mmap(mmap: MmapMut, width: usize, height: usize) -> Self {
+        // Field elements are stored in their canonical `u64` form, so the all-zero bytes of a
+        // fresh mapping are a valid trace of zeros.
+        assert_eq!(
+            mmap.as_ptr() as usize % core::mem::align_of::<T>(),
+            0,
+            "The trace mapping is not aligned"
+        );
+        Self {
+            mmap,
+            width,
+            height,
+            _marker: PhantomData,
+        }
+    }
+
+    #[inline]
+    fn values(&self) -> &[T] {
+        // Safety: the mapping is page aligned and holds exactly `width * height` elements.
+        unsafe { core::slice::from_raw_parts(self.mmap.as_ptr() as *const T, self.len()) }
+    }
+
+    #[inline]
+    fn values_mut(&mut self) -> &mut [T] {
+        let len = self.len();
+        // Safety: the mapping is page aligned and holds exactly `width * height` elements.
+        unsafe { core::slice::from_raw_parts_mut(self.mmap.as_mut_ptr() as *mut T, len) }
+    }
+
+    #[inline]
+    fn len(&self) -> usize {
+        self.width * self.height
+    }
+
+    #[inline]
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    #[inline]
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    #[inline]
+    pub fn row(&self, r: usize) -> &[T] {
+        debug_assert!(r < self.height());
+        &self.values()[r * self.width..(r + 1) * self.width]
+    }
+
+    #[inline]
+    pub fn row_mut(&mut self, r: usize) -> &mut [T] {
+        debug_assert!(r < self.height());
+        let width = self.width;
+        &mut self.values_mut()[r * width..(r + 1) * width]
+    }
+
+    #[inline]
+    pub fn rows(&self) -> ChunksExact<'_, T> {
+        self.values().chunks_exact(self.width)
+    }
+
+    #[inline]
+    pub fn rows_mut(&mut self) -> ChunksExactMut<'_, T> {
+        let width = self.width;
+        self.values_mut().chunks_exact_mut(width)
+    }
+
+    #[inline]
+    pub fn rows_par(&self) -> ParChunksExact<'_, T> {
+        self.values().par_chunks_exact(self.width)
+    }
+
+    #[inline]
+    pub fn rows_par_mut(&mut self) -> ParChunksExactMut<'_, T> {
+        let width = self.width;
+        self.values_mut().par_chunks_exact_mut(width)
+    }
+
+    #[inline]
+    pub fn view(&self) -> TraceView<'_, T> {
+        TraceView {
+            values: self.values(),
+            width: self.width,
+        }
+    }
+
+    #[inline]
+    pub fn view_mut(&mut self) -> TraceViewMut<'_, T> {
+        let width = self.width;
+        TraceViewMut {
+            values: self.values_mut(),
+            width,
+        }
+    }
+
+    /// Flushes outstanding modifications to the backing file.
+    pub fn flush(&self) -> io::Result<()> {
+        self.mmap.flush()
+    }
+
+    pub fn as_columns(&self) -> Vec<Vec<T>> {
+        let mut columns = vec![Vec::with_capacity(self.height()); self.width];
+        for row in self.rows() {
+            for (i, &v) in row.iter().enumerate() {
+                columns[i].push(v);
+            }
+        }
+        columns
+    }
+
+    /// Copies the trace into an in-memory [`AirTrace`].
+    ///
+    /// To prove the trace without copying it, use
+    /// [`StarkyProver::prove_mmap`](crate::plonky2::stark::prover::StarkyProver::prove_mmap).
+    pub fn to_air_trace(&self) -> AirTrace<T> {
+        AirTrace::from_rows(self.values().to_vec(), self.width)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use plonky2_maybe_rayon::{IndexedParallelIterator, ParallelIterator};
+
+    use super::*;
+    use crate::air::fibonacci::FibonacciAir;
+    use crate::math::prelude::*;
+    use crate::plonky2::stark::config::PoseidonGoldilocksStarkConfig;
+    use crate::plonky2::stark::prover::StarkyProver;
+    use crate::plonky2::stark::verifier::StarkyVerifier;
+    use crate::plonky2::stark::Starky;
+    use crate::trace::generator::ConstantGenerator;
+
+    #[test]
+    fn test_mmap_trace_proof() {
+        type F = GoldilocksField;
+        type SC = PoseidonGoldilocksStarkConfig;
+
+        let num_rows = 1 << 10usize;
+        let stark = Starky::new(FibonacciAir::new());
+        let config = SC::standard_fast_config(num_rows);
+
+        let public_inputs = [
+            F::ZERO,
+            F::ONE,
+            FibonacciAir::fibonacci(num_rows - 1, F::ZERO, F::ONE),
+        ];
+
+        let trace = FibonacciAir::generate_trace(F::ZERO, F::ONE, num_rows);
+
+        let path = std::env::temp_dir().join(format!("starkyx_mmap_trace_{}", std::process::id()));
+        let mut mmap_trace = MmapAirTrace::<F>::new(&path, trace.width, num_rows).unwrap();
+        mmap_trace
+            .rows_par_mut()
+            .zip(trace.rows_par())
+            .for_each(|(mmap_row, row)| mmap_row.copy_from_slice(row));
+        mmap_trace.flush().unwrap();
+
+        assert_eq!(mmap_trace.as_columns(), trace.as_columns());
+
+        let proof = StarkyProver::prove(
+            &config,
+            &stark,
+            &ConstantGenerator::new(trace),
+            &public_inputs,
+        )
+        .unwrap();
+        let copied_proof = StarkyProver::prove(
+            &config,
+            &stark,
+            &ConstantGenerator::new(mmap_trace.to_air_trace()),
+            &public_inputs,
+        )
+        .unwrap();
+        assert_eq!(proof, copied_proof);
+
+        let mmap_proof =
+            StarkyProver::prove_mmap(&config, &stark, &mut mmap_trace, &public_inputs).unwrap();
+        assert_eq!(proof, mmap_proof);
+        StarkyVerifier::verify(&config, &stark, mmap_proof, &public_inputs).unwrap();
+
+        drop(mmap_trace);
+        std::fs::remove_file(path).unwrap();
+    }
+}
@@ -1,4 +1,6 @@
 pub mod generator;
+#[cfg(feature = "mmap")]
+pub mod mmap;
 pub mod view;
 pub mod window;
 pub mod window_parser;
@@ -202,6 +202,39 @@ where
     Ok(elements.into_iter().map(|x| x.0).collect::<Vec<_>>())
 }
 
+#[allow(clippy::ptr_arg)]
+pub fn serialize_extension_target_vecs<S, const D: usize>(
+    extension_target_vecs: &Vec<Vec<ExtensionTarget<D>>>,
+    serializer: S,
+) -> Result<S::Ok, S::Error>
+where
+    S: serde::Serializer,
+{
+    extension_target_vecs
+        .iter()
+        .map(|targets| {
+            targets
+                .iter()
+                .map(|x| SerdeExtensionTarget(*x))
+                .collect::<Vec<_>>()
+        })
+        .collect::<Vec<_>>()
+        .serialize(serializer)
+}
+
+pub fn deserialize_extension_target_vecs<'de, D, const DEG: usize>(
+    deserializer: D,
+) -> Result<Vec<Vec<ExtensionTarget<DEG>>>, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    let elements = Vec::<Vec<SerdeExtensionTarget<DEG>>>::deserialize(deserializer)?;
+    Ok(elements
+        .into_iter()
+        .map(|targets| targets.into_iter().map(|x| x.0).collect::<Vec<_>>())
+        .collect::<Vec<_>>())
+}
+
 pub fn serialize_fri_proof_target<S, const D: usize>(
     fri_proof_target: &FriProofTarget<D>,
     serializer: S,